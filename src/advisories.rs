@@ -2,11 +2,11 @@ pub mod cfg;
 pub(crate) mod diags;
 mod helpers;
 
-use crate::{diag, LintLevel};
+use crate::{diag, LintLevel, ProgressEvent};
 pub use diags::Code;
 pub use helpers::{
-    db::{AdvisoryDb, DbSet, Fetch, Id, Report},
-    index::{Entry, Indices},
+    db::{AdvisoryDb, DbSet, Fetch, FetchTimeout, Id, Report},
+    index::{Entry, IndexVersion, Indices},
 };
 
 pub trait AuditReporter {
@@ -30,6 +30,10 @@ where
 
 /// Check crates against the advisory database to detect vulnerabilities or
 /// unmaintained crates
+///
+/// The databases are only borrowed, so that a [`DbSet`] loaded once can be
+/// reused to check any number of crate graphs, eg. every workspace checked by
+/// a long running process, without fetching and parsing them each time
 pub fn check<R, S>(
     ctx: crate::CheckCtx<'_, cfg::ValidConfig>,
     advisory_dbs: &DbSet,
@@ -43,10 +47,15 @@ pub fn check<R, S>(
     let mut sink = sink.into();
     let emit_audit_compatible_reports = audit_compatible_reporter.is_some();
 
+    ctx.report_progress(ProgressEvent::QueryingAdvisories {
+        krates: ctx.krates.len(),
+        databases: advisory_dbs.dbs.len(),
+    });
+
     let (report, yanked) = rayon::join(
         || Report::generate(advisory_dbs, ctx.krates, emit_audit_compatible_reports),
         || {
-            if let Some(indices) = indices {
+            if let Some(indices) = &indices {
                 let yanked: Vec<_> = ctx
                     .krates
                     .krates()
@@ -69,22 +78,92 @@ pub fn check<R, S>(
         },
     );
 
+    ctx.report_progress(ProgressEvent::AdvisoriesQueried {
+        advisories: report.advisories.len(),
+        yanked: yanked.len(),
+    });
+
     use bitvec::prelude::*;
     let mut ignore_hits: BitVec = BitVec::repeat(false, ctx.cfg.ignore.len());
     let mut ignore_yanked_hits: BitVec = BitVec::repeat(false, ctx.cfg.ignore_yanked.len());
 
+    // If configured, determine the crates that are reachable without going
+    // through a dev-dependency, ie. the crates that are actually shipped
+    let non_dev = ctx
+        .cfg
+        .dev_only
+        .is_some()
+        .then(|| ctx.krates.krates_filtered(krates::DepKind::Dev));
+
+    // If the user has set an advisory budget, we can only determine the
+    // severity of each advisory once we know how many are open
+    let mut budgeted = Vec::new();
+
     // Emit diagnostics for any advisories found that matched crates in the graph
     for (krate, advisory) in &report.advisories {
+        let dev_only = non_dev
+            .as_ref()
+            .is_some_and(|non_dev| non_dev.binary_search_by(|k| k.id.cmp(&krate.id)).is_err());
+
+        // Suggest the lowest version that is not affected by the advisory
+        let fix = indices
+            .as_ref()
+            .filter(|_| ctx.suggest_fixes)
+            .and_then(|indices| {
+                indices.newer_version(krate, |version| !advisory.versions.is_vulnerable(version))
+            });
+
         let diag = ctx.diag_for_advisory(
             krate,
             &advisory.metadata,
             Some(&advisory.versions),
+            dev_only,
+            fix,
             |index| {
                 ignore_hits.as_mut_bitslice().set(index, true);
             },
         );
 
-        sink.push(diag);
+        if ctx.cfg.max_open.is_some() {
+            budgeted.push(diag);
+        } else {
+            sink.push(diag);
+        }
+    }
+
+    if let Some(max_open) = &ctx.cfg.max_open {
+        use diag::Severity;
+
+        // Ignored advisories, or advisories for dev-only crates that are
+        // allowed, are notes, everything else is an open advisory
+        let is_open =
+            |diag: &diag::Diag| matches!(diag.diag.severity, Severity::Error | Severity::Warning);
+
+        let open = budgeted
+            .iter()
+            .filter(|pack| pack.iter().any(is_open))
+            .count();
+        let exceeded = open > max_open.value.count as usize;
+
+        // Within the budget every open advisory is tolerated as a warning,
+        // including vulnerabilities whose lint level can't be lowered
+        for mut pack in budgeted {
+            for diag in pack.iter_mut() {
+                if is_open(diag) {
+                    diag.diag.severity = if exceeded {
+                        Severity::Error
+                    } else {
+                        Severity::Warning
+                    };
+                }
+            }
+
+            sink.push(pack);
+        }
+
+        if exceeded {
+            sink.push(ctx.diag_for_advisory_budget_exceeded(open, max_open));
+        }
     }
 
     for (krate, status) in yanked {
@@ -107,7 +186,22 @@ pub fn check<R, S>(
                 sink.push(ctx.diag_for_yanked_ignore(krate, i));
                 ignore_yanked_hits.as_mut_bitslice().set(i, true);
             } else {
-                sink.push(ctx.diag_for_yanked(krate));
+                // Suggest the lowest semver compatible version that is not yanked
+                let fix = indices
+                    .as_ref()
+                    .filter(|_| ctx.suggest_fixes)
+                    .and_then(|indices| {
+                        let req = semver::Comparator {
+                            op: semver::Op::Caret,
+                            major: krate.version.major,
+                            minor: Some(krate.version.minor),
+                            patch: Some(krate.version.patch),
+                            pre: krate.version.pre.clone(),
+                        };
+                        indices.newer_version(krate, |version| req.matches(version))
+                    });
+
+                sink.push(ctx.diag_for_yanked(krate, fix));
             }
         }
     }
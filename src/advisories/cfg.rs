@@ -11,7 +11,7 @@ use url::Url;
 
 pub(crate) type AdvisoryId = Spanned<advisory::Id>;
 
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(serde::Serialize)]
 pub(crate) struct IgnoreId {
     pub id: AdvisoryId,
     pub reason: Option<Reason>,
@@ -65,6 +65,87 @@ impl PartialEq for IgnoreId {
 
 impl Eq for IgnoreId {}
 
+/// The maximum number of advisories that can be open before they are treated
+/// as errors rather than warnings
+#[derive(serde::Serialize)]
+pub struct MaxOpen {
+    pub count: u32,
+}
+
+impl<'de> Deserialize<'de> for MaxOpen {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, toml_span::DeserError> {
+        let mut th = TableHelper::new(value)?;
+        let count = th.required("count")?;
+        th.finalize(None)?;
+
+        Ok(Self { count })
+    }
+}
+
+/// The lint levels for each class of informational advisory
+#[derive(Default, serde::Serialize)]
+pub struct Informational {
+    /// The lint level for `unmaintained` advisories
+    pub unmaintained: Option<Spanned<LintLevel>>,
+    /// The lint level for `unsound` advisories
+    pub unsound: Option<Spanned<LintLevel>>,
+    /// The lint level for `notice` advisories
+    pub notice: Option<Spanned<LintLevel>>,
+}
+
+impl<'de> Deserialize<'de> for Informational {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, toml_span::DeserError> {
+        let mut th = TableHelper::new(value)?;
+        let unmaintained = th.optional("unmaintained");
+        let unsound = th.optional("unsound");
+        let notice = th.optional("notice");
+        th.finalize(None)?;
+
+        Ok(Self {
+            unmaintained,
+            unsound,
+            notice,
+        })
+    }
+}
+
+/// The lint level for vulnerabilities whose CVSS severity is below a threshold
+#[derive(serde::Serialize)]
+pub struct SeverityThreshold {
+    /// The minimum CVSS severity for a vulnerability to be denied
+    pub threshold: Spanned<advisory::Severity>,
+    /// The lint level for vulnerabilities below the threshold, defaults to
+    /// `allow`
+    pub below_threshold: Option<Spanned<LintLevel>>,
+}
+
+impl<'de> Deserialize<'de> for SeverityThreshold {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, toml_span::DeserError> {
+        let mut th = TableHelper::new(value)?;
+        let threshold = th.required_s::<std::borrow::Cow<'de, str>>("threshold")?;
+        let below_threshold = th.optional("below-threshold");
+        th.finalize(None)?;
+
+        let threshold = match threshold.value.parse() {
+            Ok(severity) => Spanned::with_span(severity, threshold.span),
+            Err(err) => {
+                return Err(toml_span::Error::from((
+                    toml_span::ErrorKind::Custom(
+                        format!("failed to parse rustsec::Severity: {err}").into(),
+                    ),
+                    threshold.span,
+                ))
+                .into());
+            }
+        };
+
+        Ok(Self {
+            threshold,
+            below_threshold,
+        })
+    }
+}
+
 pub struct Config {
     /// Path to the root directory where advisory databases are stored (default: $CARGO_HOME/advisory-dbs)
     pub db_path: Option<Spanned<PathBuf>>,
@@ -74,7 +155,7 @@ pub struct Config {
     pub yanked: Spanned<LintLevel>,
     /// Ignore advisories for the given IDs
     ignore: Vec<Spanned<IgnoreId>>,
-    /// Ignore yanked crates
+    /// Ignore yanked crates, specified either in `ignore` or `yanked-allow`
     pub ignore_yanked: Vec<Spanned<PackageSpecOrExtended<Reason>>>,
     /// Use the git executable to fetch advisory database rather than gitoxide
     pub git_fetch_with_cli: Option<bool>,
@@ -88,6 +169,29 @@ pub struct Config {
     /// use the '.' separator instead of ',' which is used by some locales and
     /// supported in the RFC3339 format, but not by this implementation
     pub maximum_db_staleness: Spanned<Duration>,
+    /// The maximum amount of time fetching an advisory database is allowed
+    /// to take, eg. `30s`
+    pub fetch_timeout: Option<Spanned<Duration>>,
+    /// If set to true and a fetch times out, the existing local copy of the
+    /// advisory database is used instead, if it exists
+    pub fallback_to_cache: bool,
+    /// If set to true, at least one advisory database must have been loaded
+    /// and contain advisories, otherwise the run fails
+    pub require_db: bool,
+    /// If set, advisories for crates that are only reachable from the
+    /// workspace via dev-dependencies use this lint level instead
+    pub dev_only: Option<Spanned<LintLevel>>,
+    /// If set, advisories that aren't ignored are emitted as warnings as long
+    /// as there are no more than the budgeted count, otherwise they are errors
+    pub max_open: Option<Spanned<MaxOpen>>,
+    /// Overrides the lint level for informational advisories, per class
+    pub informational: Informational,
+    /// The lint level for informational advisories of a kind that is not
+    /// known, defaults to warn
+    pub other: Option<Spanned<LintLevel>>,
+    /// If set, vulnerabilities with a CVSS severity below the threshold use
+    /// the configured lint level instead of being denied
+    pub severity: Option<Spanned<SeverityThreshold>>,
     deprecated_spans: Vec<Span>,
 }
 
@@ -102,6 +206,14 @@ impl Default for Config {
             git_fetch_with_cli: None,
             disable_yank_checking: false,
             maximum_db_staleness: Spanned::new(Duration::seconds_f64(NINETY_DAYS)),
+            fetch_timeout: None,
+            fallback_to_cache: false,
+            require_db: false,
+            dev_only: None,
+            max_open: None,
+            informational: Informational::default(),
+            other: None,
+            severity: None,
             deprecated_spans: Vec::new(),
         }
     }
@@ -151,7 +263,7 @@ impl<'de> Deserialize<'de> for Config {
         let yanked = th
             .optional_s("yanked")
             .unwrap_or(Spanned::new(LintLevel::Warn));
-        let (ignore, ignore_yanked) = if let Some((_, mut ignore)) = th.take("ignore") {
+        let (ignore, mut ignore_yanked) = if let Some((_, mut ignore)) = th.take("ignore") {
             let mut u = Vec::new();
             let mut y = Vec::new();
 
@@ -223,6 +335,12 @@ impl<'de> Deserialize<'de> for Config {
         } else {
             (Vec::new(), Vec::new())
         };
+
+        // Yanked crates can also be allowed via their own list, which is
+        // treated the same as the package specs in `ignore`
+        if let Some(mut yanked_allow) = th.optional::<Vec<_>>("yanked-allow") {
+            ignore_yanked.append(&mut yanked_allow);
+        }
         let st = |th: &mut TableHelper<'_>, fdeps: &mut Vec<Span>| {
             let (k, mut v) = th.take("severity-threshold")?;
 
@@ -281,6 +399,37 @@ impl<'de> Deserialize<'de> for Config {
             None
         };
 
+        let fetch_timeout = if let Some((_, mut val)) = th.take("fetch-timeout") {
+            match val.take_string(Some("a duration, eg. '30s'")) {
+                Ok(ft) => match parse_timeout(&ft) {
+                    Ok(ft) => Some(Spanned::with_span(ft, val.span)),
+                    Err(err) => {
+                        th.errors.push(
+                            (
+                                toml_span::ErrorKind::Custom(err.to_string().into()),
+                                val.span,
+                            )
+                                .into(),
+                        );
+                        None
+                    }
+                },
+                Err(err) => {
+                    th.errors.push(err);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let fallback_to_cache = th.optional("fallback-to-cache").unwrap_or_default();
+        let require_db = th.optional("require-db").unwrap_or_default();
+        let dev_only = th.optional("dev-only");
+        let max_open = th.optional_s("max-open");
+        let informational = th.optional("informational").unwrap_or_default();
+        let other = th.optional("other");
+        let severity = th.optional_s("severity");
+
         th.finalize(None)?;
 
         // Use the 90 days default as a fallback
@@ -296,6 +445,14 @@ impl<'de> Deserialize<'de> for Config {
             git_fetch_with_cli,
             disable_yank_checking,
             maximum_db_staleness,
+            fetch_timeout,
+            fallback_to_cache,
+            require_db,
+            dev_only,
+            max_open,
+            informational,
+            other,
+            severity,
             deprecated_spans: fdeps,
         })
     }
@@ -404,11 +561,19 @@ impl crate::cfg::UnvalidatedConfig for Config {
             git_fetch_with_cli: self.git_fetch_with_cli.unwrap_or_default(),
             disable_yank_checking: self.disable_yank_checking,
             maximum_db_staleness: self.maximum_db_staleness,
+            fetch_timeout: self.fetch_timeout,
+            fallback_to_cache: self.fallback_to_cache,
+            require_db: self.require_db,
+            dev_only: self.dev_only,
+            max_open: self.max_open,
+            informational: self.informational,
+            other: self.other,
+            severity: self.severity,
         }
     }
 }
 
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(serde::Serialize)]
 pub struct ValidConfig {
     pub file_id: FileId,
     pub db_path: PathBuf,
@@ -419,6 +584,54 @@ pub struct ValidConfig {
     pub git_fetch_with_cli: bool,
     pub disable_yank_checking: bool,
     pub maximum_db_staleness: Spanned<Duration>,
+    pub fetch_timeout: Option<Spanned<Duration>>,
+    pub fallback_to_cache: bool,
+    pub require_db: bool,
+    pub dev_only: Option<Spanned<LintLevel>>,
+    pub max_open: Option<Spanned<MaxOpen>>,
+    pub informational: Informational,
+    pub other: Option<Spanned<LintLevel>>,
+    pub severity: Option<Spanned<SeverityThreshold>>,
+}
+
+impl ValidConfig {
+    /// Gets the fetch timeout, if any, preferring the override over the configured value
+    pub fn fetch_timeout(&self, timeout_override: Option<Duration>) -> Option<super::FetchTimeout> {
+        timeout_override
+            .or_else(|| self.fetch_timeout.as_ref().map(|ft| ft.value))
+            .map(|duration| super::FetchTimeout {
+                duration: duration.unsigned_abs(),
+                fallback_to_cache: self.fallback_to_cache,
+            })
+    }
+}
+
+/// Parses a simple duration consisting of a number followed by a unit, eg.
+/// `500ms`, `30s`, `5m`, or `1h`
+pub fn parse_timeout(value: &str) -> anyhow::Result<Duration> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .context("duration requires a unit, eg. '30s'")?;
+    let (amount, unit) = value.split_at(split);
+    let amount: f64 = amount
+        .parse()
+        .with_context(|| format!("'{amount}' is not a valid duration amount"))?;
+
+    let secs = match unit {
+        "ms" => amount / 1000.,
+        "s" => amount,
+        "m" => amount * 60.,
+        "h" => amount * 60. * 60.,
+        other => {
+            anyhow::bail!("unknown duration unit '{other}', expected one of 'ms', 's', 'm', or 'h'")
+        }
+    };
+
+    anyhow::ensure!(secs > 0., "duration '{value}' must be greater than zero");
+
+    Duration::checked_seconds_f64(secs)
+        .with_context(|| format!("duration '{value}' is out of range"))
 }
 
 /// We need to implement this ourselves since time doesn't support it
@@ -801,6 +1014,21 @@ ignore = [
         insta::assert_snapshot!(failures);
     }
 
+    #[test]
+    fn parses_timeouts() {
+        assert_eq!(parse_timeout("500ms").unwrap(), Duration::milliseconds(500));
+        assert_eq!(parse_timeout("30s").unwrap(), Duration::seconds(30));
+        assert_eq!(parse_timeout("1.5m").unwrap(), Duration::seconds(90));
+        assert_eq!(parse_timeout("2h").unwrap(), Duration::hours(2));
+
+        assert!(parse_timeout("30").is_err());
+        assert!(parse_timeout("s").is_err());
+        assert!(parse_timeout("30d").is_err());
+        assert!(parse_timeout("0s").is_err());
+        assert!(parse_timeout("0.0ms").is_err());
+        assert!(parse_timeout(&format!("{}h", "9".repeat(400))).is_err());
+    }
+
     /// Validates we can parse many durations.
     ///
     /// Note the values were copied from <https://ijmacd.github.io/rfc3339-iso8601/>
@@ -1,7 +1,7 @@
-use super::cfg::IgnoreId;
+use super::cfg::{IgnoreId, MaxOpen};
 use crate::{
     diag::{Check, Diagnostic, FileId, Label, Pack, Severity},
-    LintLevel,
+    LintLevel, Spanned,
 };
 use rustsec::advisory::{Informational, Metadata, Versions};
 
@@ -43,6 +43,8 @@ pub enum Code {
     AdvisoryNotDetected,
     YankedNotDetected,
     UnknownAdvisory,
+    AdvisoryBudgetExceeded,
+    OtherAdvisory,
 }
 
 impl From<Code> for String {
@@ -66,45 +68,76 @@ fn get_notes_from_advisory(advisory: &Metadata) -> Vec<String> {
     n
 }
 
+/// A `cargo update` invocation that updates the crate to the specified version
+fn suggested_update(krate: &crate::Krate, version: &semver::Version) -> String {
+    format!(
+        "Suggested fix: `cargo update -p {}@{} --precise {version}`",
+        krate.name, krate.version
+    )
+}
+
 impl crate::CheckCtx<'_, super::cfg::ValidConfig> {
     pub(crate) fn diag_for_advisory<F>(
         &self,
         krate: &crate::Krate,
         advisory: &Metadata,
         versions: Option<&Versions>,
+        dev_only: bool,
+        fix: Option<&semver::Version>,
         mut on_ignore: F,
     ) -> Pack
     where
         F: FnMut(usize),
     {
         #[derive(Clone, Copy)]
-        enum AdvisoryType {
+        enum AdvisoryType<'a> {
             Vulnerability,
             Notice,
             Unmaintained,
             Unsound,
+            /// An informational kind this version of cargo-deny doesn't know about
+            Other(&'a str),
         }
 
         let mut pack = Pack::with_kid(Check::Advisories, krate.id.clone());
 
+        // The crate is only reachable via dev-dependencies and the user has
+        // configured a lint level for that case
+        let dev_only = self.cfg.dev_only.as_ref().filter(|_| dev_only);
+
+        let mut info_level = None;
+        let mut below_threshold = None;
+
         let (severity, ty) = {
-            let adv_ty = advisory.informational.as_ref().map_or(AdvisoryType::Vulnerability, |info| {
-                match info {
-                    // Crate is unmaintained / abandoned
-                    Informational::Unmaintained => AdvisoryType::Unmaintained,
-                    Informational::Unsound => AdvisoryType::Unsound,
-                    Informational::Notice => AdvisoryType::Notice,
-                    Informational::Other(other) => {
-                        unreachable!("rustsec only returns Informational::Other({other}) advisories if we ask, and there are none at the moment to ask for");
-                    }
-                    _ => unreachable!("non_exhaustive enums are the worst"),
-                }
-            });
+            let adv_ty =
+                advisory
+                    .informational
+                    .as_ref()
+                    .map_or(AdvisoryType::Vulnerability, |info| {
+                        match info {
+                            // Crate is unmaintained / abandoned
+                            Informational::Unmaintained => AdvisoryType::Unmaintained,
+                            Informational::Unsound => AdvisoryType::Unsound,
+                            Informational::Notice => AdvisoryType::Notice,
+                            // Either a kind we didn't ask for, or a new kind that was
+                            // added to rustsec, in either case we don't want to panic
+                            other => AdvisoryType::Other(other.as_str()),
+                        }
+                    });
 
             // Ok, we found a crate whose version lies within the range of an
             // advisory, but the user might have decided to ignore it
             // for "reasons", but in that case we still emit it to the log
             // so it doesn't just disappear into the aether
+            let informational = &self.cfg.informational;
+            let configured = match adv_ty {
+                AdvisoryType::Vulnerability => None,
+                AdvisoryType::Notice => informational.notice.as_ref(),
+                AdvisoryType::Unmaintained => informational.unmaintained.as_ref(),
+                AdvisoryType::Unsound => informational.unsound.as_ref(),
+                AdvisoryType::Other(_) => self.cfg.other.as_ref(),
+            };
+
             let lint_level = if let Ok(index) = self
                 .cfg
                 .ignore
@@ -123,6 +156,29 @@ impl crate::CheckCtx<'_, super::cfg::ValidConfig> {
                 );
 
                 LintLevel::Allow
+            } else if let Some(dev_only) = dev_only {
+                dev_only.value
+            } else if let Some((severity, st)) = self
+                .cfg
+                .severity
+                .as_ref()
+                .filter(|_| matches!(adv_ty, AdvisoryType::Vulnerability))
+                .and_then(|st| {
+                    // Vulnerabilities without a CVSS score can't be compared
+                    // against the threshold, so are always denied
+                    let severity = advisory.cvss.as_ref()?.severity();
+                    (severity < st.value.threshold.value).then_some((severity, &st.value))
+                })
+            {
+                below_threshold = Some((severity, st));
+                st.below_threshold
+                    .as_ref()
+                    .map_or(LintLevel::Allow, |bt| bt.value)
+            } else if let Some(configured) = configured {
+                info_level = Some(configured);
+                configured.value
+            } else if matches!(adv_ty, AdvisoryType::Other(_)) {
+                LintLevel::Warn
             } else {
                 LintLevel::Deny
             };
@@ -132,6 +188,19 @@ impl crate::CheckCtx<'_, super::cfg::ValidConfig> {
 
         let mut notes = get_notes_from_advisory(advisory);
 
+        if dev_only.is_some() {
+            notes.push(format!(
+                "'{krate}' is only reachable from the workspace via dev-dependencies"
+            ));
+        }
+
+        if let Some((severity, st)) = below_threshold {
+            notes.push(format!(
+                "the CVSS severity '{severity}' is below the threshold '{}'",
+                st.threshold.value
+            ));
+        }
+
         if let Some(versions) = versions {
             if versions.patched().is_empty() {
                 notes.push("Solution: No safe upgrade is available!".to_owned());
@@ -150,21 +219,76 @@ impl crate::CheckCtx<'_, super::cfg::ValidConfig> {
             }
         };
 
+        if let Some(fix) = fix.filter(|_| severity != Severity::Note) {
+            notes.push(suggested_update(krate, fix));
+        }
+
+        if let AdvisoryType::Other(kind) = ty {
+            notes.push(format!(
+                "'{kind}' is not a known informational advisory kind, cargo-deny may need to be updated"
+            ));
+        }
+
         let (message, code) = match ty {
-            AdvisoryType::Vulnerability => ("security vulnerability detected", Code::Vulnerability),
-            AdvisoryType::Notice => ("notice advisory detected", Code::Notice),
-            AdvisoryType::Unmaintained => ("unmaintained advisory detected", Code::Unmaintained),
-            AdvisoryType::Unsound => ("unsound advisory detected", Code::Unsound),
+            AdvisoryType::Vulnerability => (
+                "security vulnerability detected".to_owned(),
+                Code::Vulnerability,
+            ),
+            AdvisoryType::Notice => ("notice advisory detected".to_owned(), Code::Notice),
+            AdvisoryType::Unmaintained => (
+                "unmaintained advisory detected".to_owned(),
+                Code::Unmaintained,
+            ),
+            AdvisoryType::Unsound => ("unsound advisory detected".to_owned(), Code::Unsound),
+            AdvisoryType::Other(kind) => {
+                (format!("'{kind}' advisory detected"), Code::OtherAdvisory)
+            }
         };
 
+        let mut labels = vec![Label::primary(
+            self.krate_spans.lock_id,
+            self.krate_spans.lock_span(&krate.id).total,
+        )
+        .with_message(message)];
+
+        if let Some(dev_only) = dev_only {
+            labels.push(
+                Label::secondary(self.cfg.file_id, dev_only.span)
+                    .with_message("lint level for dev-only crates"),
+            );
+        }
+
+        if let Some((_, st)) = below_threshold {
+            labels.push(
+                Label::secondary(self.cfg.file_id, st.threshold.span)
+                    .with_message("severity threshold"),
+            );
+
+            if let Some(bt) = &st.below_threshold {
+                labels.push(
+                    Label::secondary(self.cfg.file_id, bt.span)
+                        .with_message("lint level for vulnerabilities below the threshold"),
+                );
+            }
+        }
+
+        if let Some(info_level) = info_level {
+            labels.push(
+                Label::secondary(self.cfg.file_id, info_level.span).with_message(format!(
+                    "lint level for {} advisories",
+                    if let AdvisoryType::Other(_) = ty {
+                        "other"
+                    } else {
+                        code.into()
+                    }
+                )),
+            );
+        }
+
         let diag = pack.push(
             Diagnostic::new(severity)
                 .with_message(advisory.title.clone())
-                .with_labels(vec![Label::primary(
-                    self.krate_spans.lock_id,
-                    self.krate_spans.lock_span(&krate.id).total,
-                )
-                .with_message(message)])
+                .with_labels(labels)
                 .with_code(code)
                 .with_notes(notes),
         );
@@ -176,7 +300,11 @@ impl crate::CheckCtx<'_, super::cfg::ValidConfig> {
         pack
     }
 
-    pub(crate) fn diag_for_yanked(&self, krate: &crate::Krate) -> Pack {
+    pub(crate) fn diag_for_yanked(
+        &self,
+        krate: &crate::Krate,
+        fix: Option<&semver::Version>,
+    ) -> Pack {
         let mut pack = Pack::with_kid(Check::Advisories, krate.id.clone());
         pack.push(
             Diagnostic::new(self.cfg.yanked.value.into())
@@ -189,7 +317,12 @@ impl crate::CheckCtx<'_, super::cfg::ValidConfig> {
                     self.krate_spans.lock_id,
                     self.krate_spans.lock_span(&krate.id).total,
                 )
-                .with_message("yanked version")]),
+                .with_message("yanked version")])
+                .with_notes(
+                    fix.map(|fix| suggested_update(krate, fix))
+                        .into_iter()
+                        .collect(),
+                ),
         );
 
         pack
@@ -277,6 +410,30 @@ impl crate::CheckCtx<'_, super::cfg::ValidConfig> {
             .into()
     }
 
+    pub(crate) fn diag_for_advisory_budget_exceeded(
+        &self,
+        open: usize,
+        max_open: &Spanned<MaxOpen>,
+    ) -> Pack {
+        (
+            Check::Advisories,
+            Diagnostic::new(Severity::Error)
+                .with_message(format!(
+                    "{open} advisories are open, which exceeds the budget of {}",
+                    max_open.value.count
+                ))
+                .with_code(Code::AdvisoryBudgetExceeded)
+                .with_labels(vec![
+                    Label::primary(self.cfg.file_id, max_open.span).with_message("advisory budget")
+                ])
+                .with_notes(vec![
+                    "resolve or ignore an existing advisory to get back under the budget"
+                        .to_owned(),
+                ]),
+        )
+            .into()
+    }
+
     pub(crate) fn diag_for_unknown_advisory(&self, unknown: &IgnoreId) -> Pack {
         (
             Check::Advisories,
@@ -2,7 +2,7 @@ use crate::{Krate, Krates, Path, PathBuf};
 use anyhow::Context as _;
 use log::{debug, info};
 pub use rustsec::{advisory::Id, Database};
-use std::fmt;
+use std::{fmt, sync::atomic::AtomicBool};
 use url::Url;
 
 /// The default, official, rustsec advisory database
@@ -18,6 +18,16 @@ pub enum Fetch {
     Disallow(time::Duration),
 }
 
+/// Limits how long fetching an advisory database is allowed to take
+#[derive(Copy, Clone, Debug)]
+pub struct FetchTimeout {
+    /// The maximum amount of time a fetch can take before it is cancelled
+    pub duration: std::time::Duration,
+    /// If true and the fetch times out, the existing local copy of the database
+    /// is opened instead of failing
+    pub fallback_to_cache: bool,
+}
+
 pub struct AdvisoryDb {
     /// Remote url of the database
     pub url: Url,
@@ -41,6 +51,12 @@ impl fmt::Debug for AdvisoryDb {
 /// A collection of [`Database`]s that is used to query advisories
 /// in many different databases.
 ///
+/// Loading the databases is by far the most expensive part of the advisories
+/// check, so the set is owned by the caller rather than the check, which only
+/// borrows it. The set is immutable once loaded, and is `Send + Sync`, so it
+/// can be shared by reference, or via an `Arc`, between any number of checks,
+/// including ones running concurrently on different threads.
+///
 /// [`Database`]: https://docs.rs/rustsec/latest/rustsec/database/struct.Database.html
 #[derive(Debug)]
 pub struct DbSet {
@@ -48,7 +64,14 @@ pub struct DbSet {
 }
 
 impl DbSet {
-    pub fn load(root: PathBuf, mut urls: Vec<Url>, fetch: Fetch) -> anyhow::Result<Self> {
+    /// Loads, and if allowed, fetches, the databases at the specified urls,
+    /// falling back to the default rustsec database if none are specified
+    pub fn load(
+        root: PathBuf,
+        mut urls: Vec<Url>,
+        fetch: Fetch,
+        timeout: Option<FetchTimeout>,
+    ) -> anyhow::Result<Self> {
         if urls.is_empty() {
             info!("No advisory database configured, falling back to default '{DEFAULT_URL}'");
             urls.push(Url::parse(DEFAULT_URL).unwrap());
@@ -68,7 +91,7 @@ impl DbSet {
         use rayon::prelude::*;
         let mut dbs = Vec::with_capacity(urls.len());
         urls.into_par_iter()
-            .map(|url| load_db(url, root.clone(), fetch))
+            .map(|url| load_db(url, root.clone(), fetch, timeout))
             .collect_into_vec(&mut dbs);
 
         Ok(Self {
@@ -85,6 +108,20 @@ impl DbSet {
     pub fn has_advisory(&self, id: &Id) -> bool {
         self.dbs.iter().any(|adb| adb.db.get(id).is_some())
     }
+
+    /// Ensures that at least one of the databases contains advisories, so
+    /// that a missing or empty database doesn't pass the check vacuously
+    pub fn ensure_advisories(&self) -> anyhow::Result<()> {
+        if self.dbs.iter().any(|adb| adb.db.iter().next().is_some()) {
+            return Ok(());
+        }
+
+        let paths: Vec<_> = self.dbs.iter().map(|adb| adb.path.as_str()).collect();
+        anyhow::bail!(
+            "`advisories.require-db` is set, but none of the advisory databases contain any advisories, checked: {}",
+            paths.join(", ")
+        )
+    }
 }
 
 /// Convert an advisory url to a directory underneath a specified root
@@ -95,26 +132,62 @@ fn url_to_db_path(mut db_path: PathBuf, url: &Url) -> anyhow::Result<PathBuf> {
     Ok(db_path)
 }
 
-fn load_db(url: Url, root_db_path: PathBuf, fetch: Fetch) -> anyhow::Result<AdvisoryDb> {
+fn load_db(
+    url: Url,
+    root_db_path: PathBuf,
+    fetch: Fetch,
+    timeout: Option<FetchTimeout>,
+) -> anyhow::Result<AdvisoryDb> {
     let db_url = &url;
     let db_path = url_to_db_path(root_db_path, db_url)?;
 
     let fetch_start = std::time::Instant::now();
-    match fetch {
-        Fetch::Allow => {
-            debug!("Fetching advisory database from '{db_url}'");
-            fetch_via_gix(db_url, &db_path)
-                .with_context(|| format!("failed to fetch advisory database {db_url}"))?;
-        }
-        Fetch::AllowWithGitCli => {
-            debug!("Fetching advisory database with git cli from '{db_url}'");
+    // The fetch may outlive this function if it times out, so it can't borrow
+    // anything
+    let fetch_url = url.clone();
+    let fetch_path = db_path.clone();
+    let do_fetch = move |should_interrupt: &AtomicBool| -> anyhow::Result<()> {
+        let (db_url, db_path) = (&fetch_url, &fetch_path);
+
+        match fetch {
+            Fetch::Allow => {
+                debug!("Fetching advisory database from '{db_url}'");
+                fetch_via_gix(db_url, db_path, should_interrupt)
+                    .with_context(|| format!("failed to fetch advisory database {db_url}"))?;
+            }
+            Fetch::AllowWithGitCli => {
+                debug!("Fetching advisory database with git cli from '{db_url}'");
 
-            fetch_via_cli(db_url.as_str(), &db_path)
-                .with_context(|| format!("failed to fetch advisory database {db_url} with cli"))?;
+                fetch_via_cli(db_url.as_str(), db_path, should_interrupt).with_context(|| {
+                    format!("failed to fetch advisory database {db_url} with cli")
+                })?;
+            }
+            Fetch::Disallow(_) => {
+                debug!("Opening advisory database at '{db_path}'");
+            }
         }
-        Fetch::Disallow(_) => {
-            debug!("Opening advisory database at '{db_path}'");
+
+        Ok(())
+    };
+
+    match timeout {
+        Some(timeout) if !matches!(fetch, Fetch::Disallow(_)) => {
+            match fetch_with_timeout(timeout.duration, do_fetch) {
+                Ok(()) => {}
+                // Only fallback to the local copy if it actually exists, otherwise
+                // the error will be far more confusing than just the timeout,
+                // and never if the fetch may still be writing to it
+                Err(FetchFailure::Stopped(err))
+                    if timeout.fallback_to_cache && db_path.join(".git").exists() =>
+                {
+                    log::warn!(
+                        "{err:#}, falling back to the existing local database at '{db_path}'"
+                    );
+                }
+                Err(FetchFailure::Stopped(err) | FetchFailure::Running(err)) => return Err(err),
+            }
         }
+        _ => do_fetch(&gix::interrupt::IS_INTERRUPTED)?,
     }
 
     // Verify that the repository is actually valid and that it is fresh
@@ -154,6 +227,88 @@ fn load_db(url: Url, root_db_path: PathBuf, fetch: Fetch) -> anyhow::Result<Advi
     })
 }
 
+/// How long a fetch that has timed out is given to observe the interrupt and
+/// stop before we give up waiting on it
+const FETCH_STOP_GRACE: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// The reason a fetch run via [`fetch_with_timeout`] did not succeed
+enum FetchFailure {
+    /// The fetch failed, or was interrupted, and is no longer running
+    Stopped(anyhow::Error),
+    /// The fetch was interrupted but did not stop within [`FETCH_STOP_GRACE`],
+    /// so it may still be writing to the database
+    Running(anyhow::Error),
+}
+
+/// Runs the fetch on a separate thread, interrupting it if it does not complete
+/// within the specified duration
+///
+/// Once interrupted, the fetch is given [`FETCH_STOP_GRACE`] to stop, but a
+/// fetch that is stuck in network I/O won't observe the interrupt until the
+/// I/O completes, if ever, so the thread is then detached rather than joined
+fn fetch_with_timeout<F>(duration: std::time::Duration, fetch: F) -> Result<(), FetchFailure>
+where
+    F: FnOnce(&AtomicBool) -> anyhow::Result<()> + Send + 'static,
+{
+    use crossbeam::channel;
+    use std::sync::{atomic::Ordering, Arc};
+
+    // We can't just use the global interrupt flag since that would interrupt
+    // every fetch, so we use our own and forward the global flag to it if the
+    // user aborts
+    let should_interrupt = Arc::new(AtomicBool::new(false));
+    let deadline = std::time::Instant::now() + duration;
+
+    let (tx, rx) = channel::bounded(1);
+
+    {
+        let should_interrupt = should_interrupt.clone();
+        std::thread::Builder::new()
+            .name("advisory-db-fetch".into())
+            .spawn(move || {
+                let _ = tx.send(fetch(&should_interrupt));
+            })
+            .context("failed to spawn fetch thread")
+            .map_err(FetchFailure::Stopped)?;
+    }
+
+    // Interrupts the fetch and waits for it to stop
+    let interrupt = |err: anyhow::Error| {
+        should_interrupt.store(true, Ordering::Relaxed);
+
+        match rx.recv_timeout(FETCH_STOP_GRACE) {
+            Ok(_) | Err(channel::RecvTimeoutError::Disconnected) => FetchFailure::Stopped(err),
+            Err(channel::RecvTimeoutError::Timeout) => FetchFailure::Running(err),
+        }
+    };
+
+    loop {
+        let wait = deadline
+            .saturating_duration_since(std::time::Instant::now())
+            .min(std::time::Duration::from_millis(100));
+
+        match rx.recv_timeout(wait) {
+            Ok(res) => return res.map_err(FetchFailure::Stopped),
+            Err(channel::RecvTimeoutError::Disconnected) => {
+                return Err(FetchFailure::Stopped(anyhow::anyhow!(
+                    "fetch thread exited without a result"
+                )));
+            }
+            Err(channel::RecvTimeoutError::Timeout) => {}
+        }
+
+        if gix::interrupt::IS_INTERRUPTED.load(Ordering::Relaxed) {
+            return Err(interrupt(anyhow::anyhow!("fetch was interrupted")));
+        }
+
+        if std::time::Instant::now() >= deadline {
+            return Err(interrupt(anyhow::anyhow!(
+                "fetch timed out after {duration:?}"
+            )));
+        }
+    }
+}
+
 const DIR: gix::remote::Direction = gix::remote::Direction::Fetch;
 
 fn get_fetch_time(repo: &gix::Repository) -> anyhow::Result<time::OffsetDateTime> {
@@ -220,9 +375,11 @@ fn get_fetch_time(repo: &gix::Repository) -> anyhow::Result<time::OffsetDateTime
 /// how to do it, or else gix has support for updating HEAD and checking it out
 /// when doing a clone, but if you are performing a fetch on an existing repo
 /// ...you have to do that all yourself, which is pretty tedious
-fn fetch_and_checkout(repo: &mut gix::Repository) -> anyhow::Result<()> {
+fn fetch_and_checkout(
+    repo: &mut gix::Repository,
+    should_interrupt: &AtomicBool,
+) -> anyhow::Result<()> {
     let mut progress = gix::progress::Discard;
-    let should_interrupt = &gix::interrupt::IS_INTERRUPTED;
 
     {
         let mut config = repo.config_snapshot_mut();
@@ -356,7 +513,7 @@ fn fetch_and_checkout(repo: &mut gix::Repository) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn fetch_via_gix(url: &Url, db_path: &Path) -> anyhow::Result<()> {
+fn fetch_via_gix(url: &Url, db_path: &Path, should_interrupt: &AtomicBool) -> anyhow::Result<()> {
     anyhow::ensure!(
         url.scheme() == "https" || url.scheme() == "ssh",
         "expected '{}' to be an `https` or `ssh` url",
@@ -419,7 +576,6 @@ fn fetch_via_gix(url: &Url, db_path: &Path) -> anyhow::Result<()> {
             (repo, None)
         } else {
             let mut progress = gix::progress::Discard;
-            let should_interrupt = &gix::interrupt::IS_INTERRUPTED;
 
             let (mut prep_checkout, out) = gix::prepare_clone(url.as_str(), db_path)
                 .map_err(Box::new)?
@@ -454,7 +610,7 @@ fn fetch_via_gix(url: &Url, db_path: &Path) -> anyhow::Result<()> {
         // Gix also doesn't write the FETCH_HEAD, which we rely on for staleness
         // checking, so we write it ourselves to keep identical logic between gix
         // and git/git2
-        fetch_and_checkout(&mut repo)?;
+        fetch_and_checkout(&mut repo, should_interrupt)?;
     }
 
     repo.object_cache_size_if_unset(4 * 1024 * 1024);
@@ -462,8 +618,8 @@ fn fetch_via_gix(url: &Url, db_path: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn fetch_via_cli(url: &str, db_path: &Path) -> anyhow::Result<()> {
-    use std::{fs, process::Command};
+fn fetch_via_cli(url: &str, db_path: &Path, should_interrupt: &AtomicBool) -> anyhow::Result<()> {
+    use std::{fs, io::Read, process::Command, sync::atomic::Ordering};
 
     if let Some(parent) = db_path.parent() {
         if !parent.is_dir() {
@@ -475,21 +631,55 @@ fn fetch_via_cli(url: &str, db_path: &Path) -> anyhow::Result<()> {
         anyhow::bail!("invalid directory: {db_path}");
     }
 
+    // Reads the pipe to completion on a separate thread, git blocks once a
+    // pipe's buffer is full, so both pipes need to be drained while we wait
+    // for it to exit
+    fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> std::thread::JoinHandle<Vec<u8>> {
+        std::thread::spawn(move || {
+            let mut output = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut output);
+            }
+            output
+        })
+    }
+
     let capture = |mut cmd: Command| -> anyhow::Result<String> {
         cmd.stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped());
 
-        let output = cmd
-            .spawn()
-            .context("failed to spawn git")?
-            .wait_with_output()
-            .context("failed to wait on git output")?;
+        let mut child = cmd.spawn().context("failed to spawn git")?;
+
+        let stdout = drain(child.stdout.take());
+        let stderr = drain(child.stderr.take());
+
+        // Poll rather than block so that we can kill git if the fetch is
+        // interrupted, eg. due to a timeout
+        let status = loop {
+            if let Some(status) = child.try_wait().context("failed to wait on git")? {
+                break status;
+            }
+
+            if should_interrupt.load(Ordering::Relaxed) {
+                let _ = child.kill();
+                let _ = child.wait();
+                // The readers are not joined, a subprocess spawned by git,
+                // eg. a remote helper, may still hold the pipes open
+                anyhow::bail!("git command was interrupted");
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        };
+
+        if status.success() {
+            let output = stdout.join().unwrap_or_default();
 
-        if output.status.success() {
-            String::from_utf8(output.stdout)
+            String::from_utf8(output)
                 .or_else(|_err| Ok("git command succeeded but gave non-utf8 output".to_owned()))
         } else {
-            String::from_utf8(output.stderr)
+            let output = stderr.join().unwrap_or_default();
+
+            String::from_utf8(output)
                 .map_err(|_err| anyhow::anyhow!("git command failed and gave non-utf8 output"))
         }
     };
@@ -698,9 +888,47 @@ impl<'db, 'k> Report<'db, 'k> {
 
 #[cfg(test)]
 mod test {
-    use super::url_to_db_path;
+    use super::{fetch_with_timeout, url_to_db_path, FetchFailure};
     use url::Url;
 
+    /// Ensures a fetch that never completes, nor checks if it should be
+    /// interrupted, eg. due to being stuck on a slow or unreachable host,
+    /// still times out
+    #[test]
+    fn times_out_stuck_fetches() {
+        let start = std::time::Instant::now();
+
+        let Err(FetchFailure::Running(err)) =
+            fetch_with_timeout(std::time::Duration::from_millis(200), |_| {
+                std::thread::sleep(std::time::Duration::from_secs(60));
+                Ok(())
+            })
+        else {
+            panic!("stuck fetch was not reported as still running");
+        };
+
+        assert_eq!(err.to_string(), "fetch timed out after 200ms");
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+
+        // Fetches that observe the interrupt are waited on, so that the
+        // database can safely be used as a fallback
+        let Err(FetchFailure::Stopped(err)) =
+            fetch_with_timeout(std::time::Duration::from_millis(200), |should_interrupt| {
+                while !should_interrupt.load(std::sync::atomic::Ordering::Relaxed) {
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                }
+                anyhow::bail!("interrupted")
+            })
+        else {
+            panic!("interrupted fetch was not reported as stopped");
+        };
+
+        assert_eq!(err.to_string(), "fetch timed out after 200ms");
+
+        // Fetches that complete in time are unaffected
+        assert!(fetch_with_timeout(std::time::Duration::from_secs(10), |_| Ok(())).is_ok());
+    }
+
     #[test]
     #[cfg(all(target_pointer_width = "64", target_endian = "little"))]
     fn converts_url_to_path() {
@@ -3,11 +3,20 @@ use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 use std::collections::BTreeMap;
 use tame_index::{index::ComboIndexCache, Error, IndexLocation, IndexUrl};
 
-type YankMap = Vec<(semver::Version, bool)>;
+/// A version of a crate in the index
+#[derive(Clone)]
+pub struct IndexVersion {
+    pub version: semver::Version,
+    pub yanked: bool,
+    /// The sha-256 checksum of the crate's package
+    pub checksum: String,
+}
+
+type VersionMap = Vec<IndexVersion>;
 
 #[derive(Clone)]
 pub enum Entry {
-    Map(YankMap),
+    Map(VersionMap),
     Error(String),
 }
 
@@ -94,8 +103,8 @@ impl<'k> Indices<'k> {
                                 &cargo_package_lock,
                             ) {
                                 Ok(Some(ik)) => {
-                                    let yank_map = Self::load_index_krate(ik);
-                                    Entry::Map(yank_map)
+                                    let versions = Self::load_index_krate(ik);
+                                    Entry::Map(versions)
                                 }
                                 Ok(None) => Entry::Error(
                                     "unable to locate index entry for crate".to_owned(),
@@ -120,10 +129,16 @@ impl<'k> Indices<'k> {
     }
 
     #[inline]
-    fn load_index_krate(ik: tame_index::IndexKrate) -> YankMap {
+    fn load_index_krate(ik: tame_index::IndexKrate) -> VersionMap {
         ik.versions
             .into_iter()
-            .filter_map(|iv| Some((iv.version.parse().ok()?, iv.yanked)))
+            .filter_map(|iv| {
+                Some(IndexVersion {
+                    version: iv.version.parse().ok()?,
+                    yanked: iv.yanked,
+                    checksum: iv.checksum.to_string(),
+                })
+            })
             .collect()
     }
 
@@ -143,11 +158,43 @@ impl<'k> Indices<'k> {
             Entry::Map(cache_entry) => {
                 let is_yanked = cache_entry
                     .iter()
-                    .find_map(|kv| (kv.0 == krate.version).then_some(kv.1));
+                    .find_map(|iv| (iv.version == krate.version).then_some(iv.yanked));
 
                 is_yanked.ok_or_else(|| format!("unable to locate version '{}'", krate.version))
             }
             Entry::Error(err) => Err(err.clone()),
         }
     }
+
+    /// Gets the lowest version of the crate in the index that is newer than
+    /// its current version, is not yanked, and satisfies the predicate
+    pub fn newer_version(
+        &self,
+        krate: &'k Krate,
+        pred: impl Fn(&semver::Version) -> bool,
+    ) -> Option<&semver::Version> {
+        let src = krate.source.as_ref().filter(|s| s.is_registry())?;
+        let Entry::Map(cache_entry) = self.cache.get(&(krate.name.as_str(), src))? else {
+            return None;
+        };
+
+        cache_entry
+            .iter()
+            .filter(|iv| !iv.yanked && iv.version > krate.version && pred(&iv.version))
+            .map(|iv| &iv.version)
+            .min()
+    }
+
+    /// Gets the checksum recorded in the index for the crate's version, if
+    /// its index entry could be loaded
+    pub fn checksum(&self, krate: &'k Krate) -> Option<&str> {
+        let src = krate.source.as_ref().filter(|s| s.is_registry())?;
+        let Entry::Map(cache_entry) = self.cache.get(&(krate.name.as_str(), src))? else {
+            return None;
+        };
+
+        cache_entry
+            .iter()
+            .find_map(|iv| (iv.version == krate.version).then_some(iv.checksum.as_str()))
+    }
 }
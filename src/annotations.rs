@@ -0,0 +1,81 @@
+//! Emits the freeform notes attached to crates via the top-level `annotations`
+//! config, which serve as a lightweight audit trail in the output
+
+use crate::{
+    diag::{general::Code, Check, Diagnostic, ErrorSink, FileId, Label, Pack, Severity},
+    root_cfg::Annotation,
+};
+
+#[derive(serde::Serialize)]
+pub struct ValidConfig {
+    pub file_id: FileId,
+    pub annotations: Vec<Annotation>,
+}
+
+/// Emits a note for every crate that matches an annotation, and a warning for
+/// every annotation that didn't match any crate
+///
+/// Annotations are reported as part of the `bans` check
+pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>) {
+    if ctx.cfg.annotations.is_empty() {
+        return;
+    }
+
+    let mut sink = sink.into();
+    let file_id = ctx.cfg.file_id;
+    let mut matched = vec![false; ctx.cfg.annotations.len()];
+
+    for krate in ctx.krates.krates() {
+        let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
+
+        for (annotation, matched) in ctx.cfg.annotations.iter().zip(matched.iter_mut()) {
+            if !crate::match_krate(krate, &annotation.spec) {
+                continue;
+            }
+
+            *matched = true;
+
+            pack.push(
+                Diagnostic::new(Severity::Note)
+                    .with_message(format!("crate '{krate}': {}", annotation.note.value))
+                    .with_code(Code::Annotation)
+                    .with_labels(vec![
+                        Label::primary(
+                            ctx.krate_spans.lock_id,
+                            ctx.krate_spans.lock_span(&krate.id).total,
+                        ),
+                        Label::secondary(file_id, annotation.note.span).with_message("annotation"),
+                    ]),
+            );
+        }
+
+        if !pack.is_empty() {
+            sink.push(pack);
+        }
+    }
+
+    let mut pack = Pack::new(Check::Bans);
+
+    for (annotation, _) in ctx
+        .cfg
+        .annotations
+        .iter()
+        .zip(matched)
+        .filter(|(_, matched)| !matched)
+    {
+        pack.push(
+            Diagnostic::new(Severity::Warning)
+                .with_message(format!(
+                    "annotated crate '{}' was not encountered",
+                    annotation.spec
+                ))
+                .with_code(Code::UnusedAnnotation)
+                .with_labels(vec![Label::primary(file_id, annotation.spec.name.span)
+                    .with_message("unmatched annotation")]),
+        );
+    }
+
+    if !pack.is_empty() {
+        sink.push(pack);
+    }
+}
@@ -1,6 +1,7 @@
 pub mod cfg;
 mod diags;
 mod graph;
+mod suggestions;
 
 use self::cfg::{ValidBuildConfig, ValidConfig, ValidTreeSkip};
 use crate::{
@@ -13,6 +14,7 @@ pub use diags::Code;
 use krates::cm::DependencyKind;
 use semver::VersionReq;
 use std::fmt;
+pub use suggestions::SuggestedCrates;
 
 struct ReqMatch<'vr> {
     specr: &'vr SpecAndReason,
@@ -26,7 +28,6 @@ pub(crate) struct SpecAndReason {
     pub(crate) file_id: FileId,
 }
 
-#[cfg(test)]
 impl serde::Serialize for SpecAndReason {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -198,23 +199,97 @@ pub fn check(
         file_id,
         denied,
         denied_multiple_versions,
+        deny_from: _,
         allowed,
         features,
         workspace_default_features,
         external_default_features,
-        skipped,
+        on_feature_resolution_error,
+        report_default_features,
+        report_host_target_version_skew,
+        build_graph_duplicates,
+        mut skipped,
         multiple_versions,
         multiple_versions_include_dev,
+        max_versions,
+        duplicate_sources,
         workspace_dependencies,
         highlight,
-        tree_skipped,
+        mut tree_skipped,
         wildcards,
         allow_wildcard_paths,
         build,
+        min_edition,
+        max_rust_version,
+        deny_prerelease,
+        allow_prerelease,
+        max_crates,
+        report_feature_origin,
+        suggested_crates,
     } = ctx.cfg;
 
     let mut sink = sink.into();
     let krate_spans = &ctx.krate_spans;
+
+    ctx.report_progress(crate::ProgressEvent::CheckingBans {
+        krates: ctx.krates.len(),
+    });
+
+    // Skips that have expired are removed so that the crates they would have
+    // matched are checked as normal
+    let today = time::OffsetDateTime::now_utc().date();
+    let mut expired = Pack::new(Check::Bans);
+    let mut remove_expired = |spec: &PackageSpec, expires: Option<&Spanned<time::Date>>| {
+        let Some(expires) = expires.filter(|expires| expires.value < today) else {
+            return true;
+        };
+
+        expired.push(diags::ExpiredSkip {
+            skip_cfg: CfgCoord {
+                file: file_id,
+                span: spec.name.span,
+            },
+            spec,
+            expires,
+        });
+        false
+    };
+
+    skipped.retain(|skip| {
+        remove_expired(
+            &skip.spec,
+            skip.inner.as_ref().and_then(|inner| inner.expires.as_ref()),
+        )
+    });
+    tree_skipped.retain(|skip| {
+        remove_expired(
+            &skip.spec,
+            skip.inner.as_ref().and_then(|inner| inner.expires.as_ref()),
+        )
+    });
+
+    if !expired.is_empty() {
+        sink.push(expired);
+    }
+
+    if let Some(max_crates) = &max_crates {
+        if let Some(pack) = check_max_crates(ctx.krates, max_crates, file_id) {
+            sink.push(pack);
+        }
+    }
+
+    if let Some(level) = &report_host_target_version_skew {
+        if let Some(pack) = check_host_target_version_skew(ctx.krates, level, file_id) {
+            sink.push(pack);
+        }
+    }
+
+    if let Some(level) = &build_graph_duplicates {
+        if let Some(pack) = check_build_graph_duplicates(ctx.krates, level, file_id) {
+            sink.push(pack);
+        }
+    }
+
     let (mut tree_skipper, build_diags) = TreeSkipper::build(tree_skipped, ctx.krates, file_id);
 
     if !build_diags.is_empty() {
@@ -258,8 +333,10 @@ pub fn check(
         }
     }
 
-    let (denied_ids, mut ban_wrappers) = {
+    let (denied_ids, mut ban_wrappers, deny_below, deny_features) = {
         let mut bw = BTreeMap::new();
+        let mut db = BTreeMap::new();
+        let mut df = BTreeMap::new();
 
         (
             SpecsAndReasons(
@@ -272,6 +349,16 @@ pub fn check(
                                 bw.insert(i, (0, wrappers));
                             }
 
+                            if let Some(min) = ext.deny_below {
+                                db.insert(i, min);
+                            }
+
+                            if let Some(features) =
+                                ext.deny_features_present.filter(|f| !f.is_empty())
+                            {
+                                df.insert(i, features);
+                            }
+
                             (ext.reason, ext.use_instead)
                         } else {
                             (None, None)
@@ -281,15 +368,21 @@ pub fn check(
                             spec: kb.spec,
                             reason,
                             use_instead,
-                            file_id,
+                            file_id: kb.file_id,
                         }
                     })
                     .collect(),
             ),
             BanWrappers::new(bw),
+            db,
+            df,
         )
     };
 
+    // Keep track of the `deny-below` bans whose crate was encountered at all,
+    // regardless of version, so that stale entries can be reported
+    let mut deny_below_hit: BitVec = BitVec::repeat(false, deny_below.len());
+
     let (feature_ids, features): (Vec<_>, Vec<_>) = features
         .into_iter()
         .map(|cf| {
@@ -347,7 +440,7 @@ pub fn check(
     let dmv = SpecsAndReasons(
         denied_multiple_versions
             .into_iter()
-            .map(|spec| SpecAndReason {
+            .map(|(spec, file_id)| SpecAndReason {
                 spec,
                 reason: None,
                 use_instead: None,
@@ -368,12 +461,23 @@ pub fn check(
             .collect(),
     );
 
+    // Skips that only apply to the duplicate detection don't prevent the crate
+    // from being checked otherwise
+    let skip_scopes: Vec<_> = skipped
+        .iter()
+        .map(|skip| {
+            skip.inner
+                .as_ref()
+                .map_or(cfg::SkipScope::All, |inner| inner.scope)
+        })
+        .collect();
+
     let skipped = SpecsAndReasons(
         skipped
             .into_iter()
             .map(|skip| SpecAndReason {
                 spec: skip.spec,
-                reason: skip.inner,
+                reason: skip.inner.and_then(|inner| inner.reason),
                 use_instead: None,
                 file_id,
             })
@@ -390,10 +494,15 @@ pub fn check(
             multi_detector.krates_with_dupes.push(multi_detector.name);
         }
 
-        if multi_detector.dupes.len() - skipped <= 1 {
+        let num_versions = multi_detector.dupes.len() - skipped;
+        if num_versions <= 1 {
             return;
         }
 
+        // The maximum number of versions configured for the crate, if it is
+        // what determines the lint level
+        let mut exceeded = None;
+
         let lint_level = if multi_detector.dupes.iter().any(|(kindex, skipped)| {
             if *skipped {
                 return false;
@@ -403,6 +512,18 @@ pub fn check(
             dmv.matches(krate).is_some()
         }) {
             LintLevel::Deny
+        } else if let Some(max) = max_versions
+            .iter()
+            .find(|mv| mv.name.value == multi_detector.name)
+        {
+            // The cap replaces the global lint level for the crate, so any
+            // number of versions up to it is allowed
+            if num_versions <= max.count.value {
+                return;
+            }
+
+            exceeded = Some(max);
+            max.level
         } else {
             multiple_versions
         };
@@ -451,16 +572,53 @@ pub fn check(
         }
 
         {
-            let mut diag: Diag = diags::Duplicates {
-                krate_name: multi_detector.name,
-                num_dupes: kids.len(),
-                krates_coord: KrateCoord {
-                    file: krate_spans.lock_id,
-                    span: (all_start..all_end).into(),
-                },
-                severity,
+            let krates_coord = KrateCoord {
+                file: krate_spans.lock_id,
+                span: (all_start..all_end).into(),
+            };
+
+            let mut diag: Diag = if let Some(max) = exceeded {
+                diags::TooManyVersions {
+                    krate_name: multi_detector.name,
+                    versions: kids.iter().map(|dupe| &dupe.version).collect(),
+                    max: &max.count,
+                    krates_coord,
+                    cfg_file_id: file_id,
+                    severity,
+                }
+                .into()
+            } else {
+                diags::Duplicates {
+                    krate_name: multi_detector.name,
+                    num_dupes: kids.len(),
+                    krates_coord,
+                    severity,
+                }
+                .into()
+            };
+
+            // Older versions that are semver compatible with the newest version
+            // can be unified by updating them to it
+            if ctx.suggest_fixes {
+                if let Some((newest, older)) = kids.split_last() {
+                    for dupe in older {
+                        let req = semver::Comparator {
+                            op: semver::Op::Caret,
+                            major: dupe.version.major,
+                            minor: Some(dupe.version.minor),
+                            patch: Some(dupe.version.patch),
+                            pre: dupe.version.pre.clone(),
+                        };
+
+                        if req.matches(&newest.version) {
+                            diag.diag.notes.push(format!(
+                                "Suggested fix: `cargo update -p {}@{} --precise {}`",
+                                multi_detector.name, dupe.version, newest.version
+                            ));
+                        }
+                    }
+                }
             }
-            .into();
 
             diag.graph_nodes = kids
                 .into_iter()
@@ -527,6 +685,7 @@ pub fn check(
 
     struct BuildCheckCtx {
         bypasses: parking_lot::Mutex<BitVec>,
+        allowed: parking_lot::Mutex<BitVec>,
         diag_packs: parking_lot::Mutex<std::collections::BTreeMap<usize, Pack>>,
         cargo_home: Option<crate::PathBuf>,
         build_config: ValidBuildConfig,
@@ -552,11 +711,14 @@ pub fn check(
         // if they're configured but not actually used
         let bypasses =
             parking_lot::Mutex::<BitVec>::new(BitVec::repeat(false, build_config.bypass.len()));
+        let allowed =
+            parking_lot::Mutex::<BitVec>::new(BitVec::repeat(false, build_config.allow.len()));
 
         (
             BuildCheckCtx {
                 cargo_home,
                 bypasses,
+                allowed,
                 diag_packs: parking_lot::Mutex::new(std::collections::BTreeMap::new()),
                 build_config,
             },
@@ -576,13 +738,48 @@ pub fn check(
                 // Check if the crate has been explicitly banned
                 if let Some(matches) = denied_ids.matches(krate) {
                     for rm in matches {
+                        // The crate is only banned if one or more of the
+                        // specified features are enabled
+                        let features_present = if let Some(features) = deny_features.get(&rm.index)
+                        {
+                            // The ban can't be evaluated, which is reported
+                            // so that a banned feature doesn't go unnoticed
+                            let Some(enabled) = ctx.krates.get_enabled_features(&krate.id) else {
+                                if on_feature_resolution_error != LintLevel::Allow {
+                                    pack.push(diags::UnableToCheckBannedFeatures {
+                                        krate,
+                                        ban_cfg: rm.specr,
+                                        level: on_feature_resolution_error,
+                                    });
+                                }
+                                continue;
+                            };
+
+                            let present: Vec<_> = features
+                                .iter()
+                                .filter(|feature| enabled.contains(&feature.value))
+                                .map(|feature| {
+                                    (feature, feature_enablers(ctx.krates, krate, &feature.value))
+                                })
+                                .collect();
+
+                            if present.is_empty() {
+                                continue;
+                            }
+
+                            Some(present)
+                        } else {
+                            None
+                        };
+
                         let ban_cfg = CfgCoord {
-                            file: file_id,
+                            file: rm.specr.file_id,
                             span: rm.specr.spec.name.span,
                         };
 
                         // The crate is banned, but it might be allowed if it's
                         // wrapped by one or more particular crates
+                        let mut dependents = Vec::new();
                         let is_allowed_by_wrapper = if ban_wrappers.has_wrappers(rm.index) {
                             let nid = ctx.krates.nid_for_kid(&krate.id).unwrap();
 
@@ -598,7 +795,7 @@ pub fn check(
                                             diags::BannedAllowedByWrapper {
                                                 ban_cfg: ban_cfg.clone(),
                                                 ban_exception_cfg: CfgCoord {
-                                                    file: file_id,
+                                                    file: rm.specr.file_id,
                                                     span,
                                                 },
                                                 banned_krate: krate,
@@ -619,6 +816,7 @@ pub fn check(
                                     };
 
                                 pack.push(diag);
+                                dependents.push((src.krate, is_allowed));
                                 all = all && is_allowed;
                             }
 
@@ -628,14 +826,35 @@ pub fn check(
                         };
 
                         if !is_allowed_by_wrapper {
-                            pack.push(diags::ExplicitlyBanned {
-                                krate,
-                                ban_cfg: rm.specr,
-                            });
+                            if let Some(features) = features_present {
+                                pack.push(diags::BannedFeaturesPresent {
+                                    krate,
+                                    ban_cfg: rm.specr,
+                                    features,
+                                });
+                            } else if let Some(min) = deny_below.get(&rm.index) {
+                                pack.push(diags::BelowMinimumVersion {
+                                    krate,
+                                    ban_cfg: rm.specr,
+                                    min,
+                                });
+                            } else {
+                                pack.push(diags::ExplicitlyBanned {
+                                    krate,
+                                    ban_cfg: rm.specr,
+                                    dependents,
+                                });
+                            }
                         }
                     }
                 }
 
+                for (i, index) in deny_below.keys().enumerate() {
+                    if denied_ids.0[*index].spec.name.value == krate.name {
+                        deny_below_hit.set(i, true);
+                    }
+                }
+
                 if !allowed.0.is_empty() {
                     // Since only allowing specific crates is pretty draconian,
                     // also emit which allow filters actually passed each crate
@@ -654,16 +873,76 @@ pub fn check(
                     }
                 }
 
-                let enabled_features = ctx.krates.get_enabled_features(&krate.id).unwrap();
-
-                let default_lint_level = if enabled_features.contains("default") {
-                    if ctx.krates.workspace_members().any(|n| {
-                        if let krates::Node::Krate { id, .. } = n {
-                            id == &krate.id
-                        } else {
-                            false
+                if let Some(me) = &min_edition {
+                    if me.level != LintLevel::Allow
+                        && !me.allow.iter().any(|spec| crate::match_krate(krate, spec))
+                    {
+                        // Editions we don't know about are always newer than
+                        // the configured minimum
+                        if let Ok(edition) = krate.edition.as_str().parse::<cfg::Edition>() {
+                            if edition < me.edition.value {
+                                pack.push(diags::OutdatedEdition {
+                                    krate,
+                                    edition,
+                                    min_edition: &me.edition,
+                                    level: me.level,
+                                    file_id,
+                                });
+                            }
                         }
-                    }) {
+                    }
+                }
+
+                // Crates that don't declare a rust-version can't be checked
+                if let Some((mrv, rust_version)) = max_rust_version
+                    .as_ref()
+                    .filter(|mrv| mrv.level != LintLevel::Allow)
+                    .zip(krate.rust_version.as_ref())
+                {
+                    if *rust_version > mrv.version.value.0 {
+                        pack.push(diags::RustVersionTooNew {
+                            krate,
+                            rust_version,
+                            max_rust_version: &mrv.version,
+                            level: mrv.level,
+                            file_id,
+                        });
+                    }
+                }
+
+                if deny_prerelease != LintLevel::Allow
+                    && !krate.version.pre.is_empty()
+                    && !allow_prerelease
+                        .iter()
+                        .any(|spec| crate::match_krate(krate, spec))
+                {
+                    pack.push(diags::PrereleaseVersion {
+                        krate,
+                        level: deny_prerelease,
+                    });
+                }
+
+                // The enabled features can fail to be resolved in unusual
+                // graphs, in which case the features can't be evaluated
+                let enabled_features = ctx.krates.get_enabled_features(&krate.id);
+                if enabled_features.is_none() && on_feature_resolution_error != LintLevel::Allow {
+                    pack.push(diags::UnableToGetDefaultFeatures {
+                        krate,
+                        level: on_feature_resolution_error,
+                    });
+                }
+
+                let default_enabled = enabled_features.is_some_and(|ef| ef.contains("default"));
+                let is_workspace_member = ctx.krates.workspace_members().any(|n| {
+                    if let krates::Node::Krate { id, .. } = n {
+                        id == &krate.id
+                    } else {
+                        false
+                    }
+                });
+
+                let default_lint_level = if default_enabled {
+                    if is_workspace_member {
                         workspace_default_features.as_ref()
                     } else {
                         external_default_features.as_ref()
@@ -682,8 +961,43 @@ pub fn check(
                     }
                 }
 
+                // Report every external crate with default features enabled,
+                // unless it has already been warned about above
+                if let Some(level) = &report_default_features {
+                    if default_enabled
+                        && !is_workspace_member
+                        && !default_lint_level.is_some_and(|ll| ll.value == LintLevel::Warn)
+                    {
+                        pack.push(diags::DefaultFeatureReported {
+                            krate,
+                            level,
+                            file_id,
+                        });
+                    }
+                }
+
+                // Report what enabled each of the crate's features
+                if let Some((spec, enabled_features)) = report_feature_origin
+                    .iter()
+                    .find(|spec| crate::match_krate(krate, spec))
+                    .zip(enabled_features)
+                {
+                    let enabled: Vec<_> = enabled_features.iter().map(|ef| ef.as_str()).collect();
+
+                    pack.push(diags::FeatureOrigin {
+                        krate,
+                        features: feature_origins(ctx.krates, krate, &enabled),
+                        report_cfg: CfgCoord {
+                            file: file_id,
+                            span: spec.name.span,
+                        },
+                    });
+                }
+
                 // Check if the crate has had features denied/allowed or are required to be exact
-                if let Some(matches) = feature_ids.matches(krate) {
+                if let Some((matches, enabled_features)) =
+                    feature_ids.matches(krate).zip(enabled_features)
+                {
                     for rm in matches {
                         let feature_bans = &features[rm.index];
 
@@ -870,7 +1184,11 @@ pub fn check(
                 }
 
                 if should_add_dupe(&krate.id) {
-                    if let Some(matches) = skipped.matches(krate) {
+                    let skip_matches = skipped.matches(krate);
+                    let is_skipped = skip_matches.is_some();
+                    let mut skip_all = false;
+
+                    if let Some(matches) = skip_matches {
                         if multi_detector.name != krate.name {
                             report_duplicates(&mut multi_detector, &mut sink);
 
@@ -881,6 +1199,8 @@ pub fn check(
                         multi_detector.dupes.push((i, true));
 
                         for rm in matches {
+                            skip_all |= skip_scopes[rm.index] == cfg::SkipScope::All;
+
                             pack.push(diags::Skipped {
                                 krate,
                                 skip_cfg: rm.specr,
@@ -891,15 +1211,19 @@ pub fn check(
                             // their dependency graph changes over time
                             skip_hit.as_mut_bitslice().set(rm.index, true);
                         }
-                    } else if !tree_skipper.matches(krate, &mut pack) {
-                        if multi_detector.name != krate.name {
-                            report_duplicates(&mut multi_detector, &mut sink);
+                    }
 
-                            multi_detector.name = &krate.name;
-                            multi_detector.dupes.clear();
-                        }
+                    if !skip_all && !tree_skipper.matches(krate, &mut pack) {
+                        if !is_skipped {
+                            if multi_detector.name != krate.name {
+                                report_duplicates(&mut multi_detector, &mut sink);
+
+                                multi_detector.name = &krate.name;
+                                multi_detector.dupes.clear();
+                            }
 
-                        multi_detector.dupes.push((i, false));
+                            multi_detector.dupes.push((i, false));
+                        }
 
                         'wildcards: {
                             if wildcards != LintLevel::Allow && !krate.is_git_source() {
@@ -1009,6 +1333,7 @@ pub fn check(
                         build_ctx.cargo_home.as_deref(),
                         krate,
                         ctx.krates,
+                        &build_ctx.allowed,
                         &mut pack,
                     ) {
                         build_ctx.bypasses.lock().set(bcc, true);
@@ -1056,6 +1381,21 @@ pub fn check(
             });
         }
 
+        for spec in bcc
+            .allowed
+            .into_inner()
+            .into_iter()
+            .zip(bcc.build_config.allow.iter())
+            .filter_map(|(hit, spec)| if !hit { Some(spec) } else { None })
+        {
+            pack.push(diags::UnmatchedBuildAllow {
+                allow_cfg: CfgCoord {
+                    file: file_id,
+                    span: spec.name.span,
+                },
+            });
+        }
+
         sink.push(pack);
     }
 
@@ -1064,6 +1404,15 @@ pub fn check(
     }
 
     if let Some(ws_deps) = workspace_dependencies {
+        if ws_deps.require_workspace_table != LintLevel::Allow {
+            check_workspace_table(
+                ctx.krates,
+                krate_spans,
+                ws_deps.require_workspace_table,
+                &mut sink,
+            );
+        }
+
         if ws_deps.unused != LintLevel::Allow {
             if let Some(id) = krate_spans
                 .workspace_id
@@ -1078,11 +1427,47 @@ pub fn check(
         }
     }
 
+    if let Some(severity) = match duplicate_sources {
+        LintLevel::Warn => Some(Severity::Warning),
+        LintLevel::Deny => Some(Severity::Error),
+        LintLevel::Allow => None,
+    } {
+        // Crates are sorted by name, so every crate with the same name, but
+        // from a different source, is adjacent, regardless of version
+        let krates: Vec<_> = ctx.krates.krates().collect();
+
+        for group in krates.chunk_by(|a, b| a.name == b.name) {
+            if group.iter().all(|krate| krate.source == group[0].source) {
+                continue;
+            }
+
+            let (start, end) = group.iter().fold((usize::MAX, 0), |(start, end), krate| {
+                let span = &krate_spans.lock_span(&krate.id).total;
+                (start.min(span.start), end.max(span.end))
+            });
+
+            let mut pack = Pack::new(Check::Bans);
+            pack.push(diags::DuplicateSources {
+                krate_name: &group[0].name,
+                krates: group.to_vec(),
+                krates_coord: KrateCoord {
+                    file: krate_spans.lock_id,
+                    span: (start..end).into(),
+                },
+                severity,
+            });
+            sink.push(pack);
+        }
+    }
+
     let mut pack = Pack::new(Check::Bans);
 
     for (hit, skip) in skip_hit.into_iter().zip(skipped.0.into_iter()) {
         if !hit {
-            pack.push(diags::UnmatchedSkip { skip_cfg: &skip });
+            pack.push(diags::UnmatchedSkip {
+                skip_cfg: &skip,
+                suggest_fix: ctx.suggest_fixes,
+            });
         } else if multi_detector
             .krates_with_dupes
             .binary_search(&skip.spec.name.value.as_str())
@@ -1092,21 +1477,52 @@ pub fn check(
         }
     }
 
-    for wrapper in ban_wrappers
+    for (hit, (index, min)) in deny_below_hit.into_iter().zip(deny_below.iter()) {
+        if !hit {
+            pack.push(diags::UnmatchedDenyBelow {
+                ban_cfg: &denied_ids.0[*index],
+                min,
+            });
+        }
+    }
+
+    if let Some(suggested_crates) = &suggested_crates {
+        for ban in &denied_ids.0 {
+            if let Some(use_instead) = ban
+                .use_instead
+                .as_ref()
+                .filter(|ui| suggested_crates.exists(&ui.value) == Some(false))
+            {
+                pack.push(diags::UnknownSuggestion {
+                    ban_cfg: ban,
+                    use_instead,
+                });
+            }
+        }
+    }
+
+    for (index, wrapper) in ban_wrappers
         .hits
         .into_iter()
-        .zip(ban_wrappers.map.into_values().flat_map(|(_, w)| w))
+        .zip(
+            ban_wrappers
+                .map
+                .into_iter()
+                .flat_map(|(i, (_, w))| w.into_iter().map(move |w| (i, w))),
+        )
         .filter_map(|(hit, wrapper)| (!hit).then_some(wrapper))
     {
         pack.push(diags::UnusedWrapper {
             wrapper_cfg: CfgCoord {
-                file: file_id,
+                file: denied_ids.0[index].file_id,
                 span: wrapper.span,
             },
         });
     }
 
     sink.push(pack);
+
+    ctx.report_progress(crate::ProgressEvent::BansChecked);
 }
 
 pub fn check_build(
@@ -1115,10 +1531,34 @@ pub fn check_build(
     home: Option<&crate::Path>,
     krate: &Krate,
     krates: &Krates,
+    allowed: &parking_lot::Mutex<BitVec>,
     pack: &mut Pack,
 ) -> Option<usize> {
     use krates::cm::TargetKind;
 
+    // Crates that have been vetted are not checked at all, but are only
+    // considered used if they actually execute at build time
+    if let Some(i) = config
+        .allow
+        .iter()
+        .position(|spec| crate::match_krate(krate, spec))
+    {
+        if executes_at_buildtime(krate) {
+            allowed.lock().set(i, true);
+        }
+
+        return None;
+    }
+
+    if let Some((level, lib)) = config.links.as_ref().zip(krate.links.as_deref()) {
+        pack.push(diags::LinksNativeLibrary {
+            krate,
+            lib,
+            level,
+            file_id,
+        });
+    }
+
     let build_script_allowed = if let Some(allow_build_scripts) = &config.allow_build_scripts {
         let has_build_script = krate
             .targets
@@ -1595,6 +2035,214 @@ fn validate_file_checksum(path: &crate::Path, expected: &cfg::Checksum) -> anyho
     Ok(())
 }
 
+/// Checks if the graph has more crates than the configured maximum, reporting
+/// the crates that exceed it, ordered by their distance from the workspace
+fn check_max_crates(
+    krates: &Krates,
+    max_crates: &cfg::MaxCratesConfig,
+    file_id: FileId,
+) -> Option<Pack> {
+    let count = krates.len();
+    if max_crates.level == LintLevel::Allow || count <= max_crates.count.value {
+        return None;
+    }
+
+    // Walk the graph breadth first from the workspace members so that the
+    // crates furthest from the workspace are the ones that are reported
+    let mut depths = vec![usize::MAX; count];
+    let mut pending: std::collections::VecDeque<_> = krates
+        .workspace_members()
+        .filter_map(|wsm| {
+            let krates::Node::Krate { id, .. } = wsm else {
+                return None;
+            };
+            krates.nid_for_kid(id)
+        })
+        .map(|nid| (nid, 0))
+        .collect();
+
+    while let Some((nid, depth)) = pending.pop_front() {
+        let Some(d) = depths.get_mut(nid.index()) else {
+            continue;
+        };
+        if *d <= depth {
+            continue;
+        }
+        *d = depth;
+
+        for dep in krates.direct_dependencies(nid) {
+            pending.push_back((dep.node_id, depth + 1));
+        }
+    }
+
+    let mut ordered: Vec<_> = krates
+        .krates()
+        .enumerate()
+        .map(|(i, krate)| (depths[i], krate))
+        .collect();
+    ordered.sort_by(|(ad, a), (bd, b)| {
+        ad.cmp(bd)
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.version.cmp(&b.version))
+    });
+
+    let mut pack = Pack::new(Check::Bans);
+    pack.push(diags::TooManyCrates {
+        count,
+        max: &max_crates.count,
+        level: max_crates.level,
+        file_id,
+        excess: ordered[max_crates.count.value..]
+            .iter()
+            .map(|(_, krate)| *krate)
+            .collect(),
+    });
+
+    Some(pack)
+}
+
+/// Determines which crates are used by the target, and which are used by the
+/// host, ie. build dependencies and proc-macros and their dependencies
+///
+/// Note that the graph is resolved with features unified across both, so a
+/// crate is only considered to be in the host graph if it can't be reached
+/// from a workspace member without going through a build dependency or
+/// proc-macro, ie. a crate can be in both
+fn host_and_target(krates: &Krates) -> (Vec<bool>, Vec<bool>) {
+    use crate::DepKind;
+    use krates::{cm::TargetKind, Edge};
+
+    let is_proc_macro = |krate: &Krate| {
+        krate
+            .targets
+            .iter()
+            .any(|t| t.kind.contains(&TargetKind::ProcMacro))
+    };
+
+    let graph = krates.graph();
+    let mut host = vec![false; krates.len()];
+    let mut target = vec![false; krates.len()];
+
+    let mut pending: Vec<_> = krates
+        .workspace_members()
+        .filter_map(|wsm| {
+            let krates::Node::Krate { id, krate, .. } = wsm else {
+                return None;
+            };
+            Some((krates.nid_for_kid(id)?, is_proc_macro(krate)))
+        })
+        .collect();
+
+    while let Some((nid, is_host)) = pending.pop() {
+        let visited = if is_host { &mut host } else { &mut target };
+        let Some(visited) = visited.get_mut(nid.index()) else {
+            continue;
+        };
+        if *visited {
+            continue;
+        }
+        *visited = true;
+
+        for dep in krates.direct_dependencies(nid) {
+            let is_build = matches!(
+                graph[dep.edge_id],
+                Edge::Dep {
+                    kind: DepKind::Build,
+                    ..
+                } | Edge::DepFeature {
+                    kind: DepKind::Build,
+                    ..
+                }
+            );
+
+            pending.push((dep.node_id, is_host || is_build || is_proc_macro(dep.krate)));
+        }
+    }
+
+    (host, target)
+}
+
+/// Reports crates that are resolved to different versions for the host, ie.
+/// build dependencies and proc-macros and their dependencies, than for the
+/// target
+fn check_host_target_version_skew(
+    krates: &Krates,
+    level: &Spanned<cfg::ReportLevel>,
+    file_id: FileId,
+) -> Option<Pack> {
+    let (host, target) = host_and_target(krates);
+
+    // Gather the versions of each crate that are used by the target, and the
+    // versions that are only used by the host
+    let mut versions = std::collections::BTreeMap::<_, (Vec<_>, Vec<_>)>::new();
+    for (i, krate) in krates.krates().enumerate() {
+        if target[i] {
+            versions
+                .entry(krate.name.as_str())
+                .or_default()
+                .0
+                .push(krate);
+        } else if host[i] {
+            versions
+                .entry(krate.name.as_str())
+                .or_default()
+                .1
+                .push(krate);
+        }
+    }
+
+    let mut pack = Pack::new(Check::Bans);
+    for (name, (target, host)) in versions {
+        if target.is_empty() || host.is_empty() {
+            continue;
+        }
+
+        pack.push(diags::HostTargetVersionSkew {
+            name,
+            target,
+            host,
+            level,
+            file_id,
+        });
+    }
+
+    (!pack.is_empty()).then_some(pack)
+}
+
+/// Reports crates that are only used by the host, ie. build dependencies and
+/// proc-macros and their dependencies, that have multiple versions, as each
+/// version adds to compile times without affecting the final artifact
+fn check_build_graph_duplicates(
+    krates: &Krates,
+    level: &Spanned<cfg::ReportLevel>,
+    file_id: FileId,
+) -> Option<Pack> {
+    let (host, target) = host_and_target(krates);
+
+    let mut versions = std::collections::BTreeMap::<_, Vec<_>>::new();
+    for (i, krate) in krates.krates().enumerate() {
+        if host[i] && !target[i] {
+            versions.entry(krate.name.as_str()).or_default().push(krate);
+        }
+    }
+
+    let mut pack = Pack::new(Check::Bans);
+    for (name, dupes) in versions {
+        if dupes.len() < 2 {
+            continue;
+        }
+
+        pack.push(diags::BuildGraphDuplicate {
+            name,
+            dupes,
+            level,
+            file_id,
+        });
+    }
+
+    (!pack.is_empty()).then_some(pack)
+}
+
 fn check_workspace_duplicates(
     krates: &Krates,
     krate_spans: &crate::diag::KrateSpans<'_>,
@@ -1728,3 +2376,173 @@ fn check_workspace_duplicates(
         diags.push(pack);
     }
 }
+
+/// Finds dependencies of workspace members that are declared with an explicit
+/// version even though the same crate is declared in `[workspace.dependencies]`
+fn check_workspace_table(
+    krates: &Krates,
+    krate_spans: &crate::diag::KrateSpans<'_>,
+    level: LintLevel,
+    sink: &mut diag::ErrorSink,
+) {
+    use crate::diag::Label;
+
+    let Some(ws_id) = krate_spans.workspace_id else {
+        return;
+    };
+
+    for wsm in krates.workspace_members() {
+        let krates::Node::Krate { id, krate, .. } = wsm else {
+            continue; /* unreachable */
+        };
+
+        let Some(man) = krate_spans.manifest(id) else {
+            continue;
+        };
+
+        let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
+
+        for mdep in man.deps(true) {
+            if mdep.workspace.is_some() {
+                continue;
+            }
+
+            let Some((version, ws_span)) = mdep
+                .version_req
+                .as_ref()
+                .zip(krate_spans.workspace_span(&mdep.krate.id))
+            else {
+                continue;
+            };
+
+            pack.push(diags::WorkspaceTableNotUsed {
+                member: krate,
+                dep: mdep.krate,
+                version_label: Label::primary(man.id, version.span)
+                    .with_message("explicit version"),
+                workspace_label: Label::secondary(ws_id, ws_span.key)
+                    .with_message("workspace dependency"),
+                level,
+            });
+        }
+
+        if !pack.is_empty() {
+            sink.push(pack);
+        }
+    }
+}
+
+/// Gets what enabled each of the enabled features of a crate, either one of its
+/// direct dependents, or another of its own enabled features
+fn feature_origins<'k>(
+    krates: &'k Krates,
+    krate: &'k Krate,
+    enabled: &[&'k str],
+) -> Vec<(&'k str, Vec<diags::FeatureEnabler<'k>>)> {
+    enabled
+        .iter()
+        .map(|&feature| {
+            let mut enablers: Vec<_> = feature_enablers(krates, krate, feature)
+                .into_iter()
+                .map(diags::FeatureEnabler::Dependent)
+                .collect();
+
+            // Features can also enable optional dependencies of the same name
+            let dep_feature = format!("dep:{feature}");
+            enablers.extend(
+                enabled
+                    .iter()
+                    .filter(|&&ef| {
+                        ef != feature
+                            && krate.features.get(ef).is_some_and(|sub| {
+                                sub.iter().any(|sf| *sf == feature || *sf == dep_feature)
+                            })
+                    })
+                    .map(|&ef| diags::FeatureEnabler::Feature(ef)),
+            );
+
+            (feature, enablers)
+        })
+        .collect()
+}
+
+/// Whether the feature is the crate's `default` feature, or is enabled by it,
+/// either directly or via another of its features
+fn is_default_feature(krate: &Krate, feature: &str) -> bool {
+    let mut stack = vec!["default"];
+    let mut visited = std::collections::BTreeSet::new();
+
+    while let Some(current) = stack.pop() {
+        if current == feature {
+            return true;
+        }
+
+        if !visited.insert(current) {
+            continue;
+        }
+
+        // Only plain feature names enable other features of the same crate,
+        // `dep:` and `<dep>/<feature>` entries don't
+        if let Some(sub) = krate.features.get(current) {
+            stack.extend(
+                sub.iter()
+                    .map(String::as_str)
+                    .filter(|sf| !sf.contains(':') && !sf.contains('/')),
+            );
+        }
+    }
+
+    false
+}
+
+/// Gets the direct dependents of a crate that enable the specified feature on
+/// it, either directly in their dependency declaration, by not disabling its
+/// default features, or via one of their own enabled features
+fn feature_enablers<'k>(krates: &'k Krates, krate: &Krate, feature: &str) -> Vec<&'k Krate> {
+    let Some(nid) = krates.nid_for_kid(&krate.id) else {
+        return Vec::new();
+    };
+
+    let is_default = is_default_feature(krate, feature);
+    let mut enablers: Vec<&Krate> = Vec::new();
+
+    for dd in krates.direct_dependents(nid) {
+        let parent = dd.krate;
+        if enablers.iter().any(|e| e.id == parent.id) {
+            continue;
+        }
+
+        let enables = parent
+            .deps
+            .iter()
+            .filter(|dep| dep.name == krate.name && dep.req.matches(&krate.version))
+            .any(|dep| {
+                if dep.features.iter().any(|f| f == feature)
+                    || (is_default && dep.uses_default_features)
+                {
+                    return true;
+                }
+
+                let Some(parent_features) = krates.get_enabled_features(&parent.id) else {
+                    return false;
+                };
+
+                let dep_name = dep.rename.as_deref().unwrap_or(&dep.name);
+                let strong = format!("{dep_name}/{feature}");
+                let weak = format!("{dep_name}?/{feature}");
+
+                parent_features.iter().any(|pf| {
+                    parent
+                        .features
+                        .get(pf)
+                        .is_some_and(|sub| sub.iter().any(|sf| *sf == strong || *sf == weak))
+                })
+            });
+
+        if enables {
+            enablers.push(parent);
+        }
+    }
+
+    enablers
+}
@@ -3,6 +3,7 @@ use crate::{
     diag::{Diagnostic, FileId, Label},
     LintLevel, Spanned,
 };
+use std::fmt;
 use toml_span::{de_helpers::TableHelper, value::Value, DeserError, Deserialize};
 
 #[cfg_attr(test, derive(Debug, PartialEq, Eq))]
@@ -18,6 +19,10 @@ pub struct CrateBanExtended {
     /// The crate to use instead of the banned crate, could be just the crate name
     /// or a URL
     pub use_instead: Option<Spanned<String>>,
+    /// Bans every version of the crate below this version
+    pub deny_below: Option<Spanned<semver::Version>>,
+    /// Only bans the crate if one or more of these features are enabled
+    pub deny_features_present: Option<Spanned<Vec<Spanned<String>>>>,
 }
 
 impl<'de> Deserialize<'de> for CrateBanExtended {
@@ -28,6 +33,21 @@ impl<'de> Deserialize<'de> for CrateBanExtended {
         let deny_multiple_versions = th.optional("deny-multiple-versions");
         let reason = th.optional_s("reason");
         let use_instead = th.optional("use-instead");
+        let deny_below =
+            th.optional::<Spanned<String>>("deny-below")
+                .and_then(|db| match db.value.parse() {
+                    Ok(version) => Some(Spanned::with_span(version, db.span)),
+                    Err(err) => {
+                        th.errors.push(toml_span::Error::from((
+                            toml_span::ErrorKind::Custom(
+                                format!("'{}' is not a valid version: {err}", db.value).into(),
+                            ),
+                            db.span,
+                        )));
+                        None
+                    }
+                });
+        let deny_features_present = th.optional("deny-features-present");
         th.finalize(None)?;
 
         Ok(Self {
@@ -35,6 +55,8 @@ impl<'de> Deserialize<'de> for CrateBanExtended {
             deny_multiple_versions,
             reason: reason.map(Reason::from),
             use_instead,
+            deny_below,
+            deny_features_present,
         })
     }
 }
@@ -75,8 +97,9 @@ impl<'de> Deserialize<'de> for CrateFeatures {
     }
 }
 
-#[cfg_attr(test, derive(serde::Serialize))]
-#[derive(PartialEq, Eq, Copy, Clone, Default, strum::VariantArray, strum::VariantNames)]
+#[derive(
+    serde::Serialize, PartialEq, Eq, Copy, Clone, Default, strum::VariantArray, strum::VariantNames,
+)]
 #[strum(serialize_all = "kebab-case")]
 pub enum GraphHighlight {
     /// Highlights the path to a duplicate dependency with the fewest number
@@ -91,6 +114,35 @@ pub enum GraphHighlight {
 
 crate::enum_deser!(GraphHighlight);
 
+/// The severity of the diagnostics emitted by
+/// [`Config::report_default_features`],
+/// [`Config::report_host_target_version_skew`],
+/// [`Config::build_graph_duplicates`], and [`BuildConfig::links`]
+#[derive(serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, strum::VariantArray, strum::VariantNames)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ReportLevel {
+    /// A note is emitted, which doesn't affect the outcome of the check
+    Note,
+    /// A warning is emitted
+    Warn,
+    /// An error is emitted, failing the check
+    Deny,
+}
+
+crate::enum_deser!(ReportLevel);
+
+impl From<ReportLevel> for crate::diag::Severity {
+    fn from(rl: ReportLevel) -> Self {
+        match rl {
+            ReportLevel::Note => Self::Note,
+            ReportLevel::Warn => Self::Warning,
+            ReportLevel::Deny => Self::Error,
+        }
+    }
+}
+
 impl GraphHighlight {
     #[inline]
     pub(crate) fn simplest(self) -> bool {
@@ -103,8 +155,7 @@ impl GraphHighlight {
     }
 }
 
-#[derive(Clone)]
-#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Checksum(pub [u8; 32]);
 
 #[cfg_attr(test, derive(Debug, PartialEq, Eq))]
@@ -165,12 +216,9 @@ impl<'de> Deserialize<'de> for Checksum {
     }
 }
 
-#[cfg(test)]
-impl serde::Serialize for Checksum {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
+impl Checksum {
+    /// Encodes the checksum as a lowercase hex string
+    fn to_hex(&self) -> [u8; 64] {
         let mut hexs = [0; 64];
 
         const CHARS: &[u8] = b"0123456789abcdef";
@@ -181,12 +229,27 @@ impl serde::Serialize for Checksum {
             hexs[i + 1] = CHARS[(byte & 0xf) as usize];
         }
 
-        serializer.serialize_str(std::str::from_utf8(&hexs).unwrap())
+        hexs
     }
 }
 
-#[derive(Clone)]
-#[cfg_attr(test, derive(PartialEq, Eq, serde::Serialize))]
+impl std::fmt::Display for Checksum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(std::str::from_utf8(&self.to_hex()).unwrap())
+    }
+}
+
+impl serde::Serialize for Checksum {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(std::str::from_utf8(&self.to_hex()).unwrap())
+    }
+}
+
+#[derive(Clone, serde::Serialize)]
+#[cfg_attr(test, derive(PartialEq, Eq))]
 pub struct BypassPath {
     /// The crate-relative path to the executable
     pub path: Spanned<crate::PathBuf>,
@@ -252,6 +315,9 @@ pub struct BuildConfig {
     /// List of crates that are allowed to have build scripts. If this is set,
     /// any crates with a build script that aren't listed here will be banned
     pub allow_build_scripts: Option<Vec<PackageSpec>>,
+    /// Crates with build scripts or that are proc macros which have been
+    /// vetted, and are not checked at all
+    pub allow: Vec<PackageSpec>,
     /// Lint level for when executables are detected within crates with build
     /// scripts or are proc macros, or are a dependency of either of them
     pub executables: LintLevel,
@@ -271,12 +337,16 @@ pub struct BuildConfig {
     pub include_workspace: bool,
     /// If true, archive files are counted as native executables
     pub include_archives: bool,
+    /// If set, crates that declare a `links` key, ie. link a native library,
+    /// are reported at the specified level
+    pub links: Option<Spanned<ReportLevel>>,
 }
 
 impl<'de> Deserialize<'de> for BuildConfig {
     fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
         let mut th = TableHelper::new(value)?;
         let allow_build_scripts = th.optional("allow-build-scripts");
+        let allow = th.optional("allow").unwrap_or_default();
         let executables = th.optional("executables").unwrap_or(LintLevel::Deny);
         let interpreted = th.optional("interpreted").unwrap_or(LintLevel::Allow);
         let script_extensions = th.optional("script-extensions");
@@ -285,10 +355,12 @@ impl<'de> Deserialize<'de> for BuildConfig {
         let include_dependencies = th.optional("include-dependencies").unwrap_or_default();
         let include_workspace = th.optional("include-workspace").unwrap_or_default();
         let include_archives = th.optional("include-archives").unwrap_or_default();
+        let links = th.optional("links");
         th.finalize(None)?;
 
         Ok(Self {
             allow_build_scripts,
+            allow,
             executables,
             interpreted,
             script_extensions,
@@ -297,16 +369,66 @@ impl<'de> Deserialize<'de> for BuildConfig {
             include_dependencies,
             include_workspace,
             include_archives,
+            links,
         })
     }
 }
 
-#[derive(Clone)]
-#[cfg_attr(test, derive(Debug, PartialEq, Eq, serde::Serialize))]
+/// What a [`CrateSkip`] skips the crate for
+#[derive(serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default, strum::VariantArray, strum::VariantNames)]
+#[strum(serialize_all = "kebab-case")]
+pub enum SkipScope {
+    /// The crate is only skipped when detecting duplicate versions, all other
+    /// checks, eg. wildcards, still apply to it
+    Duplicates,
+    /// The crate is skipped for all checks that consult the skip list
+    #[default]
+    All,
+}
+
+crate::enum_deser!(SkipScope);
+
+#[derive(Clone, serde::Serialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct SkipExtended {
+    /// Reason the crate is being skipped
+    pub reason: Option<Reason>,
+    /// The skip is ignored after this date
+    pub expires: Option<Spanned<time::Date>>,
+    /// What the crate is skipped for
+    pub scope: SkipScope,
+}
+
+impl<'de> Deserialize<'de> for SkipExtended {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let reason = if value.has_key("reason") {
+            Some(Reason::deserialize(value)?)
+        } else {
+            None
+        };
+
+        let mut th = TableHelper::new(value)?;
+        let expires = crate::cfg::take_date(&mut th, "expires");
+        let scope = th.optional("scope").unwrap_or_default();
+        th.finalize(None)?;
+        Ok(Self {
+            reason,
+            expires,
+            scope,
+        })
+    }
+}
+
+#[derive(Clone, serde::Serialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 pub struct TreeSkipExtended {
     pub depth: Option<usize>,
     /// Reason the tree is being skipped
     pub reason: Option<Reason>,
+    /// The skip is ignored after this date
+    pub expires: Option<Spanned<time::Date>>,
 }
 
 impl<'de> Deserialize<'de> for TreeSkipExtended {
@@ -319,17 +441,22 @@ impl<'de> Deserialize<'de> for TreeSkipExtended {
 
         let mut th = TableHelper::new(value)?;
         let depth = th.optional("depth");
+        let expires = crate::cfg::take_date(&mut th, "expires");
         th.finalize(None)?;
-        Ok(Self { depth, reason })
+        Ok(Self {
+            depth,
+            reason,
+            expires,
+        })
     }
 }
 
 pub type CrateBan = PackageSpecOrExtended<CrateBanExtended>;
 pub type CrateAllow = PackageSpecOrExtended<Reason>;
-pub type CrateSkip = PackageSpecOrExtended<Reason>;
+pub type CrateSkip = PackageSpecOrExtended<SkipExtended>;
 pub type TreeSkip = PackageSpecOrExtended<TreeSkipExtended>;
 
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(serde::Serialize)]
 pub struct WorkspaceDepsConfig {
     /// How to handle workspace dependencies on the same crate that aren't declared
     /// in `[workspace.dependencies]`
@@ -338,6 +465,9 @@ pub struct WorkspaceDepsConfig {
     pub include_path_dependencies: bool,
     /// How to handle [`workspace.dependencies`] that are not used
     pub unused: LintLevel,
+    /// How to handle dependencies of workspace members that declare a version
+    /// rather than using the crate's [`workspace.dependencies`] declaration
+    pub require_workspace_table: LintLevel,
 }
 
 impl<'de> Deserialize<'de> for WorkspaceDepsConfig {
@@ -347,6 +477,9 @@ impl<'de> Deserialize<'de> for WorkspaceDepsConfig {
         let duplicates = th.optional("duplicates").unwrap_or(LintLevel::Deny);
         let include_path_dependencies = th.optional("include-path-dependencies").unwrap_or(true);
         let unused = th.optional("unused").unwrap_or(LintLevel::Deny);
+        let require_workspace_table = th
+            .optional("require-workspace-table")
+            .unwrap_or(LintLevel::Allow);
 
         th.finalize(None)?;
 
@@ -354,19 +487,211 @@ impl<'de> Deserialize<'de> for WorkspaceDepsConfig {
             duplicates,
             include_path_dependencies,
             unused,
+            require_workspace_table,
+        })
+    }
+}
+
+/// A Rust edition, eg. `2018`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+pub struct Edition(pub u16);
+
+impl Edition {
+    /// The editions that have been released
+    const KNOWN: &'static [u16] = &[2015, 2018, 2021, 2024];
+}
+
+impl std::str::FromStr for Edition {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let year = s.parse().map_err(|_err| ())?;
+
+        if Self::KNOWN.contains(&year) {
+            Ok(Self(year))
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Edition {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let val = value.take_string(Some("a Rust edition"))?;
+
+        val.parse().map_err(|()| {
+            toml_span::Error::from((
+                toml_span::ErrorKind::Custom(
+                    format!("'{val}' is not a known Rust edition, expected one of 2015, 2018, 2021, or 2024").into(),
+                ),
+                value.span,
+            ))
+            .into()
+        })
+    }
+}
+
+impl fmt::Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct MinEditionConfig {
+    /// The minimum edition that crates are expected to use
+    pub edition: Spanned<Edition>,
+    /// How to handle crates that use an older edition
+    pub level: LintLevel,
+    /// Crates that are exempt from the minimum edition
+    pub allow: Vec<PackageSpec>,
+}
+
+impl<'de> Deserialize<'de> for MinEditionConfig {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+
+        let edition = th.required_s("edition")?;
+        let level = th.optional("level").unwrap_or(LintLevel::Warn);
+        let allow = th.optional("allow").unwrap_or_default();
+
+        th.finalize(None)?;
+
+        Ok(Self {
+            edition,
+            level,
+            allow,
+        })
+    }
+}
+
+/// A Rust version, eg. `1.70`, the patch version is optional
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+pub struct RustVersion(pub semver::Version);
+
+impl std::str::FromStr for RustVersion {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut components = s.split('.').map(|c| c.parse::<u64>().map_err(|_err| ()));
+
+        let major = components.next().ok_or(())??;
+        let minor = components.next().ok_or(())??;
+        let patch = components.next().transpose()?.unwrap_or_default();
+
+        if components.next().is_some() {
+            return Err(());
+        }
+
+        Ok(Self(semver::Version::new(major, minor, patch)))
+    }
+}
+
+impl<'de> Deserialize<'de> for RustVersion {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let val = value.take_string(Some("a Rust version"))?;
+
+        val.parse().map_err(|()| {
+            toml_span::Error::from((
+                toml_span::ErrorKind::Custom(
+                    format!("'{val}' is not a valid Rust version, expected eg. '1.70' or '1.70.1'")
+                        .into(),
+                ),
+                value.span,
+            ))
+            .into()
         })
     }
 }
 
+impl fmt::Display for RustVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct MaxRustVersionConfig {
+    /// The maximum `rust-version` crates may declare, ie. the MSRV of the project
+    pub version: Spanned<RustVersion>,
+    /// How to handle crates that declare a newer `rust-version`
+    pub level: LintLevel,
+}
+
+impl<'de> Deserialize<'de> for MaxRustVersionConfig {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+
+        let version = th.required_s("version")?;
+        let level = th.optional("level").unwrap_or(LintLevel::Warn);
+
+        th.finalize(None)?;
+
+        Ok(Self { version, level })
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct MaxCratesConfig {
+    /// The maximum number of crates allowed in the graph
+    pub count: Spanned<usize>,
+    /// How to handle a graph that has more crates than the maximum
+    pub level: LintLevel,
+}
+
+impl<'de> Deserialize<'de> for MaxCratesConfig {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+
+        let count = th.required_s("count")?;
+        let level = th.optional("level").unwrap_or(LintLevel::Warn);
+
+        th.finalize(None)?;
+
+        Ok(Self { count, level })
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct MaxVersionsConfig {
+    /// The name of the crate
+    pub name: Spanned<String>,
+    /// The maximum number of versions of the crate allowed in the graph
+    pub count: Spanned<usize>,
+    /// How to handle a graph that has more versions of the crate than the maximum
+    pub level: LintLevel,
+}
+
+impl<'de> Deserialize<'de> for MaxVersionsConfig {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+
+        let name = th.required_s("name")?;
+        let count = th.required_s("count")?;
+        let level = th.optional("level").unwrap_or(LintLevel::Warn);
+
+        th.finalize(None)?;
+
+        Ok(Self { name, count, level })
+    }
+}
+
 pub struct Config {
     /// How to handle multiple versions of the same crate
     pub multiple_versions: LintLevel,
     pub multiple_versions_include_dev: bool,
+    /// Caps the number of versions of specific crates, overriding
+    /// `multiple_versions` for those crates
+    pub max_versions: Vec<MaxVersionsConfig>,
+    /// How to handle crates with the same name that come from multiple sources
+    pub duplicate_sources: LintLevel,
     pub workspace_dependencies: Option<WorkspaceDepsConfig>,
     /// How the duplicate graphs are highlighted
     pub highlight: GraphHighlight,
     /// The crates that will cause us to emit failures
     pub deny: Vec<CrateBan>,
+    /// A URL to a deny list containing additional `deny` entries
+    pub deny_from: Option<Spanned<String>>,
     /// If specified, means only the listed crates are allowed
     pub allow: Vec<CrateAllow>,
     /// Allows specifying features that are or are not allowed on crates
@@ -377,6 +702,18 @@ pub struct Config {
     /// The default lint level for default features for workspace crates, can be
     /// overridden in `features` on a crate by crate basis
     pub workspace_default_features: Option<Spanned<LintLevel>>,
+    /// How to handle crates whose enabled features can't be determined
+    pub on_feature_resolution_error: LintLevel,
+    /// If specified, reports every non-workspace crate in the graph that has
+    /// the `default` feature enabled
+    pub report_default_features: Option<Spanned<ReportLevel>>,
+    /// If specified, reports every crate that is resolved to a different
+    /// version for the host, ie. build dependencies and proc-macros, than for
+    /// the target
+    pub report_host_target_version_skew: Option<Spanned<ReportLevel>>,
+    /// If specified, reports every crate that is only used by the host, ie.
+    /// build dependencies and proc-macros, and has multiple versions
+    pub build_graph_duplicates: Option<Spanned<ReportLevel>>,
     /// If specified, disregards the crate completely
     pub skip: Vec<CrateSkip>,
     /// If specified, disregards the crate's transitive dependencies
@@ -396,6 +733,18 @@ pub struct Config {
     pub allow_build_scripts: Option<Spanned<Vec<PackageSpec>>>,
     /// Options for crates that run at build time
     pub build: Option<BuildConfig>,
+    /// The minimum edition crates are expected to use
+    pub min_edition: Option<MinEditionConfig>,
+    /// The maximum `rust-version` crates are allowed to declare
+    pub max_rust_version: Option<MaxRustVersionConfig>,
+    /// How to handle crates that resolve to a pre-release version
+    pub deny_prerelease: LintLevel,
+    /// Crates that are allowed to resolve to a pre-release version
+    pub allow_prerelease: Vec<PackageSpec>,
+    /// The maximum number of crates allowed in the graph
+    pub max_crates: Option<MaxCratesConfig>,
+    /// Crates to report the origin of each of their enabled features for
+    pub report_feature_origin: Vec<PackageSpec>,
 }
 
 impl Default for Config {
@@ -403,19 +752,32 @@ impl Default for Config {
         Self {
             multiple_versions: LintLevel::Warn,
             multiple_versions_include_dev: false,
+            max_versions: Vec::new(),
+            duplicate_sources: LintLevel::Allow,
             workspace_dependencies: None,
             highlight: GraphHighlight::All,
             deny: Vec::new(),
+            deny_from: None,
             allow: Vec::new(),
             features: Vec::new(),
             external_default_features: None,
             workspace_default_features: None,
+            on_feature_resolution_error: LintLevel::Deny,
+            report_default_features: None,
+            report_host_target_version_skew: None,
+            build_graph_duplicates: None,
             skip: Vec::new(),
             skip_tree: Vec::new(),
             wildcards: LintLevel::Allow,
             allow_wildcard_paths: false,
             allow_build_scripts: None,
             build: None,
+            min_edition: None,
+            max_rust_version: None,
+            deny_prerelease: LintLevel::Allow,
+            allow_prerelease: Vec::new(),
+            max_crates: None,
+            report_feature_origin: Vec::new(),
         }
     }
 }
@@ -428,18 +790,33 @@ impl<'de> Deserialize<'de> for Config {
         let multiple_versions_include_dev = th
             .optional("multiple-versions-include-dev")
             .unwrap_or_default();
+        let max_versions = th.optional("max-versions").unwrap_or_default();
+        let duplicate_sources = th.optional("duplicate-sources").unwrap_or(LintLevel::Allow);
         let highlight = th.optional("highlight").unwrap_or_default();
         let deny = th.optional("deny").unwrap_or_default();
+        let deny_from = th.optional_s("deny-from");
         let allow = th.optional("allow").unwrap_or_default();
         let features = th.optional("features").unwrap_or_default();
         let external_default_features = th.optional("external-default-features");
         let workspace_default_features = th.optional("workspace-default-features");
+        let on_feature_resolution_error = th
+            .optional("on-feature-resolution-error")
+            .unwrap_or(LintLevel::Deny);
+        let report_default_features = th.optional("report-default-features");
+        let report_host_target_version_skew = th.optional("report-host-target-version-skew");
+        let build_graph_duplicates = th.optional("build-graph-duplicates");
         let skip = th.optional("skip").unwrap_or_default();
         let skip_tree = th.optional("skip-tree").unwrap_or_default();
         let wildcards = th.optional("wildcards").unwrap_or(LintLevel::Allow);
         let allow_wildcard_paths = th.optional("allow-wildcard-paths").unwrap_or_default();
         let allow_build_scripts = th.optional("allow-build-scripts");
         let build = th.optional("build");
+        let min_edition = th.optional("min-edition");
+        let max_rust_version = th.optional("max-rust-version");
+        let deny_prerelease = th.optional("deny-prerelease").unwrap_or(LintLevel::Allow);
+        let allow_prerelease = th.optional("allow-prerelease").unwrap_or_default();
+        let max_crates = th.optional("max-crates");
+        let report_feature_origin = th.optional("report-feature-origin").unwrap_or_default();
 
         let workspace_dependencies = th.optional("workspace-dependencies");
 
@@ -448,19 +825,32 @@ impl<'de> Deserialize<'de> for Config {
         Ok(Self {
             multiple_versions,
             multiple_versions_include_dev,
+            max_versions,
+            duplicate_sources,
             workspace_dependencies,
             highlight,
             deny,
+            deny_from,
             allow,
             features,
             external_default_features,
             workspace_default_features,
+            on_feature_resolution_error,
+            report_default_features,
+            report_host_target_version_skew,
+            build_graph_duplicates,
             skip,
             skip_tree,
             wildcards,
             allow_wildcard_paths,
             allow_build_scripts,
             build,
+            min_edition,
+            max_rust_version,
+            deny_prerelease,
+            allow_prerelease,
+            max_crates,
+            report_feature_origin,
         })
     }
 }
@@ -471,53 +861,29 @@ impl crate::cfg::UnvalidatedConfig for Config {
     fn validate(self, mut ctx: ValidationContext<'_>) -> Self::ValidCfg {
         let cfg_id = ctx.cfg_id;
 
-        let (denied_multiple_versions, denied) = {
-            let mut dmulti = Vec::new();
-            let mut denied = Vec::new();
-            for deny_spec in self.deny {
-                let spec = deny_spec.spec;
-
-                let inner = if let Some(extended) = deny_spec.inner {
-                    let dmv = extended.deny_multiple_versions;
-                    let wrappers = extended.wrappers;
-
-                    if let Some((dmv, wrappers)) = dmv.as_ref().zip(wrappers.as_ref()) {
-                        if dmv.value && !wrappers.value.is_empty() {
-                            ctx.push(
-                                Diagnostic::error()
-                                    .with_message(
-                                        "a crate ban was specified with both `wrappers` and `deny-multiple-versions` = true",
-                                    )
-                                    .with_labels(vec![
-                                        Label::secondary(cfg_id, wrappers.span)
-                                            .with_message(format!("has {} `wrappers`", wrappers.value.len())),
-                                        Label::secondary(cfg_id, dmv.span)
-                                            .with_message("has `deny-multiple-versions` set to true"),
-                                    ]),
-                            );
-                            continue;
-                        }
-                    }
-
-                    if dmv.is_some_and(|d| d.value) {
-                        dmulti.push(spec);
-                        continue;
-                    }
+        let mut denied_multiple_versions = Vec::new();
+        let mut denied = Vec::new();
+        validate_deny(
+            self.deny,
+            cfg_id,
+            ctx.diagnostics,
+            &mut denied_multiple_versions,
+            &mut denied,
+        );
 
-                    Some(KrateBan {
-                        wrappers: wrappers.map(|sv| sv.value),
-                        reason: extended.reason,
-                        use_instead: extended.use_instead,
-                    })
-                } else {
+        let deny_from = self
+            .deny_from
+            .and_then(|df| match url::Url::parse(&df.value) {
+                Ok(url) => Some(Spanned::with_span(url, df.span)),
+                Err(err) => {
+                    ctx.push(
+                        Diagnostic::error()
+                            .with_message(format!("failed to parse deny list url: {err}"))
+                            .with_labels(vec![Label::primary(cfg_id, df.span)]),
+                    );
                     None
-                };
-
-                denied.push(ValidKrateBan { spec, inner });
-            }
-
-            (dmulti, denied)
-        };
+                }
+            });
 
         let allowed = self.allow;
         let skipped = self.skip;
@@ -724,6 +1090,7 @@ impl crate::cfg::UnvalidatedConfig for Config {
 
             Some(ValidBuildConfig {
                 allow_build_scripts: bc.allow_build_scripts,
+                allow: bc.allow,
                 executables: bc.executables,
                 script_extensions,
                 bypass,
@@ -731,6 +1098,7 @@ impl crate::cfg::UnvalidatedConfig for Config {
                 include_workspace: bc.include_workspace,
                 include_archives: bc.include_archives,
                 interpreted: bc.interpreted,
+                links: bc.links,
             })
         } else if let Some(abs) = self.allow_build_scripts {
             ctx.push(Diagnostic::warning()
@@ -740,6 +1108,7 @@ impl crate::cfg::UnvalidatedConfig for Config {
                 ]));
             Some(ValidBuildConfig {
                 allow_build_scripts: Some(abs.value),
+                allow: Vec::new(),
                 executables: LintLevel::Allow,
                 script_extensions: ValidGlobSet::default(),
                 bypass: Vec::new(),
@@ -747,6 +1116,7 @@ impl crate::cfg::UnvalidatedConfig for Config {
                 include_workspace: false,
                 include_archives: false,
                 interpreted: LintLevel::Warn,
+                links: None,
             })
         } else {
             None
@@ -756,20 +1126,303 @@ impl crate::cfg::UnvalidatedConfig for Config {
             file_id: ctx.cfg_id,
             multiple_versions: self.multiple_versions,
             multiple_versions_include_dev: self.multiple_versions_include_dev,
+            max_versions: self.max_versions,
+            duplicate_sources: self.duplicate_sources,
             workspace_dependencies: self.workspace_dependencies,
             highlight: self.highlight,
             denied,
             denied_multiple_versions,
+            deny_from,
             allowed,
             features,
             external_default_features: self.external_default_features,
             workspace_default_features: self.workspace_default_features,
+            on_feature_resolution_error: self.on_feature_resolution_error,
+            report_default_features: self.report_default_features,
+            report_host_target_version_skew: self.report_host_target_version_skew,
+            build_graph_duplicates: self.build_graph_duplicates,
             skipped,
             wildcards: self.wildcards,
             allow_wildcard_paths: self.allow_wildcard_paths,
             tree_skipped: self.skip_tree,
             build,
+            min_edition: self.min_edition,
+            max_rust_version: self.max_rust_version,
+            deny_prerelease: self.deny_prerelease,
+            allow_prerelease: self.allow_prerelease,
+            max_crates: self.max_crates,
+            report_feature_origin: self.report_feature_origin,
+            suggested_crates: None,
+        }
+    }
+}
+
+/// Loads the deny list specified by [`ValidConfig::deny_from`], if any, and
+/// merges its `deny` entries into the config
+///
+/// The deny list is cached in `$CARGO_HOME/deny-lists` so that it can be used
+/// when `offline` is true, or the fetch fails. A deny list that is already
+/// present in `files`, eg. because multiple workspaces use the same config,
+/// is not fetched again.
+pub fn load_deny_list(
+    cfg: &mut ValidConfig,
+    offline: bool,
+    files: &mut crate::diag::Files,
+    diags: &mut Vec<Diagnostic>,
+) {
+    let Some(url) = &cfg.deny_from else {
+        return;
+    };
+
+    let file_id = if let Some(id) = files.id_for_path(crate::Path::new(url.value.as_str())) {
+        id
+    } else {
+        let fetch = || -> anyhow::Result<String> {
+            use anyhow::Context as _;
+
+            let cache_dir = crate::utf8path(
+                home::cargo_home()
+                    .context("failed to resolve CARGO_HOME or HOME")?
+                    .join("deny-lists"),
+            )?;
+
+            let client = if offline {
+                None
+            } else {
+                Some(crate::http_client()?)
+            };
+
+            fetch_deny_list(client.as_ref(), &url.value, &cache_dir)
+        };
+
+        let content = match fetch() {
+            Ok(c) => c,
+            Err(err) => {
+                diags.push(
+                    Diagnostic::error()
+                        .with_message("failed to load deny list")
+                        .with_labels(vec![Label::primary(cfg.file_id, url.span)])
+                        .with_notes(vec![format!("error = {err:#}")]),
+                );
+                return;
+            }
+        };
+
+        // The url is used as the path so that diagnostics for entries in the
+        // deny list point to where they actually came from
+        files.add(url.value.as_str(), content)
+    };
+
+    merge_deny_list(cfg, file_id, files, diags);
+}
+
+/// Parses the deny list in the specified file, and merges its `deny` entries
+/// into the config
+fn merge_deny_list(
+    cfg: &mut ValidConfig,
+    file_id: FileId,
+    files: &crate::diag::Files,
+    diags: &mut Vec<Diagnostic>,
+) {
+    let get_deny = || -> Result<Vec<CrateBan>, DeserError> {
+        let mut parsed = toml_span::parse(files.source(file_id))?;
+        let mut th = TableHelper::new(&mut parsed)?;
+        let deny = th.required("deny")?;
+        th.finalize(None)?;
+        Ok(deny)
+    };
+
+    match get_deny() {
+        Ok(deny) => validate_deny(
+            deny,
+            file_id,
+            diags,
+            &mut cfg.denied_multiple_versions,
+            &mut cfg.denied,
+        ),
+        Err(err) => {
+            diags.extend(err.errors.into_iter().map(|err| err.to_diagnostic(file_id)));
+        }
+    }
+}
+
+#[inline]
+fn deny_list_cache_path(cache_dir: &crate::Path, url: &url::Url) -> crate::PathBuf {
+    cache_dir.join(format!("{:08x}.toml", crate::hash(url.as_str().as_bytes())))
+}
+
+/// Retrieves the contents of the deny list at the specified url, caching it
+/// in `cache_dir`
+///
+/// If `client` is `None`, ie. cargo-deny is offline, only the cached copy is
+/// used, and the cached copy is also used if the fetch fails
+fn fetch_deny_list(
+    client: Option<&reqwest::blocking::Client>,
+    url: &url::Url,
+    cache_dir: &crate::Path,
+) -> anyhow::Result<String> {
+    use anyhow::Context as _;
+
+    let cache_path = deny_list_cache_path(cache_dir, url);
+
+    let read_cache = || {
+        std::fs::read_to_string(&cache_path)
+            .with_context(|| format!("failed to read cached deny list '{cache_path}'"))
+    };
+
+    let Some(client) = client else {
+        return read_cache();
+    };
+
+    let fetch = || -> anyhow::Result<String> {
+        let res = client.get(url.clone()).send()?.error_for_status()?;
+        Ok(res.text()?)
+    };
+
+    match fetch() {
+        Ok(content) => {
+            if let Err(err) = std::fs::create_dir_all(cache_dir)
+                .and_then(|_| std::fs::write(&cache_path, &content))
+            {
+                log::warn!("failed to cache deny list to '{cache_path}': {err}");
+            }
+
+            Ok(content)
         }
+        Err(err) => {
+            let content =
+                read_cache().with_context(|| format!("failed to fetch '{url}': {err:#}"))?;
+            log::warn!("failed to fetch deny list '{url}', using cached copy: {err:#}");
+            Ok(content)
+        }
+    }
+}
+
+/// Validates `deny` entries, which can come from the config or a remote deny
+/// list, separating out the crates that are only denied multiple versions
+fn validate_deny(
+    deny: Vec<CrateBan>,
+    file_id: FileId,
+    diags: &mut Vec<Diagnostic>,
+    denied_multiple_versions: &mut Vec<(PackageSpec, FileId)>,
+    denied: &mut Vec<ValidKrateBan>,
+) {
+    for deny_spec in deny {
+        let mut spec = deny_spec.spec;
+
+        let inner = if let Some(extended) = deny_spec.inner {
+            let dmv = extended.deny_multiple_versions;
+            let wrappers = extended.wrappers;
+            let deny_below = extended.deny_below;
+
+            if let Some((dmv, wrappers)) = dmv.as_ref().zip(wrappers.as_ref()) {
+                if dmv.value && !wrappers.value.is_empty() {
+                    diags.push(
+                        Diagnostic::error()
+                            .with_message(
+                                "a crate ban was specified with both `wrappers` and `deny-multiple-versions` = true",
+                            )
+                            .with_labels(vec![
+                                Label::secondary(file_id, wrappers.span)
+                                    .with_message(format!("has {} `wrappers`", wrappers.value.len())),
+                                Label::secondary(file_id, dmv.span)
+                                    .with_message("has `deny-multiple-versions` set to true"),
+                            ]),
+                    );
+                    continue;
+                }
+            }
+
+            if let Some(deny_below) = &deny_below {
+                if spec.version_req.is_some() {
+                    diags.push(
+                        Diagnostic::error()
+                            .with_message(
+                                "a crate ban was specified with both a version requirement and `deny-below`",
+                            )
+                            .with_labels(vec![
+                                Label::primary(file_id, spec.name.span)
+                                    .with_message("has a version requirement"),
+                                Label::secondary(file_id, deny_below.span)
+                                    .with_message("has `deny-below`"),
+                            ]),
+                    );
+                    continue;
+                }
+
+                if let Some(dmv) = dmv.as_ref().filter(|dmv| dmv.value) {
+                    diags.push(
+                        Diagnostic::error()
+                            .with_message(
+                                "a crate ban was specified with both `deny-below` and `deny-multiple-versions` = true",
+                            )
+                            .with_labels(vec![
+                                Label::secondary(file_id, deny_below.span)
+                                    .with_message("has `deny-below`"),
+                                Label::secondary(file_id, dmv.span)
+                                    .with_message("has `deny-multiple-versions` set to true"),
+                            ]),
+                    );
+                    continue;
+                }
+
+                // The ban is matched like any other version requirement,
+                // the original version is kept for diagnostics
+                let min = &deny_below.value;
+                spec.version_req = Some(semver::VersionReq {
+                    comparators: vec![semver::Comparator {
+                        op: semver::Op::Less,
+                        major: min.major,
+                        minor: Some(min.minor),
+                        patch: Some(min.patch),
+                        pre: min.pre.clone(),
+                    }],
+                });
+            }
+
+            let deny_features_present = extended.deny_features_present;
+
+            if let Some((dmv, dfp)) = dmv
+                .as_ref()
+                .filter(|dmv| dmv.value)
+                .zip(deny_features_present.as_ref())
+            {
+                diags.push(
+                    Diagnostic::error()
+                        .with_message(
+                            "a crate ban was specified with both `deny-features-present` and `deny-multiple-versions` = true",
+                        )
+                        .with_labels(vec![
+                            Label::secondary(file_id, dfp.span)
+                                .with_message("has `deny-features-present`"),
+                            Label::secondary(file_id, dmv.span)
+                                .with_message("has `deny-multiple-versions` set to true"),
+                        ]),
+                );
+                continue;
+            }
+
+            if dmv.is_some_and(|d| d.value) {
+                denied_multiple_versions.push((spec, file_id));
+                continue;
+            }
+
+            Some(KrateBan {
+                wrappers: wrappers.map(|sv| sv.value),
+                reason: extended.reason,
+                use_instead: extended.use_instead,
+                deny_below,
+                deny_features_present: deny_features_present.map(|sv| sv.value),
+            })
+        } else {
+            None
+        };
+
+        denied.push(ValidKrateBan {
+            spec,
+            inner,
+            file_id,
+        });
     }
 }
 
@@ -800,30 +1453,38 @@ pub(crate) fn exact_match<'v, T>(
         .find_map(|sid| (&sid.spec == id).then_some(&sid.spec))
 }
 
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(serde::Serialize)]
 pub(crate) struct KrateBan {
     pub wrappers: Option<Vec<Spanned<String>>>,
     pub reason: Option<Reason>,
     pub use_instead: Option<Spanned<String>>,
+    pub deny_below: Option<Spanned<semver::Version>>,
+    pub deny_features_present: Option<Vec<Spanned<String>>>,
 }
 
-pub(crate) type ValidKrateBan = PackageSpecOrExtended<KrateBan>;
+#[derive(serde::Serialize)]
+pub(crate) struct ValidKrateBan {
+    pub spec: PackageSpec,
+    pub inner: Option<KrateBan>,
+    /// The file the ban was specified in, either the config or a remote deny list
+    pub file_id: FileId,
+}
 
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(serde::Serialize)]
 pub struct Features {
     pub allow: Spanned<Vec<Spanned<String>>>,
     pub deny: Vec<Spanned<String>>,
     pub exact: Spanned<bool>,
 }
 
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(serde::Serialize)]
 pub(crate) struct ValidKrateFeatures {
     pub spec: PackageSpec,
     pub features: Features,
     pub reason: Option<Reason>,
 }
 
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(serde::Serialize)]
 pub enum GlobPattern {
     Builtin((Spanned<String>, FileId)),
     User(Spanned<String>),
@@ -864,7 +1525,6 @@ pub struct ValidGlobSet {
     pub(crate) patterns: Vec<GlobPattern>,
 }
 
-#[cfg(test)]
 impl serde::Serialize for ValidGlobSet {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -900,7 +1560,7 @@ impl ValidGlobSet {
     }
 }
 
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(serde::Serialize)]
 pub struct ValidBypass {
     pub spec: PackageSpec,
     pub build_script: Option<Spanned<Checksum>>,
@@ -909,9 +1569,10 @@ pub struct ValidBypass {
     pub allow: Vec<BypassPath>,
 }
 
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(serde::Serialize)]
 pub struct ValidBuildConfig {
     pub allow_build_scripts: Option<Vec<PackageSpec>>,
+    pub allow: Vec<PackageSpec>,
     pub executables: LintLevel,
     pub script_extensions: ValidGlobSet,
     pub bypass: Vec<ValidBypass>,
@@ -919,29 +1580,47 @@ pub struct ValidBuildConfig {
     pub include_workspace: bool,
     pub include_archives: bool,
     pub interpreted: LintLevel,
+    pub links: Option<Spanned<ReportLevel>>,
 }
 
 pub type ValidTreeSkip = PackageSpecOrExtended<TreeSkipExtended>;
 pub type SpecAndReason = PackageSpecOrExtended<Reason>;
 
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(serde::Serialize)]
 pub struct ValidConfig {
     pub file_id: FileId,
     pub multiple_versions: LintLevel,
     pub multiple_versions_include_dev: bool,
+    pub max_versions: Vec<MaxVersionsConfig>,
+    pub duplicate_sources: LintLevel,
     pub workspace_dependencies: Option<WorkspaceDepsConfig>,
     pub highlight: GraphHighlight,
     pub(crate) denied: Vec<ValidKrateBan>,
-    pub(crate) denied_multiple_versions: Vec<PackageSpec>,
+    pub(crate) denied_multiple_versions: Vec<(PackageSpec, FileId)>,
+    pub deny_from: Option<Spanned<url::Url>>,
     pub(crate) allowed: Vec<SpecAndReason>,
     pub(crate) features: Vec<ValidKrateFeatures>,
     pub external_default_features: Option<Spanned<LintLevel>>,
     pub workspace_default_features: Option<Spanned<LintLevel>>,
-    pub(crate) skipped: Vec<SpecAndReason>,
+    pub on_feature_resolution_error: LintLevel,
+    pub report_default_features: Option<Spanned<ReportLevel>>,
+    pub report_host_target_version_skew: Option<Spanned<ReportLevel>>,
+    pub build_graph_duplicates: Option<Spanned<ReportLevel>>,
+    pub(crate) skipped: Vec<CrateSkip>,
     pub(crate) tree_skipped: Vec<ValidTreeSkip>,
     pub wildcards: LintLevel,
     pub allow_wildcard_paths: bool,
     pub build: Option<ValidBuildConfig>,
+    pub min_edition: Option<MinEditionConfig>,
+    pub max_rust_version: Option<MaxRustVersionConfig>,
+    pub deny_prerelease: LintLevel,
+    pub allow_prerelease: Vec<PackageSpec>,
+    pub max_crates: Option<MaxCratesConfig>,
+    pub report_feature_origin: Vec<PackageSpec>,
+    /// Whether the crates suggested via `use-instead` exist on crates.io,
+    /// retrieved by the binary if suggestions are validated
+    #[serde(skip)]
+    pub suggested_crates: Option<super::SuggestedCrates>,
 }
 
 #[cfg(test)]
@@ -971,4 +1650,94 @@ mod test {
 
         insta::assert_json_snapshot!(validated);
     }
+
+    /// Ensures the entries of a deny list are merged with the config's own,
+    /// and that diagnostics for them point to the deny list rather than the
+    /// config
+    #[test]
+    fn merges_deny_list() {
+        use crate::UnvalidatedConfig as _;
+
+        let mut cd = ConfigData::<Config>::load_str(
+            "deny-from.toml",
+            "deny-from = 'https://example.com/deny.toml'\ndeny = ['openssl']",
+        );
+
+        let mut diags = Vec::new();
+        let mut cfg = cd.config.validate(crate::cfg::ValidationContext {
+            cfg_id: cd.id,
+            files: &mut cd.files,
+            diagnostics: &mut diags,
+        });
+        assert!(diags.is_empty());
+
+        let list_id = cd.files.add(
+            "https://example.com/deny.toml",
+            r#"
+deny = [
+    { name = "openssl-sys", wrappers = ["native-tls"] },
+    { name = "bad", wrappers = ["good"], deny-multiple-versions = true },
+]
+"#,
+        );
+
+        merge_deny_list(&mut cfg, list_id, &cd.files, &mut diags);
+
+        assert_eq!(
+            cfg.denied
+                .iter()
+                .map(|kb| (kb.spec.name.value.as_str(), kb.file_id))
+                .collect::<Vec<_>>(),
+            [("openssl", cd.id), ("openssl-sys", list_id)]
+        );
+
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].labels.iter().all(|l| l.file_id == list_id));
+
+        // Errors in the deny list itself also point to it
+        let bad_id = cd
+            .files
+            .add("https://example.com/bad.toml", "deny = 'openssl'");
+        diags.clear();
+        merge_deny_list(&mut cfg, bad_id, &cd.files, &mut diags);
+
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].labels.iter().all(|l| l.file_id == bad_id));
+    }
+
+    /// Ensures only the cached copy of the deny list is used when offline,
+    /// and that the cached copy is used if the fetch fails
+    #[test]
+    fn falls_back_to_cached_deny_list() {
+        let td = tempfile::tempdir().unwrap();
+        let cache_dir = crate::utf8path(td.path().to_owned()).unwrap();
+
+        // Nothing listens on the discard port, so the fetch fails immediately
+        let url = url::Url::parse("http://127.0.0.1:9/deny.toml").unwrap();
+        let client = crate::http_client().unwrap();
+
+        assert!(fetch_deny_list(None, &url, &cache_dir).is_err());
+        let err = fetch_deny_list(Some(&client), &url, &cache_dir).unwrap_err();
+        assert!(format!("{err:#}").contains("failed to fetch"));
+
+        let cached = "deny = ['openssl']";
+        std::fs::write(deny_list_cache_path(&cache_dir, &url), cached).unwrap();
+
+        assert_eq!(fetch_deny_list(None, &url, &cache_dir).unwrap(), cached);
+        assert_eq!(
+            fetch_deny_list(Some(&client), &url, &cache_dir).unwrap(),
+            cached
+        );
+    }
+
+    /// Ensures checksums are displayed as the lowercase hex string that cargo
+    /// records in the lockfile and index, regardless of how they were parsed
+    #[test]
+    fn displays_checksum_as_hex() {
+        let hex = "474a3eb189a698475d8a6f4b358eb0790db6379aea8b8a85ac925102784cd520";
+        let checksum: Checksum = hex.to_uppercase().parse().unwrap();
+
+        assert_eq!(checksum.to_string(), hex);
+        assert_eq!(serde_json::to_value(&checksum).unwrap(), hex);
+    }
 }
@@ -34,6 +34,7 @@ pub enum Code {
     SkippedByRoot,
     UnmatchedSkipRoot,
     BuildScriptNotAllowed,
+    LinksNativeLibrary,
     ExactFeaturesMismatch,
     FeatureNotExplicitlyAllowed,
     FeatureBanned,
@@ -55,6 +56,21 @@ pub enum Code {
     WorkspaceDuplicate,
     UnresolvedWorkspaceDependency,
     UnusedWorkspaceDependency,
+    OutdatedEdition,
+    PrereleaseVersion,
+    UnmatchedDenyBelow,
+    DuplicateSource,
+    ExpiredSkip,
+    UnableToGetDefaultFeatures,
+    WorkspaceTableNotUsed,
+    TooManyCrates,
+    FeatureOrigin,
+    UnmatchedBuildAllow,
+    HostTargetVersionSkew,
+    BuildGraphDuplicate,
+    RustVersionTooNew,
+    TooManyVersions,
+    UnknownSuggestion,
 }
 
 impl From<Code> for String {
@@ -91,14 +107,119 @@ impl SpecAndReason {
 pub(crate) struct ExplicitlyBanned<'a> {
     pub(crate) krate: &'a Krate,
     pub(crate) ban_cfg: &'a SpecAndReason,
+    /// The direct dependents of the banned crate, and whether each of them is
+    /// an allowed wrapper, only populated if the ban has wrappers
+    pub(crate) dependents: Vec<(&'a Krate, bool)>,
 }
 
 impl<'a> From<ExplicitlyBanned<'a>> for Diag {
     fn from(eb: ExplicitlyBanned<'a>) -> Self {
+        let notes = eb
+            .dependents
+            .into_iter()
+            .map(|(dependent, is_wrapper)| {
+                format!(
+                    "direct dependent '{dependent}' {} an allowed wrapper",
+                    if is_wrapper { "is" } else { "is not" }
+                )
+            })
+            .collect();
+
         Diagnostic::new(Severity::Error)
             .with_message(format!("crate '{}' is explicitly banned", eb.krate))
             .with_code(Code::Banned)
             .with_labels(eb.ban_cfg.to_labels(Some("banned here")))
+            .with_notes(notes)
+            .into()
+    }
+}
+
+pub(crate) struct BannedFeaturesPresent<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) ban_cfg: &'a SpecAndReason,
+    /// The banned features that are enabled, and the direct dependents that
+    /// enable each of them
+    pub(crate) features: Vec<(&'a Spanned<String>, Vec<&'a Krate>)>,
+}
+
+impl<'a> From<BannedFeaturesPresent<'a>> for Diag {
+    fn from(bfp: BannedFeaturesPresent<'a>) -> Self {
+        let mut labels = bfp.ban_cfg.to_labels(Some("banned here"));
+        for (feature, _) in &bfp.features {
+            labels.insert(
+                1,
+                Label::secondary(bfp.ban_cfg.file_id, feature.span).with_message("enabled"),
+            );
+        }
+
+        let notes = bfp
+            .features
+            .iter()
+            .filter(|(_, enablers)| !enablers.is_empty())
+            .map(|(feature, enablers)| {
+                format!(
+                    "'{}' enabled by {}",
+                    feature.value,
+                    enablers
+                        .iter()
+                        .map(|krate| format!("'{krate}'"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+            .collect();
+
+        let diag = Diagnostic::new(Severity::Error)
+            .with_message(format!(
+                "crate '{}' is banned as feature(s) {} are enabled",
+                bfp.krate,
+                bfp.features
+                    .iter()
+                    .map(|(feature, _)| format!("'{}'", feature.value))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+            .with_code(Code::Banned)
+            .with_labels(labels)
+            .with_notes(notes);
+
+        Diag {
+            diag,
+            graph_nodes: bfp
+                .features
+                .iter()
+                .map(|(feature, _)| GraphNode {
+                    kid: bfp.krate.id.clone(),
+                    feature: Some(feature.value.clone()),
+                })
+                .collect(),
+            extra: None,
+            with_features: true,
+        }
+    }
+}
+
+pub(crate) struct BelowMinimumVersion<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) ban_cfg: &'a SpecAndReason,
+    pub(crate) min: &'a Spanned<semver::Version>,
+}
+
+impl<'a> From<BelowMinimumVersion<'a>> for Diag {
+    fn from(bmv: BelowMinimumVersion<'a>) -> Self {
+        let mut labels = bmv.ban_cfg.to_labels(Some("banned here"));
+        labels.insert(
+            1,
+            Label::secondary(bmv.ban_cfg.file_id, bmv.min.span).with_message("minimum version"),
+        );
+
+        Diagnostic::new(Severity::Error)
+            .with_message(format!(
+                "{} {} is below the required minimum {}",
+                bmv.krate.name, bmv.krate.version, bmv.min.value
+            ))
+            .with_code(Code::Banned)
+            .with_labels(labels)
             .into()
     }
 }
@@ -131,6 +252,139 @@ impl<'a> From<NotAllowed<'a>> for Diag {
     }
 }
 
+pub(crate) struct OutdatedEdition<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) edition: cfg::Edition,
+    pub(crate) min_edition: &'a Spanned<cfg::Edition>,
+    pub(crate) level: crate::LintLevel,
+    pub(crate) file_id: FileId,
+}
+
+impl<'a> From<OutdatedEdition<'a>> for Diag {
+    fn from(oe: OutdatedEdition<'a>) -> Self {
+        Diagnostic::new(oe.level.into())
+            .with_message(format!(
+                "crate '{}' uses edition {}, which is older than the minimum edition {}",
+                oe.krate, oe.edition, oe.min_edition.value,
+            ))
+            .with_code(Code::OutdatedEdition)
+            .with_labels(vec![
+                Label::primary(oe.file_id, oe.min_edition.span).with_message("minimum edition")
+            ])
+            .into()
+    }
+}
+
+pub(crate) struct RustVersionTooNew<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) rust_version: &'a semver::Version,
+    pub(crate) max_rust_version: &'a Spanned<cfg::RustVersion>,
+    pub(crate) level: crate::LintLevel,
+    pub(crate) file_id: FileId,
+}
+
+impl<'a> From<RustVersionTooNew<'a>> for Diag {
+    fn from(rv: RustVersionTooNew<'a>) -> Self {
+        Diagnostic::new(rv.level.into())
+            .with_message(format!(
+                "crate '{}' requires rust {}, which is newer than the maximum rust version {}",
+                rv.krate, rv.rust_version, rv.max_rust_version.value,
+            ))
+            .with_code(Code::RustVersionTooNew)
+            .with_labels(vec![Label::primary(rv.file_id, rv.max_rust_version.span)
+                .with_message("maximum rust version")])
+            .into()
+    }
+}
+
+pub(crate) struct TooManyCrates<'a> {
+    pub(crate) count: usize,
+    pub(crate) max: &'a Spanned<usize>,
+    pub(crate) level: crate::LintLevel,
+    pub(crate) file_id: FileId,
+    pub(crate) excess: Vec<&'a Krate>,
+}
+
+impl<'a> From<TooManyCrates<'a>> for Diag {
+    fn from(tmc: TooManyCrates<'a>) -> Self {
+        Diagnostic::new(tmc.level.into())
+            .with_message(format!(
+                "the crate graph contains {} crates, which is {} more than the maximum of {}",
+                tmc.count,
+                tmc.excess.len(),
+                tmc.max.value,
+            ))
+            .with_code(Code::TooManyCrates)
+            .with_labels(vec![
+                Label::primary(tmc.file_id, tmc.max.span).with_message("maximum crates")
+            ])
+            .with_notes(
+                tmc.excess
+                    .into_iter()
+                    .map(|krate| format!("exceeds the maximum: {krate}"))
+                    .collect(),
+            )
+            .into()
+    }
+}
+
+pub(crate) struct PrereleaseVersion<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) level: crate::LintLevel,
+}
+
+impl<'a> From<PrereleaseVersion<'a>> for Diag {
+    fn from(pv: PrereleaseVersion<'a>) -> Self {
+        Diagnostic::new(pv.level.into())
+            .with_message(format!(
+                "crate '{}' is a pre-release version ('{}')",
+                pv.krate, pv.krate.version.pre,
+            ))
+            .with_code(Code::PrereleaseVersion)
+            .into()
+    }
+}
+
+pub(crate) struct UnableToGetDefaultFeatures<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) level: crate::LintLevel,
+}
+
+impl<'a> From<UnableToGetDefaultFeatures<'a>> for Diag {
+    fn from(ugdf: UnableToGetDefaultFeatures<'a>) -> Self {
+        Diagnostic::new(ugdf.level.into())
+            .with_message(format!(
+                "unable to determine the enabled features for crate '{}'",
+                ugdf.krate,
+            ))
+            .with_code(Code::UnableToGetDefaultFeatures)
+            .with_notes(vec![
+                "feature bans and default feature lints are not evaluated for this crate"
+                    .to_owned(),
+            ])
+            .into()
+    }
+}
+
+pub(crate) struct UnableToCheckBannedFeatures<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) ban_cfg: &'a SpecAndReason,
+    pub(crate) level: crate::LintLevel,
+}
+
+impl<'a> From<UnableToCheckBannedFeatures<'a>> for Diag {
+    fn from(ucbf: UnableToCheckBannedFeatures<'a>) -> Self {
+        Diagnostic::new(ucbf.level.into())
+            .with_message(format!(
+                "unable to determine if the banned features of crate '{}' are enabled",
+                ucbf.krate,
+            ))
+            .with_code(Code::UnableToGetDefaultFeatures)
+            .with_labels(ucbf.ban_cfg.to_labels(Some("features banned here")))
+            .into()
+    }
+}
+
 pub(crate) struct Duplicates<'a> {
     pub(crate) krate_name: &'a str,
     pub(crate) num_dupes: usize,
@@ -154,6 +408,94 @@ impl<'a> From<Duplicates<'a>> for Diag {
     }
 }
 
+pub(crate) struct TooManyVersions<'a> {
+    pub(crate) krate_name: &'a str,
+    pub(crate) versions: Vec<&'a semver::Version>,
+    pub(crate) max: &'a Spanned<usize>,
+    pub(crate) krates_coord: KrateCoord,
+    pub(crate) cfg_file_id: FileId,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<TooManyVersions<'a>> for Diag {
+    fn from(tmv: TooManyVersions<'a>) -> Self {
+        let versions = tmv
+            .versions
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Diagnostic::new(tmv.severity)
+            .with_message(format!(
+                "found {} versions of crate '{}', which is more than the maximum of {}",
+                tmv.versions.len(),
+                tmv.krate_name,
+                tmv.max.value,
+            ))
+            .with_code(Code::TooManyVersions)
+            .with_labels(vec![
+                tmv.krates_coord.into_label().with_message("lock entries"),
+                Label::secondary(tmv.cfg_file_id, tmv.max.span).with_message("maximum versions"),
+            ])
+            .with_notes(vec![format!("versions: {versions}")])
+            .into()
+    }
+}
+
+pub(crate) struct DuplicateSources<'a> {
+    pub(crate) krate_name: &'a str,
+    /// Each crate with the name, and the source it comes from
+    pub(crate) krates: Vec<&'a Krate>,
+    pub(crate) krates_coord: KrateCoord,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<DuplicateSources<'a>> for Diag {
+    fn from(ds: DuplicateSources<'a>) -> Self {
+        let source = |krate: &Krate| {
+            krate
+                .source
+                .as_ref()
+                .map_or_else(|| "path".to_owned(), |src| src.to_string())
+        };
+
+        let mut sources: Vec<_> = ds.krates.iter().map(|krate| source(krate)).collect();
+        sources.sort();
+        sources.dedup();
+
+        let mut diag: Diag = Diagnostic::new(ds.severity)
+            .with_message(format!(
+                "found {} sources for crate '{}'",
+                sources.len(),
+                ds.krate_name,
+            ))
+            .with_code(Code::DuplicateSource)
+            .with_labels(vec![ds
+                .krates_coord
+                .into_label()
+                .with_message("lock entries")])
+            .with_notes(
+                ds.krates
+                    .iter()
+                    .map(|krate| format!("{} {} from {}", krate.name, krate.version, source(krate)))
+                    .collect(),
+            )
+            .into();
+
+        diag.graph_nodes = ds
+            .krates
+            .iter()
+            .map(|krate| GraphNode {
+                kid: krate.id.clone(),
+                feature: None,
+            })
+            .collect();
+
+        diag
+    }
+}
+
 pub(crate) struct Skipped<'a> {
     pub(crate) krate: &'a Krate,
     pub(crate) skip_cfg: &'a SpecAndReason,
@@ -208,6 +550,7 @@ impl<'a> From<Wildcards<'a>> for Pack {
 
 pub(crate) struct UnmatchedSkip<'a> {
     pub(crate) skip_cfg: &'a SpecAndReason,
+    pub(crate) suggest_fix: bool,
 }
 
 impl<'a> From<UnmatchedSkip<'a>> for Diag {
@@ -219,6 +562,56 @@ impl<'a> From<UnmatchedSkip<'a>> for Diag {
             ))
             .with_code(Code::UnmatchedSkip)
             .with_labels(us.skip_cfg.to_labels(Some("unmatched skip configuration")))
+            .with_notes(if us.suggest_fix {
+                vec!["Suggested fix: remove this entry from `bans.skip`".to_owned()]
+            } else {
+                Vec::new()
+            })
+            .into()
+    }
+}
+
+pub(crate) struct UnmatchedDenyBelow<'a> {
+    pub(crate) ban_cfg: &'a SpecAndReason,
+    pub(crate) min: &'a Spanned<semver::Version>,
+}
+
+impl<'a> From<UnmatchedDenyBelow<'a>> for Diag {
+    fn from(udb: UnmatchedDenyBelow<'a>) -> Self {
+        Diagnostic::new(Severity::Warning)
+            .with_message(format!(
+                "crate '{}' with a required minimum of {} was not encountered",
+                udb.ban_cfg.spec.name.value, udb.min.value,
+            ))
+            .with_code(Code::UnmatchedDenyBelow)
+            .with_labels(vec![
+                Label::primary(udb.ban_cfg.file_id, udb.ban_cfg.spec.name.span)
+                    .with_message("unmatched `deny-below` ban"),
+                Label::secondary(udb.ban_cfg.file_id, udb.min.span).with_message("minimum version"),
+            ])
+            .into()
+    }
+}
+
+pub(crate) struct UnknownSuggestion<'a> {
+    pub(crate) ban_cfg: &'a SpecAndReason,
+    pub(crate) use_instead: &'a Spanned<String>,
+}
+
+impl<'a> From<UnknownSuggestion<'a>> for Diag {
+    fn from(us: UnknownSuggestion<'a>) -> Self {
+        Diagnostic::new(Severity::Warning)
+            .with_message(format!(
+                "crate '{}' suggested instead of banned crate '{}' does not exist on crates.io",
+                us.use_instead.value, us.ban_cfg.spec.name.value,
+            ))
+            .with_code(Code::UnknownSuggestion)
+            .with_labels(vec![
+                Label::primary(us.ban_cfg.file_id, us.use_instead.span)
+                    .with_message("unknown crate"),
+                Label::secondary(us.ban_cfg.file_id, us.ban_cfg.spec.name.span)
+                    .with_message("banned crate"),
+            ])
             .into()
     }
 }
@@ -319,6 +712,33 @@ impl<'a> From<SkippedByRoot<'a>> for Diag {
     }
 }
 
+pub(crate) struct ExpiredSkip<'a> {
+    pub(crate) skip_cfg: CfgCoord,
+    pub(crate) spec: &'a crate::cfg::PackageSpec,
+    pub(crate) expires: &'a Spanned<time::Date>,
+}
+
+impl<'a> From<ExpiredSkip<'a>> for Diag {
+    fn from(es: ExpiredSkip<'a>) -> Self {
+        let file_id = es.skip_cfg.file;
+
+        Diagnostic::new(Severity::Warning)
+            .with_message(format!(
+                "skip for crate '{}' expired on {}",
+                es.spec, es.expires.value
+            ))
+            .with_code(Code::ExpiredSkip)
+            .with_labels(vec![
+                es.skip_cfg.into_label().with_message("expired skip"),
+                Label::secondary(file_id, es.expires.span).with_message("expiration date"),
+            ])
+            .with_notes(vec![
+                "the skip is ignored and the crate is checked as normal".to_owned(),
+            ])
+            .into()
+    }
+}
+
 pub(crate) struct UnmatchedSkipRoot {
     pub(crate) skip_root_cfg: CfgCoord,
 }
@@ -352,6 +772,28 @@ impl<'a> From<BuildScriptNotAllowed<'a>> for Diag {
     }
 }
 
+pub(crate) struct LinksNativeLibrary<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) lib: &'a str,
+    pub(crate) level: &'a Spanned<cfg::ReportLevel>,
+    pub(crate) file_id: FileId,
+}
+
+impl<'a> From<LinksNativeLibrary<'a>> for Diag {
+    fn from(lnl: LinksNativeLibrary<'a>) -> Self {
+        Diagnostic::new(lnl.level.value.into())
+            .with_message(format!(
+                "crate '{}' links to the native library '{}'",
+                lnl.krate, lnl.lib
+            ))
+            .with_code(Code::LinksNativeLibrary)
+            .with_labels(vec![
+                Label::primary(lnl.file_id, lnl.level.span).with_message("reported here")
+            ])
+            .into()
+    }
+}
+
 pub(crate) struct ExactFeaturesMismatch<'a> {
     pub(crate) missing_allowed: Vec<CfgCoord>,
     pub(crate) not_allowed: &'a [&'a str],
@@ -535,6 +977,123 @@ impl From<DefaultFeatureEnabled<'_>> for Diag {
     }
 }
 
+pub(crate) struct DefaultFeatureReported<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) level: &'a Spanned<cfg::ReportLevel>,
+    pub(crate) file_id: FileId,
+}
+
+impl From<DefaultFeatureReported<'_>> for Diag {
+    fn from(dfr: DefaultFeatureReported<'_>) -> Diag {
+        let diag = Diagnostic::new(dfr.level.value.into())
+            .with_message(format!(
+                "'default' feature enabled for crate '{}'",
+                dfr.krate,
+            ))
+            .with_code(Code::DefaultFeatureEnabled)
+            .with_labels(vec![
+                Label::primary(dfr.file_id, dfr.level.span).with_message("reported here")
+            ]);
+
+        Diag {
+            diag,
+            graph_nodes: std::iter::once(GraphNode {
+                kid: dfr.krate.id.clone(),
+                feature: Some("default".to_owned()),
+            })
+            .collect(),
+            extra: None,
+            with_features: true,
+        }
+    }
+}
+
+pub(crate) struct HostTargetVersionSkew<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) target: Vec<&'a Krate>,
+    pub(crate) host: Vec<&'a Krate>,
+    pub(crate) level: &'a Spanned<cfg::ReportLevel>,
+    pub(crate) file_id: FileId,
+}
+
+impl From<HostTargetVersionSkew<'_>> for Diag {
+    fn from(htvs: HostTargetVersionSkew<'_>) -> Diag {
+        let diag = Diagnostic::new(htvs.level.value.into())
+            .with_message(format!(
+                "crate '{}' is resolved to different versions for the host and the target",
+                htvs.name,
+            ))
+            .with_code(Code::HostTargetVersionSkew)
+            .with_labels(vec![
+                Label::primary(htvs.file_id, htvs.level.span).with_message("reported here")
+            ])
+            .with_notes(
+                htvs.target
+                    .iter()
+                    .map(|krate| format!("target: {krate}"))
+                    .chain(htvs.host.iter().map(|krate| format!("host: {krate}")))
+                    .collect(),
+            );
+
+        Diag {
+            diag,
+            graph_nodes: htvs
+                .target
+                .into_iter()
+                .chain(htvs.host)
+                .map(|krate| GraphNode {
+                    kid: krate.id.clone(),
+                    feature: None,
+                })
+                .collect(),
+            extra: None,
+            with_features: false,
+        }
+    }
+}
+
+pub(crate) struct BuildGraphDuplicate<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) dupes: Vec<&'a Krate>,
+    pub(crate) level: &'a Spanned<cfg::ReportLevel>,
+    pub(crate) file_id: FileId,
+}
+
+impl From<BuildGraphDuplicate<'_>> for Diag {
+    fn from(bgd: BuildGraphDuplicate<'_>) -> Diag {
+        let diag = Diagnostic::new(bgd.level.value.into())
+            .with_message(format!(
+                "found {} duplicate versions of crate '{}' that is only used at build time",
+                bgd.dupes.len(),
+                bgd.name,
+            ))
+            .with_code(Code::BuildGraphDuplicate)
+            .with_labels(vec![
+                Label::primary(bgd.file_id, bgd.level.span).with_message("reported here")
+            ])
+            .with_notes(
+                bgd.dupes
+                    .iter()
+                    .map(|krate| format!("version: {}", krate.version))
+                    .collect(),
+            );
+
+        Diag {
+            diag,
+            graph_nodes: bgd
+                .dupes
+                .into_iter()
+                .map(|krate| GraphNode {
+                    kid: krate.id.clone(),
+                    feature: None,
+                })
+                .collect(),
+            extra: None,
+            with_features: false,
+        }
+    }
+}
+
 pub(crate) struct HomePath<'a> {
     pub(crate) path: &'a crate::Path,
     pub(crate) root: &'a crate::Path,
@@ -833,6 +1392,23 @@ impl<'a> From<UnmatchedBypass<'a>> for Diag {
     }
 }
 
+pub(crate) struct UnmatchedBuildAllow {
+    pub(crate) allow_cfg: CfgCoord,
+}
+
+impl From<UnmatchedBuildAllow> for Diag {
+    fn from(uba: UnmatchedBuildAllow) -> Self {
+        Diagnostic::new(Severity::Warning)
+            .with_message("allowed build crate was not encountered")
+            .with_code(Code::UnmatchedBuildAllow)
+            .with_labels(vec![uba
+                .allow_cfg
+                .into_label()
+                .with_message("unmatched build allowance")])
+            .into()
+    }
+}
+
 pub(crate) struct UnmatchedPathBypass<'a> {
     pub(crate) unmatched: &'a super::cfg::BypassPath,
     pub(crate) file_id: FileId,
@@ -890,6 +1466,28 @@ impl<'k> From<WorkspaceDuplicate<'k>> for Diag {
     }
 }
 
+pub(crate) struct WorkspaceTableNotUsed<'k> {
+    pub(crate) member: &'k Krate,
+    pub(crate) dep: &'k Krate,
+    pub(crate) version_label: Label,
+    pub(crate) workspace_label: Label,
+    pub(crate) level: crate::LintLevel,
+}
+
+impl<'k> From<WorkspaceTableNotUsed<'k>> for Diag {
+    fn from(wtnu: WorkspaceTableNotUsed<'k>) -> Self {
+        Diagnostic::new(wtnu.level.into())
+            .with_message(format!(
+                "crate {} declares a version for dependency '{}' rather than using the shared workspace dependency",
+                wtnu.member, wtnu.dep.name,
+            ))
+            .with_code(Code::WorkspaceTableNotUsed)
+            .with_labels(vec![wtnu.version_label, wtnu.workspace_label])
+            .with_notes(vec![format!("use `{}.workspace = true` instead", wtnu.dep.name)])
+            .into()
+    }
+}
+
 pub(crate) struct UnresolveWorkspaceDependency<'m, 'k> {
     pub(crate) manifest: &'m crate::diag::Manifest<'k>,
     pub(crate) dep: &'m crate::diag::ManifestDep<'k>,
@@ -958,3 +1556,53 @@ impl<'u> From<UnusedWorkspaceDependencies<'u>> for Pack {
         pack
     }
 }
+
+/// What enabled a feature on a crate
+pub(crate) enum FeatureEnabler<'a> {
+    /// A direct dependent enabled the feature
+    Dependent(&'a Krate),
+    /// Another enabled feature of the same crate enabled the feature
+    Feature(&'a str),
+}
+
+pub(crate) struct FeatureOrigin<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) features: Vec<(&'a str, Vec<FeatureEnabler<'a>>)>,
+    pub(crate) report_cfg: CfgCoord,
+}
+
+impl From<FeatureOrigin<'_>> for Diag {
+    fn from(fo: FeatureOrigin<'_>) -> Diag {
+        let notes = fo
+            .features
+            .into_iter()
+            .map(|(feature, enablers)| {
+                if enablers.is_empty() {
+                    return format!("'{feature}' is not enabled by a dependent or another feature");
+                }
+
+                format!(
+                    "'{feature}' enabled by {}",
+                    enablers
+                        .iter()
+                        .map(|enabler| match enabler {
+                            FeatureEnabler::Dependent(krate) => format!("'{krate}'"),
+                            FeatureEnabler::Feature(feature) => format!("feature '{feature}'"),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+            .collect();
+
+        Diagnostic::new(Severity::Note)
+            .with_message(format!("enabled features for crate '{}'", fo.krate))
+            .with_code(Code::FeatureOrigin)
+            .with_labels(vec![fo
+                .report_cfg
+                .into_label()
+                .with_message("reported here")])
+            .with_notes(notes)
+            .into()
+    }
+}
@@ -84,6 +84,12 @@ struct DupNode<'k> {
     feature: Option<&'k str>,
 }
 
+/// Creates a [DOT](https://graphviz.org/doc/info/lang.html) graph of the
+/// paths to each of the duplicate versions of a crate
+///
+/// The graph is only ever written to files consumed by graphviz, so the
+/// highlighting of duplicates and paths is done via DOT color attributes and
+/// is not affected by `--color`, as ANSI escapes would make the graph invalid
 pub(crate) fn create_graph(
     dup_name: &str,
     highlight: GraphHighlight,
@@ -0,0 +1,90 @@
+//! Verification that the crates suggested via `use-instead` for banned crates
+//! actually exist in the crates.io index, used by `--validate-suggestions`
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Whether each crate suggested via `use-instead` exists on crates.io, keyed
+/// by crate name
+#[derive(Default, Debug)]
+pub struct SuggestedCrates {
+    exists: BTreeMap<String, bool>,
+}
+
+impl SuggestedCrates {
+    /// Looks up every crate suggested via `use-instead` in the crates.io
+    /// sparse index
+    ///
+    /// Crates that can't be looked up, eg. due to network errors, are logged
+    /// and omitted
+    pub fn fetch(cfg: &super::cfg::ValidConfig) -> Self {
+        use tame_index::index::{RemoteSparseIndex, SparseIndex};
+
+        let names: BTreeSet<_> = cfg
+            .denied
+            .iter()
+            .filter_map(|kb| kb.inner.as_ref()?.use_instead.as_ref())
+            .map(|ui| ui.value.as_str())
+            .collect();
+
+        if names.is_empty() {
+            return Self::default();
+        }
+
+        let index = match SparseIndex::new(tame_index::IndexLocation::new(
+            tame_index::IndexUrl::CratesIoSparse,
+        )) {
+            Ok(index) => index,
+            Err(err) => {
+                log::error!("failed to open the crates.io sparse index: {err}");
+                return Self::default();
+            }
+        };
+
+        let client = match crate::http_client() {
+            Ok(client) => client,
+            Err(err) => {
+                log::error!("unable to validate suggestions: {err:#}");
+                return Self::default();
+            }
+        };
+
+        let index = RemoteSparseIndex::new(index, client);
+        // We only read from the index and never write cache entries, so there
+        // is no need to take cargo's package lock
+        let lock = tame_index::utils::flock::FileLock::unlocked();
+
+        let mut exists = BTreeMap::new();
+        for name in names {
+            // A name that isn't valid on crates.io can't exist there
+            let Ok(kname) = name.try_into() else {
+                exists.insert(name.to_owned(), false);
+                continue;
+            };
+
+            match index.krate(kname, false, &lock) {
+                Ok(krate) => {
+                    exists.insert(name.to_owned(), krate.is_some());
+                }
+                Err(err) => {
+                    log::warn!("failed to look up suggested crate '{name}': {err}");
+                }
+            }
+        }
+
+        Self { exists }
+    }
+
+    /// Whether the specified crate exists on crates.io, if it could be looked up
+    #[inline]
+    pub fn exists(&self, name: &str) -> Option<bool> {
+        self.exists.get(name).copied()
+    }
+}
+
+impl FromIterator<(String, bool)> for SuggestedCrates {
+    fn from_iter<I: IntoIterator<Item = (String, bool)>>(iter: I) -> Self {
+        Self {
+            exists: iter.into_iter().collect(),
+        }
+    }
+}
@@ -1,15 +1,23 @@
 use crate::{
     common::ValidConfig,
     stats::{AllStats, Stats},
+    timings::Timings,
 };
+use anyhow::Context as _;
 use cargo_deny::{
-    advisories, bans,
-    diag::{DiagnosticCode, DiagnosticOverrides, ErrorSink, Files, Severity},
+    advisories, annotations, bans,
+    diag::{Check, DiagnosticCode, DiagnosticOverrides, ErrorSink, Files, Pack, Severity},
     licenses, sources, CheckCtx, PathBuf,
 };
 use log::error;
 use std::time::Instant;
 
+mod graph_snapshot;
+mod prune;
+mod since;
+mod subset;
+mod unused;
+
 #[derive(clap::ValueEnum, Debug, PartialEq, Eq, Copy, Clone)]
 pub enum WhichCheck {
     Advisories,
@@ -90,8 +98,28 @@ pub struct Args {
     /// Disable fetching of the advisory database
     ///
     /// When running the `advisories` check, the configured advisory database will be fetched and opened. If this flag is passed, the database won't be fetched, but an error will occur if it doesn't already exist locally.
-    #[arg(short, long)]
+    #[arg(short, long, visible_alias = "no-fetch", conflicts_with = "fetch_only")]
     pub disable_fetch: bool,
+    /// The maximum amount of time fetching each advisory database may take, eg. `30s`
+    ///
+    /// Overrides the `advisories.fetch-timeout` config field if specified.
+    #[arg(long, value_parser = advisories::cfg::parse_timeout)]
+    pub fetch_timeout: Option<time::Duration>,
+    /// Fetches the advisory database and crates, then exits without running any checks
+    ///
+    /// This allows CI to populate caches in a separate step from the checks themselves, which can then be run with `--no-fetch`. The advisory database is only fetched if the `advisories` check would have been run.
+    #[arg(long)]
+    pub fetch_only: bool,
+    /// Prints the fully resolved config as JSON, then exits without running any checks
+    ///
+    /// The config is printed after it has been validated, and merged with local exceptions and the graph options specified on the command line, which is useful for debugging which values are actually used.
+    #[arg(long, conflicts_with = "fetch_only")]
+    pub dump_config: bool,
+    /// Only loads and validates the config, then exits without running any checks
+    ///
+    /// Errors and warnings in the config are reported as usual, but the crate graph is never resolved, and neither the advisory database, the license store, nor the `bans.deny-from` deny list are loaded.
+    #[arg(long, conflicts_with_all = ["fetch_only", "dump_config"])]
+    pub validate_only: bool,
     /// If set, excludes all dev-dependencies, not just ones for non-workspace crates
     #[arg(long)]
     pub exclude_dev: bool,
@@ -100,9 +128,95 @@ pub struct Args {
     /// Note that this flag only applies when the output format is JSON, and note that since cargo-deny supports multiple advisory databases, instead of a single JSON object, there will be 1 for each unique advisory database.
     #[arg(long)]
     pub audit_compatible_output: bool,
+    /// Only report diagnostics for crates that were added or changed in `Cargo.lock` since the specified git revision
+    ///
+    /// The advisories check is always run against, and reports diagnostics for, the full crate graph.
+    #[arg(long, value_name = "GIT_REF")]
+    pub since: Option<String>,
+    /// Removes unused entries from the config file after the checks have run
+    ///
+    /// Entries that are reported as unmatched, such as skips that matched no crates, ignored advisories that were not encountered, or allowed sources that were not used, are removed from the config. All other entries, comments, and formatting are preserved.
+    #[arg(long)]
+    pub prune_config: bool,
+    /// Fails if the hash of the resolved crate graph doesn't match the specified hex encoded SHA-256 hash
+    ///
+    /// The hash is calculated from the name, version, and source of every crate in the graph, so that a repository can pin the exact set of dependencies it has audited. The computed hash is printed if it doesn't match.
+    #[arg(long, value_name = "HASH", value_parser = parse_graph_hash)]
+    pub assert_lockfile_hash: Option<String>,
+    /// Fails if the config allows licenses or sources that the specified base config doesn't, or is less strict than it
+    ///
+    /// The `licenses.allow`, `licenses.exceptions`, `sources.allow-registry`, `sources.allow-git`, and `sources.allow-org` entries must all be allowed by the base config, and lint levels and thresholds must be at least as strict, so that a repository's config can only be more restrictive than an organization's baseline policy.
+    #[arg(long, value_name = "PATH")]
+    pub assert_subset_of: Option<PathBuf>,
+    /// Path to a lock file that records the license expression of every crate
+    ///
+    /// If the file doesn't exist it is created, otherwise a warning is emitted for every crate whose license expression differs from the one recorded in the lock, eg. due to the crate being relicensed in a new version.
+    #[arg(long, value_name = "PATH")]
+    pub license_lock: Option<PathBuf>,
+    /// Updates the `--license-lock` file with the current license expressions
+    #[arg(long, requires = "license_lock")]
+    pub update_license_lock: bool,
+    /// Path to a snapshot of the crates in the graph, used to track how the graph changes between runs
+    ///
+    /// The name, version, and source of every crate in the graph are written to the file as JSON on each run. If the file already exists, a note listing the crates that were added, removed, or changed versions since the previous run is emitted as part of the bans check.
+    #[arg(long, value_name = "PATH")]
+    pub graph_snapshot: Option<PathBuf>,
+    /// Evaluates the specified SPDX license expression against the licenses config, then exits without running any checks
+    ///
+    /// The expression is evaluated as if it were the license of a crate that doesn't match any of the `licenses.exceptions`, and the reason each of its requirements is accepted or rejected is printed. The crate graph is never resolved, and the exit code is non-zero if the expression is rejected. Can only be used with the licenses check, eg. `cargo deny check licenses --eval "MIT OR Apache-2.0"`.
+    #[arg(long, value_name = "EXPR", conflicts_with_all = ["fetch_only", "dump_config", "validate_only"])]
+    pub eval: Option<String>,
     /// Show stats for all the checks, regardless of the log-level
     #[arg(short, long)]
     pub show_stats: bool,
+    /// Uses a distinct exit code for each check that failed
+    ///
+    /// By default the exit code is a bitset of the failed checks, starting at 1 for `advisories`, which can't be distinguished from other errors. With this flag the bitset starts at 2, ie. advisories=2, bans=4, licenses=8, sources=16, so that an exit code of 1 always means cargo-deny itself failed.
+    #[arg(long)]
+    pub exit_code_per_check: bool,
+    /// Treats warnings emitted by the specified check(s) as errors when determining if the check failed
+    ///
+    /// The severity of the diagnostics is not changed. Appends to the `output.deny-warnings` config field.
+    #[arg(long, value_enum, value_name = "CHECK")]
+    pub deny_warnings: Vec<WhichCheck>,
+    /// Fails if the total number of warnings across all checks exceeds the specified maximum
+    ///
+    /// If exceeded, every check that emitted a warning is considered failed, the same as with `--deny-warnings`. Errors always fail the check regardless of this option.
+    #[arg(long, value_name = "N")]
+    pub max_warnings: Option<u32>,
+    /// Adds the fingerprint of each diagnostic as a note
+    ///
+    /// The fingerprint identifies a specific diagnostic, and can be added to the `output.acknowledge` config field to downgrade that diagnostic to a note.
+    #[arg(long)]
+    pub list_fingerprints: bool,
+    /// Prints the wall-clock duration of each phase, such as gathering crate metadata and running each check
+    #[arg(long)]
+    pub timings: bool,
+    /// Adds suggested fixes to actionable diagnostics as notes
+    ///
+    /// Vulnerable and yanked crates get a `cargo update` command for the lowest version in the index that resolves them, duplicates that can be unified get a `cargo update` command for the newest version, and unused skips get a note to remove them from the config.
+    #[arg(long)]
+    pub suggest_fixes: bool,
+    /// Gathers license information for crates serially rather than in parallel
+    ///
+    /// License gathering is the slowest part of the licenses check, so it is parallelized by default. This makes the gathering fully deterministic at the cost of speed, which can be useful when comparing the license diagnostics of different runs.
+    #[arg(long)]
+    pub single_threaded_licenses: bool,
+    /// Verifies that the crates suggested via `use-instead` in `bans.deny` exist on crates.io
+    ///
+    /// Each suggested crate is looked up in the crates.io sparse index, and a warning is emitted for every suggestion that doesn't exist, eg. due to a typo or the crate being removed. This is skipped in offline mode.
+    #[arg(long)]
+    pub validate_suggestions: bool,
+    /// Checks the specified `Cargo.lock` for advisories, without needing a buildable workspace
+    ///
+    /// The crate graph is built from the lockfile instead of `cargo metadata`, so only the `advisories` check can be run, as the other checks require information that is not present in the lockfile.
+    #[arg(long, value_name = "PATH", conflicts_with = "fetch_only")]
+    pub lockfile: Option<PathBuf>,
+    /// Sorts diagnostics before they are printed
+    ///
+    /// The checks are run in parallel, so the order diagnostics are emitted in can vary between runs. Sorting orders them by check, location, code, and crate, so that the output of different runs can be easily compared. Sorting requires all diagnostics to be collected before any are printed.
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    pub sort: bool,
     #[command(flatten)]
     pub lint_levels: LintLevels,
     /// Specifies the depth at which feature edges are added in inclusion graphs
@@ -116,22 +230,55 @@ pub struct Args {
 pub(crate) fn cmd(
     log_ctx: crate::common::LogContext,
     args: Args,
-    mut krate_ctx: crate::common::KrateContext,
+    krate_ctxs: Vec<crate::common::KrateContext>,
+    outputs: Vec<(crate::Format, PathBuf)>,
 ) -> anyhow::Result<AllStats> {
     let mut files = Files::new();
-    let ValidConfig {
-        advisories,
-        bans,
-        licenses,
-        sources,
-        graph,
-        output,
-    } = ValidConfig::load(
-        krate_ctx.get_config_path(args.config.clone()),
-        krate_ctx.get_local_exceptions_path(),
-        &mut files,
-        log_ctx,
-    )?;
+
+    let multiple_workspaces = krate_ctxs.len() > 1;
+    anyhow::ensure!(
+        !(multiple_workspaces && args.prune_config),
+        "--prune-config can't be used when checking multiple workspaces, as an entry that is unused in one workspace may be used by another"
+    );
+    anyhow::ensure!(
+        !(multiple_workspaces && args.assert_lockfile_hash.is_some()),
+        "--assert-lockfile-hash can't be used when checking multiple workspaces"
+    );
+    anyhow::ensure!(
+        !(multiple_workspaces && args.lockfile.is_some()),
+        "--lockfile can't be used when checking multiple workspaces"
+    );
+    anyhow::ensure!(
+        args.lockfile.is_none()
+            || (!args.which.is_empty() && args.which.iter().all(|w| *w == WhichCheck::Advisories)),
+        "--lockfile can only be used with the advisories check, eg. `cargo deny check --lockfile Cargo.lock advisories`"
+    );
+    anyhow::ensure!(
+        args.eval.is_none()
+            || (!args.which.is_empty()
+                && args
+                    .which
+                    .iter()
+                    .all(|w| *w == WhichCheck::Licenses || *w == WhichCheck::License)),
+        "--eval can only be used with the licenses check, eg. `cargo deny check licenses --eval \"MIT OR Apache-2.0\"`"
+    );
+    anyhow::ensure!(
+        !(multiple_workspaces && args.license_lock.is_some()),
+        "--license-lock can't be used when checking multiple workspaces"
+    );
+    anyhow::ensure!(
+        !(multiple_workspaces && args.graph_snapshot.is_some()),
+        "--graph-snapshot can't be used when checking multiple workspaces"
+    );
+    anyhow::ensure!(
+        !(multiple_workspaces && args.dump_config),
+        "--dump-config can't be used when checking multiple workspaces"
+    );
+
+    // Every workspace is checked against the same config, which is resolved
+    // relative to the first manifest, though each workspace can still have
+    // its own local exceptions
+    let cfg_path = krate_ctxs[0].get_config_path(args.config.clone());
 
     let check_advisories = args.which.is_empty()
         || args
@@ -156,19 +303,158 @@ pub(crate) fn cmd(
             .iter()
             .any(|w| *w == WhichCheck::Sources || *w == WhichCheck::All);
 
+    let offline = krate_ctxs[0].offline;
+
+    let mut workspaces = Vec::with_capacity(krate_ctxs.len());
+    for mut krate_ctx in krate_ctxs {
+        let mut cfg = ValidConfig::load(
+            cfg_path.clone(),
+            krate_ctx.get_local_exceptions_path(),
+            (check_bans && !args.validate_only).then_some(krate_ctx.offline),
+            &mut files,
+            log_ctx,
+        )?;
+
+        krate_ctx.exclude_dev |= args.exclude_dev;
+        krate_ctx.merge_graph_config(&mut cfg.graph);
+
+        workspaces.push((krate_ctx, cfg));
+    }
+
+    crate::common::set_colors(workspaces[0].1.output.colors);
+
+    // Loading the config reports any problems with it, and fails if any of
+    // them are errors, so there is nothing else to do
+    if args.validate_only {
+        log::info!("the config is valid");
+        return Ok(AllStats::default());
+    }
+
+    if args.dump_config {
+        let cfg =
+            serde_json::to_string_pretty(&workspaces[0].1).context("failed to serialize config")?;
+        #[allow(clippy::disallowed_macros)]
+        {
+            println!("{cfg}");
+        }
+        return Ok(AllStats::default());
+    }
+
+    if let Some(expr) = &args.eval {
+        let parsed = spdx::Expression::parse(expr)
+            .with_context(|| format!("failed to parse license expression '{expr}'"))?;
+
+        let file_id = files.add("--eval", expr.clone());
+        let diag = licenses::evaluate(&workspaces[0].1.licenses, &parsed, file_id);
+        let rejected = diag.severity == Severity::Error;
+
+        // The reason for every requirement is the entire point, so the
+        // evaluation is printed regardless of the log level
+        let eval_ctx = crate::common::LogContext {
+            log_level: log::LevelFilter::Trace,
+            quiet: false,
+            ..log_ctx
+        };
+
+        if let Some(printer) = crate::common::DiagPrinter::new(eval_ctx, None, None, &[]) {
+            printer.lock().print(diag, &files);
+        }
+
+        anyhow::ensure!(
+            !rejected,
+            "license expression '{expr}' is not allowed by the config"
+        );
+        return Ok(AllStats::default());
+    }
+
+    let mut license_lock_out = None;
+    if let Some(path) = &args.license_lock {
+        let lock = licenses::LicenseLock::load(path)?;
+
+        // The lock is created the first time it is used
+        if lock.is_none() || args.update_license_lock {
+            license_lock_out = Some(path.clone());
+        }
+
+        workspaces[0].1.licenses.lock = lock;
+    }
+
+    if let Some(base_path) = &args.assert_subset_of {
+        anyhow::ensure!(
+            base_path.exists(),
+            "base config '{base_path}' specified with --assert-subset-of doesn't exist"
+        );
+
+        // A config is trivially a subset of itself
+        if files.id_for_path(base_path).is_none() {
+            let base = ValidConfig::load(Some(base_path.clone()), None, None, &mut files, log_ctx)?;
+
+            let diags: Vec<_> = workspaces
+                .iter()
+                .enumerate()
+                .flat_map(|(i, (_, cfg))| subset::exceeding(cfg, &base, base_path, i > 0))
+                .collect();
+
+            if !diags.is_empty() {
+                let count = diags.len();
+
+                if let Some(printer) = crate::common::DiagPrinter::new(log_ctx, None, None, &[]) {
+                    let mut lock = printer.lock();
+                    for diag in diags {
+                        lock.print(diag, &files);
+                    }
+                }
+
+                anyhow::bail!("the config allows {count} entries that are not allowed by the base config '{base_path}'");
+            }
+        }
+    }
+
+    if args.fetch_only {
+        fetch(&workspaces, check_advisories, &args)?;
+        return Ok(AllStats::default());
+    }
+
+    let output = &workspaces[0].1.output;
     let feature_depth = args.feature_depth.or(output.feature_depth);
 
-    krate_ctx.all_features |= graph.all_features;
-    krate_ctx.no_default_features |= graph.no_default_features;
-    krate_ctx.exclude_dev |= graph.exclude_dev | args.exclude_dev;
-    krate_ctx.exclude_unpublished |= graph.exclude_unpublished;
+    let deny_warnings = |check: Check, which: &[WhichCheck]| {
+        output.deny_warnings.contains(&check)
+            || args
+                .deny_warnings
+                .iter()
+                .any(|w| *w == WhichCheck::All || which.contains(w))
+    };
+
+    let mut stats = AllStats::default();
 
-    // If not specified on the cmd line, fallback to the feature related config options
-    if krate_ctx.features.is_empty() {
-        krate_ctx.features = graph.features;
+    let check_stats = |check: Check, which: &[WhichCheck]| Stats {
+        deny_warnings: deny_warnings(check, which),
+        ..Default::default()
+    };
+
+    if check_advisories {
+        stats.advisories = Some(check_stats(Check::Advisories, &[WhichCheck::Advisories]));
+    }
+
+    if check_bans {
+        stats.bans = Some(check_stats(
+            Check::Bans,
+            &[WhichCheck::Bans, WhichCheck::Ban],
+        ));
+    }
+
+    if check_licenses {
+        stats.licenses = Some(check_stats(
+            Check::Licenses,
+            &[WhichCheck::Licenses, WhichCheck::License],
+        ));
+    }
+
+    if check_sources {
+        stats.sources = Some(check_stats(Check::Sources, &[WhichCheck::Sources]));
     }
 
-    let mut krates = None;
     let mut license_store = None;
     let mut advisory_dbs = None;
 
@@ -176,7 +462,12 @@ pub(crate) fn cmd(
     let overrides = {
         let ll = args.lint_levels;
 
-        if ll.allow.is_empty() && ll.deny.is_empty() && ll.warn.is_empty() {
+        if ll.allow.is_empty()
+            && ll.deny.is_empty()
+            && ll.warn.is_empty()
+            && output.acknowledge.is_empty()
+            && !args.list_fingerprints
+        {
             None
         } else {
             let mut code_overrides = std::collections::BTreeMap::new();
@@ -221,27 +512,54 @@ pub(crate) fn cmd(
             Some(std::sync::Arc::new(DiagnosticOverrides {
                 code_overrides,
                 level_overrides,
+                acknowledged: output.acknowledge.iter().cloned().collect(),
+                list_fingerprints: args.list_fingerprints,
             }))
         }
     };
 
+    let (krate_ctxs, mut cfgs): (Vec<_>, Vec<_>) = workspaces.into_iter().unzip();
+    let graphs: Vec<_> = cfgs
+        .iter_mut()
+        .map(|cfg| {
+            (
+                std::mem::take(&mut cfg.graph.targets),
+                std::mem::take(&mut cfg.graph.exclude),
+            )
+        })
+        .collect();
+    let mut gathered: Vec<_> = krate_ctxs.iter().map(|_| None).collect();
+
+    let timings = args.timings.then(Timings::default);
+    let timings = timings.as_ref();
+
     rayon::scope(|s| {
-        s.spawn(|_s| {
-            // Always run a fetch first in a separate step so that the user can
-            // see what parts are actually taking time
-            let start = std::time::Instant::now();
-            log::info!("fetching crates for {}", krate_ctx.manifest_path);
-            if let Err(err) = krate_ctx.fetch_krates() {
-                log::error!("failed to fetch crates: {err:#}");
-            } else {
-                log::info!("fetched crates in {:?}", start.elapsed());
-            }
+        for ((krate_ctx, (targets, exclude)), krates) in
+            krate_ctxs.into_iter().zip(graphs).zip(gathered.iter_mut())
+        {
+            s.spawn(move |_s| {
+                // Always run a fetch first in a separate step so that the user can
+                // see what parts are actually taking time, unless there is only
+                // a lockfile, in which case there is nothing to fetch
+                if krate_ctx.lockfile.is_none() {
+                    let start = std::time::Instant::now();
+                    log::info!("fetching crates for {}", krate_ctx.manifest_path);
+                    if let Err(err) = krate_ctx.fetch_krates() {
+                        log::error!("failed to fetch crates: {err:#}");
+                    } else {
+                        log::info!("fetched crates in {:?}", start.elapsed());
+                    }
+                }
 
-            krates = Some(krate_ctx.gather_krates(graph.targets, graph.exclude));
-        });
+                *krates = Some(krate_ctx.gather_krates(targets, exclude, timings));
+            });
+        }
 
         if check_advisories {
+            let advisories = &cfgs[0].advisories;
+
             s.spawn(|_| {
+                let start = Instant::now();
                 advisory_dbs = Some(advisories::DbSet::load(
                     advisories.db_path.clone(),
                     advisories
@@ -249,14 +567,13 @@ pub(crate) fn cmd(
                         .iter()
                         .map(|us| us.as_ref().clone())
                         .collect(),
-                    if args.disable_fetch {
-                        advisories::Fetch::Disallow(advisories.maximum_db_staleness.value)
-                    } else if advisories.git_fetch_with_cli {
-                        advisories::Fetch::AllowWithGitCli
-                    } else {
-                        advisories::Fetch::Allow
-                    },
+                    db_fetch(&args, advisories),
+                    advisories.fetch_timeout(args.fetch_timeout),
                 ));
+
+                if let Some(timings) = timings {
+                    timings.record("advisory databases", start.elapsed());
+                }
             });
         }
 
@@ -265,70 +582,420 @@ pub(crate) fn cmd(
         }
     });
 
-    let krates = krates.unwrap()?;
-
     let advisory_db_set = if check_advisories {
         let dbset = advisory_dbs.unwrap()?;
+        if cfgs[0].advisories.require_db {
+            dbset.ensure_advisories()?;
+        }
         Some(dbset)
     } else {
         None
     };
 
-    let krate_spans = cargo_deny::diag::KrateSpans::synthesize(
-        &krates,
-        krates.workspace_root().as_str(),
-        &mut files,
-    );
-
-    let license_summary = if check_licenses {
-        let store = license_store.unwrap()?;
-        let gatherer = licenses::Gatherer::default()
-            .with_store(std::sync::Arc::new(store))
-            .with_confidence_threshold(licenses.confidence_threshold);
-
-        Some(gatherer.gather(&krates, &mut files, Some(&licenses)))
+    let license_store = if check_licenses {
+        Some(std::sync::Arc::new(license_store.unwrap()?))
     } else {
         None
     };
 
-    let graph_out_dir = args.graph;
+    let outputs = outputs
+        .into_iter()
+        .map(|(format, path)| crate::common::OutputFile::create(format, path))
+        .collect::<anyhow::Result<Vec<_>>>()?;
 
-    let (tx, rx) = crossbeam::channel::unbounded();
+    let shared = Shared {
+        log_ctx,
+        outputs: &outputs,
+        overrides,
+        advisory_db_set: advisory_db_set.as_ref(),
+        license_store,
+        check_bans,
+        check_sources,
+        offline,
+        show_inclusion_graphs: !args.hide_inclusion_graph,
+        audit_compatible_output: args.audit_compatible_output
+            && log_ctx.format == crate::Format::Json,
+        graph_out_dir: args.graph,
+        since: args.since,
+        prune_config: args.prune_config,
+        license_lock_out,
+        graph_snapshot: args.graph_snapshot,
+        feature_depth,
+        multiple_workspaces,
+        sort: args.sort,
+        timings,
+        suggest_fixes: args.suggest_fixes,
+        single_threaded_licenses: args.single_threaded_licenses,
+        validate_suggestions: args.validate_suggestions,
+    };
 
-    let krates = &krates;
+    // An entry in the shared config is only unused if none of the workspaces
+    // used it, so those diagnostics are reported once all of them are checked
+    let mut unused =
+        multiple_workspaces.then(|| unused::UnusedEntries::new(cfgs[0].annotations.file_id));
 
-    let mut stats = AllStats::default();
+    for (krates, cfg) in gathered.into_iter().zip(cfgs) {
+        let krates = krates.unwrap()?;
 
-    if check_advisories {
-        stats.advisories = Some(Stats::default());
+        if let Some(expected) = &args.assert_lockfile_hash {
+            let computed = graph_hash(&krates);
+            anyhow::ensure!(
+                &computed == expected,
+                "the hash of the crate graph '{computed}' does not match the expected hash '{expected}'"
+            );
+            log::info!("crate graph matches the expected hash '{expected}'");
+        }
+        check_workspace(
+            &shared,
+            krates,
+            cfg,
+            &mut files,
+            &mut stats,
+            unused.as_mut(),
+        )?;
+
+        if let Some(unused) = &mut unused {
+            unused.finish_workspace();
+        }
     }
 
-    if check_bans {
-        stats.bans = Some(Stats::default());
+    if let Some(unused) = unused {
+        let dp = crate::common::DiagPrinter::new(log_ctx, None, feature_depth, &outputs);
+        let mut lock = dp.as_ref().map(|dp| dp.lock());
+
+        for (check, diag) in unused.into_unused() {
+            record_stats(&mut stats, check, std::iter::once(&diag));
+
+            if let Some(lock) = &mut lock {
+                lock.print(diag.diag, &files);
+            }
+        }
     }
 
-    if check_licenses {
-        stats.licenses = Some(Stats::default());
+    if let Some(timings) = timings {
+        timings.print(log_ctx.format);
     }
 
-    if check_sources {
-        stats.sources = Some(Stats::default());
+    for output in outputs {
+        output.finish()?;
+    }
+
+    Ok(stats)
+}
+
+fn db_fetch(args: &Args, advisories: &advisories::cfg::ValidConfig) -> advisories::Fetch {
+    if args.disable_fetch {
+        advisories::Fetch::Disallow(advisories.maximum_db_staleness.value)
+    } else if advisories.git_fetch_with_cli {
+        advisories::Fetch::AllowWithGitCli
+    } else {
+        advisories::Fetch::Allow
+    }
+}
+
+/// Fetches the crates for every workspace, as well as the advisory databases
+/// if `fetch_dbs` is true, for `--fetch-only`
+fn fetch(
+    workspaces: &[(crate::common::KrateContext, ValidConfig)],
+    fetch_dbs: bool,
+    args: &Args,
+) -> anyhow::Result<()> {
+    let mut fetched: Vec<_> = workspaces.iter().map(|_| None).collect();
+    let mut advisory_dbs = None;
+
+    rayon::scope(|s| {
+        for ((krate_ctx, _), fetched) in workspaces.iter().zip(fetched.iter_mut()) {
+            s.spawn(move |_s| {
+                let start = std::time::Instant::now();
+                log::info!("fetching crates for {}", krate_ctx.manifest_path);
+                *fetched = Some(krate_ctx.fetch_krates());
+                log::info!("fetched crates in {:?}", start.elapsed());
+            });
+        }
+
+        if fetch_dbs {
+            let advisories = &workspaces[0].1.advisories;
+
+            s.spawn(|_| {
+                advisory_dbs = Some(advisories::DbSet::load(
+                    advisories.db_path.clone(),
+                    advisories
+                        .db_urls
+                        .iter()
+                        .map(|us| us.as_ref().clone())
+                        .collect(),
+                    db_fetch(args, advisories),
+                    advisories.fetch_timeout(args.fetch_timeout),
+                ));
+            });
+        }
+    });
+
+    for fetched in fetched {
+        fetched.unwrap().context("failed to fetch crates")?;
+    }
+
+    if let Some(dbs) = advisory_dbs {
+        dbs.context("failed to fetch advisory database")?;
     }
 
-    let show_inclusion_graphs = !args.hide_inclusion_graph;
-    let serialize_extra = match log_ctx.format {
-        crate::Format::Json => true,
-        crate::Format::Human => false,
+    Ok(())
+}
+
+/// Parses a hex encoded SHA-256 hash, normalized to lowercase so that it can
+/// be compared with the output of [`graph_hash`]
+fn parse_graph_hash(s: &str) -> anyhow::Result<String> {
+    anyhow::ensure!(
+        s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit()),
+        "'{s}' is not a valid hex encoded SHA-256 hash"
+    );
+
+    Ok(s.to_ascii_lowercase())
+}
+
+/// Calculates a hash of the name, version, and source of every crate in the
+/// graph, which only changes if the set of resolved crates changes
+///
+/// The hash is a hex encoded SHA-256 digest, as it is used to pin an audited
+/// graph, so it must not be feasible to produce a different graph with the
+/// same hash
+fn graph_hash(krates: &cargo_deny::Krates) -> String {
+    let mut entries: Vec<_> = krates
+        .krates()
+        .map(|krate| {
+            let source = krate
+                .source
+                .as_ref()
+                .map_or_else(|| "path".to_owned(), |src| src.to_string());
+            format!("{} {} {source}", krate.name, krate.version)
+        })
+        .collect();
+    entries.sort();
+
+    let digest = ring::digest::digest(&ring::digest::SHA256, entries.join("\n").as_bytes());
+
+    digest
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// The state shared by the checks for every workspace
+struct Shared<'a> {
+    log_ctx: crate::common::LogContext,
+    /// The formats whose diagnostics are written to files
+    outputs: &'a [crate::common::OutputFile],
+    overrides: Option<std::sync::Arc<DiagnosticOverrides>>,
+    advisory_db_set: Option<&'a advisories::DbSet>,
+    license_store: Option<std::sync::Arc<licenses::LicenseStore>>,
+    check_bans: bool,
+    check_sources: bool,
+    offline: bool,
+    show_inclusion_graphs: bool,
+    audit_compatible_output: bool,
+    graph_out_dir: Option<PathBuf>,
+    since: Option<String>,
+    prune_config: bool,
+    license_lock_out: Option<PathBuf>,
+    graph_snapshot: Option<PathBuf>,
+    feature_depth: Option<u32>,
+    multiple_workspaces: bool,
+    sort: bool,
+    timings: Option<&'a Timings>,
+    suggest_fixes: bool,
+    single_threaded_licenses: bool,
+    validate_suggestions: bool,
+}
+
+/// Runs all of the enabled checks against a single workspace
+fn check_workspace(
+    shared: &Shared<'_>,
+    krates: cargo_deny::Krates,
+    cfg: ValidConfig,
+    files: &mut Files,
+    stats: &mut AllStats,
+    unused: Option<&mut unused::UnusedEntries>,
+) -> anyhow::Result<()> {
+    let ValidConfig {
+        advisories,
+        mut bans,
+        licenses,
+        mut sources,
+        annotations,
+        ..
+    } = cfg;
+
+    let log_ctx = shared.log_ctx;
+    let overrides = shared.overrides.clone();
+    let check_bans = shared.check_bans;
+    let check_sources = shared.check_sources;
+    let show_inclusion_graphs = shared.show_inclusion_graphs;
+    let audit_compatible_output = shared.audit_compatible_output;
+    let feature_depth = shared.feature_depth;
+    let timings = shared.timings;
+    let suggest_fixes = shared.suggest_fixes;
+
+    let changed = shared
+        .since
+        .as_deref()
+        .map(|rev| since::Changed::load(&krates, rev))
+        .transpose()?;
+
+    if check_sources
+        && !sources.trusted_owners.is_empty()
+        && sources.untrusted_owner != cargo_deny::LintLevel::Allow
+    {
+        if shared.offline {
+            log::warn!("crate owners can't be retrieved in offline mode, skipping the `sources.trusted-owners` check");
+        } else {
+            sources.owners = Some(sources::CrateOwners::fetch(&krates));
+        }
+    }
+
+    // Suggestions can only be validated against the remote index, but unlike
+    // the other online checks this is purely config hygiene, so we don't
+    // bother warning when offline
+    if check_bans && shared.validate_suggestions && !shared.offline {
+        bans.suggested_crates = Some(bans::SuggestedCrates::fetch(&bans));
+    }
+
+    let krate_spans =
+        cargo_deny::diag::KrateSpans::synthesize(&krates, krates.workspace_root().as_str(), files);
+
+    let license_summary = if let Some(store) = &shared.license_store {
+        let mut gatherer = licenses::Gatherer::default()
+            .with_store(store.clone())
+            .with_confidence_threshold(licenses.confidence_threshold)
+            .with_single_threaded(shared.single_threaded_licenses);
+
+        let start = Instant::now();
+
+        if licenses.fetch_missing_license_files {
+            gatherer = gatherer.with_fetched_license_files(licenses::FetchedLicenseFiles::fetch(
+                &krates,
+                &licenses.license_file_patterns,
+                shared.offline,
+            ));
+        }
+
+        let summary = gatherer.gather(&krates, files, Some(&licenses));
+
+        if let Some(timings) = timings {
+            timings.record("license gathering", start.elapsed());
+        }
+
+        Some(summary)
+    } else {
+        None
+    };
+
+    if let Some((path, summary)) = shared
+        .license_lock_out
+        .as_ref()
+        .zip(license_summary.as_ref())
+    {
+        licenses::LicenseLock::from_summary(summary).write(path)?;
+        log::info!("wrote license lock to '{path}'");
+    }
+
+    // The change is reported by the bans check, so the snapshot is left as
+    // is if it isn't run, otherwise the change would never be reported
+    let graph_snapshot = shared.graph_snapshot.as_ref().filter(|path| {
+        if !check_bans {
+            log::warn!("the bans check is not being run, '{path}' was not updated");
+        }
+        check_bans
+    });
+
+    let graph_changed = if let Some(path) = graph_snapshot {
+        let current = graph_snapshot::GraphSnapshot::from_krates(&krates);
+        let changed = graph_snapshot::GraphSnapshot::load(path)?
+            .and_then(|previous| current.diff(&previous, path));
+
+        current.write(path)?;
+        log::info!("wrote graph snapshot to '{path}'");
+        changed
+    } else {
+        None
     };
-    let audit_compatible_output =
-        args.audit_compatible_output && log_ctx.format == crate::Format::Json;
+
+    // Each workspace gets its own directory if there are multiple, so that
+    // the graphs for one don't overwrite the graphs for another
+    let graph_out_dir = shared.graph_out_dir.as_ref().map(|pb| {
+        let mut pb = pb.join("graph_output");
+        if shared.multiple_workspaces {
+            pb.push(krates.workspace_root().file_name().unwrap_or("workspace"));
+        }
+        pb
+    });
+
+    // Tag every diagnostic with the workspace it came from if there are
+    // multiple, as the diagnostics for all of them are reported together
+    let workspace = shared
+        .multiple_workspaces
+        .then(|| krates.workspace_root().to_string());
+
+    let (tx, rx) = crossbeam::channel::unbounded();
+
+    let krates = &krates;
+
+    let serialize_extra = std::iter::once(log_ctx.format)
+        .chain(shared.outputs.iter().map(|of| of.format()))
+        .any(|format| match format {
+            crate::Format::Json => true,
+            crate::Format::Human
+            | crate::Format::Github
+            | crate::Format::Sarif
+            | crate::Format::Junit => false,
+        });
 
     let colorize = log_ctx.format == crate::Format::Human
         && crate::common::should_colorize(log_ctx.color, std::io::stderr());
 
     let log_level = log_ctx.log_level;
 
-    let files = &files;
+    let files = &*files;
+    let mut pruner = shared.prune_config.then(prune::Pruner::default);
+
+    let check_yanks = shared.advisory_db_set.is_some() && !advisories.disable_yank_checking;
+    let check_checksums = check_sources && sources.verify_checksums != cargo_deny::LintLevel::Allow;
+
+    // The index entries are used both for the yanked check and to verify the
+    // lockfile checksums, so they are only loaded once. We need to have all
+    // the crates when opening indices, so can't load them at the same time as
+    // the dbset, but meh, this should be very fast since we only load from
+    // cache, in parallel
+    let indices = (check_yanks || check_checksums).then(|| {
+        // If we can't find the cargo home directory, we won't be able
+        // to load the cargo indices. We _could_ actually do a fetch
+        // into a temporary directory instead, but this almost certainly
+        // means that something is wrong
+        tame_index::utils::cargo_home().map(|cargo_home| {
+            log::info!("loading index metadata for crates...");
+            let start = Instant::now();
+
+            let indices = advisories::Indices::load(krates, cargo_home);
+
+            log::info!(
+                "cached index metadata loaded in {}ms",
+                start.elapsed().as_millis()
+            );
+            indices
+        })
+    });
+
+    if check_checksums {
+        match &indices {
+            Some(Ok(indices)) => {
+                sources.checksums = Some(sources::CrateChecksums::load(krates, indices));
+            }
+            Some(Err(err)) => {
+                log::warn!("unable to find cargo home directory, skipping the `sources.verify-checksums` check: {err:#}");
+            }
+            None => {}
+        }
+    }
 
     rayon::scope(|s| {
         // Asynchronously displays messages sent from the checks
@@ -336,14 +1003,17 @@ pub(crate) fn cmd(
             print_diagnostics(
                 rx,
                 log_ctx,
-                if show_inclusion_graphs {
-                    Some(krates)
-                } else {
-                    None
-                },
+                shared.outputs,
+                krates,
+                show_inclusion_graphs,
                 files,
-                &mut stats,
+                stats,
+                changed.as_ref(),
+                pruner.as_mut(),
+                unused,
                 feature_depth,
+                workspace.as_deref(),
+                shared.sort,
             );
         });
 
@@ -361,6 +1031,8 @@ pub(crate) fn cmd(
                 colorize,
                 log_level,
                 files,
+                progress: None,
+                suggest_fixes,
             };
 
             s.spawn(move |_| {
@@ -369,12 +1041,15 @@ pub(crate) fn cmd(
                 licenses::check(ctx, summary, sink);
 
                 log::info!("licenses checked in {}ms", start.elapsed().as_millis());
+
+                if let Some(timings) = timings {
+                    timings.record("licenses check", start.elapsed());
+                }
             });
         }
 
         if check_bans {
-            let output_graph = graph_out_dir.map(|pb| -> Box<bans::OutputGraph> {
-                let output_dir = pb.join("graph_output");
+            let output_graph = graph_out_dir.map(|output_dir| -> Box<bans::OutputGraph> {
                 let _ = std::fs::remove_dir_all(&output_dir);
 
                 match std::fs::create_dir_all(&output_dir) {
@@ -399,6 +1074,36 @@ pub(crate) fn cmd(
                 }
             });
 
+            let annotations_sink = ErrorSink {
+                overrides: overrides.clone(),
+                channel: tx.clone(),
+            };
+
+            let ctx = CheckCtx {
+                cfg: annotations,
+                krates,
+                krate_spans: &krate_spans,
+                serialize_extra,
+                colorize,
+                log_level,
+                files,
+                progress: None,
+                suggest_fixes,
+            };
+
+            s.spawn(|_| annotations::check(ctx, annotations_sink));
+
+            if let Some(diag) = graph_changed {
+                let mut pack = Pack::new(Check::Bans);
+                pack.push(diag);
+
+                let mut sink = ErrorSink {
+                    overrides: overrides.clone(),
+                    channel: tx.clone(),
+                };
+                sink.push(pack);
+            }
+
             let bans_sink = ErrorSink {
                 overrides: overrides.clone(),
                 channel: tx.clone(),
@@ -412,6 +1117,8 @@ pub(crate) fn cmd(
                 colorize,
                 log_level,
                 files,
+                progress: None,
+                suggest_fixes,
             };
 
             s.spawn(|_| {
@@ -420,6 +1127,10 @@ pub(crate) fn cmd(
                 bans::check(ctx, output_graph, bans_sink);
 
                 log::info!("bans checked in {}ms", start.elapsed().as_millis());
+
+                if let Some(timings) = timings {
+                    timings.record("bans check", start.elapsed());
+                }
             });
         }
 
@@ -437,6 +1148,8 @@ pub(crate) fn cmd(
                 colorize,
                 log_level,
                 files,
+                progress: None,
+                suggest_fixes,
             };
 
             s.spawn(|_| {
@@ -445,10 +1158,14 @@ pub(crate) fn cmd(
                 sources::check(ctx, sources_sink);
 
                 log::info!("sources checked in {}ms", start.elapsed().as_millis());
+
+                if let Some(timings) = timings {
+                    timings.record("sources check", start.elapsed());
+                }
             });
         }
 
-        if let Some(dbset) = advisory_db_set {
+        if let Some(dbset) = shared.advisory_db_set {
             let mut advisories_sink = ErrorSink {
                 overrides,
                 channel: tx,
@@ -462,39 +1179,20 @@ pub(crate) fn cmd(
                 colorize,
                 log_level,
                 files,
+                progress: None,
+                suggest_fixes,
             };
 
             s.spawn(move |_| {
-                // We need to have all the crates when opening indices, so can't
-                // load them at the same time as the dbset, but meh, this should
-                // be very fast since we only load from cache, in parallel
-                let indices = if !ctx.cfg.disable_yank_checking {
-                    // If we can't find the cargo home directory, we won't be able
-                    // to load the cargo indices. We _could_ actually do a fetch
-                    // into a temporary directory instead, but this almost certainly
-                    // means that something is wrong
-                    match tame_index::utils::cargo_home() {
-                        Ok(cargo_home) => {
-                            log::info!("loading index metadata for crates...");
-                            let start = Instant::now();
-
-                            let indices = advisories::Indices::load(krates, cargo_home);
-
-                            log::info!(
-                                "cached index metadata loaded in {}ms",
-                                start.elapsed().as_millis()
-                            );
-                            Some(indices)
-                        }
-                        Err(err) => {
-                            advisories_sink.push(ctx.diag_for_index_load_failure(format!(
-                                "unable to find cargo home directory: {err:#}"
-                            )));
-                            None
-                        }
+                let indices = match indices.filter(|_| !ctx.cfg.disable_yank_checking) {
+                    Some(Ok(indices)) => Some(indices),
+                    Some(Err(err)) => {
+                        advisories_sink.push(ctx.diag_for_index_load_failure(format!(
+                            "unable to find cargo home directory: {err:#}"
+                        )));
+                        None
                     }
-                } else {
-                    None
+                    None => None,
                 };
 
                 log::info!("checking advisories...");
@@ -509,49 +1207,175 @@ pub(crate) fn cmd(
                     None
                 };
 
-                advisories::check(ctx, &dbset, audit_reporter, indices, advisories_sink);
+                advisories::check(ctx, dbset, audit_reporter, indices, advisories_sink);
 
                 log::info!("advisories checked in {}ms", start.elapsed().as_millis());
+
+                if let Some(timings) = timings {
+                    timings.record("advisories check", start.elapsed());
+                }
             });
         }
     });
 
-    Ok(stats)
+    if let Some(pruner) = pruner {
+        pruner.prune(files)?;
+    }
+
+    Ok(())
 }
 
 #[allow(clippy::too_many_arguments)]
 fn print_diagnostics(
     rx: crossbeam::channel::Receiver<cargo_deny::diag::Pack>,
     log_ctx: crate::common::LogContext,
-    krates: Option<&cargo_deny::Krates>,
+    outputs: &[crate::common::OutputFile],
+    krates: &cargo_deny::Krates,
+    show_inclusion_graphs: bool,
     files: &Files,
     stats: &mut AllStats,
+    changed: Option<&since::Changed>,
+    mut pruner: Option<&mut prune::Pruner>,
+    mut unused: Option<&mut unused::UnusedEntries>,
     feature_depth: Option<u32>,
+    workspace: Option<&str>,
+    sort: bool,
 ) {
-    use cargo_deny::diag::Check;
+    // The github and sarif formats always need the graph to determine if a
+    // diagnostic can be attached to a workspace manifest
+    let needs_krates = std::iter::once(log_ctx.format)
+        .chain(outputs.iter().map(|of| of.format()))
+        .any(|format| matches!(format, crate::Format::Github | crate::Format::Sarif));
+    let dp = crate::common::DiagPrinter::new(
+        log_ctx,
+        (show_inclusion_graphs || needs_krates).then_some(krates),
+        feature_depth,
+        outputs,
+    );
 
-    let dp = crate::common::DiagPrinter::new(log_ctx, krates, feature_depth);
+    let mut sorted = Vec::new();
 
-    for pack in rx {
-        let check_stats = match pack.check {
-            Check::Advisories => stats.advisories.as_mut().unwrap(),
-            Check::Bans => stats.bans.as_mut().unwrap(),
-            Check::Licenses => stats.licenses.as_mut().unwrap(),
-            Check::Sources => stats.sources.as_mut().unwrap(),
-        };
+    for mut pack in rx {
+        // Advisories are always reported for the full graph, but all other
+        // crate specific diagnostics are only reported for changed crates
+        if let Some((changed, kid)) = changed.zip(pack.kid()) {
+            if !matches!(pack.check, Check::Advisories) && !changed.contains(kid) {
+                continue;
+            }
+        }
+
+        if let Some(unused) = unused.as_deref_mut() {
+            unused.collect(&mut pack);
+
+            if pack.iter().next().is_none() {
+                continue;
+            }
+        }
+
+        record_stats(stats, pack.check, pack.iter());
 
-        for diag in pack.iter() {
-            match diag.diag.severity {
-                Severity::Error => check_stats.errors += 1,
-                Severity::Warning => check_stats.warnings += 1,
-                Severity::Note => check_stats.notes += 1,
-                Severity::Help => check_stats.helps += 1,
-                Severity::Bug => {}
+        if let Some(pruner) = pruner.as_deref_mut() {
+            pruner.collect(&pack);
+        }
+
+        if let Some(workspace) = workspace {
+            for diag in pack.iter_mut() {
+                diag.diag.notes.push(format!("workspace: {workspace}"));
             }
         }
 
-        if let Some(mut lock) = dp.as_ref().map(|dp| dp.lock()) {
+        if sort {
+            sorted.push(pack);
+        } else if let Some(mut lock) = dp.as_ref().map(|dp| dp.lock()) {
+            lock.print_krate_pack(pack, files);
+        }
+    }
+
+    if sorted.is_empty() {
+        return;
+    }
+
+    let krate = |pack: &cargo_deny::diag::Pack| {
+        pack.kid()
+            .and_then(|kid| krates.node_for_kid(kid))
+            .map(|krate| (krate.name.as_str(), &krate.version))
+    };
+
+    // Diagnostics are sorted within each pack, and packs are then sorted by
+    // their first diagnostic, which keeps the diagnostics for the same crate
+    // together so that their inclusion graphs are only printed once
+    for pack in &mut sorted {
+        let krate = krate(pack);
+        pack.sort_by(|a, b| sort_key(a, krate).cmp(&sort_key(b, krate)));
+    }
+
+    sorted.sort_by(|a, b| {
+        let key = |pack: &cargo_deny::diag::Pack| {
+            (
+                pack.check,
+                pack.iter().next().map(|diag| sort_key(diag, krate(pack))),
+            )
+        };
+
+        key(a).cmp(&key(b))
+    });
+
+    if let Some(dp) = &dp {
+        let mut lock = dp.lock();
+        for pack in sorted {
             lock.print_krate_pack(pack, files);
         }
     }
 }
+
+/// Counts the diagnostics emitted by a check by their severity
+fn record_stats<'d>(
+    stats: &mut AllStats,
+    check: Check,
+    diags: impl Iterator<Item = &'d cargo_deny::diag::Diag>,
+) {
+    let check_stats = match check {
+        Check::Advisories => stats.advisories.as_mut().unwrap(),
+        Check::Bans => stats.bans.as_mut().unwrap(),
+        Check::Licenses => stats.licenses.as_mut().unwrap(),
+        Check::Sources => stats.sources.as_mut().unwrap(),
+    };
+
+    for diag in diags {
+        match diag.diag.severity {
+            Severity::Error => check_stats.errors += 1,
+            Severity::Warning => check_stats.warnings += 1,
+            Severity::Note => check_stats.notes += 1,
+            Severity::Help => check_stats.helps += 1,
+            Severity::Bug => {}
+        }
+    }
+}
+
+/// The key used to deterministically order diagnostics, which is the primary
+/// location, code, and crate the diagnostic refers to, with the message as the
+/// final tie breaker
+#[allow(clippy::type_complexity)]
+fn sort_key<'d>(
+    diag: &'d cargo_deny::diag::Diag,
+    krate: Option<(&'d str, &'d semver::Version)>,
+) -> (
+    Option<(usize, usize)>,
+    Option<&'d str>,
+    Option<(&'d str, &'d semver::Version)>,
+    &'d str,
+) {
+    let labels = &diag.diag.labels;
+    let location = labels
+        .iter()
+        .find(|label| label.style == codespan_reporting::diagnostic::LabelStyle::Primary)
+        .or_else(|| labels.first())
+        .map(|label| (label.file_id, label.range.start));
+
+    (
+        location,
+        diag.diag.code.as_deref(),
+        krate,
+        &diag.diag.message,
+    )
+}
@@ -0,0 +1,204 @@
+//! Support for `check --graph-snapshot <path>`, which records the crates in
+//! the graph each run, and reports the crates that were added, removed, or
+//! changed since the previous run
+
+use anyhow::Context as _;
+use cargo_deny::{
+    diag::{general::Code, Diagnostic, Severity},
+    Krates, Path,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+struct SnapshotKrate {
+    name: String,
+    version: semver::Version,
+    /// The source of the crate, `None` for path dependencies
+    source: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SnapshotFile {
+    version: u32,
+    crates: Vec<SnapshotKrate>,
+}
+
+/// The name, version, and source of every crate in the graph
+pub(crate) struct GraphSnapshot {
+    krates: BTreeSet<SnapshotKrate>,
+}
+
+impl GraphSnapshot {
+    pub(crate) fn from_krates(krates: &Krates) -> Self {
+        Self {
+            krates: krates
+                .krates()
+                .map(|krate| SnapshotKrate {
+                    name: krate.name.clone(),
+                    version: krate.version.clone(),
+                    source: krate.source.as_ref().map(|src| src.to_string()),
+                })
+                .collect(),
+        }
+    }
+
+    /// Loads the snapshot at the specified path, returning `None` if it
+    /// doesn't exist yet
+    pub(crate) fn load(path: &Path) -> anyhow::Result<Option<Self>> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read graph snapshot '{path}'"))
+            }
+        };
+
+        let file: SnapshotFile = serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse graph snapshot '{path}'"))?;
+        anyhow::ensure!(
+            file.version == FORMAT_VERSION,
+            "graph snapshot '{path}' has format version {}, but only version {FORMAT_VERSION} is supported",
+            file.version
+        );
+
+        Ok(Some(Self {
+            krates: file.crates.into_iter().collect(),
+        }))
+    }
+
+    /// Writes the snapshot to the specified path, the crates are sorted so
+    /// that the file only changes if the graph does
+    pub(crate) fn write(&self, path: &Path) -> anyhow::Result<()> {
+        let file = SnapshotFile {
+            version: FORMAT_VERSION,
+            crates: self.krates.iter().cloned().collect(),
+        };
+
+        let mut contents =
+            serde_json::to_string_pretty(&file).context("failed to serialize graph snapshot")?;
+        contents.push('\n');
+
+        std::fs::write(path, contents)
+            .with_context(|| format!("failed to write graph snapshot '{path}'"))
+    }
+
+    /// Creates a note listing the crates that were added, removed, or changed
+    /// versions since the previous snapshot, or `None` if nothing changed
+    ///
+    /// Crates are identified by their name and source, so if there are
+    /// multiple versions of a crate, the removed and added versions are paired
+    /// in order as version changes
+    pub(crate) fn diff(&self, previous: &Self, path: &Path) -> Option<Diagnostic> {
+        type Versions<'s> = BTreeMap<(&'s str, Option<&'s str>), BTreeSet<&'s semver::Version>>;
+
+        fn versions(snapshot: &GraphSnapshot) -> Versions<'_> {
+            let mut versions = Versions::new();
+            for krate in &snapshot.krates {
+                versions
+                    .entry((krate.name.as_str(), krate.source.as_deref()))
+                    .or_default()
+                    .insert(&krate.version);
+            }
+            versions
+        }
+
+        let current = versions(self);
+        let previous = versions(previous);
+
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        let mut changed = Vec::new();
+
+        let keys: BTreeSet<_> = current.keys().chain(previous.keys()).collect();
+        let empty = BTreeSet::new();
+
+        for key in keys {
+            let (name, source) = *key;
+            let cur = current.get(key).unwrap_or(&empty);
+            let prev = previous.get(key).unwrap_or(&empty);
+
+            let display = |version: &semver::Version| {
+                // Crates from crates.io are by far the most common, so we
+                // only note the source of the crates that aren't
+                match source.filter(|src| !src.ends_with(tame_index::CRATES_IO_INDEX)) {
+                    Some(src) => format!("'{name} = {version}' ({src})"),
+                    None => format!("'{name} = {version}'"),
+                }
+            };
+
+            let mut new = cur.difference(prev).peekable();
+            let mut old = prev.difference(cur).peekable();
+
+            while let (Some(o), Some(n)) = (old.peek(), new.peek()) {
+                changed.push(format!("changed {} -> {n}", display(o)));
+                old.next();
+                new.next();
+            }
+
+            added.extend(new.map(|n| format!("added {}", display(n))));
+            removed.extend(old.map(|o| format!("removed {}", display(o))));
+        }
+
+        if added.is_empty() && removed.is_empty() && changed.is_empty() {
+            return None;
+        }
+
+        Some(
+            Diagnostic::new(Severity::Note)
+                .with_message(format!(
+                    "the crate graph changed since the graph snapshot '{path}': {} added, {} removed, {} changed",
+                    added.len(),
+                    removed.len(),
+                    changed.len()
+                ))
+                .with_code(Code::GraphChanged)
+                .with_notes(added.into_iter().chain(removed).chain(changed).collect()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn snapshot(krates: &[(&str, &str)]) -> GraphSnapshot {
+        GraphSnapshot {
+            krates: krates
+                .iter()
+                .map(|(name, version)| SnapshotKrate {
+                    name: (*name).to_owned(),
+                    version: version.parse().unwrap(),
+                    source: Some(format!("registry+{}", tame_index::CRATES_IO_INDEX)),
+                })
+                .collect(),
+        }
+    }
+
+    /// Ensures crates are reported as added, removed, or changed, and that
+    /// nothing is reported if the graph didn't change
+    #[test]
+    fn diffs_snapshots() {
+        let path = Path::new("graph.json");
+        let previous = snapshot(&[("a", "1.0.0"), ("b", "0.1.0"), ("c", "2.0.0")]);
+
+        assert!(previous.diff(&previous, path).is_none());
+
+        let current = snapshot(&[("a", "1.0.0"), ("c", "2.1.0"), ("d", "0.3.0")]);
+        let diag = current.diff(&previous, path).unwrap();
+
+        assert_eq!(
+            diag.message,
+            "the crate graph changed since the graph snapshot 'graph.json': 1 added, 1 removed, 1 changed"
+        );
+        assert_eq!(
+            diag.notes,
+            [
+                "added 'd = 0.3.0'",
+                "removed 'b = 0.1.0'",
+                "changed 'c = 2.0.0' -> 2.1.0"
+            ]
+        );
+    }
+}
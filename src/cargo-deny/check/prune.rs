@@ -0,0 +1,346 @@
+//! Support for `check --prune-config`, which removes configuration entries
+//! that the checks reported as not being used
+
+use anyhow::Context as _;
+use cargo_deny::{
+    advisories, bans,
+    diag::{FileId, Files, Pack},
+    licenses, sources,
+};
+use codespan_reporting::diagnostic::LabelStyle;
+use std::{collections::BTreeMap, ops::Range};
+use toml_edit::{Item, Value};
+
+/// Diagnostic codes that are emitted for configuration entries that did not
+/// match anything
+fn is_prunable(code: &str) -> bool {
+    let prunable: [&'static str; 9] = [
+        bans::Code::UnmatchedSkip.into(),
+        bans::Code::UnmatchedSkipRoot.into(),
+        bans::Code::UnmatchedDenyBelow.into(),
+        licenses::Code::LicenseExceptionNotEncountered.into(),
+        advisories::Code::AdvisoryNotDetected.into(),
+        advisories::Code::YankedNotDetected.into(),
+        sources::Code::UnmatchedSource.into(),
+        sources::Code::UnmatchedOrganization.into(),
+        sources::Code::UnmatchedGitHost.into(),
+    ];
+
+    prunable.contains(&code)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// An array element to remove
+struct Removal {
+    path: Vec<Segment>,
+    index: usize,
+    /// The original text of the element, for the summary
+    display: String,
+}
+
+#[derive(Default)]
+pub(crate) struct Pruner {
+    unused: BTreeMap<FileId, Vec<Range<usize>>>,
+}
+
+impl Pruner {
+    /// Records the config locations of any diagnostics that indicate an unused
+    /// configuration entry
+    pub(crate) fn collect(&mut self, pack: &Pack) {
+        for diag in pack.iter() {
+            if !diag.diag.code.as_deref().is_some_and(is_prunable) {
+                continue;
+            }
+
+            if let Some(label) = diag
+                .diag
+                .labels
+                .iter()
+                .find(|l| l.style == LabelStyle::Primary)
+            {
+                self.unused
+                    .entry(label.file_id)
+                    .or_default()
+                    .push(label.range.clone());
+            }
+        }
+    }
+
+    /// Removes the entries that contain the unused locations from each config
+    /// file they were found in, preserving all other formatting and comments
+    pub(crate) fn prune(self, files: &Files) -> anyhow::Result<()> {
+        use codespan_reporting::files::Files as _;
+
+        if self.unused.is_empty() {
+            log::info!("no unused configuration entries to prune");
+            return Ok(());
+        }
+
+        for (file_id, unused) in self.unused {
+            let path = files
+                .name(file_id)
+                .context("unable to find config file path")?;
+
+            let doc = toml_edit::ImDocument::parse(files.source(file_id))
+                .with_context(|| format!("failed to parse '{path}'"))?;
+
+            let mut removals = Vec::new();
+            find_removals(doc.as_item(), &mut Vec::new(), &unused, &mut removals);
+
+            if removals.is_empty() {
+                continue;
+            }
+
+            // Remove later elements first so that earlier removals don't change
+            // the indices of later ones
+            removals.sort_by(|a, b| (&b.path, b.index).cmp(&(&a.path, a.index)));
+
+            let mut doc = doc.into_mut();
+            for removal in &removals {
+                remove(doc.as_item_mut(), &removal.path, removal.index);
+            }
+
+            std::fs::write(path, doc.to_string())
+                .with_context(|| format!("failed to write '{path}'"))?;
+
+            for removal in removals.iter().rev() {
+                log::warn!(
+                    "pruned unused entry {} from '{path}'",
+                    removal.display.trim()
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[inline]
+fn contains_unused(span: Option<Range<usize>>, unused: &[Range<usize>]) -> bool {
+    span.is_some_and(|span| {
+        unused
+            .iter()
+            .any(|u| span.start <= u.start && u.end <= span.end)
+    })
+}
+
+fn find_removals(
+    item: &Item,
+    path: &mut Vec<Segment>,
+    unused: &[Range<usize>],
+    removals: &mut Vec<Removal>,
+) {
+    match item {
+        Item::Table(table) => {
+            for (key, item) in table.iter() {
+                path.push(Segment::Key(key.to_owned()));
+                find_removals(item, path, unused, removals);
+                path.pop();
+            }
+        }
+        Item::ArrayOfTables(aot) => {
+            for (index, table) in aot.iter().enumerate() {
+                if contains_unused(table.span(), unused) {
+                    removals.push(Removal {
+                        path: path.clone(),
+                        index,
+                        display: table
+                            .iter()
+                            .map(|(k, v)| format!("{k} = {}", v.to_string().trim()))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    });
+                } else {
+                    path.push(Segment::Index(index));
+                    for (key, item) in table.iter() {
+                        path.push(Segment::Key(key.to_owned()));
+                        find_removals(item, path, unused, removals);
+                        path.pop();
+                    }
+                    path.pop();
+                }
+            }
+        }
+        Item::Value(value) => find_value_removals(value, path, unused, removals),
+        Item::None => {}
+    }
+}
+
+fn find_value_removals(
+    value: &Value,
+    path: &mut Vec<Segment>,
+    unused: &[Range<usize>],
+    removals: &mut Vec<Removal>,
+) {
+    match value {
+        Value::Array(array) => {
+            for (index, value) in array.iter().enumerate() {
+                if contains_unused(value.span(), unused) {
+                    removals.push(Removal {
+                        path: path.clone(),
+                        index,
+                        display: value.to_string(),
+                    });
+                } else {
+                    path.push(Segment::Index(index));
+                    find_value_removals(value, path, unused, removals);
+                    path.pop();
+                }
+            }
+        }
+        Value::InlineTable(table) => {
+            for (key, value) in table.iter() {
+                path.push(Segment::Key(key.to_owned()));
+                find_value_removals(value, path, unused, removals);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn remove(root: &mut Item, path: &[Segment], index: usize) {
+    let mut item = root;
+    for segment in path {
+        item = match segment {
+            Segment::Key(key) => &mut item[key.as_str()],
+            Segment::Index(index) => &mut item[*index],
+        };
+    }
+
+    match item {
+        Item::ArrayOfTables(aot) => aot.remove(index),
+        Item::Value(Value::Array(array)) => {
+            array.remove(index);
+        }
+        _ => unreachable!("removals are only ever recorded for arrays"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cargo_deny::diag::{Check, Diagnostic, Label};
+
+    const CONFIG: &str = r#"# The policy for the project
+[bans]
+multiple-versions = "deny" # duplicates are not allowed
+skip = [
+    # Needed until the next release
+    "used-skip",
+    "unused-skip-1",
+    { crate = "unused-skip-2", reason = "no longer needed" },
+    "used-skip-2",
+    "unused-skip-3",
+]
+
+# Exceptions for crates with unusual licenses
+[[licenses.exceptions]]
+crate = "used-exception"
+allow = ["Zlib"]
+
+# This crate was removed
+[[licenses.exceptions]]
+crate = "unused-exception"
+allow = ["ISC"]
+
+[[licenses.exceptions]]
+crate = "used-exception-2"
+allow = ["BSL-1.0"]
+"#;
+
+    /// Runs the pruner over the config, with a diagnostic with the specified
+    /// code for each of the specified strings, returning the pruned config
+    fn prune(unused: &[(&str, &str)]) -> String {
+        let td = tempfile::tempdir().unwrap();
+        let path = cargo_deny::PathBuf::from_path_buf(td.path().join("deny.toml")).unwrap();
+        std::fs::write(&path, CONFIG).unwrap();
+
+        let mut files = Files::new();
+        let file_id = files.add(&path, CONFIG);
+
+        let mut pruner = Pruner::default();
+        for (code, unused) in unused {
+            let start = CONFIG.find(unused).unwrap();
+            pruner.collect(&Pack::from((
+                Check::Bans,
+                Diagnostic::warning()
+                    .with_code(*code)
+                    .with_labels(vec![Label::primary(file_id, start..start + unused.len())]),
+            )));
+        }
+
+        pruner.prune(&files).unwrap();
+        std::fs::read_to_string(&path).unwrap()
+    }
+
+    fn array(doc: &toml_edit::DocumentMut, path: &[&str]) -> Vec<String> {
+        let mut item = doc.as_item();
+        for key in path {
+            item = &item[*key];
+        }
+
+        if let Some(aot) = item.as_array_of_tables() {
+            aot.iter()
+                .map(|table| table["crate"].as_str().unwrap().to_owned())
+                .collect()
+        } else {
+            item.as_array()
+                .unwrap()
+                .iter()
+                .map(|value| match value {
+                    Value::InlineTable(table) => table["crate"].as_str().unwrap().to_owned(),
+                    value => value.as_str().unwrap().to_owned(),
+                })
+                .collect()
+        }
+    }
+
+    /// Ensures only the entries reported as unused are removed, even when
+    /// several elements are removed from the same array, and that comments
+    /// and formatting of the rest of the config are preserved
+    #[test]
+    fn prunes_unused_entries() {
+        let unmatched_skip: &str = bans::Code::UnmatchedSkip.into();
+        let exception: &str = licenses::Code::LicenseExceptionNotEncountered.into();
+
+        let pruned = prune(&[
+            (unmatched_skip, "\"unused-skip-1\""),
+            (unmatched_skip, "unused-skip-2"),
+            (unmatched_skip, "\"unused-skip-3\""),
+            (exception, "\"unused-exception\""),
+            // Diagnostics that don't indicate an unused entry are ignored
+            ("banned", "\"used-skip-2\""),
+        ]);
+
+        let doc: toml_edit::DocumentMut = pruned.parse().unwrap();
+        assert_eq!(array(&doc, &["bans", "skip"]), ["used-skip", "used-skip-2"]);
+        assert_eq!(
+            array(&doc, &["licenses", "exceptions"]),
+            ["used-exception", "used-exception-2"]
+        );
+
+        for kept in [
+            "# The policy for the project\n[bans]\n",
+            "multiple-versions = \"deny\" # duplicates are not allowed\n",
+            "    # Needed until the next release\n    \"used-skip\",\n",
+            "# Exceptions for crates with unusual licenses\n[[licenses.exceptions]]\ncrate = \"used-exception\"\nallow = [\"Zlib\"]\n",
+        ] {
+            assert!(pruned.contains(kept), "'{kept}' was not preserved:\n{pruned}");
+        }
+
+        assert!(!pruned.contains("# This crate was removed"));
+        assert!(!pruned.contains("no longer needed"));
+    }
+
+    /// Ensures the config is left untouched if nothing is unused
+    #[test]
+    fn leaves_used_config_untouched() {
+        assert_eq!(prune(&[]), CONFIG);
+    }
+}
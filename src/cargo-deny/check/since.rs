@@ -0,0 +1,185 @@
+//! Support for `check --since <git-ref>`, which restricts the crates that
+//! diagnostics are reported for to the ones that were added or changed in the
+//! lockfile since the specified revision
+
+use anyhow::Context as _;
+use cargo_deny::{Kid, Krates};
+use std::collections::{BTreeSet, HashSet};
+
+/// The set of crates that were added or changed since a revision
+pub(crate) struct Changed {
+    kids: BTreeSet<Kid>,
+}
+
+impl Changed {
+    /// Diffs the workspace's current `Cargo.lock` against the one at the
+    /// specified git revision
+    pub(crate) fn load(krates: &Krates, rev: &str) -> anyhow::Result<Self> {
+        let lock_path = krates.workspace_root().join("Cargo.lock");
+
+        let previous = read_lockfile_at(&lock_path, rev)
+            .with_context(|| format!("failed to read '{lock_path}' at revision '{rev}'"))?;
+
+        let kids = diff(krates, &previous);
+
+        log::info!(
+            "{} of {} crates were added or changed since '{rev}'",
+            kids.len(),
+            krates.len()
+        );
+
+        Ok(Self { kids })
+    }
+
+    /// Returns true if the crate was added or changed since the revision
+    #[inline]
+    pub(crate) fn contains(&self, kid: &Kid) -> bool {
+        self.kids.contains(kid)
+    }
+}
+
+/// Gets the crates in the graph whose name and version are not in the
+/// previous lockfile, ie. crates that were added, or whose version changed
+///
+/// Crates that were removed are not in the graph, and so are never reported
+fn diff(krates: &Krates, previous: &HashSet<(String, String)>) -> BTreeSet<Kid> {
+    krates
+        .krates()
+        .filter(|krate| !previous.contains(&(krate.name.clone(), krate.version.to_string())))
+        .map(|krate| krate.id.clone())
+        .collect()
+}
+
+/// Reads the name and version of every package in the lockfile at the
+/// specified revision
+fn read_lockfile_at(
+    lock_path: &cargo_deny::Path,
+    rev: &str,
+) -> anyhow::Result<HashSet<(String, String)>> {
+    let repo = gix::discover(lock_path.parent().context("lockfile has no parent")?)
+        .context("failed to discover git repository")?;
+
+    let work_dir = repo
+        .work_dir()
+        .context("git repository does not have a working directory")?;
+    let work_dir = cargo_deny::Path::from_path(work_dir).context("non-utf8 path")?;
+    // The path in the tree always uses forward slashes
+    let rel_path = lock_path
+        .strip_prefix(work_dir)
+        .context("lockfile is not inside the git repository")?
+        .as_str()
+        .replace('\\', "/");
+
+    let blob = repo
+        .rev_parse_single(format!("{rev}:{rel_path}").as_str())
+        .context("failed to resolve revision")?
+        .object()
+        .context("failed to read lockfile blob")?;
+
+    let contents = std::str::from_utf8(&blob.data).context("lockfile is not utf-8")?;
+    parse_lockfile(contents)
+}
+
+/// Parses the name and version of every package in the lockfile
+fn parse_lockfile(contents: &str) -> anyhow::Result<HashSet<(String, String)>> {
+    let lockfile = toml_span::parse(contents)?;
+
+    let mut set = HashSet::new();
+
+    let Some(packages) = lockfile.pointer("/package").and_then(|p| p.as_array()) else {
+        return Ok(set);
+    };
+
+    for pkg in packages.iter().filter_map(|p| p.as_table()) {
+        let field = |key: &str| pkg.get(key).and_then(|v| v.as_str()).map(String::from);
+
+        if let Some((name, version)) = field("name").zip(field("version")) {
+            set.insert((name, version));
+        }
+    }
+
+    Ok(set)
+}
+
+#[cfg(test)]
+mod test {
+    /// Ensures crates that were added or whose version changed are reported,
+    /// while unchanged and removed crates are not
+    #[test]
+    fn diffs_lockfiles() {
+        let previous = super::parse_lockfile(
+            r#"
+version = 3
+
+[[package]]
+name = "since-test"
+version = "0.1.0"
+dependencies = ["unchanged", "changed", "removed"]
+
+[[package]]
+name = "unchanged"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "changed"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "removed"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(previous.len(), 4);
+
+        let td = tempfile::tempdir().unwrap();
+        let lock_path = cargo_deny::PathBuf::from_path_buf(td.path().join("Cargo.lock")).unwrap();
+        std::fs::write(
+            &lock_path,
+            r#"
+version = 3
+
+[[package]]
+name = "since-test"
+version = "0.1.0"
+dependencies = ["unchanged", "changed", "added"]
+
+[[package]]
+name = "unchanged"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "changed"
+version = "2.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "added"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+        )
+        .unwrap();
+
+        let md = crate::common::load_lockfile(&lock_path).unwrap();
+        let krates: cargo_deny::Krates = krates::Builder::new()
+            .build_with_metadata(md, krates::NoneFilter)
+            .unwrap();
+
+        let mut changed: Vec<_> = super::diff(&krates, &previous)
+            .iter()
+            .map(|kid| {
+                let krate = krates.node_for_kid(kid).unwrap();
+                format!("{} {}", krate.name, krate.version)
+            })
+            .collect();
+        changed.sort();
+
+        assert_eq!(changed, ["added 1.0.0", "changed 2.0.0"]);
+    }
+}
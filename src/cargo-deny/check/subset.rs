@@ -0,0 +1,446 @@
+//! Support for `check --assert-subset-of`, which ensures that a config only
+//! allows licenses and sources that a base config also allows, and is at
+//! least as strict as the base config
+
+use crate::common::ValidConfig;
+use cargo_deny::{
+    diag::{Diagnostic, Label},
+    licenses::cfg::Licensee,
+    LintLevel, Path,
+};
+
+/// Gathers an error for every entry in the allow lists of the config that is
+/// not also allowed by the base config, and for every lint level or threshold
+/// that is less strict than the one in the base config
+///
+/// If `exceptions_only` is true, only the license exceptions that were loaded
+/// from a file other than the config itself are compared, as the rest of the
+/// config is shared between workspaces
+pub(crate) fn exceeding(
+    cfg: &ValidConfig,
+    base: &ValidConfig,
+    base_path: &Path,
+    exceptions_only: bool,
+) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+    let note = || vec![format!("base config: {base_path}")];
+
+    let licenses = &cfg.licenses;
+    let base_allows = |licensee: &Licensee| {
+        base.licenses
+            .allowed
+            .iter()
+            .any(|allowed| allowed.0.value == licensee.0.value)
+    };
+
+    if !exceptions_only {
+        for licensee in licenses.allowed.iter().filter(|l| !base_allows(l)) {
+            diags.push(
+                Diagnostic::error()
+                    .with_message(format!(
+                        "license '{}' is not allowed by the base config",
+                        licensee.0.value
+                    ))
+                    .with_labels(vec![Label::primary(licenses.file_id, licensee.0.span)
+                        .with_message("not in the base `licenses.allow`")])
+                    .with_notes(note()),
+            );
+        }
+    }
+
+    for exc in &licenses.exceptions {
+        if exceptions_only && exc.file_id == licenses.file_id {
+            continue;
+        }
+
+        // The base exceptions that apply to at least the same versions and
+        // sources of the crate
+        let base_exceptions = || {
+            base.licenses.exceptions.iter().filter(|bexc| {
+                bexc.spec.name.value == exc.spec.name.value
+                    && (bexc.spec.version_req.is_none()
+                        || bexc.spec.version_req == exc.spec.version_req)
+                    && (bexc.source.is_none() || bexc.source == exc.source)
+            })
+        };
+
+        if let Some(expr) = &exc.expression {
+            if !base_exceptions().any(|bexc| {
+                bexc.expression
+                    .as_ref()
+                    .is_some_and(|bexpr| bexpr.matches(&expr.0.value))
+            }) {
+                diags.push(
+                    Diagnostic::error()
+                        .with_message(format!(
+                            "exception for '{}' with license expression '{}' is not allowed by the base config",
+                            exc.spec, expr.0.value
+                        ))
+                        .with_labels(vec![Label::primary(exc.file_id, expr.0.span)
+                            .with_message("not in the base `licenses.exceptions`")])
+                        .with_notes(note()),
+                );
+            }
+        }
+
+        for licensee in &exc.allowed {
+            if base_allows(licensee)
+                || base_exceptions().any(|bexc| {
+                    bexc.allowed
+                        .iter()
+                        .any(|allowed| allowed.0.value == licensee.0.value)
+                })
+            {
+                continue;
+            }
+
+            diags.push(
+                Diagnostic::error()
+                    .with_message(format!(
+                        "license '{}' allowed for '{}' is not allowed by the base config",
+                        licensee.0.value, exc.spec
+                    ))
+                    .with_labels(vec![Label::primary(exc.file_id, licensee.0.span)
+                        .with_message(
+                            "not in the base `licenses.allow` or `licenses.exceptions`",
+                        )])
+                    .with_notes(note()),
+            );
+        }
+    }
+
+    if exceptions_only {
+        return diags;
+    }
+
+    let sources = &cfg.sources;
+    for src in &sources.allowed_sources {
+        if base
+            .sources
+            .allowed_sources
+            .iter()
+            .any(|bsrc| bsrc.url.value == src.url.value)
+        {
+            continue;
+        }
+
+        diags.push(
+            Diagnostic::error()
+                .with_message(format!(
+                    "source '{}' is not allowed by the base config",
+                    src.url.value
+                ))
+                .with_labels(vec![Label::primary(sources.file_id, src.url.span)
+                    .with_message(
+                        "not in the base `sources.allow-registry` or `sources.allow-git`",
+                    )])
+                .with_notes(note()),
+        );
+    }
+
+    for host in &sources.allowed_git_hosts {
+        if base
+            .sources
+            .allowed_git_hosts
+            .iter()
+            .any(|bhost| bhost.value == host.value)
+        {
+            continue;
+        }
+
+        diags.push(
+            Diagnostic::error()
+                .with_message(format!(
+                    "git host '{}' is not allowed by the base config",
+                    host.value
+                ))
+                .with_labels(vec![Label::primary(sources.file_id, host.span)
+                    .with_message("not in the base `sources.allow-git-hosts`")])
+                .with_notes(note()),
+        );
+    }
+
+    for (org_type, org) in &sources.allowed_orgs {
+        if base
+            .sources
+            .allowed_orgs
+            .iter()
+            .any(|(bot, borg)| bot == org_type && borg.value.eq_ignore_ascii_case(&org.value))
+        {
+            continue;
+        }
+
+        diags.push(
+            Diagnostic::error()
+                .with_message(format!(
+                    "{org_type} organization '{}' is not allowed by the base config",
+                    org.value
+                ))
+                .with_labels(vec![Label::primary(sources.file_id, org.span)
+                    .with_message("not in the base `sources.allow-org`")])
+                .with_notes(note()),
+        );
+    }
+
+    if sources.allow_private_registries.value && !base.sources.allow_private_registries.value {
+        diags.push(
+            Diagnostic::error()
+                .with_message(
+                    "`sources.allow-private-registries` is not enabled in the base config",
+                )
+                .with_labels(vec![Label::primary(
+                    sources.file_id,
+                    sources.allow_private_registries.span,
+                )])
+                .with_notes(note()),
+        );
+    }
+
+    // Lint levels aren't spanned, so the diagnostics can only name the field
+    let mut less_strict = |field: &str, level: LintLevel, base_level: LintLevel| {
+        if level >= base_level {
+            return;
+        }
+
+        let (level, base_level): (&str, &str) = (level.into(), base_level.into());
+        diags.push(
+            Diagnostic::error()
+                .with_message(format!(
+                    "`{field}` is '{level}', which is less strict than '{base_level}' in the base config"
+                ))
+                .with_notes(note()),
+        );
+    };
+
+    let (bsources, blicenses) = (&base.sources, &base.licenses);
+    for (field, level, base_level) in [
+        (
+            "sources.unknown-registry",
+            sources.unknown_registry,
+            bsources.unknown_registry,
+        ),
+        (
+            "sources.unknown-git",
+            sources.unknown_git,
+            bsources.unknown_git,
+        ),
+        (
+            "sources.deny-patched",
+            sources.deny_patched,
+            bsources.deny_patched,
+        ),
+        (
+            "sources.multiple-git-revs",
+            sources.multiple_git_revs,
+            bsources.multiple_git_revs,
+        ),
+        (
+            "sources.detect-name-collision",
+            sources.detect_name_collision,
+            bsources.detect_name_collision,
+        ),
+        (
+            "sources.git-require-locked-rev",
+            sources.git_require_locked_rev,
+            bsources.git_require_locked_rev,
+        ),
+        (
+            "sources.untrusted-owner",
+            sources.untrusted_owner,
+            bsources.untrusted_owner,
+        ),
+        (
+            "sources.verify-checksums",
+            sources.verify_checksums,
+            bsources.verify_checksums,
+        ),
+        (
+            "sources.require-https",
+            sources.require_https,
+            bsources.require_https,
+        ),
+        (
+            "licenses.unused-allowed-license",
+            licenses.unused_allowed_license,
+            blicenses.unused_allowed_license,
+        ),
+        (
+            "licenses.unused-clarification",
+            licenses.unused_clarification,
+            blicenses.unused_clarification,
+        ),
+        (
+            "licenses.unknown-license-ref",
+            licenses.unknown_license_ref,
+            blicenses.unknown_license_ref,
+        ),
+        (
+            "licenses.check-internal-compatibility",
+            licenses.check_internal_compatibility,
+            blicenses.check_internal_compatibility,
+        ),
+        (
+            "licenses.require-clarification-for-license-file",
+            licenses.require_clarification_for_license_file,
+            blicenses.require_clarification_for_license_file,
+        ),
+        (
+            "licenses.deny-deprecated",
+            licenses.deny_deprecated,
+            blicenses.deny_deprecated,
+        ),
+    ] {
+        less_strict(field, level, base_level);
+    }
+
+    if licenses.confidence_threshold < blicenses.confidence_threshold {
+        diags.push(
+            Diagnostic::error()
+                .with_message(format!(
+                    "`licenses.confidence-threshold` is {}, which is lower than {} in the base config",
+                    licenses.confidence_threshold, blicenses.confidence_threshold
+                ))
+                .with_notes(note()),
+        );
+    }
+
+    if licenses.private.ignore && !blicenses.private.ignore {
+        diags.push(
+            Diagnostic::error()
+                .with_message("`licenses.private.ignore` is not enabled in the base config")
+                .with_notes(note()),
+        );
+    }
+
+    diags
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cargo_deny::diag::Files;
+
+    fn load(files: &mut Files, dir: &Path, name: &str, contents: &str) -> ValidConfig {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+
+        ValidConfig::load(
+            Some(path),
+            None,
+            None,
+            files,
+            crate::common::LogContext {
+                format: crate::Format::Human,
+                color: crate::Color::Never,
+                log_level: log::LevelFilter::Off,
+                quiet: true,
+            },
+        )
+        .unwrap()
+    }
+
+    fn exceeding_messages(cfg: &str, base: &str) -> Vec<String> {
+        let td = tempfile::tempdir().unwrap();
+        let dir = cargo_deny::utf8path(td.path().to_owned()).unwrap();
+        let mut files = Files::new();
+
+        let base_path = dir.join("base.toml");
+        let base = load(&mut files, &dir, "base.toml", base);
+        let cfg = load(&mut files, &dir, "deny.toml", cfg);
+
+        exceeding(&cfg, &base, &base_path, false)
+            .into_iter()
+            .map(|d| d.message)
+            .collect()
+    }
+
+    const BASE: &str = r#"
+[licenses]
+allow = ["MIT", "Apache-2.0"]
+confidence-threshold = 0.9
+
+[sources]
+unknown-registry = "deny"
+unknown-git = "deny"
+allow-git = ["https://github.com/EmbarkStudios/cargo-deny"]
+"#;
+
+    /// Ensures a config that is identical to, or stricter than, the base
+    /// config is a subset of it
+    #[test]
+    fn allows_stricter_configs() {
+        assert!(exceeding_messages(BASE, BASE).is_empty());
+
+        assert!(exceeding_messages(
+            r#"
+[licenses]
+allow = ["MIT"]
+confidence-threshold = 0.95
+unused-allowed-license = "deny"
+
+[sources]
+unknown-registry = "deny"
+unknown-git = "deny"
+"#,
+            BASE
+        )
+        .is_empty());
+    }
+
+    /// Ensures entries that aren't in the base allow lists are reported
+    #[test]
+    fn reports_broader_allow_lists() {
+        assert_eq!(
+            exceeding_messages(
+                r#"
+[licenses]
+allow = ["MIT", "GPL-3.0-only"]
+confidence-threshold = 0.9
+exceptions = [{ name = "ring", allow = ["ISC"] }]
+
+[sources]
+unknown-registry = "deny"
+unknown-git = "deny"
+allow-git = ["https://github.com/EmbarkStudios/krates"]
+allow-org = { github = ["EmbarkStudios"] }
+"#,
+                BASE
+            ),
+            [
+                "license 'GPL-3.0-only' is not allowed by the base config",
+                "license 'ISC' allowed for 'ring' is not allowed by the base config",
+                "source 'https://github.com/EmbarkStudios/krates' is not allowed by the base config",
+                "github.com organization 'EmbarkStudios' is not allowed by the base config",
+            ]
+        );
+    }
+
+    /// Ensures lint levels and thresholds that are less strict than the base
+    /// config are reported
+    #[test]
+    fn reports_less_strict_levels() {
+        assert_eq!(
+            exceeding_messages(
+                r#"
+[licenses]
+allow = ["MIT"]
+confidence-threshold = 0.8
+unused-allowed-license = "allow"
+
+[sources]
+unknown-registry = "allow"
+unknown-git = "warn"
+allow-private-registries = true
+"#,
+                BASE
+            ),
+            [
+                "`sources.allow-private-registries` is not enabled in the base config",
+                "`sources.unknown-registry` is 'allow', which is less strict than 'deny' in the base config",
+                "`sources.unknown-git` is 'warn', which is less strict than 'deny' in the base config",
+                "`licenses.unused-allowed-license` is 'allow', which is less strict than 'warn' in the base config",
+                "`licenses.confidence-threshold` is 0.8, which is lower than 0.9 in the base config",
+            ]
+        );
+    }
+}
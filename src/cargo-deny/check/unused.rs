@@ -0,0 +1,162 @@
+//! Support for checking multiple workspaces against a single config, where a
+//! config entry is only unused if none of the workspaces used it
+
+use cargo_deny::{
+    advisories, bans,
+    diag::{general, Check, Diag, FileId, Pack},
+    licenses, sources,
+};
+use codespan_reporting::diagnostic::LabelStyle;
+use std::collections::BTreeMap;
+
+/// Diagnostic codes that are emitted for configuration entries that did not
+/// match anything in the workspace being checked
+fn is_unused_entry(code: &str) -> bool {
+    let unused: [&'static str; 19] = [
+        advisories::Code::AdvisoryNotDetected.into(),
+        advisories::Code::YankedNotDetected.into(),
+        bans::Code::UnmatchedSkip.into(),
+        bans::Code::UnnecessarySkip.into(),
+        bans::Code::UnmatchedWrapper.into(),
+        bans::Code::UnusedWrapper.into(),
+        bans::Code::UnmatchedSkipRoot.into(),
+        bans::Code::UnmatchedBypass.into(),
+        bans::Code::UnmatchedPathBypass.into(),
+        bans::Code::UnmatchedGlob.into(),
+        bans::Code::UnmatchedDenyBelow.into(),
+        bans::Code::UnmatchedBuildAllow.into(),
+        licenses::Code::LicenseNotEncountered.into(),
+        licenses::Code::LicenseExceptionNotEncountered.into(),
+        licenses::Code::ClarificationNotEncountered.into(),
+        sources::Code::UnmatchedSource.into(),
+        sources::Code::UnmatchedOrganization.into(),
+        sources::Code::UnmatchedGitHost.into(),
+        general::Code::UnusedAnnotation.into(),
+    ];
+
+    unused.contains(&code)
+}
+
+struct Reported {
+    check: Check,
+    diag: Diag,
+    /// The number of workspaces that reported the entry as unused
+    count: usize,
+    /// The last workspace that reported the entry as unused
+    last: usize,
+}
+
+/// Combines the diagnostics for unused entries in the config that is shared
+/// by every workspace, so that each entry is reported once, and only if no
+/// workspace used it
+///
+/// Entries in files that are specific to a workspace, such as its local
+/// exceptions, are not affected
+pub(crate) struct UnusedEntries {
+    cfg_id: FileId,
+    workspace: usize,
+    /// The diagnostics keyed by their code and location in the config
+    reported: BTreeMap<(String, usize, usize), Reported>,
+}
+
+impl UnusedEntries {
+    pub(crate) fn new(cfg_id: FileId) -> Self {
+        Self {
+            cfg_id,
+            workspace: 0,
+            reported: BTreeMap::new(),
+        }
+    }
+
+    /// Removes the diagnostics for unused entries in the shared config from
+    /// the pack, they are instead reported by [`Self::into_unused`] once every
+    /// workspace has been checked
+    pub(crate) fn collect(&mut self, pack: &mut Pack) {
+        let cfg_id = self.cfg_id;
+        let key = |diag: &Diag| {
+            let code = diag.diag.code.as_deref().filter(|c| is_unused_entry(c))?;
+            let label = diag
+                .diag
+                .labels
+                .iter()
+                .find(|l| l.style == LabelStyle::Primary && l.file_id == cfg_id)?;
+
+            Some((code.to_owned(), label.range.start, label.range.end))
+        };
+
+        for diag in pack.take_matching(|diag| key(diag).is_some()) {
+            let key = key(&diag).unwrap();
+            let reported = self.reported.entry(key).or_insert(Reported {
+                check: pack.check,
+                diag,
+                count: 0,
+                last: usize::MAX,
+            });
+
+            if reported.last != self.workspace {
+                reported.last = self.workspace;
+                reported.count += 1;
+            }
+        }
+    }
+
+    /// Marks the end of the diagnostics for the current workspace
+    pub(crate) fn finish_workspace(&mut self) {
+        self.workspace += 1;
+    }
+
+    /// The diagnostics for the entries that were unused in every workspace
+    pub(crate) fn into_unused(self) -> impl Iterator<Item = (Check, Diag)> {
+        let workspaces = self.workspace;
+        self.reported
+            .into_values()
+            .filter(move |rep| rep.count == workspaces)
+            .map(|rep| (rep.check, rep.diag))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cargo_deny::diag::{Diagnostic, Label};
+
+    fn unmatched(file_id: FileId, range: std::ops::Range<usize>) -> Diagnostic {
+        Diagnostic::warning()
+            .with_code(bans::Code::UnmatchedSkip)
+            .with_message("skipped crate was not encountered")
+            .with_labels(vec![Label::primary(file_id, range)])
+    }
+
+    /// Ensures unused entries in the shared config are only reported if they
+    /// were unused in every workspace, while all other diagnostics are left
+    /// in place
+    #[test]
+    fn only_reports_entries_unused_by_every_workspace() {
+        let mut unused = UnusedEntries::new(0);
+
+        let mut collect = |diag: Diagnostic| {
+            let mut pack = Pack::from((Check::Bans, diag));
+            unused.collect(&mut pack);
+            pack.iter().count()
+        };
+
+        assert_eq!(collect(unmatched(0, 10..20)), 0);
+        assert_eq!(collect(unmatched(0, 30..40)), 0);
+        // Entries in workspace specific files are left in the pack
+        assert_eq!(collect(unmatched(1, 10..20)), 1);
+        assert_eq!(
+            collect(Diagnostic::error().with_message("not an unused entry")),
+            1
+        );
+        unused.finish_workspace();
+
+        let mut pack = Pack::from((Check::Bans, unmatched(0, 30..40)));
+        unused.collect(&mut pack);
+        unused.finish_workspace();
+
+        let unused: Vec<_> = unused.into_unused().collect();
+        assert_eq!(unused.len(), 1);
+        assert_eq!(unused[0].0, Check::Bans);
+        assert_eq!(unused[0].1.diag.labels[0].range, 30..40);
+    }
+}
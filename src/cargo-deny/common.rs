@@ -1,7 +1,7 @@
 use cargo_deny::{
     diag::{self, FileId, Files, Severity},
     licenses::LicenseStore,
-    PathBuf,
+    Kid, Path, PathBuf,
 };
 
 mod cfg;
@@ -12,10 +12,15 @@ pub(crate) fn load_license_store() -> Result<LicenseStore, anyhow::Error> {
     LicenseStore::from_cache()
 }
 
+#[derive(Clone)]
 pub struct KrateContext {
     pub manifest_path: PathBuf,
     pub workspace: bool,
     pub exclude: Vec<String>,
+    /// The workspace members used as the roots of the graph, if empty, the
+    /// roots are determined by the manifest path and `workspace`
+    pub packages: Vec<String>,
+    pub exclude_paths: Vec<String>,
     pub targets: Vec<String>,
     pub no_default_features: bool,
     pub all_features: bool,
@@ -24,10 +29,38 @@ pub struct KrateContext {
     pub locked: bool,
     pub offline: bool,
     pub exclude_dev: bool,
+    /// The names of crates whose dev dependencies are excluded from the graph
+    pub exclude_dev_deps_of: Vec<String>,
     pub exclude_unpublished: bool,
+    /// Resolves crates that are only used by the host without their default
+    /// features
+    pub no_host_default_features: bool,
+    pub metadata_json: Option<PathBuf>,
+    /// A `Cargo.lock` to build the crate graph from, instead of the manifest
+    pub lockfile: Option<PathBuf>,
 }
 
 impl KrateContext {
+    /// Merges the `[graph]` config into the context.
+    ///
+    /// Features specified on the command line take precedence over the
+    /// features in the config, while the flags are enabled if they are set in
+    /// either the command line or the config
+    pub fn merge_graph_config(&mut self, graph: &mut cargo_deny::root_cfg::GraphConfig) {
+        self.all_features |= graph.all_features;
+        self.no_default_features |= graph.no_default_features;
+        self.exclude_dev |= graph.exclude_dev;
+        self.exclude_unpublished |= graph.exclude_unpublished;
+        self.no_host_default_features |= graph.no_host_default_features;
+
+        if self.features.is_empty() {
+            self.features = std::mem::take(&mut graph.features);
+        }
+
+        self.exclude_paths = std::mem::take(&mut graph.exclude_paths);
+        self.exclude_dev_deps_of = std::mem::take(&mut graph.exclude_dev_deps_of);
+    }
+
     pub fn get_config_path(&self, config_path: Option<PathBuf>) -> Option<PathBuf> {
         if let Some(cp) = config_path {
             if cp.is_absolute() {
@@ -111,33 +144,99 @@ impl KrateContext {
         self,
         cfg_targets: Vec<cargo_deny::root_cfg::Target>,
         cfg_excludes: Vec<String>,
+        timings: Option<&crate::timings::Timings>,
     ) -> Result<cargo_deny::Krates, anyhow::Error> {
         log::info!("gathering crates for {}", self.manifest_path);
         let start = std::time::Instant::now();
 
         log::debug!("gathering crate metadata");
-        let metadata = Self::get_metadata(MetadataOptions {
-            no_default_features: self.no_default_features,
-            all_features: self.all_features,
-            features: self.features,
-            manifest_path: self.manifest_path,
-            frozen: self.frozen,
-            locked: self.locked,
-            offline: self.offline,
-        })?;
+        let mut metadata = if let Some(lockfile) = &self.lockfile {
+            load_lockfile(lockfile)?
+        } else if let Some(metadata_json) = &self.metadata_json {
+            if self.all_features || self.no_default_features || !self.features.is_empty() {
+                log::warn!("features were already resolved when '{metadata_json}' was generated, feature options are ignored");
+            }
+
+            read_metadata(metadata_json)?
+        } else {
+            // As with cargo, features without a package prefix apply to each
+            // of the selected packages
+            let features = if self.packages.is_empty() {
+                self.features.clone()
+            } else {
+                self.features
+                    .iter()
+                    .flat_map(|feature| {
+                        if feature.contains('/') {
+                            vec![feature.clone()]
+                        } else {
+                            self.packages
+                                .iter()
+                                .map(|pkg| format!("{pkg}/{feature}"))
+                                .collect()
+                        }
+                    })
+                    .collect()
+            };
+
+            Self::get_metadata(MetadataOptions {
+                no_default_features: self.no_default_features,
+                all_features: self.all_features,
+                features,
+                manifest_path: self.manifest_path,
+                frozen: self.frozen,
+                locked: self.locked,
+                offline: self.offline,
+            })?
+        };
         log::debug!(
             "gathered crate metadata in {}ms",
             start.elapsed().as_millis()
         );
+        if let Some(timings) = timings {
+            timings.record("cargo metadata", start.elapsed());
+        }
+        let graph_start = std::time::Instant::now();
+
+        // Features are only used when cargo resolves the metadata
+        let features = if self.lockfile.is_none() && self.metadata_json.is_none() {
+            self.features.as_slice()
+        } else {
+            &[]
+        };
+        validate_packages(&metadata, &self.packages, features)?;
+
+        if !self.exclude_dev_deps_of.is_empty() {
+            exclude_dev_deps_of(&mut metadata, &self.exclude_dev_deps_of);
+        }
+
+        if self.no_host_default_features {
+            disable_host_default_features(&mut metadata);
+        }
 
         use krates::{Builder, DepKind};
 
         let mut gb = Builder::new();
 
         // Use targets passed on the command line first, and fallback to config
-        // based targets otherwise
-        if !self.targets.is_empty() {
-            gb.include_targets(self.targets.into_iter().map(|t| (t, Vec::new())));
+        // based targets otherwise. Targets are a union, so if any of them is
+        // the wildcard, no target filtering is done at all
+        if self.targets.iter().any(|t| t == "all") {
+            log::debug!("`--target all` specified, target filtering is disabled");
+        } else if !self.targets.is_empty() {
+            gb.include_targets(self.targets.into_iter().map(|t| {
+                let target = krates::Target::from(t);
+
+                // As with config targets, let the user know if the triple is
+                // not a built-in, as cfg() expressions can't be evaluated for it
+                if let krates::Target::Unknown(triple) = &target {
+                    log::warn!("unknown target `{triple}` specified, it won't be evaluated against cfg() sections, just explicit triples");
+                }
+
+                (target, Vec::new())
+            }));
+        } else if cfg_targets.iter().any(|targ| targ.is_wildcard()) {
+            log::debug!("`targets = [\"*\"]` specified, target filtering is disabled");
         } else if !cfg_targets.is_empty() {
             gb.include_targets(
                 cfg_targets
@@ -170,11 +269,61 @@ impl KrateContext {
                     }),
             );
         }
-        if self.exclude_unpublished {
+        let exclude_paths = if self.exclude_paths.is_empty() {
+            None
+        } else {
+            use anyhow::Context as _;
+
+            let mut builder = globset::GlobSetBuilder::new();
+            for pattern in &self.exclude_paths {
+                match globset::Glob::new(pattern) {
+                    Ok(glob) => {
+                        builder.add(glob);
+                    }
+                    Err(err) => log::warn!("invalid exclude path glob '{pattern}': {err}"),
+                }
+            }
+
+            Some(
+                builder
+                    .build()
+                    .context("failed to build exclude path globs")?,
+            )
+        };
+
+        if !self.packages.is_empty() || self.exclude_unpublished || exclude_paths.is_some() {
+            let root = &metadata.workspace_root;
+
             gb.include_workspace_crates(metadata.workspace_packages().iter().filter_map(
-                |package| match package.publish {
-                    Some(ref registries) if registries.is_empty() => None,
-                    _ => Some(package.manifest_path.as_std_path()),
+                |package| {
+                    if !self.packages.is_empty() && !self.packages.contains(&package.name) {
+                        return None;
+                    }
+
+                    if self.exclude_unpublished
+                        && package
+                            .publish
+                            .as_ref()
+                            .is_some_and(|registries| registries.is_empty())
+                    {
+                        return None;
+                    }
+
+                    // Globs can match either the manifest or the directory
+                    // containing it
+                    if let Some((globs, rel_path)) = exclude_paths
+                        .as_ref()
+                        .zip(package.manifest_path.strip_prefix(root).ok())
+                    {
+                        if globs.is_match(rel_path)
+                            || rel_path.parent().is_some_and(|dir| globs.is_match(dir))
+                        {
+                            log::debug!("excluding '{}' from the graph roots", package.name);
+                            return None;
+                        }
+                    }
+
+                    Some(package.manifest_path.as_std_path())
                 },
             ));
         }
@@ -201,6 +350,10 @@ impl KrateContext {
         });
 
         if let Ok(krates) = &graph {
+            if let Err(err) = cargo_deny::sources::set_locked_checksums(krates) {
+                log::warn!("unable to read crate checksums from the lockfile, package specs with a checksum will not match: {err:#}");
+            }
+
             log::info!(
                 "gathered {} crates in {}ms",
                 krates.len(),
@@ -208,6 +361,10 @@ impl KrateContext {
             );
         }
 
+        if let Some(timings) = timings {
+            timings.record("crate graph", graph_start.elapsed());
+        }
+
         Ok(graph?)
     }
 
@@ -235,6 +392,490 @@ impl KrateContext {
     }
 }
 
+/// The only `cargo metadata --format-version` that is supported
+const METADATA_FORMAT_VERSION: u64 = 1;
+
+/// Ensures the packages specified via `--package`, or as the prefix of a
+/// `member/feature`, exist in the workspace
+fn validate_packages(
+    metadata: &krates::cm::Metadata,
+    packages: &[String],
+    features: &[String],
+) -> anyhow::Result<()> {
+    let members = metadata.workspace_packages();
+    let is_member = |name: &str| members.iter().any(|member| member.name == name);
+
+    for package in packages {
+        anyhow::ensure!(
+            is_member(package),
+            "package '{package}' specified with `--package` is not a member of the workspace"
+        );
+    }
+
+    for feature in features {
+        let Some((package, _)) = feature.split_once('/') else {
+            continue;
+        };
+
+        // As with cargo, `dependency/feature` enables a feature of a dependency
+        // of the workspace members
+        let is_dependency = members.iter().any(|member| {
+            member
+                .dependencies
+                .iter()
+                .any(|dep| dep.rename.as_deref().unwrap_or(&dep.name) == package)
+        });
+
+        anyhow::ensure!(
+            is_member(package) || is_dependency,
+            "feature '{feature}' refers to '{package}', which is not a member of the workspace or a dependency of one"
+        );
+    }
+
+    Ok(())
+}
+
+/// Removes the dev-dependency edges of the crates with the specified names, so
+/// that crates only reachable through them are excluded from the graph
+fn exclude_dev_deps_of(metadata: &mut krates::cm::Metadata, names: &[String]) {
+    use krates::cm::DependencyKind;
+
+    for name in names {
+        if !metadata.packages.iter().any(|pkg| &pkg.name == name) {
+            log::warn!(
+                "crate '{name}' in `graph.exclude-dev-deps-of` was not found in the metadata"
+            );
+        }
+    }
+
+    let ids: std::collections::BTreeSet<_> = metadata
+        .packages
+        .iter_mut()
+        .filter(|pkg| names.contains(&pkg.name))
+        .map(|pkg| {
+            pkg.dependencies
+                .retain(|dep| dep.kind != DependencyKind::Development);
+            pkg.id.clone()
+        })
+        .collect();
+
+    let Some(resolve) = &mut metadata.resolve else {
+        return;
+    };
+
+    for node in resolve
+        .nodes
+        .iter_mut()
+        .filter(|node| ids.contains(&node.id))
+    {
+        // A dependency can be both a normal and a dev dependency, in which
+        // case only the dev edge is removed
+        node.deps.retain_mut(|dep| {
+            dep.dep_kinds
+                .retain(|dk| dk.kind != DependencyKind::Development);
+            !dep.dep_kinds.is_empty()
+        });
+
+        let deps = &node.deps;
+        node.dependencies
+            .retain(|id| deps.iter().any(|dep| &dep.pkg == id));
+    }
+}
+
+/// Removes the default features of the crates that are only used by the host,
+/// ie. build dependencies and proc-macros and their dependencies, along with
+/// the features and optional dependencies that were only enabled by them
+///
+/// This is not a full feature resolution, features that a host crate enables
+/// on its own dependencies are not removed, even if they were only enabled by
+/// one of its removed features
+fn disable_host_default_features(metadata: &mut krates::cm::Metadata) {
+    use krates::cm::{DependencyKind, PackageId, TargetKind};
+    use std::collections::{BTreeMap, BTreeSet};
+
+    let Some(resolve) = &mut metadata.resolve else {
+        return;
+    };
+
+    let packages: BTreeMap<_, _> = metadata.packages.iter().map(|pkg| (&pkg.id, pkg)).collect();
+    let is_proc_macro = |id: &PackageId| {
+        packages.get(id).is_some_and(|pkg| {
+            pkg.targets
+                .iter()
+                .any(|t| t.kind.contains(&TargetKind::ProcMacro))
+        })
+    };
+
+    let nodes: BTreeMap<_, _> = resolve.nodes.iter().map(|node| (&node.id, node)).collect();
+
+    // As with cargo, a crate is only built without its default features for
+    // the host if it can't be reached from a workspace member without going
+    // through a build dependency or proc-macro
+    let mut host = BTreeSet::new();
+    let mut target = BTreeSet::new();
+    let mut pending: Vec<_> = metadata
+        .workspace_members
+        .iter()
+        .map(|id| (id, is_proc_macro(id)))
+        .collect();
+
+    while let Some((id, is_host)) = pending.pop() {
+        let visited = if is_host { &mut host } else { &mut target };
+        if !visited.insert(id) {
+            continue;
+        }
+
+        let Some(node) = nodes.get(id) else {
+            continue;
+        };
+
+        for dep in &node.deps {
+            let is_host = is_host || is_proc_macro(&dep.pkg);
+
+            if dep.dep_kinds.is_empty() {
+                pending.push((&dep.pkg, is_host));
+            }
+
+            for dk in &dep.dep_kinds {
+                pending.push((&dep.pkg, is_host || dk.kind == DependencyKind::Build));
+            }
+        }
+    }
+
+    let mut changes = BTreeMap::new();
+
+    for id in host
+        .difference(&target)
+        .filter(|id| !metadata.workspace_members.contains(id))
+    {
+        let (Some(pkg), Some(node)) = (packages.get(id), nodes.get(id)) else {
+            continue;
+        };
+
+        // Gather the features that dependents explicitly enable, either on the
+        // dependency itself, or via one of their own enabled features
+        let mut requested = Vec::new();
+        for dependent in resolve
+            .nodes
+            .iter()
+            .filter(|node| node.deps.iter().any(|dep| &dep.pkg == *id))
+        {
+            let Some(dependent_pkg) = packages.get(&dependent.id) else {
+                continue;
+            };
+
+            for dep in dependent_pkg
+                .dependencies
+                .iter()
+                .filter(|dep| dep.name == pkg.name && dep.req.matches(&pkg.version))
+            {
+                requested.extend(dep.features.iter().map(String::as_str));
+
+                let key = dep.rename.as_deref().unwrap_or(&dep.name);
+                requested.extend(
+                    dependent
+                        .features
+                        .iter()
+                        .filter_map(|feat| dependent_pkg.features.get(feat))
+                        .flatten()
+                        .filter_map(|fv| {
+                            let rest = fv.strip_prefix(key)?;
+                            rest.strip_prefix('/').or_else(|| rest.strip_prefix("?/"))
+                        }),
+                );
+            }
+        }
+
+        let mut enabled = BTreeSet::new();
+        while let Some(feat) = requested.pop() {
+            if !enabled.insert(feat) {
+                continue;
+            }
+
+            requested.extend(
+                pkg.features
+                    .get(feat)
+                    .into_iter()
+                    .flatten()
+                    .filter(|fv| !fv.contains('/') && !fv.starts_with("dep:"))
+                    .map(String::as_str),
+            );
+        }
+
+        // An optional dependency is only kept if it's still enabled by one of
+        // the remaining features
+        let is_dep_enabled = |key: &str| {
+            enabled.contains(key)
+                || enabled
+                    .iter()
+                    .filter_map(|feat| pkg.features.get(*feat))
+                    .flatten()
+                    .any(|fv| {
+                        fv.strip_prefix("dep:") == Some(key)
+                            || fv
+                                .strip_prefix(key)
+                                .is_some_and(|rest| rest.starts_with('/'))
+                    })
+        };
+
+        let removed: BTreeSet<_> = node
+            .deps
+            .iter()
+            .filter(|nd| {
+                let Some(dep_pkg) = packages.get(&nd.pkg) else {
+                    return false;
+                };
+
+                let mut decls = pkg
+                    .dependencies
+                    .iter()
+                    .filter(|dep| dep.name == dep_pkg.name && dep.req.matches(&dep_pkg.version))
+                    .peekable();
+
+                decls.peek().is_some()
+                    && decls.all(|dep| {
+                        dep.optional && !is_dep_enabled(dep.rename.as_deref().unwrap_or(&dep.name))
+                    })
+            })
+            .map(|nd| nd.pkg.clone())
+            .collect();
+
+        let features: Vec<_> = node
+            .features
+            .iter()
+            .filter(|feat| enabled.contains(feat.as_str()))
+            .cloned()
+            .collect();
+
+        if features.len() != node.features.len() || !removed.is_empty() {
+            log::debug!(
+                "removing default features of host crate '{} {}'",
+                pkg.name,
+                pkg.version
+            );
+            changes.insert((*id).clone(), (features, removed));
+        }
+    }
+
+    for node in &mut resolve.nodes {
+        let Some((features, removed)) = changes.remove(&node.id) else {
+            continue;
+        };
+
+        node.features = features;
+        node.deps.retain(|dep| !removed.contains(&dep.pkg));
+        node.dependencies.retain(|id| !removed.contains(id));
+    }
+}
+
+/// Reads the output of a previous `cargo metadata` invocation
+fn read_metadata(path: &cargo_deny::Path) -> anyhow::Result<krates::cm::Metadata> {
+    use anyhow::Context as _;
+
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("failed to read '{path}'"))?;
+    let json: serde_json::Value = serde_json::from_str(&contents)
+        .with_context(|| format!("failed to parse '{path}' as JSON"))?;
+
+    // Check the version before attempting to deserialize the full metadata so
+    // that the user gets a clear error rather than a random missing field
+    match json.get("version").and_then(|v| v.as_u64()) {
+        Some(METADATA_FORMAT_VERSION) => {}
+        Some(version) => anyhow::bail!(
+            "'{path}' has metadata format version {version}, but only version {METADATA_FORMAT_VERSION} is supported, generate it with `cargo metadata --format-version {METADATA_FORMAT_VERSION}`"
+        ),
+        None => anyhow::bail!(
+            "'{path}' does not appear to be `cargo metadata` output, it is missing the format version"
+        ),
+    }
+
+    anyhow::ensure!(
+        json.get("resolve").is_some_and(|r| !r.is_null()),
+        "'{path}' does not contain the dependency resolve, it must be generated without `--no-deps`"
+    );
+
+    serde_json::from_value(json)
+        .with_context(|| format!("failed to deserialize metadata from '{path}'"))
+}
+
+/// Synthesizes `cargo metadata` output from a `Cargo.lock`, so that a crate
+/// graph can be built without a buildable workspace
+///
+/// The lockfile only records the name, version, source, and dependencies of
+/// each package, so the graph has no features, targets, or dependency kinds,
+/// and the packages without a source are used as the workspace members
+pub(crate) fn load_lockfile(path: &Path) -> anyhow::Result<krates::cm::Metadata> {
+    use anyhow::Context as _;
+
+    let path = path
+        .canonicalize_utf8()
+        .with_context(|| format!("failed to find lockfile '{path}'"))?;
+    let root = path.parent().context("lockfile has no parent")?;
+
+    let contents =
+        std::fs::read_to_string(&path).with_context(|| format!("failed to read '{path}'"))?;
+    let lockfile =
+        toml_span::parse(&contents).with_context(|| format!("failed to parse '{path}'"))?;
+
+    struct LockPackage<'l> {
+        name: &'l str,
+        version: &'l str,
+        source: Option<&'l str>,
+        deps: Vec<&'l str>,
+        id: String,
+    }
+
+    let root_url = url::Url::from_directory_path(root)
+        .map_err(|()| anyhow::anyhow!("unable to create url for '{root}'"))?;
+
+    let packages: Vec<_> = lockfile
+        .pointer("/package")
+        .and_then(|p| p.as_array())
+        .context("lockfile doesn't contain any packages")?
+        .iter()
+        .filter_map(|pkg| {
+            let pkg = pkg.as_table()?;
+            let field = |key: &str| pkg.get(key).and_then(|v| v.as_str());
+
+            let name = field("name")?;
+            let version = field("version")?;
+            let source = field("source");
+            let deps = pkg
+                .get("dependencies")
+                .and_then(|d| d.as_array())
+                .map(|deps| deps.iter().filter_map(|d| d.as_str()).collect())
+                .unwrap_or_default();
+
+            let id = if let Some(source) = source {
+                format!("{name} {version} ({source})")
+            } else {
+                format!("{name} {version} (path+{root_url}{name})")
+            };
+
+            Some(LockPackage {
+                name,
+                version,
+                source,
+                deps,
+                id,
+            })
+        })
+        .collect();
+
+    // Dependencies are specified as `<name>`, or `<name> <version>` or
+    // `<name> <version> (<source>)` if the name alone is ambiguous
+    fn resolve_dep<'p, 'l>(
+        packages: &'p [LockPackage<'l>],
+        dep: &str,
+    ) -> anyhow::Result<&'p LockPackage<'l>> {
+        let mut parts = dep.splitn(3, ' ');
+        let name = parts.next().unwrap_or_default();
+        let version = parts.next();
+        let source = parts
+            .next()
+            .and_then(|s| s.strip_prefix('('))
+            .and_then(|s| s.strip_suffix(')'));
+
+        packages
+            .iter()
+            .find(|pkg| {
+                pkg.name == name
+                    && version.map_or(true, |v| pkg.version == v)
+                    && source.map_or(true, |s| pkg.source == Some(s))
+            })
+            .with_context(|| format!("unable to find dependency '{dep}' in lockfile"))
+    }
+
+    let lib_name = |name: &str| name.replace('-', "_");
+    let manifest_path = |pkg: &LockPackage<'_>| {
+        if pkg.source.is_some() {
+            root.join(format!("{}-{}", pkg.name, pkg.version))
+                .join("Cargo.toml")
+        } else {
+            root.join(pkg.name).join("Cargo.toml")
+        }
+    };
+
+    let mut json_packages = Vec::with_capacity(packages.len());
+    let mut nodes = Vec::with_capacity(packages.len());
+
+    for pkg in &packages {
+        let deps = pkg
+            .deps
+            .iter()
+            .map(|dep| resolve_dep(&packages, dep))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .with_context(|| format!("failed to resolve dependencies of '{}'", pkg.name))?;
+
+        let manifest_path = manifest_path(pkg);
+
+        json_packages.push(serde_json::json!({
+            "name": pkg.name,
+            "version": pkg.version,
+            "id": pkg.id,
+            "source": pkg.source,
+            "dependencies": deps.iter().map(|dep| serde_json::json!({
+                "name": dep.name,
+                "source": dep.source,
+                "req": format!("={}", dep.version),
+                "kind": null,
+                "rename": null,
+                "optional": false,
+                "uses_default_features": true,
+                "features": [],
+                "target": null,
+                "registry": null,
+                "path": null,
+            })).collect::<Vec<_>>(),
+            "targets": [{
+                "name": lib_name(pkg.name),
+                "kind": ["lib"],
+                "crate_types": ["lib"],
+                "src_path": manifest_path.with_file_name("src/lib.rs").as_str(),
+            }],
+            "features": {},
+            "manifest_path": manifest_path.as_str(),
+        }));
+
+        nodes.push(serde_json::json!({
+            "id": pkg.id,
+            "dependencies": deps.iter().map(|dep| &dep.id).collect::<Vec<_>>(),
+            "deps": deps.iter().map(|dep| serde_json::json!({
+                "name": lib_name(dep.name),
+                "pkg": dep.id,
+                "dep_kinds": [{ "kind": null, "target": null }],
+            })).collect::<Vec<_>>(),
+            "features": [],
+        }));
+    }
+
+    let members: Vec<_> = packages
+        .iter()
+        .filter(|pkg| pkg.source.is_none())
+        .map(|pkg| &pkg.id)
+        .collect();
+
+    anyhow::ensure!(
+        !members.is_empty(),
+        "lockfile '{path}' doesn't contain any packages without a source to use as the workspace members"
+    );
+
+    serde_json::from_value(serde_json::json!({
+        "packages": json_packages,
+        "workspace_members": members,
+        "workspace_default_members": members,
+        "resolve": {
+            "nodes": nodes,
+            "root": null,
+        },
+        "target_directory": root.join("target").as_str(),
+        "version": METADATA_FORMAT_VERSION,
+        "workspace_root": root.as_str(),
+        "metadata": null,
+    }))
+    .with_context(|| format!("failed to synthesize metadata from '{path}'"))
+}
+
 struct MetadataOptions {
     no_default_features: bool,
     all_features: bool,
@@ -285,7 +926,11 @@ pub fn log_level_to_severity(log_level: log::LevelFilter) -> Option<Severity> {
     }
 }
 
-use codespan_reporting::term::{self, termcolor::ColorChoice};
+use codespan_reporting::{
+    diagnostic::LabelStyle,
+    files::Files as _,
+    term::{self, termcolor::ColorChoice},
+};
 use std::io::Write;
 
 fn color_to_choice(color: crate::Color, stream: impl std::io::IsTerminal) -> ColorChoice {
@@ -313,41 +958,542 @@ pub fn should_colorize(color: crate::Color, stream: impl std::io::IsTerminal) ->
     }
 }
 
+use cargo_deny::root_cfg::{OutputColors, TermColor};
+
+/// The colors from `output.colors`, which is set once the config is loaded,
+/// as the logger is initialized before that
+static COLORS: std::sync::OnceLock<OutputColors> = std::sync::OnceLock::new();
+
+/// Sets the colors configured in `output.colors`
+#[inline]
+pub fn set_colors(colors: OutputColors) {
+    // Every workspace shares the same config, so only the first set matters
+    let _ = COLORS.set(colors);
+}
+
+/// Gets the colors configured in `output.colors`, or the defaults if the
+/// config has not been loaded
+#[inline]
+pub fn colors() -> OutputColors {
+    COLORS.get().copied().unwrap_or_default()
+}
+
+/// Converts a configured color to the color used for log messages and stats,
+/// using the default if the color was not configured
+///
+/// The color is resolved via [`TermColor::to_termcolor`] so that log messages
+/// and diagnostics always use the same color for the same config
+pub fn ansi_color(color: Option<TermColor>, default: nu_ansi_term::Color) -> nu_ansi_term::Color {
+    use codespan_reporting::term::termcolor::Color as Tc;
+    use nu_ansi_term::Color;
+
+    let Some(color) = color else {
+        return default;
+    };
+
+    match color.to_termcolor() {
+        Tc::Black => Color::Black,
+        Tc::Red => Color::Red,
+        Tc::Green => Color::Green,
+        Tc::Yellow => Color::Yellow,
+        Tc::Blue => Color::Blue,
+        Tc::Magenta => Color::Purple,
+        Tc::Cyan => Color::Cyan,
+        Tc::White => Color::White,
+        Tc::Ansi256(code) => Color::Fixed(code),
+        Tc::Rgb(r, g, b) => Color::Rgb(r, g, b),
+        _ => default,
+    }
+}
+
 type CsDiag = codespan_reporting::diagnostic::Diagnostic<FileId>;
 
+/// A `--format` whose diagnostics are written to a file rather than stderr
+pub struct OutputFile {
+    format: crate::Format,
+    path: PathBuf,
+    file: std::fs::File,
+    /// SARIF is a single JSON document, so the results are collected and only
+    /// written once every diagnostic has been emitted
+    sarif_results: std::sync::Mutex<Vec<serde_json::Value>>,
+    /// Likewise, JUnit is a single XML document grouped by check
+    junit_cases: std::sync::Mutex<Vec<JunitCase>>,
+}
+
+impl OutputFile {
+    pub fn create(format: crate::Format, path: PathBuf) -> anyhow::Result<Self> {
+        use anyhow::Context as _;
+
+        let file = std::fs::File::create(&path)
+            .with_context(|| format!("failed to create output file '{path}'"))?;
+
+        Ok(Self {
+            format,
+            path,
+            file,
+            sarif_results: std::sync::Mutex::new(Vec::new()),
+            junit_cases: std::sync::Mutex::new(Vec::new()),
+        })
+    }
+
+    #[inline]
+    pub fn format(&self) -> crate::Format {
+        self.format
+    }
+
+    /// Writes the document for SARIF and JUnit outputs, all other formats are
+    /// written as the diagnostics are emitted
+    pub fn finish(self) -> anyhow::Result<()> {
+        use anyhow::Context as _;
+
+        match self.format {
+            crate::Format::Sarif => {}
+            crate::Format::Junit => {
+                let cases = self.junit_cases.into_inner().unwrap();
+                return write_junit(std::io::BufWriter::new(&self.file), &cases)
+                    .with_context(|| format!("failed to write JUnit report to '{}'", self.path));
+            }
+            crate::Format::Human | crate::Format::Json | crate::Format::Github => return Ok(()),
+        }
+
+        let results = self.sarif_results.into_inner().unwrap();
+        let rules: Vec<_> = results
+            .iter()
+            .filter_map(|result| result["ruleId"].as_str())
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .map(|id| serde_json::json!({ "id": id }))
+            .collect();
+
+        let log = serde_json::json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "cargo-deny",
+                        "informationUri": "https://embarkstudios.github.io/cargo-deny/",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules,
+                    },
+                },
+                "results": results,
+            }],
+        });
+
+        serde_json::to_writer_pretty(&self.file, &log)
+            .with_context(|| format!("failed to write SARIF log to '{}'", self.path))
+    }
+}
+
 pub struct Human<'a> {
-    stream: term::termcolor::StandardStream,
+    stream: HumanStream<'a>,
     grapher: Option<diag::InclusionGrapher<'a>>,
     config: term::Config,
     feature_depth: Option<u32>,
 }
 
-pub enum StdioStream {
+enum HumanStream<'a> {
+    Err(term::termcolor::StandardStream),
+    /// Files are never colored
+    File(&'a std::fs::File),
+}
+
+impl HumanStream<'_> {
+    fn lock(&self) -> Box<dyn term::termcolor::WriteColor + '_> {
+        match self {
+            Self::Err(stderr) => Box::new(stderr.lock()),
+            Self::File(file) => Box::new(term::termcolor::NoColor::new(*file)),
+        }
+    }
+}
+
+pub enum StdioStream<'a> {
     //Out(std::io::Stdout),
     Err(std::io::Stderr),
+    File(&'a std::fs::File),
 }
 
-impl StdioStream {
+impl StdioStream<'_> {
     pub fn lock(&self) -> StdLock<'_> {
         match self {
             //Self::Out(o) => StdLock::Out(o.lock()),
             Self::Err(o) => StdLock::Err(o.lock()),
+            Self::File(f) => StdLock::File(f),
         }
     }
 }
 
 pub struct Json<'a> {
-    stream: StdioStream,
+    stream: StdioStream<'a>,
     grapher: Option<diag::InclusionGrapher<'a>>,
 }
 
+pub struct Github<'a> {
+    stream: StdioStream<'a>,
+    krates: Option<&'a cargo_deny::Krates>,
+    /// The root that file paths are made relative to, as annotations are only
+    /// attached to files in the repository
+    root: Option<PathBuf>,
+}
+
+impl Github<'_> {
+    /// Writes the diagnostic as a workflow command, see
+    /// <https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions>
+    fn emit(&self, w: &mut impl Write, diag: &CsDiag, file: Option<&Path>, files: &Files) {
+        let command = match diag.severity {
+            Severity::Bug | Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note | Severity::Help => return,
+        };
+
+        let mut properties = Vec::new();
+
+        if let Some(file) = file {
+            let relative = relative_path(self.root.as_deref(), file);
+            properties.push(format!("file={}", escape_property(relative.as_str())));
+
+            if let Some((line, col)) = file_location(diag, file, files) {
+                properties.push(format!("line={line}"));
+                properties.push(format!("col={col}"));
+            }
+        }
+
+        if let Some(code) = &diag.code {
+            properties.push(format!("title={}", escape_property(code)));
+        }
+
+        let _ = writeln!(
+            w,
+            "::{command}{}{}::{}",
+            if properties.is_empty() { "" } else { " " },
+            properties.join(","),
+            escape_data(&full_message(diag))
+        );
+    }
+}
+
+pub struct Sarif<'a> {
+    results: &'a std::sync::Mutex<Vec<serde_json::Value>>,
+    krates: Option<&'a cargo_deny::Krates>,
+    /// The root that artifact locations are made relative to
+    root: Option<PathBuf>,
+}
+
+impl Sarif<'_> {
+    /// Converts the diagnostic to a SARIF result, see
+    /// <https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html>
+    ///
+    /// The fingerprint is the same one used by `output.acknowledge`, so that
+    /// code scanning can track the same diagnostic across runs
+    fn result(
+        &self,
+        diag: &CsDiag,
+        fingerprint: String,
+        file: Option<&Path>,
+        files: &Files,
+    ) -> serde_json::Value {
+        let level = match diag.severity {
+            Severity::Bug | Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note | Severity::Help => "note",
+        };
+
+        let mut result = serde_json::json!({
+            "level": level,
+            "message": { "text": full_message(diag) },
+            "partialFingerprints": { "cargoDeny/v1": fingerprint },
+        });
+
+        if let Some(code) = &diag.code {
+            result["ruleId"] = code.as_str().into();
+        }
+
+        if let Some(file) = file {
+            let uri = relative_path(self.root.as_deref(), file)
+                .as_str()
+                .replace('\\', "/");
+            let mut location = serde_json::json!({ "artifactLocation": { "uri": uri } });
+
+            if let Some((line, col)) = file_location(diag, file, files) {
+                location["region"] = serde_json::json!({ "startLine": line, "startColumn": col });
+            }
+
+            result["locations"] = serde_json::json!([{ "physicalLocation": location }]);
+        }
+
+        result
+    }
+}
+
+/// A diagnostic as a JUnit test case
+pub struct JunitCase {
+    /// The check that emitted the diagnostic, each of which is a test suite
+    suite: &'static str,
+    /// The name of the crate the diagnostic is for, if any
+    classname: Option<String>,
+    code: Option<String>,
+    message: String,
+    details: String,
+    failed: bool,
+}
+
+pub struct Junit<'a> {
+    cases: &'a std::sync::Mutex<Vec<JunitCase>>,
+}
+
+impl Junit<'_> {
+    /// Converts the diagnostic to a test case, errors are failures and
+    /// warnings are skipped
+    fn case(suite: &'static str, diag: &CsDiag, krate: Option<&Kid>) -> JunitCase {
+        JunitCase {
+            suite,
+            classname: krate.map(|kid| kid.name().to_owned()),
+            code: diag.code.clone(),
+            message: diag.message.clone(),
+            details: full_message(diag),
+            failed: diag.severity >= Severity::Error,
+        }
+    }
+}
+
+/// Writes the test cases as a JUnit XML report, see
+/// <https://github.com/testmoapp/junitxml>
+fn write_junit(mut w: impl Write, cases: &[JunitCase]) -> std::io::Result<()> {
+    let mut suites = std::collections::BTreeMap::<_, Vec<_>>::new();
+    for case in cases {
+        suites.entry(case.suite).or_default().push(case);
+    }
+
+    let failures = |cases: &[&JunitCase]| cases.iter().filter(|case| case.failed).count();
+    let all: Vec<_> = cases.iter().collect();
+
+    writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        w,
+        r#"<testsuites name="cargo-deny" tests="{}" failures="{}" skipped="{}">"#,
+        all.len(),
+        failures(&all),
+        all.len() - failures(&all)
+    )?;
+
+    for (suite, cases) in suites {
+        let failed = failures(&cases);
+        writeln!(
+            w,
+            r#"  <testsuite name="{suite}" tests="{}" failures="{failed}" skipped="{}">"#,
+            cases.len(),
+            cases.len() - failed
+        )?;
+
+        for case in cases {
+            let name = match &case.code {
+                Some(code) => format!("{code}: {}", case.message),
+                None => case.message.clone(),
+            };
+
+            writeln!(
+                w,
+                r#"    <testcase name="{}" classname="{}">"#,
+                escape_xml(&name),
+                escape_xml(case.classname.as_deref().unwrap_or(suite))
+            )?;
+
+            if case.failed {
+                writeln!(
+                    w,
+                    r#"      <failure message="{}" type="{}">{}</failure>"#,
+                    escape_xml(&case.message),
+                    escape_xml(case.code.as_deref().unwrap_or("error")),
+                    escape_xml(&case.details)
+                )?;
+            } else {
+                writeln!(
+                    w,
+                    r#"      <skipped message="{}"/>"#,
+                    escape_xml(&case.details)
+                )?;
+            }
+
+            writeln!(w, "    </testcase>")?;
+        }
+
+        writeln!(w, "  </testsuite>")?;
+    }
+
+    writeln!(w, "</testsuites>")?;
+    w.flush()
+}
+
+/// Escapes text for use in XML attributes and elements
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("&#10;"),
+            // Control characters other than tabs and newlines are not
+            // allowed in XML 1.0 at all
+            c if c.is_control() && c != '\t' && c != '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Gets the file a diagnostic is attached to for the formats that annotate
+/// files in the repository
+///
+/// Diagnostics for crates are only attached to the crate's manifest if it
+/// is a workspace member, as external crates have no file in the repository
+/// the annotation can be attached to, all other diagnostics are attached to
+/// the file of their primary label, eg. the config
+fn annotated_file<'f>(
+    krates: Option<&'f cargo_deny::Krates>,
+    diag: &'f diag::Diag,
+    files: &'f Files,
+) -> Option<&'f Path> {
+    let Some(gn) = diag.graph_nodes.first() else {
+        return primary_file(&diag.diag, files);
+    };
+
+    let krates = krates?;
+    krates
+        .workspace_members()
+        .any(|n| matches!(n, krates::Node::Krate { id, .. } if id == &gn.kid))
+        .then(|| krates.nid_for_kid(&gn.kid))
+        .flatten()
+        .map(|nid| krates[nid].manifest_path.as_path())
+}
+
+#[inline]
+fn primary_file<'f>(diag: &CsDiag, files: &'f Files) -> Option<&'f Path> {
+    diag.labels
+        .iter()
+        .find(|label| label.style == LabelStyle::Primary)
+        .and_then(|label| files.name(label.file_id).ok())
+}
+
+/// The 1-based line and column of the diagnostic in the file, preferring the
+/// primary label if there are multiple in the same file
+fn file_location(diag: &CsDiag, file: &Path, files: &Files) -> Option<(usize, usize)> {
+    diag.labels
+        .iter()
+        .filter(|label| files.name(label.file_id).is_ok_and(|name| name == file))
+        .min_by_key(|label| label.style != LabelStyle::Primary)
+        .and_then(|label| files.location(label.file_id, label.range.start as u32).ok())
+        .map(|location| (location.line.to_usize() + 1, location.column.to_usize() + 1))
+}
+
+#[inline]
+fn relative_path<'p>(root: Option<&Path>, file: &'p Path) -> &'p Path {
+    root.and_then(|root| file.strip_prefix(root).ok())
+        .unwrap_or(file)
+}
+
+/// The root of the repository, which file paths are made relative to
+fn repository_root() -> Option<PathBuf> {
+    std::env::var("GITHUB_WORKSPACE")
+        .ok()
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::current_dir()
+                .ok()
+                .and_then(|cd| cargo_deny::utf8path(cd).ok())
+        })
+}
+
+/// The message of the diagnostic followed by each of its notes, for formats
+/// that only have a single message
+fn full_message(diag: &CsDiag) -> String {
+    let mut message = diag.message.clone();
+    for note in &diag.notes {
+        message.push('\n');
+        message.push_str(note);
+    }
+    message
+}
+
+/// Escapes the message of a workflow command
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes the value of a workflow command property
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
 #[allow(clippy::large_enum_variant)]
 enum OutputFormat<'a> {
     Human(Human<'a>),
     Json(Json<'a>),
+    Github(Github<'a>),
+    Sarif(Sarif<'a>),
+    Junit(Junit<'a>),
 }
 
 impl<'a> OutputFormat<'a> {
+    fn new(
+        format: crate::Format,
+        file: Option<&'a OutputFile>,
+        color: crate::Color,
+        krates: Option<&'a cargo_deny::Krates>,
+        feature_depth: Option<u32>,
+    ) -> Self {
+        let stream = || {
+            file.map_or_else(
+                || StdioStream::Err(std::io::stderr()),
+                |of| StdioStream::File(&of.file),
+            )
+        };
+
+        match format {
+            crate::Format::Human => Self::Human(Human {
+                stream: match file {
+                    Some(of) => HumanStream::File(&of.file),
+                    None => HumanStream::Err(term::termcolor::StandardStream::stderr(
+                        color_to_choice(color, std::io::stderr()),
+                    )),
+                },
+                grapher: krates.map(diag::InclusionGrapher::new),
+                config: {
+                    let mut config = cargo_deny::diag::codespan_config();
+                    colors().apply(&mut config);
+                    config
+                },
+                feature_depth,
+            }),
+            crate::Format::Json => Self::Json(Json {
+                stream: stream(),
+                grapher: krates.map(diag::InclusionGrapher::new),
+            }),
+            crate::Format::Github => Self::Github(Github {
+                stream: stream(),
+                krates,
+                root: repository_root(),
+            }),
+            crate::Format::Sarif => Self::Sarif(Sarif {
+                // Writing SARIF to stderr is rejected when parsing the arguments
+                results: &file
+                    .expect("SARIF can only be written to a file")
+                    .sarif_results,
+                krates,
+                root: repository_root(),
+            }),
+            crate::Format::Junit => Self::Junit(Junit {
+                cases: &file
+                    .expect("JUnit can only be written to a file")
+                    .junit_cases,
+            }),
+        }
+    }
+
     fn lock(&'a self, max_severity: Severity) -> OutputLock<'a, 'a> {
         match self {
             Self::Human(human) => OutputLock::Human(
@@ -357,6 +1503,11 @@ impl<'a> OutputFormat<'a> {
                 human.feature_depth,
             ),
             Self::Json(json) => OutputLock::Json(json, max_severity, json.stream.lock()),
+            Self::Github(github) => OutputLock::Github(github, max_severity, github.stream.lock()),
+            Self::Sarif(sarif) => {
+                OutputLock::Sarif(sarif, max_severity, sarif.results.lock().unwrap())
+            }
+            Self::Junit(junit) => OutputLock::Junit(max_severity, junit.cases.lock().unwrap()),
         }
     }
 }
@@ -364,6 +1515,7 @@ impl<'a> OutputFormat<'a> {
 pub enum StdLock<'a> {
     Err(std::io::StderrLock<'a>),
     //Out(std::io::StdoutLock<'a>),
+    File(&'a std::fs::File),
 }
 
 impl Write for StdLock<'_> {
@@ -371,6 +1523,7 @@ impl Write for StdLock<'_> {
         match self {
             Self::Err(stderr) => stderr.write(d),
             //Self::Out(stdout) => stdout.write(d),
+            Self::File(file) => file.write(d),
         }
     }
 
@@ -378,6 +1531,7 @@ impl Write for StdLock<'_> {
         match self {
             Self::Err(stderr) => stderr.flush(),
             //Self::Out(stdout) => stdout.flush(),
+            Self::File(file) => file.flush(),
         }
     }
 }
@@ -386,10 +1540,17 @@ pub enum OutputLock<'a, 'b> {
     Human(
         &'a Human<'a>,
         Severity,
-        term::termcolor::StandardStreamLock<'b>,
+        Box<dyn term::termcolor::WriteColor + 'b>,
         Option<u32>,
     ),
     Json(&'a Json<'a>, Severity, StdLock<'b>),
+    Github(&'a Github<'a>, Severity, StdLock<'b>),
+    Sarif(
+        &'a Sarif<'a>,
+        Severity,
+        std::sync::MutexGuard<'b, Vec<serde_json::Value>>,
+    ),
+    Junit(Severity, std::sync::MutexGuard<'b, Vec<JunitCase>>),
 }
 
 impl OutputLock<'_, '_> {
@@ -417,6 +1578,28 @@ impl OutputLock<'_, '_> {
                     let _ = w.write(b"\n");
                 }
             }
+            Self::Github(cfg, max, w) => {
+                if diag.severity < *max {
+                    return;
+                }
+
+                cfg.emit(w, &diag, primary_file(&diag, files), files);
+            }
+            Self::Sarif(cfg, max, results) => {
+                if diag.severity < *max {
+                    return;
+                }
+
+                let fingerprint = diag::Diag::from(diag.clone()).fingerprint();
+                results.push(cfg.result(&diag, fingerprint, primary_file(&diag, files), files));
+            }
+            Self::Junit(max, cases) => {
+                if diag.severity < *max {
+                    return;
+                }
+
+                cases.push(Junit::case("cargo-deny", &diag, None));
+            }
         }
     }
 
@@ -473,20 +1656,96 @@ impl OutputLock<'_, '_> {
                     }
                 }
             }
+            Self::Github(cfg, max, w) => {
+                for diag in pack {
+                    if diag.diag.severity < *max {
+                        continue;
+                    }
+
+                    cfg.emit(
+                        w,
+                        &diag.diag,
+                        annotated_file(cfg.krates, &diag, files),
+                        files,
+                    );
+                }
+            }
+            Self::Sarif(cfg, max, results) => {
+                for diag in pack {
+                    if diag.diag.severity < *max {
+                        continue;
+                    }
+
+                    results.push(cfg.result(
+                        &diag.diag,
+                        diag.fingerprint(),
+                        annotated_file(cfg.krates, &diag, files),
+                        files,
+                    ));
+                }
+            }
+            Self::Junit(max, cases) => {
+                let suite = match pack.check {
+                    diag::Check::Advisories => "advisories",
+                    diag::Check::Bans => "bans",
+                    diag::Check::Licenses => "licenses",
+                    diag::Check::Sources => "sources",
+                };
+
+                for diag in pack {
+                    if diag.diag.severity < *max {
+                        continue;
+                    }
+
+                    cases.push(Junit::case(
+                        suite,
+                        &diag.diag,
+                        diag.graph_nodes.first().map(|gn| &gn.kid),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Locks every output of a [`DiagPrinter`], so that each diagnostic is
+/// written to all of them
+pub struct PrinterLock<'a> {
+    locks: Vec<OutputLock<'a, 'a>>,
+}
+
+impl PrinterLock<'_> {
+    pub fn print(&mut self, diag: CsDiag, files: &Files) {
+        if let Some((last, rest)) = self.locks.split_last_mut() {
+            for lock in rest {
+                lock.print(diag.clone(), files);
+            }
+            last.print(diag, files);
+        }
+    }
+
+    pub fn print_krate_pack(&mut self, pack: cargo_deny::diag::Pack, files: &Files) {
+        if let Some((last, rest)) = self.locks.split_last_mut() {
+            for lock in rest {
+                lock.print_krate_pack(pack.clone(), files);
+            }
+            last.print_krate_pack(pack, files);
         }
     }
 }
 
 #[derive(Clone, Copy)]
 pub struct LogContext {
+    /// The format of the log messages, and the diagnostics written to stderr
     pub format: crate::Format,
     pub color: crate::Color,
     pub log_level: log::LevelFilter,
+    /// If true, diagnostics below [`Severity::Warning`] are not printed
+    pub quiet: bool,
 }
 
 pub struct DiagPrinter<'a> {
-    which: OutputFormat<'a>,
-    max_severity: Severity,
+    outputs: Vec<(OutputFormat<'a>, Severity)>,
 }
 
 impl<'a> DiagPrinter<'a> {
@@ -494,38 +1753,257 @@ impl<'a> DiagPrinter<'a> {
         ctx: LogContext,
         krates: Option<&'a cargo_deny::Krates>,
         feature_depth: Option<u32>,
+        files: &'a [OutputFile],
     ) -> Option<Self> {
-        let max_severity = log_level_to_severity(ctx.log_level);
-
-        max_severity.map(|max_severity| match ctx.format {
-            crate::Format::Human => {
-                let stream = term::termcolor::StandardStream::stderr(color_to_choice(
-                    ctx.color,
-                    std::io::stderr(),
-                ));
-
-                Self {
-                    which: OutputFormat::Human(Human {
-                        stream,
-                        grapher: krates.map(diag::InclusionGrapher::new),
-                        config: cargo_deny::diag::codespan_config(),
-                        feature_depth,
-                    }),
-                    max_severity,
-                }
+        let max_severity = log_level_to_severity(ctx.log_level).map(|max_severity| {
+            if ctx.quiet {
+                max_severity.max(Severity::Warning)
+            } else {
+                max_severity
             }
-            crate::Format::Json => Self {
-                which: OutputFormat::Json(Json {
-                    stream: StdioStream::Err(std::io::stderr()),
-                    grapher: krates.map(diag::InclusionGrapher::new),
-                }),
-                max_severity,
-            },
-        })
+        })?;
+
+        let outputs = std::iter::once((ctx.format, None))
+            .chain(files.iter().map(|of| (of.format, Some(of))))
+            .map(|(format, file)| {
+                let max_severity = match format {
+                    crate::Format::Human | crate::Format::Json => max_severity,
+                    // Annotations, code scanning alerts, and test cases are
+                    // only useful for errors and warnings
+                    crate::Format::Github | crate::Format::Sarif | crate::Format::Junit => {
+                        max_severity.max(Severity::Warning)
+                    }
+                };
+
+                (
+                    OutputFormat::new(format, file, ctx.color, krates, feature_depth),
+                    max_severity,
+                )
+            })
+            .collect();
+
+        Some(Self { outputs })
     }
 
     #[inline]
-    pub fn lock(&'a self) -> OutputLock<'a, 'a> {
-        self.which.lock(self.max_severity)
+    pub fn lock(&'a self) -> PrinterLock<'a> {
+        PrinterLock {
+            locks: self
+                .outputs
+                .iter()
+                .map(|(which, max_severity)| which.lock(*max_severity))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    /// Ensures a crate graph can be built from just a lockfile, including
+    /// dependencies that are disambiguated by their version
+    #[test]
+    fn builds_graph_from_lockfile() {
+        let path = cargo_deny::Path::new("tests/test_data/build-bans/Cargo.lock");
+        let md = super::load_lockfile(path).unwrap();
+
+        let contents = std::fs::read_to_string(path).unwrap();
+        let packages = contents.matches("[[package]]").count();
+
+        let krates: cargo_deny::Krates = krates::Builder::new()
+            .build_with_metadata(md, krates::NoneFilter)
+            .unwrap();
+
+        assert_eq!(krates.len(), packages);
+        assert_eq!(krates.krates_by_name("bitflags").count(), 2);
+    }
+
+    /// Ensures only the dev dependencies of the specified crates are removed
+    /// from the graph
+    #[test]
+    fn excludes_dev_deps_of_specific_crates() {
+        let build = |exclude: &[&str]| -> cargo_deny::Krates {
+            let mut md = super::read_metadata(cargo_deny::Path::new(
+                "tests/test_data/features-galore/metadata.json",
+            ))
+            .unwrap();
+            super::exclude_dev_deps_of(
+                &mut md,
+                &exclude.iter().map(|s| (*s).to_owned()).collect::<Vec<_>>(),
+            );
+
+            krates::Builder::new()
+                .build_with_metadata(md, krates::NoneFilter)
+                .unwrap()
+        };
+
+        let all = build(&[]);
+        assert_eq!(all.krates_by_name("simple_ecs").count(), 1);
+
+        // Crates without dev dependencies are unaffected
+        assert_eq!(build(&["simple_ecs"]).len(), all.len());
+
+        let excluded = build(&["features-galore"]);
+        assert_eq!(excluded.krates_by_name("simple_ecs").count(), 0);
+        assert!(excluded.len() < all.len());
+    }
+
+    /// Ensures only the crates used solely by the host lose their default
+    /// features, while features enabled by their dependents are kept
+    #[test]
+    fn disables_host_default_features() {
+        let mut md = super::read_metadata(cargo_deny::Path::new(
+            "tests/test_data/features-galore/metadata.json",
+        ))
+        .unwrap();
+        super::disable_host_default_features(&mut md);
+
+        let features = |name: &str| {
+            let pkg = md.packages.iter().find(|pkg| pkg.name == name).unwrap();
+            let resolve = md.resolve.as_ref().unwrap();
+            let node = resolve.nodes.iter().find(|node| node.id == pkg.id).unwrap();
+            node.features.clone()
+        };
+
+        // serde_derive is a proc-macro so is only used by the host
+        assert!(features("serde_derive").is_empty());
+        // reqwest is both a normal and build dependency of the workspace
+        assert!(features("reqwest").contains(&"cookies".to_owned()));
+        // wasm-bindgen enables the feature on its proc-macro
+        assert_eq!(features("wasm-bindgen-macro"), ["spans"]);
+    }
+
+    /// Ensures diagnostics are grouped into a test suite per check, with
+    /// errors as failures and warnings as skipped
+    #[test]
+    fn writes_junit_report() {
+        use super::JunitCase;
+
+        let cases = [
+            JunitCase {
+                suite: "licenses",
+                classname: None,
+                code: Some("license-not-encountered".to_owned()),
+                message: "license was not encountered".to_owned(),
+                details: "license was not encountered".to_owned(),
+                failed: false,
+            },
+            JunitCase {
+                suite: "bans",
+                classname: Some("openssl".to_owned()),
+                code: Some("banned".to_owned()),
+                message: "crate 'openssl = 0.10.66' is explicitly banned".to_owned(),
+                details: "crate 'openssl = 0.10.66' is explicitly banned\nreason: <vendored>"
+                    .to_owned(),
+                failed: true,
+            },
+        ];
+
+        let mut report = Vec::new();
+        super::write_junit(&mut report, &cases).unwrap();
+
+        assert_eq!(
+            String::from_utf8(report).unwrap(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="cargo-deny" tests="2" failures="1" skipped="1">
+  <testsuite name="bans" tests="1" failures="1" skipped="0">
+    <testcase name="banned: crate 'openssl = 0.10.66' is explicitly banned" classname="openssl">
+      <failure message="crate 'openssl = 0.10.66' is explicitly banned" type="banned">crate 'openssl = 0.10.66' is explicitly banned&#10;reason: &lt;vendored&gt;</failure>
+    </testcase>
+  </testsuite>
+  <testsuite name="licenses" tests="1" failures="0" skipped="1">
+    <testcase name="license-not-encountered: license was not encountered" classname="licenses">
+      <skipped message="license was not encountered"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+
+    /// Ensures configured colors replace the defaults for their severity only
+    #[test]
+    fn applies_configured_colors() {
+        use cargo_deny::root_cfg::OutputConfig;
+        use codespan_reporting::term::termcolor::Color;
+        use toml_span::Deserialize as _;
+
+        let mut value = toml_span::parse(
+            r#"
+[colors]
+error = "magenta"
+note = 208
+"#,
+        )
+        .unwrap();
+        let colors = OutputConfig::deserialize(&mut value).unwrap().colors;
+
+        let mut config = cargo_deny::diag::codespan_config();
+        colors.apply(&mut config);
+
+        assert_eq!(config.styles.header_error.fg(), Some(&Color::Magenta));
+        assert_eq!(config.styles.primary_label_bug.fg(), Some(&Color::Magenta));
+        assert_eq!(config.styles.header_note.fg(), Some(&Color::Ansi256(208)));
+        assert_eq!(config.styles.header_warning.fg(), Some(&Color::Yellow));
+
+        assert_eq!(
+            super::ansi_color(colors.note, nu_ansi_term::Color::Blue),
+            nu_ansi_term::Color::Fixed(208)
+        );
+        assert_eq!(
+            super::ansi_color(colors.warning, nu_ansi_term::Color::Yellow),
+            nu_ansi_term::Color::Yellow
+        );
+
+        let mut value = toml_span::parse("[colors]\nerror = 256").unwrap();
+        assert!(OutputConfig::deserialize(&mut value).is_err());
+    }
+
+    /// Ensures every named color is converted to a distinct color for log
+    /// messages, rather than falling back to the default
+    #[test]
+    fn converts_every_named_color() {
+        use cargo_deny::root_cfg::{NamedColor, TermColor};
+        use strum::VariantArray as _;
+
+        let default = nu_ansi_term::Color::Rgb(1, 2, 3);
+        let colors: Vec<_> = NamedColor::VARIANTS
+            .iter()
+            .map(|named| super::ansi_color(Some(TermColor::Named(*named)), default))
+            .collect();
+
+        assert!(!colors.contains(&default));
+        for (i, color) in colors.iter().enumerate() {
+            assert!(!colors[i + 1..].contains(color));
+        }
+        assert_eq!(
+            super::ansi_color(Some(TermColor::Named(NamedColor::Magenta)), default),
+            nu_ansi_term::Color::Purple
+        );
+    }
+
+    /// Ensures SARIF results use the same fingerprint as `output.acknowledge`
+    #[test]
+    fn sarif_results_have_fingerprints() {
+        use cargo_deny::diag::{Diag, Diagnostic, Files};
+
+        let results = std::sync::Mutex::new(Vec::new());
+        let sarif = super::Sarif {
+            results: &results,
+            krates: None,
+            root: None,
+        };
+
+        let diag = Diagnostic::warning()
+            .with_code("unmatched-skip")
+            .with_message("skipped crate 'a' was not encountered");
+        let fingerprint = Diag::from(diag.clone()).fingerprint();
+
+        let result = sarif.result(&diag, fingerprint.clone(), None, &Files::new());
+
+        assert_eq!(result["ruleId"], "unmatched-skip");
+        assert_eq!(
+            result["partialFingerprints"]["cargoDeny/v1"],
+            fingerprint.as_str()
+        );
     }
 }
@@ -2,9 +2,10 @@ use anyhow::{Context as _, Result};
 use cargo_deny::{
     diag::{Diagnostic, Files, Severity},
     root_cfg::{GraphConfig, OutputConfig},
-    PathBuf, {advisories, bans, licenses, sources},
+    PathBuf, {advisories, annotations, bans, licenses, sources},
 };
 
+#[derive(serde::Serialize)]
 pub struct ValidConfig {
     pub advisories: advisories::cfg::ValidConfig,
     pub bans: bans::cfg::ValidConfig,
@@ -12,12 +13,18 @@ pub struct ValidConfig {
     pub sources: sources::cfg::ValidConfig,
     pub graph: GraphConfig,
     pub output: OutputConfig,
+    pub annotations: annotations::ValidConfig,
 }
 
 impl ValidConfig {
+    /// Loads and validates the config
+    ///
+    /// If `deny_list` is `Some`, the [`bans.deny-from`](bans::cfg::load_deny_list)
+    /// deny list is also loaded, with the value specifying if cargo-deny is offline
     pub fn load(
         cfg_path: Option<PathBuf>,
         exceptions_cfg_path: Option<PathBuf>,
+        deny_list: Option<bool>,
         files: &mut Files,
         log_ctx: crate::common::LogContext,
     ) -> Result<Self> {
@@ -41,14 +48,19 @@ impl ValidConfig {
             }
         };
 
-        let id = files.add(&cfg_path, cfg_contents);
+        // The same config is loaded for every workspace when checking multiple,
+        // so it is only added, and any problems with it reported, once
+        let (id, reloaded) = match files.id_for_path(&cfg_path) {
+            Some(id) => (id, true),
+            None => (files.add(&cfg_path, cfg_contents), false),
+        };
 
         let print = |files: &Files, diags: Vec<Diagnostic>| {
             if diags.is_empty() {
                 return;
             }
 
-            if let Some(printer) = crate::common::DiagPrinter::new(log_ctx, None, None) {
+            if let Some(printer) = crate::common::DiagPrinter::new(log_ctx, None, None, &[]) {
                 let mut lock = printer.lock();
                 for diag in diags {
                     lock.print(diag, files);
@@ -90,14 +102,19 @@ impl ValidConfig {
                         diagnostics: &mut diags,
                     });
 
-            let bans = cfg
-                .bans
-                .unwrap_or_default()
-                .validate(cargo_deny::cfg::ValidationContext {
-                    cfg_id: id,
-                    files,
-                    diagnostics: &mut diags,
-                });
+            let mut bans =
+                cfg.bans
+                    .unwrap_or_default()
+                    .validate(cargo_deny::cfg::ValidationContext {
+                        cfg_id: id,
+                        files,
+                        diagnostics: &mut diags,
+                    });
+
+            // Only fetch the remote deny list if it's actually going to be used
+            if let Some(offline) = deny_list {
+                bans::cfg::load_deny_list(&mut bans, offline, files, &mut diags);
+            }
             let mut licenses =
                 cfg.licenses
                     .unwrap_or_default()
@@ -126,7 +143,9 @@ impl ValidConfig {
             // or even parseable as it might mean it won't match against a cfg
             // expression they were expecting it to
             for target in &cfg.graph.targets {
-                if !matches!(&target.filter.value, krates::Target::Unknown(_)) {
+                if !matches!(&target.filter.value, krates::Target::Unknown(_))
+                    || target.is_wildcard()
+                {
                     continue;
                 }
 
@@ -176,11 +195,19 @@ impl ValidConfig {
                     sources,
                     graph: cfg.graph,
                     output: cfg.output,
+                    annotations: annotations::ValidConfig {
+                        file_id: id,
+                        annotations: cfg.annotations,
+                    },
                 },
             )
         };
 
-        let (diags, valid_cfg) = validate();
+        let (mut diags, valid_cfg) = validate();
+
+        if reloaded {
+            diags.retain(|diag| !diag.labels.iter().any(|label| label.file_id == id));
+        }
 
         let has_errors = diags.iter().any(|d| d.severity >= Severity::Error);
 
@@ -32,6 +32,7 @@ pub fn cmd(
     let ValidConfig { advisories, .. } = ValidConfig::load(
         cfg_path,
         krate_ctx.get_local_exceptions_path(),
+        None,
         &mut files,
         log_ctx,
     )?;
@@ -62,6 +63,8 @@ pub fn cmd(
 
         if fetch_db {
             s.spawn(|_| {
+                let timeout = advisories.fetch_timeout(None);
+
                 // This function already logs internally
                 dbs = Some(advisories::DbSet::load(
                     advisories.db_path,
@@ -75,6 +78,7 @@ pub fn cmd(
                     } else {
                         advisories::Fetch::Allow
                     },
+                    timeout,
                 ));
             });
         }
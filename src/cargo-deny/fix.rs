@@ -0,0 +1,364 @@
+//! Support for `fix`, which edits the configuration to resolve issues reported
+//! by the checks, preserving all other formatting and comments
+
+use crate::common::ValidConfig;
+use anyhow::{Context as _, Error};
+use cargo_deny::{
+    diag::Files,
+    licenses::{self, LicenseInfo},
+    Krate, PathBuf,
+};
+use std::io::{BufRead as _, IsTerminal as _, Write as _};
+use toml_edit::{DocumentMut, Item};
+
+#[derive(clap::Subcommand, Debug)]
+pub enum Command {
+    /// Adds `[[licenses.clarify]]` entries for crates whose license could not be determined
+    #[command(name = "licenses")]
+    Licenses(LicensesArgs),
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct Args {
+    #[command(subcommand)]
+    cmd: Command,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct LicensesArgs {
+    /// Path to the config to add clarifications to
+    ///
+    /// Defaults to a deny.toml in the same folder as the manifest path, or a deny.toml in a parent directory.
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+    /// Adds the suggested clarification for every unlicensed crate without prompting
+    ///
+    /// Crates where none of the license files resemble a known license are skipped, as there is no expression to suggest.
+    #[arg(short, long)]
+    yes: bool,
+}
+
+pub fn cmd(
+    log_ctx: crate::common::LogContext,
+    args: Args,
+    krate_ctx: crate::common::KrateContext,
+) -> Result<(), Error> {
+    match args.cmd {
+        Command::Licenses(largs) => licenses(log_ctx, largs, krate_ctx),
+    }
+}
+
+fn licenses(
+    log_ctx: crate::common::LogContext,
+    args: LicensesArgs,
+    mut krate_ctx: crate::common::KrateContext,
+) -> Result<(), Error> {
+    let cfg_path = krate_ctx
+        .get_config_path(args.config)
+        .context("unable to find a config to add clarifications to")?;
+
+    anyhow::ensure!(
+        args.yes || std::io::stdin().is_terminal(),
+        "stdin is not a terminal, use --yes to add clarifications without prompting"
+    );
+
+    let mut files = Files::new();
+    let ValidConfig {
+        mut graph,
+        licenses,
+        ..
+    } = ValidConfig::load(
+        Some(cfg_path.clone()),
+        krate_ctx.get_local_exceptions_path(),
+        None,
+        &mut files,
+        log_ctx,
+    )?;
+
+    krate_ctx.merge_graph_config(&mut graph);
+
+    let (krates, store) = rayon::join(
+        || krate_ctx.gather_krates(graph.targets, graph.exclude, None),
+        crate::common::load_license_store,
+    );
+
+    let krates = krates.context("failed to gather crates")?;
+    let store = store.context("failed to load license store")?;
+
+    let gatherer = licenses::Gatherer::default()
+        .with_store(std::sync::Arc::new(store))
+        .with_confidence_threshold(licenses.confidence_threshold);
+
+    // The existing clarifications are used so that crates that have already
+    // been clarified are not considered unlicensed
+    let summary = gatherer.gather(&krates, &mut files, Some(&licenses));
+
+    let mut stanzas = Vec::new();
+    for nfo in &summary.nfos {
+        if !matches!(nfo.lic_info, LicenseInfo::Unlicensed) {
+            continue;
+        }
+
+        let Some(suggested) = &nfo.suggested_clarification else {
+            log::warn!(
+                "crate '{}' is unlicensed, but has no license files that can be used for a clarification",
+                nfo.krate
+            );
+            continue;
+        };
+
+        let expression = if args.yes {
+            let Some(expression) = suggested.expression.clone() else {
+                log::warn!(
+                    "skipping crate '{}' as the license could not be guessed from its license files",
+                    nfo.krate
+                );
+                continue;
+            };
+
+            expression
+        } else {
+            let Some(expression) = prompt(nfo.krate, suggested)? else {
+                continue;
+            };
+
+            expression
+        };
+
+        if let Err(err) = spdx::Expression::parse(&expression) {
+            log::error!(
+                "skipping crate '{}' as '{expression}' is not a valid SPDX expression: {err}",
+                nfo.krate
+            );
+            continue;
+        }
+
+        let suggested = licenses::SuggestedClarification {
+            expression: Some(expression),
+            license_files: suggested.license_files.clone(),
+        };
+
+        stanzas.push((nfo.krate, suggested.stanza(nfo.krate)));
+    }
+
+    if stanzas.is_empty() {
+        log::info!("no clarifications to add");
+        return Ok(());
+    }
+
+    let contents = std::fs::read_to_string(&cfg_path)
+        .with_context(|| format!("failed to read '{cfg_path}'"))?;
+    let mut doc: DocumentMut = contents
+        .parse()
+        .with_context(|| format!("failed to parse '{cfg_path}'"))?;
+
+    for (krate, stanza) in &stanzas {
+        add_clarification(&mut doc, stanza)
+            .with_context(|| format!("failed to add clarification for '{krate}'"))?;
+    }
+
+    std::fs::write(&cfg_path, doc.to_string())
+        .with_context(|| format!("failed to write '{cfg_path}'"))?;
+
+    for (krate, _) in stanzas {
+        log::info!("added clarification for '{krate}' to '{cfg_path}'");
+    }
+
+    Ok(())
+}
+
+/// Prompts the user to confirm the suggested clarification, returning the
+/// expression to use, or `None` if the crate should be skipped
+fn prompt(
+    krate: &Krate,
+    suggested: &licenses::SuggestedClarification,
+) -> Result<Option<String>, Error> {
+    let mut stderr = std::io::stderr().lock();
+    let mut stdin = std::io::stdin().lock();
+    let mut line = String::new();
+
+    writeln!(
+        stderr,
+        "crate '{krate}' is unlicensed, suggested clarification:\n{}\n",
+        suggested.stanza(krate)
+    )?;
+
+    if let Some(expression) = &suggested.expression {
+        write!(
+            stderr,
+            "add clarification with expression '{expression}'? [y/N/<expression>] "
+        )?;
+    } else {
+        write!(
+            stderr,
+            "SPDX expression for '{krate}' (leave empty to skip): "
+        )?;
+    }
+    stderr.flush()?;
+
+    stdin.read_line(&mut line)?;
+    let answer = line.trim();
+
+    Ok(match (answer, &suggested.expression) {
+        ("", _) => None,
+        ("y" | "Y" | "yes", Some(expression)) => Some(expression.clone()),
+        ("n" | "N" | "no", Some(_)) => None,
+        (expression, _) => Some(expression.to_owned()),
+    })
+}
+
+/// Appends the `[[licenses.clarify]]` stanza to the config
+fn add_clarification(doc: &mut DocumentMut, stanza: &str) -> Result<(), Error> {
+    let mut parsed: DocumentMut = stanza.parse()?;
+    let mut table = parsed["licenses"]["clarify"]
+        .as_array_of_tables_mut()
+        .and_then(|aot| aot.iter_mut().next())
+        .map(std::mem::take)
+        .context("failed to parse clarification stanza")?;
+
+    // The table keeps its position from the stanza, so it needs to be placed
+    // after the existing clarifications, or the `licenses` table, and at the
+    // end of the config if neither exist
+    let position = match doc.get("licenses") {
+        Some(Item::Table(licenses)) => match licenses.get("clarify") {
+            Some(Item::ArrayOfTables(aot)) => aot.iter().filter_map(|t| t.position()).max(),
+            _ => None,
+        }
+        .or(licenses.position()),
+        _ => None,
+    };
+    table.set_position(position.unwrap_or(usize::MAX));
+
+    let licenses = doc.entry("licenses").or_insert_with(|| {
+        let mut table = toml_edit::Table::new();
+        table.set_implicit(true);
+        Item::Table(table)
+    });
+
+    // An inline table can only contain inline values, so the clarifications
+    // need to be an inline array of inline tables
+    let clarify = if licenses.is_inline_table() {
+        Item::Value(toml_edit::Value::Array(toml_edit::Array::new()))
+    } else {
+        Item::ArrayOfTables(toml_edit::ArrayOfTables::new())
+    };
+
+    let clarify = licenses
+        .as_table_like_mut()
+        .context("`licenses` is not a table")?
+        .entry("clarify")
+        .or_insert(clarify);
+
+    // The clarifications might have been specified as an inline array
+    match clarify {
+        Item::ArrayOfTables(aot) => aot.push(table),
+        Item::Value(toml_edit::Value::Array(array)) => {
+            array.push(table.into_inline_table());
+        }
+        _ => anyhow::bail!("`licenses.clarify` is not an array"),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const STANZA: &str = r#"[[licenses.clarify]]
+crate = "new-crate@0.1.0"
+expression = "MIT"
+license-files = [
+    { path = "LICENSE", hash = 0x12345678 },
+]"#;
+
+    /// Adds the clarification to the config, returning the crates that are
+    /// clarified in the resulting config, which must be valid
+    fn clarify(config: &str) -> (String, Vec<String>) {
+        let mut doc: DocumentMut = config.parse().unwrap();
+        add_clarification(&mut doc, STANZA).unwrap();
+
+        let config = doc.to_string();
+        let doc: DocumentMut = config
+            .parse()
+            .unwrap_or_else(|err| panic!("invalid config {err}:\n{config}"));
+
+        let clarify = &doc["licenses"]["clarify"];
+        let crates = if let Some(aot) = clarify.as_array_of_tables() {
+            aot.iter()
+                .map(|table| table["crate"].as_str().unwrap().to_owned())
+                .collect()
+        } else {
+            clarify
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|value| {
+                    value.as_inline_table().unwrap()["crate"]
+                        .as_str()
+                        .unwrap()
+                        .to_owned()
+                })
+                .collect()
+        };
+
+        (config, crates)
+    }
+
+    /// Ensures the `licenses` table is created if it doesn't exist
+    #[test]
+    fn adds_clarification_without_licenses_table() {
+        let (config, crates) = clarify("[bans]\nmultiple-versions = \"deny\"\n");
+
+        assert_eq!(crates, ["new-crate@0.1.0"]);
+        assert!(config.starts_with("[bans]\nmultiple-versions = \"deny\"\n"));
+        assert!(config.contains("[[licenses.clarify]]\n"));
+        assert!(!config.contains("[licenses]"));
+    }
+
+    /// Ensures the clarification is appended to existing clarifications
+    #[test]
+    fn adds_clarification_to_array_of_tables() {
+        let (config, crates) = clarify(
+            r#"[licenses]
+allow = ["MIT"]
+
+# Clarified by hand
+[[licenses.clarify]]
+crate = "old-crate"
+expression = "ISC"
+license-files = [{ path = "LICENSE", hash = 0x87654321 }]
+"#,
+        );
+
+        assert_eq!(crates, ["old-crate", "new-crate@0.1.0"]);
+        assert!(config.contains("# Clarified by hand\n[[licenses.clarify]]\ncrate = \"old-crate\""));
+    }
+
+    /// Ensures the clarification is added as an inline table if the existing
+    /// clarifications are an inline array
+    #[test]
+    fn adds_clarification_to_inline_array() {
+        let (config, crates) = clarify(
+            r#"[licenses]
+clarify = [
+    { crate = "old-crate", expression = "ISC", license-files = [] },
+]
+"#,
+        );
+
+        assert_eq!(crates, ["old-crate", "new-crate@0.1.0"]);
+        assert!(!config.contains("[[licenses.clarify]]"));
+    }
+
+    /// Ensures the clarification is added as an inline array to an inline
+    /// `licenses` table, as it can't contain an array of tables
+    #[test]
+    fn adds_clarification_to_inline_licenses_table() {
+        let (config, crates) = clarify("licenses = { allow = [\"MIT\"] }\n");
+
+        assert_eq!(crates, ["new-crate@0.1.0"]);
+        assert!(config.starts_with("licenses = {"));
+        assert!(!config.contains("[[licenses.clarify]]"));
+    }
+}
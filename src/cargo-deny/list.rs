@@ -4,6 +4,8 @@ use cargo_deny::{diag::Files, licenses, Kid, PathBuf};
 use nu_ansi_term::Color;
 use serde::Serialize;
 
+mod sbom;
+
 #[derive(clap::ValueEnum, Copy, Clone, Debug)]
 pub enum Layout {
     Crate,
@@ -15,6 +17,28 @@ pub enum OutputFormat {
     Human,
     Json,
     Tsv,
+    Spdx,
+    #[value(name = "cyclonedx")]
+    CycloneDx,
+}
+
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SourceKind {
+    CratesIo,
+    Registry,
+    Git,
+    Path,
+}
+
+impl SourceKind {
+    fn of(krate: &cargo_deny::Krate) -> Self {
+        match &krate.source {
+            None => Self::Path,
+            Some(src) if src.is_crates_io() => Self::CratesIo,
+            Some(src) if src.is_git() => Self::Git,
+            Some(_) => Self::Registry,
+        }
+    }
 }
 
 #[derive(clap::Parser, Debug)]
@@ -34,15 +58,42 @@ pub struct Args {
     /// The format of the output
     #[arg(short, long, default_value = "human", value_enum)]
     format: OutputFormat,
-    /// The layout for the output, does not apply to TSV
+    /// The layout for the output, does not apply to TSV, SPDX, or CycloneDX
     #[arg(short, long, default_value = "license", value_enum)]
     layout: Layout,
+    /// Outputs the number of crates that use each license expression, and the fraction of the graph that represents, instead of listing the crates
+    ///
+    /// Each license expression is counted as a whole, eg. `MIT OR Apache-2.0` is a single entry rather than counting towards both `MIT` and `Apache-2.0`. Only applies to the human and JSON formats.
+    #[arg(long)]
+    summary: bool,
+    /// Only lists crates from the specified source(s)
+    ///
+    /// `registry` is any registry other than crates.io, and `path` includes the workspace members. Can be specified multiple times, and applies to every format.
+    #[arg(long, value_enum)]
+    source: Vec<SourceKind>,
+    /// Only lists crates that are not sourced from crates.io
+    #[arg(long, conflicts_with = "source")]
+    non_crates_io: bool,
+}
+
+impl Args {
+    /// Whether the crate's source matches the `--source` and `--non-crates-io`
+    /// filters, if any
+    fn includes(&self, krate: &cargo_deny::Krate) -> bool {
+        let kind = SourceKind::of(krate);
+
+        if self.non_crates_io {
+            kind != SourceKind::CratesIo
+        } else {
+            self.source.is_empty() || self.source.contains(&kind)
+        }
+    }
 }
 
 pub fn cmd(
     log_ctx: crate::common::LogContext,
     args: Args,
-    krate_ctx: crate::common::KrateContext,
+    mut krate_ctx: crate::common::KrateContext,
 ) -> Result<(), Error> {
     use licenses::LicenseInfo;
     use std::{collections::BTreeMap, fmt::Write};
@@ -50,15 +101,18 @@ pub fn cmd(
     let cfg_path = krate_ctx.get_config_path(args.config.clone());
 
     let mut files = Files::new();
-    let ValidConfig { graph, .. } = ValidConfig::load(
+    let ValidConfig { mut graph, .. } = ValidConfig::load(
         cfg_path,
         krate_ctx.get_local_exceptions_path(),
+        None,
         &mut files,
         log_ctx,
     )?;
 
+    krate_ctx.merge_graph_config(&mut graph);
+
     let (krates, store) = rayon::join(
-        || krate_ctx.gather_krates(graph.targets, graph.exclude),
+        || krate_ctx.gather_krates(graph.targets, graph.exclude, None),
         crate::common::load_license_store,
     );
 
@@ -69,9 +123,39 @@ pub fn cmd(
         .with_store(std::sync::Arc::new(store))
         .with_confidence_threshold(args.threshold);
 
+    anyhow::ensure!(
+        !args.summary || matches!(args.format, OutputFormat::Human | OutputFormat::Json),
+        "--summary is only supported for the human and json formats"
+    );
+
     let mut files = Files::new();
 
-    let summary = gatherer.gather(&krates, &mut files, None);
+    let mut summary = gatherer.gather(&krates, &mut files, None);
+    summary.nfos.retain(|nfo| args.includes(nfo.krate));
+
+    // The SBOM formats have their own fixed layout
+    let sbom = match args.format {
+        OutputFormat::Spdx => Some(sbom::spdx(
+            &krates,
+            &summary.nfos,
+            time::OffsetDateTime::now_utc(),
+        )?),
+        OutputFormat::CycloneDx => Some(sbom::cyclonedx(
+            &krates,
+            &summary.nfos,
+            time::OffsetDateTime::now_utc(),
+        )?),
+        _ => None,
+    };
+
+    if let Some(sbom) = sbom {
+        std::io::Write::write_all(&mut std::io::stdout(), sbom.as_bytes())?;
+        return Ok(());
+    }
+
+    if args.summary {
+        return write_summary(&summary.nfos, args.format, log_ctx);
+    }
 
     use std::borrow::Cow;
 
@@ -329,6 +413,95 @@ pub fn cmd(
 
             std::io::Write::write_all(&mut std::io::stdout(), output.as_bytes())?;
         }
+        OutputFormat::Spdx | OutputFormat::CycloneDx => unreachable!(),
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct LicenseCount {
+    license: String,
+    crate_count: usize,
+    fraction: f64,
+}
+
+/// Writes the number of crates that use each license expression, sorted by the
+/// most used
+fn write_summary(
+    nfos: &[licenses::KrateLicense<'_>],
+    format: OutputFormat,
+    log_ctx: crate::common::LogContext,
+) -> Result<(), Error> {
+    use std::{collections::BTreeMap, fmt::Write};
+
+    const UNLICENSED: &str = "Unlicensed";
+
+    let mut counts = BTreeMap::<String, usize>::new();
+    for nfo in nfos {
+        let license = match &nfo.lic_info {
+            licenses::LicenseInfo::SpdxExpression { expr, .. } => expr.to_string(),
+            licenses::LicenseInfo::Unlicensed => UNLICENSED.to_owned(),
+        };
+
+        *counts.entry(license).or_default() += 1;
+    }
+
+    let total = nfos.len().max(1) as f64;
+    let mut counts: Vec<_> = counts
+        .into_iter()
+        .map(|(license, crate_count)| LicenseCount {
+            license,
+            crate_count,
+            fraction: crate_count as f64 / total,
+        })
+        .collect();
+    // The map is already sorted by license, so ties remain in that order
+    counts.sort_by(|a, b| b.crate_count.cmp(&a.crate_count));
+
+    match format {
+        OutputFormat::Human => {
+            let mut output = String::with_capacity(4 * 1024);
+            let color = crate::common::should_colorize(log_ctx.color, std::io::stdout());
+
+            let width = counts
+                .iter()
+                .map(|lc| lc.license.len())
+                .max()
+                .unwrap_or_default()
+                .max("License".len());
+
+            writeln!(
+                output,
+                "{:<width$}  {:>6}  {:>7}",
+                "License", "Crates", "Percent"
+            )?;
+
+            for lc in &counts {
+                let license = format!("{:<width$}", lc.license);
+                if color {
+                    let color = if lc.license == UNLICENSED {
+                        Color::Red
+                    } else {
+                        Color::Cyan
+                    };
+                    write!(output, "{}", color.paint(license))?;
+                } else {
+                    write!(output, "{license}")?;
+                }
+
+                writeln!(
+                    output,
+                    "  {:>6}  {:>6.1}%",
+                    lc.crate_count,
+                    lc.fraction * 100.0
+                )?;
+            }
+
+            std::io::Write::write_all(&mut std::io::stdout(), output.as_bytes())?;
+        }
+        OutputFormat::Json => serde_json::to_writer(std::io::stdout(), &counts)?,
+        OutputFormat::Tsv | OutputFormat::Spdx | OutputFormat::CycloneDx => unreachable!(),
     }
 
     Ok(())
@@ -0,0 +1,568 @@
+//! Serialization of the gathered license information as a software bill of
+//! materials (SBOM), in either the [SPDX](https://spdx.github.io/spdx-spec/v2.3/)
+//! or [CycloneDX](https://cyclonedx.org/docs/1.5/json/) format
+
+use cargo_deny::{
+    licenses::{KrateLicense, LicenseExprSource, LicenseInfo},
+    Krate, Krates, Source,
+};
+use serde::Serialize;
+
+const NOASSERTION: &str = "NOASSERTION";
+
+const TIMESTAMP: &[time::format_description::FormatItem<'static>] =
+    time::macros::format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]Z");
+
+/// The license information for a single crate, shared by both formats
+struct Component<'k> {
+    krate: &'k Krate,
+    /// The expression that was concluded for the crate, which is the same
+    /// expression that `check licenses` evaluates
+    concluded: Option<String>,
+    /// The expression the crate itself declared in its manifest
+    declared: Option<String>,
+    /// Where the concluded expression came from
+    source: Option<&'static str>,
+}
+
+impl<'k> Component<'k> {
+    fn new(nfo: &KrateLicense<'k>) -> Self {
+        let krate = nfo.krate;
+        // The license field is not guaranteed to be a valid SPDX expression,
+        // so only declare it if it is
+        let manifest_license = || {
+            krate
+                .license
+                .as_deref()
+                .and_then(|l| spdx::Expression::parse_mode(l, spdx::ParseMode::LAX).ok())
+                .map(|expr| expr.to_string())
+        };
+
+        match &nfo.lic_info {
+            LicenseInfo::SpdxExpression { expr, nfo } => {
+                let concluded = expr.to_string();
+                let (declared, source) = match &nfo.source {
+                    LicenseExprSource::Metadata => (Some(concluded.clone()), "metadata"),
+                    LicenseExprSource::UserOverride => (manifest_license(), "user-override"),
+                    LicenseExprSource::OverlayOverride => (manifest_license(), "overlay-override"),
+                    LicenseExprSource::LicenseFiles(_) => (None, "license-files"),
+                };
+
+                Self {
+                    krate,
+                    concluded: Some(concluded),
+                    declared,
+                    source: Some(source),
+                }
+            }
+            LicenseInfo::Unlicensed => Self {
+                krate,
+                concluded: None,
+                declared: manifest_license(),
+                source: None,
+            },
+        }
+    }
+
+    /// The [package url](https://github.com/package-url/purl-spec) for the crate
+    fn purl(&self) -> String {
+        let Krate { name, version, .. } = self.krate;
+        let purl = format!("pkg:cargo/{name}@{version}");
+
+        let (key, url) = match &self.krate.source {
+            Some(Source::Registry(url) | Source::Sparse(url)) => {
+                ("repository_url", url.as_str().to_owned())
+            }
+            Some(Source::Git {
+                url, spec_value, ..
+            }) => ("vcs_url", git_location(url, spec_value.as_deref())),
+            Some(Source::CratesIo(_)) | None => return purl,
+        };
+
+        let encoded: String = url::form_urlencoded::byte_serialize(url.as_bytes()).collect();
+        format!("{purl}?{key}={encoded}")
+    }
+
+    /// The location the crate source can be downloaded from, if it is remote
+    fn download_location(&self) -> Option<String> {
+        let Krate { name, version, .. } = self.krate;
+
+        match self.krate.source.as_ref()? {
+            Source::CratesIo(_) => Some(format!(
+                "https://crates.io/api/v1/crates/{name}/{version}/download"
+            )),
+            Source::Git {
+                url, spec_value, ..
+            } => Some(git_location(url, spec_value.as_deref())),
+            Source::Registry(_) | Source::Sparse(_) => None,
+        }
+    }
+}
+
+/// The `git+<url>@<rev>` location of a git source, where the revision is the
+/// commit the source is locked to if it is known, otherwise the branch, tag,
+/// or rev that was specified
+fn git_location(url: &url::Url, spec_value: Option<&str>) -> String {
+    let rev = url.fragment().filter(|rev| !rev.is_empty()).or(spec_value);
+
+    let mut repo = url.clone();
+    repo.set_query(None);
+    repo.set_fragment(None);
+
+    match rev {
+        Some(rev) => format!("git+{repo}@{rev}"),
+        None => format!("git+{repo}"),
+    }
+}
+
+/// The name of the document, which is the name of the workspace root directory
+fn document_name(krates: &Krates) -> &str {
+    krates.workspace_root().file_name().unwrap_or("workspace")
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxDocument<'k> {
+    spdx_version: &'static str,
+    data_license: &'static str,
+    #[serde(rename = "SPDXID")]
+    spdx_id: &'static str,
+    name: &'k str,
+    document_namespace: String,
+    creation_info: SpdxCreationInfo,
+    packages: Vec<SpdxPackage<'k>>,
+    relationships: Vec<SpdxRelationship>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    has_extracted_licensing_infos: Vec<SpdxExtractedLicense>,
+}
+
+#[derive(Serialize)]
+struct SpdxCreationInfo {
+    created: String,
+    creators: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxPackage<'k> {
+    #[serde(rename = "SPDXID")]
+    spdx_id: String,
+    name: &'k str,
+    version_info: String,
+    download_location: String,
+    files_analyzed: bool,
+    license_concluded: String,
+    license_declared: String,
+    copyright_text: &'static str,
+    external_refs: Vec<SpdxExternalRef>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxExternalRef {
+    reference_category: &'static str,
+    reference_type: &'static str,
+    reference_locator: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxRelationship {
+    spdx_element_id: String,
+    relationship_type: &'static str,
+    related_spdx_element: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxExtractedLicense {
+    license_id: String,
+    extracted_text: &'static str,
+}
+
+/// Serializes the license information as an SPDX 2.3 JSON document
+pub(super) fn spdx(
+    krates: &Krates,
+    nfos: &[KrateLicense<'_>],
+    now: time::OffsetDateTime,
+) -> anyhow::Result<String> {
+    let components: Vec<_> = nfos.iter().map(Component::new).collect();
+    spdx_document(krates, &components, now)
+}
+
+fn spdx_document(
+    krates: &Krates,
+    components: &[Component<'_>],
+    now: time::OffsetDateTime,
+) -> anyhow::Result<String> {
+    let name = document_name(krates);
+    let created = now.format(&TIMESTAMP)?;
+
+    // SPDX identifiers may only contain letters, numbers, `.` and `-`
+    let spdx_id = |i: usize, krate: &Krate| {
+        let name: String = krate
+            .name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        format!("SPDXRef-Package-{name}-{i}")
+    };
+
+    let mut extracted = std::collections::BTreeSet::new();
+    let mut packages = Vec::with_capacity(components.len());
+    let mut relationships = Vec::new();
+
+    for (i, comp) in components.iter().enumerate() {
+        let spdx_ref = spdx_id(i, comp.krate);
+
+        // Custom license references must be defined in the document
+        for expr in comp.concluded.iter().chain(comp.declared.iter()) {
+            extracted.extend(
+                expr.split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+                    .filter(|s| s.starts_with("LicenseRef-"))
+                    .map(String::from),
+            );
+        }
+
+        // The document describes the workspace, all other crates are just
+        // dependencies of it
+        if krates
+            .workspace_members()
+            .any(|n| matches!(n, krates::Node::Krate { id, .. } if id == &comp.krate.id))
+        {
+            relationships.push(SpdxRelationship {
+                spdx_element_id: "SPDXRef-DOCUMENT".to_owned(),
+                relationship_type: "DESCRIBES",
+                related_spdx_element: spdx_ref.clone(),
+            });
+        }
+
+        packages.push(SpdxPackage {
+            spdx_id: spdx_ref,
+            name: &comp.krate.name,
+            version_info: comp.krate.version.to_string(),
+            download_location: comp
+                .download_location()
+                .unwrap_or_else(|| NOASSERTION.to_owned()),
+            files_analyzed: false,
+            license_concluded: comp
+                .concluded
+                .clone()
+                .unwrap_or_else(|| NOASSERTION.to_owned()),
+            license_declared: comp
+                .declared
+                .clone()
+                .unwrap_or_else(|| NOASSERTION.to_owned()),
+            copyright_text: NOASSERTION,
+            external_refs: vec![SpdxExternalRef {
+                reference_category: "PACKAGE-MANAGER",
+                reference_type: "purl",
+                reference_locator: comp.purl(),
+            }],
+        });
+    }
+
+    // The namespace must be unique for every document, so include the time
+    // it was created as well as the crates it describes
+    let mut unique = created.clone();
+    for comp in components {
+        unique.push_str(&comp.krate.id.repr);
+    }
+
+    let doc = SpdxDocument {
+        spdx_version: "SPDX-2.3",
+        data_license: "CC0-1.0",
+        spdx_id: "SPDXRef-DOCUMENT",
+        name,
+        document_namespace: format!(
+            "https://spdx.org/spdxdocs/cargo-deny/{name}-{:08x}",
+            cargo_deny::hash(unique.as_bytes())
+        ),
+        creation_info: SpdxCreationInfo {
+            created,
+            creators: vec![format!("Tool: cargo-deny-{}", env!("CARGO_PKG_VERSION"))],
+        },
+        packages,
+        relationships,
+        has_extracted_licensing_infos: extracted
+            .into_iter()
+            .map(|license_id| SpdxExtractedLicense {
+                license_id,
+                extracted_text: NOASSERTION,
+            })
+            .collect(),
+    };
+
+    Ok(serde_json::to_string_pretty(&doc)?)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CycloneDxBom<'k> {
+    bom_format: &'static str,
+    spec_version: &'static str,
+    version: u32,
+    metadata: CycloneDxMetadata<'k>,
+    components: Vec<CycloneDxComponent<'k>>,
+}
+
+#[derive(Serialize)]
+struct CycloneDxMetadata<'k> {
+    timestamp: String,
+    tools: CycloneDxTools,
+    component: CycloneDxRoot<'k>,
+}
+
+#[derive(Serialize)]
+struct CycloneDxTools {
+    components: Vec<CycloneDxTool>,
+}
+
+#[derive(Serialize)]
+struct CycloneDxTool {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Serialize)]
+struct CycloneDxRoot<'k> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    name: &'k str,
+}
+
+#[derive(Serialize)]
+struct CycloneDxComponent<'k> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(rename = "bom-ref")]
+    bom_ref: String,
+    name: &'k str,
+    version: String,
+    purl: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    licenses: Vec<CycloneDxLicense>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    properties: Vec<CycloneDxProperty>,
+}
+
+#[derive(Serialize)]
+struct CycloneDxLicense {
+    expression: String,
+}
+
+#[derive(Serialize)]
+struct CycloneDxProperty {
+    name: &'static str,
+    value: String,
+}
+
+/// Serializes the license information as a CycloneDX 1.5 JSON document
+pub(super) fn cyclonedx(
+    krates: &Krates,
+    nfos: &[KrateLicense<'_>],
+    now: time::OffsetDateTime,
+) -> anyhow::Result<String> {
+    let components: Vec<_> = nfos.iter().map(Component::new).collect();
+    cyclonedx_document(krates, &components, now)
+}
+
+fn cyclonedx_document(
+    krates: &Krates,
+    components: &[Component<'_>],
+    now: time::OffsetDateTime,
+) -> anyhow::Result<String> {
+    let components = components
+        .iter()
+        .map(|comp| {
+            // CycloneDX 1.5 has no distinction between concluded and declared
+            // licenses, so the declared expression and where the concluded
+            // expression came from are recorded as properties
+            let properties = comp
+                .declared
+                .clone()
+                .map(|value| CycloneDxProperty {
+                    name: "cargo-deny:license-declared",
+                    value,
+                })
+                .into_iter()
+                .chain(comp.source.map(|source| CycloneDxProperty {
+                    name: "cargo-deny:license-source",
+                    value: source.to_owned(),
+                }))
+                .collect();
+
+            CycloneDxComponent {
+                kind: "library",
+                bom_ref: comp.krate.id.repr.clone(),
+                name: &comp.krate.name,
+                version: comp.krate.version.to_string(),
+                purl: comp.purl(),
+                licenses: comp
+                    .concluded
+                    .clone()
+                    .map(|expression| CycloneDxLicense { expression })
+                    .into_iter()
+                    .collect(),
+                properties,
+            }
+        })
+        .collect();
+
+    let bom = CycloneDxBom {
+        bom_format: "CycloneDX",
+        spec_version: "1.5",
+        version: 1,
+        metadata: CycloneDxMetadata {
+            timestamp: now.format(&TIMESTAMP)?,
+            tools: CycloneDxTools {
+                components: vec![CycloneDxTool {
+                    kind: "application",
+                    name: "cargo-deny",
+                    version: env!("CARGO_PKG_VERSION"),
+                }],
+            },
+            component: CycloneDxRoot {
+                kind: "application",
+                name: document_name(krates),
+            },
+        },
+        components,
+    };
+
+    Ok(serde_json::to_string_pretty(&bom)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::Component;
+
+    const LOCKFILE: &str = r#"
+version = 3
+
+[[package]]
+name = "sbom-test"
+version = "1.0.0"
+dependencies = [
+ "crates-io-dep",
+ "git-dep",
+ "registry-dep",
+ "sparse_dep",
+]
+
+[[package]]
+name = "crates-io-dep"
+version = "1.2.3"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "git-dep"
+version = "2.0.0"
+source = "git+https://github.com/EmbarkStudios/git-dep?rev=abc123#abc1234567890"
+
+[[package]]
+name = "registry-dep"
+version = "3.0.0"
+source = "registry+https://my.registry.io/index"
+
+[[package]]
+name = "sparse_dep"
+version = "4.0.0"
+source = "sparse+https://my.registry.io/sparse/"
+"#;
+
+    /// Builds the documents for a small graph with crates from each kind of
+    /// source, and returns them with the parts that differ between machines
+    /// and releases redacted
+    fn documents() -> (String, String) {
+        let td = tempfile::tempdir().unwrap();
+        let root = td.path().join("sbom-test");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(root.join("Cargo.lock"), LOCKFILE).unwrap();
+
+        let root = cargo_deny::PathBuf::from_path_buf(root.canonicalize().unwrap()).unwrap();
+        let md = crate::common::load_lockfile(&root.join("Cargo.lock")).unwrap();
+        let krates: cargo_deny::Krates = krates::Builder::new()
+            .build_with_metadata(md, krates::NoneFilter)
+            .unwrap();
+
+        let krate = |name: &str| krates.krates_by_name(name).next().unwrap().krate;
+        let component = |name: &str,
+                         concluded: Option<&str>,
+                         declared: Option<&str>,
+                         source: Option<&'static str>| Component {
+            krate: krate(name),
+            concluded: concluded.map(String::from),
+            declared: declared.map(String::from),
+            source,
+        };
+
+        let components = [
+            component(
+                "sbom-test",
+                Some("MIT OR Apache-2.0"),
+                Some("MIT OR Apache-2.0"),
+                Some("metadata"),
+            ),
+            component(
+                "crates-io-dep",
+                Some("MIT AND LicenseRef-Proprietary"),
+                Some("MIT"),
+                Some("user-override"),
+            ),
+            component("git-dep", None, None, None),
+            component(
+                "registry-dep",
+                Some("Apache-2.0"),
+                None,
+                Some("license-files"),
+            ),
+            component(
+                "sparse_dep",
+                Some("BSD-3-Clause OR LicenseRef-Custom"),
+                Some("(BSD-3-Clause OR LicenseRef-Custom)"),
+                Some("metadata"),
+            ),
+        ];
+
+        let now = time::macros::datetime!(2024-01-02 03:04:05 UTC);
+
+        let root_url = url::Url::from_directory_path(&root).unwrap();
+        let redact = |doc: String| {
+            doc.replace(root_url.as_str(), "file:///[ROOT]/")
+                .replace(env!("CARGO_PKG_VERSION"), "[VERSION]")
+        };
+
+        let spdx = super::spdx_document(&krates, &components, now).unwrap();
+
+        // The namespace is a hash of the crates, which includes the location
+        // of the workspace
+        let doc: serde_json::Value = serde_json::from_str(&spdx).unwrap();
+        let namespace = doc["documentNamespace"].as_str().unwrap();
+        assert!(namespace.starts_with("https://spdx.org/spdxdocs/cargo-deny/sbom-test-"));
+        let spdx = redact(spdx.replace(namespace, "[NAMESPACE]"));
+
+        let cyclonedx = redact(super::cyclonedx_document(&krates, &components, now).unwrap());
+
+        (spdx, cyclonedx)
+    }
+
+    /// Ensures the SPDX 2.3 document has a package for each crate, defines
+    /// the custom license references, and falls back to `NOASSERTION` for
+    /// unknown licenses and download locations
+    #[test]
+    fn spdx_document() {
+        let (spdx, _) = documents();
+        insta::assert_snapshot!(spdx);
+    }
+
+    /// Ensures the CycloneDX 1.5 document has a component for each crate, with
+    /// the declared license and its source recorded as properties
+    #[test]
+    fn cyclonedx_document() {
+        let (_, cyclonedx) = documents();
+        insta::assert_snapshot!(cyclonedx);
+    }
+}
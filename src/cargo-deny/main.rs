@@ -7,9 +7,12 @@ use clap::{Parser, Subcommand, ValueEnum};
 mod check;
 mod common;
 mod fetch;
+mod fix;
 mod init;
 mod list;
+mod schema;
 mod stats;
+mod timings;
 
 #[derive(Subcommand, Debug)]
 enum Command {
@@ -19,6 +22,9 @@ enum Command {
     /// Fetches remote data
     #[command(name = "fetch")]
     Fetch(fetch::Args),
+    /// Edits the configuration to fix issues reported by the checks
+    #[command(name = "fix")]
+    Fix(fix::Args),
     /// Creates a cargo-deny config from a template
     #[command(name = "init")]
     Init(init::Args),
@@ -31,6 +37,34 @@ enum Command {
 pub enum Format {
     Human,
     Json,
+    Github,
+    Sarif,
+    Junit,
+}
+
+/// A `--format`, and optionally the path of the file its diagnostics are
+/// written to instead of stderr
+#[derive(Clone, Debug)]
+pub struct Output {
+    pub format: Format,
+    pub path: Option<PathBuf>,
+}
+
+fn parse_output(s: &str) -> Result<Output, Error> {
+    let (format, path) = match s.split_once(':') {
+        Some((format, path)) => (format, Some(PathBuf::from(path))),
+        None => (s, None),
+    };
+
+    let format = Format::from_str(format, true).map_err(|err| {
+        anyhow::anyhow!("{err}, expected one of human, json, github, sarif, or junit")
+    })?;
+
+    if let Some(path) = &path {
+        anyhow::ensure!(!path.as_str().is_empty(), "the path for '{s}' is empty");
+    }
+
+    Ok(Output { format, path })
 }
 
 #[derive(ValueEnum, Copy, Clone, Debug)]
@@ -51,8 +85,10 @@ pub(crate) struct GraphContext {
     /// The path of a Cargo.toml to use as the context for the operation.
     ///
     /// By default, the Cargo.toml in the current working directory is used.
+    ///
+    /// The `check` subcommand accepts this multiple times to check several workspaces against the same config in a single invocation.
     #[arg(long)]
-    pub(crate) manifest_path: Option<PathBuf>,
+    pub(crate) manifest_path: Vec<PathBuf>,
     /// If passed, all workspace packages are used as roots for the crate graph.
     ///
     /// Automatically assumed if the manifest path points to a virtual manifest.
@@ -65,9 +101,14 @@ pub(crate) struct GraphContext {
     /// NOTE: Unlike cargo, this does not have to be used with the `--workspace` flag.
     #[arg(long)]
     pub(crate) exclude: Vec<String>,
+    /// One or more workspace members to use as the roots of the crate graph
+    ///
+    /// Features specified without a `member/` prefix apply to each of the packages, the same as with cargo.
+    #[arg(short, long, value_name = "SPEC")]
+    pub(crate) package: Vec<String>,
     /// One or more platforms to filter crates by
     ///
-    /// If a dependency is target specific, it will be ignored if it does not match 1 or more of the specified targets. This option overrides the top-level `targets = []` configuration value.
+    /// If a dependency is target specific, it will be ignored if it does not match 1 or more of the specified targets. This option overrides the top-level `targets = []` configuration value. `all` disables target filtering, so that every target specific dependency is included.
     #[arg(short, long)]
     pub(crate) target: Vec<String>,
     /// Activate all available features
@@ -77,6 +118,8 @@ pub(crate) struct GraphContext {
     #[arg(long)]
     pub(crate) no_default_features: bool,
     /// Space or comma separated list of features to activate
+    ///
+    /// Features of a specific workspace member can be activated with `member/feature`.
     #[arg(long, value_delimiter = ',')]
     pub(crate) features: Vec<String>,
     /// Equivalent to specifying both `--locked` and `--offline`
@@ -105,6 +148,11 @@ pub(crate) struct GraphContext {
     /// which might affect the exact version of used dependencies.
     #[arg(long)]
     pub(crate) exclude_unpublished: bool,
+    /// Path to the output of `cargo metadata --format-version 1` to use instead of invoking cargo
+    ///
+    /// The crate graph is built directly from the metadata, so the feature flags are ignored as features have already been resolved when the metadata was generated. Target and exclusion filters are still applied.
+    #[arg(long, value_name = "PATH")]
+    pub(crate) metadata_json: Option<PathBuf>,
 }
 
 /// Lints your project's crate graph
@@ -130,9 +178,24 @@ Possible values:
 * trace
 ")]
     log_level: log::LevelFilter,
+    /// Only emit diagnostics that are warnings or errors
+    ///
+    /// Notes and help diagnostics are still counted in the summary and don't affect the exit code, they are just not printed. This is independent of `--log-level`, which can still be used to further restrict the diagnostics to only errors.
+    #[arg(short, long)]
+    quiet: bool,
     /// Specify the format of cargo-deny's output
-    #[arg(short, long, default_value = "human", value_enum)]
-    format: Format,
+    ///
+    /// Possible values: human, json, github, sarif, junit
+    ///
+    /// May be specified multiple times to output diagnostics in several formats at once. A format may be followed by `:<path>`, eg. `sarif:results.sarif`, to write its diagnostics to a file instead of stderr. Only one format can be written to stderr, and `sarif` and `junit` must always be written to a file.
+    #[arg(
+        short,
+        long,
+        default_value = "human",
+        value_parser = parse_output,
+        value_name = "FORMAT[:PATH]"
+    )]
+    format: Vec<Output>,
     #[arg(
         short,
         long,
@@ -141,10 +204,15 @@ Possible values:
         env = "CARGO_TERM_COLOR"
     )]
     color: Color,
+    /// Prints the JSON Schema for deny.toml and exits
+    ///
+    /// The schema can be used by editors, eg. via taplo, to provide completion and validation for the configuration.
+    #[arg(long)]
+    config_schema: bool,
     #[clap(flatten)]
     ctx: GraphContext,
     #[clap(subcommand)]
-    cmd: Command,
+    cmd: Option<Command>,
 }
 
 fn setup_logger(
@@ -158,7 +226,9 @@ fn setup_logger(
     let now = time::OffsetDateTime::now_utc();
 
     match format {
-        Format::Human => {
+        // Workflow commands are only used for diagnostics, log messages are
+        // just displayed in the job output
+        Format::Human | Format::Github | Format::Sarif | Format::Junit => {
             const HUMAN: &[time::format_description::FormatItem<'static>] =
                 time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
 
@@ -166,13 +236,17 @@ fn setup_logger(
                 fern::Dispatch::new()
                     .level(level)
                     .format(move |out, message, record| {
+                        // The colors can't be known until the config is loaded,
+                        // which happens after the logger is initialized
+                        let colors = common::colors();
+
                         out.finish(format_args!(
                             "{date} [{level}] {message}\x1B[0m",
                             date = now.format(&HUMAN).unwrap(),
                             level = match record.level() {
-                                Error => Red.paint("ERROR"),
-                                Warn => Yellow.paint("WARN"),
-                                Info => Green.paint("INFO"),
+                                Error => common::ansi_color(colors.error, Red).paint("ERROR"),
+                                Warn => common::ansi_color(colors.warning, Yellow).paint("WARN"),
+                                Info => common::ansi_color(colors.note, Green).paint("INFO"),
                                 Debug => Blue.paint("DEBUG"),
                                 Trace => Purple.paint("TRACE"),
                             },
@@ -248,11 +322,66 @@ fn real_main() -> Result<(), Error> {
 
     let color = crate::common::should_colorize(args.color, std::io::stderr());
 
-    setup_logger(log_level, args.format, color)?;
+    // Log messages are always written to stderr, so they use the format of
+    // the diagnostics written there, or human if every format is written to
+    // a file
+    let (stderr_formats, file_outputs): (Vec<_>, Vec<_>) =
+        args.format.into_iter().partition(|o| o.path.is_none());
+    anyhow::ensure!(
+        stderr_formats.len() <= 1,
+        "only one --format can be written to stderr, the others must specify a path, eg. `--format json:diagnostics.json`"
+    );
+    let format = stderr_formats.first().map_or(Format::Human, |o| o.format);
+    anyhow::ensure!(
+        format != Format::Sarif,
+        "the sarif format can only be written to a file, eg. `--format sarif:results.sarif`"
+    );
+    anyhow::ensure!(
+        format != Format::Junit,
+        "the junit format can only be written to a file, eg. `--format junit:report.xml`"
+    );
+
+    setup_logger(log_level, format, color)?;
 
-    let manifest_path = if let Some(mpath) = args.ctx.manifest_path {
-        mpath
-    } else {
+    if args.config_schema {
+        schema::print();
+        return Ok(());
+    }
+
+    let Some(cmd) = args.cmd else {
+        use clap::CommandFactory;
+
+        Opts::command()
+            .error(
+                clap::error::ErrorKind::MissingSubcommand,
+                "a subcommand is required unless --config-schema is specified",
+            )
+            .exit();
+    };
+
+    anyhow::ensure!(
+        file_outputs.is_empty() || matches!(cmd, Command::Check(_)),
+        "--format can only be written to a file for the check subcommand"
+    );
+    let file_outputs: Vec<_> = file_outputs
+        .into_iter()
+        .filter_map(|o| o.path.map(|path| (o.format, path)))
+        .collect();
+
+    let mut manifest_paths = args.ctx.manifest_path;
+
+    let lockfile = match &cmd {
+        Command::Check(cargs) => cargs.lockfile.clone(),
+        _ => None,
+    };
+
+    // When checking a lockfile directly there might not be a manifest, it is
+    // only used to locate the config
+    if let Some(lockfile) = lockfile.as_ref().filter(|_| manifest_paths.is_empty()) {
+        manifest_paths.push(lockfile.with_file_name("Cargo.toml"));
+    }
+
+    if manifest_paths.is_empty() {
         // For now, use the context path provided by the user, but
         // we've deprecated it and it will go away at some point
         let cwd =
@@ -278,23 +407,39 @@ fn real_main() -> Result<(), Error> {
             cwd.display()
         );
 
-        man_path.try_into().context("non-utf8 path")?
-    };
+        manifest_paths.push(man_path.try_into().context("non-utf8 path")?);
+    }
+
+    for manifest_path in manifest_paths.iter().filter(|_| lockfile.is_none()) {
+        anyhow::ensure!(
+            manifest_path.file_name() == Some("Cargo.toml") && manifest_path.is_file(),
+            "--manifest-path must point to a Cargo.toml file"
+        );
+
+        anyhow::ensure!(
+            manifest_path.exists(),
+            "unable to find cargo manifest {manifest_path}"
+        );
+    }
 
     anyhow::ensure!(
-        manifest_path.file_name() == Some("Cargo.toml") && manifest_path.is_file(),
-        "--manifest-path must point to a Cargo.toml file"
+        manifest_paths.len() == 1 || matches!(cmd, Command::Check(_)),
+        "--manifest-path can only be specified multiple times for the check subcommand"
     );
-
     anyhow::ensure!(
-        manifest_path.exists(),
-        "unable to find cargo manifest {manifest_path}"
+        manifest_paths.len() == 1 || args.ctx.metadata_json.is_none(),
+        "--metadata-json can't be used with multiple manifest paths"
     );
 
+    let mut manifest_paths = manifest_paths.into_iter();
+    let manifest_path = manifest_paths.next().unwrap();
+
     let krate_ctx = common::KrateContext {
         manifest_path,
         workspace: args.ctx.workspace,
         exclude: args.ctx.exclude,
+        packages: args.ctx.package,
+        exclude_paths: Vec::new(),
         targets: args.ctx.target,
         no_default_features: args.ctx.no_default_features,
         all_features: args.ctx.all_features,
@@ -303,13 +448,18 @@ fn real_main() -> Result<(), Error> {
         locked: args.ctx.locked,
         offline: args.ctx.offline,
         exclude_dev: args.ctx.exclude_dev,
+        exclude_dev_deps_of: Vec::new(),
         exclude_unpublished: args.ctx.exclude_unpublished,
+        no_host_default_features: false,
+        metadata_json: args.ctx.metadata_json,
+        lockfile,
     };
 
     let log_ctx = crate::common::LogContext {
         color: args.color,
-        format: args.format,
+        format,
         log_level: args.log_level,
+        quiet: args.quiet,
     };
 
     // Allow gix to hook the signal handler so that it can properly release lockfiles
@@ -326,26 +476,56 @@ fn real_main() -> Result<(), Error> {
         .context("failed to initialize gix's interrupt handler")?
     };
 
-    match args.cmd {
+    match cmd {
         Command::Check(mut cargs) => {
             let show_stats = cargs.show_stats;
+            let exit_code_per_check = cargs.exit_code_per_check;
+            // Nothing is checked, so there are no stats to print
+            let nothing_checked = cargs.fetch_only
+                || cargs.dump_config
+                || cargs.validate_only
+                || cargs.eval.is_some();
+            let max_warnings = cargs.max_warnings;
 
             if args.ctx.offline {
                 log::info!("network access disabled via --offline flag, disabling advisory database fetching");
                 cargs.disable_fetch = true;
             }
 
-            let stats = check::cmd(log_ctx, cargs, krate_ctx)?;
+            // Each additional workspace uses the same options as the first
+            let krate_ctxs = std::iter::once(krate_ctx.clone())
+                .chain(manifest_paths.map(|manifest_path| common::KrateContext {
+                    manifest_path,
+                    ..krate_ctx.clone()
+                }))
+                .collect();
+
+            let mut stats = check::cmd(log_ctx, cargs, krate_ctxs, file_outputs)?;
+
+            if nothing_checked {
+                return Ok(());
+            }
+
+            let exceeded = max_warnings.and_then(|max| {
+                stats
+                    .deny_warnings_above(max)
+                    .map(|warnings| (warnings, max))
+            });
+
+            let failed = stats::print_stats(stats, show_stats, log_level, format, args.color);
+
+            if let Some((warnings, max)) = exceeded {
+                log::error!("{warnings} warnings were emitted, which exceeds the maximum of {max}");
+            }
 
-            if let Some(exit_code) =
-                stats::print_stats(stats, show_stats, log_level, args.format, args.color)
-            {
+            if let Some(exit_code) = failed.exit_code(exit_code_per_check) {
                 std::process::exit(exit_code);
             }
 
             Ok(())
         }
         Command::Fetch(fargs) => fetch::cmd(log_ctx, fargs, krate_ctx),
+        Command::Fix(fargs) => fix::cmd(log_ctx, fargs, krate_ctx),
         Command::Init(iargs) => init::cmd(iargs, krate_ctx),
         Command::List(largs) => list::cmd(log_ctx, largs, krate_ctx),
     }
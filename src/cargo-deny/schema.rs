@@ -0,0 +1,98 @@
+/// The JSON Schema for `deny.toml`, which can be used by editors to provide
+/// completion and validation
+pub const SCHEMA: &str = include_str!("../../resources/deny.schema.json");
+
+/// Prints the schema to stdout
+pub fn print() {
+    #[allow(clippy::disallowed_macros)]
+    {
+        println!("{SCHEMA}");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value as Json;
+
+    /// Resolves references and picks the table variant of unions, so that
+    /// the properties of the node can be looked up
+    fn resolve<'s>(schema: &'s Json, mut node: &'s Json) -> &'s Json {
+        loop {
+            if let Some(path) = node.get("$ref").and_then(|r| r.as_str()) {
+                let name = path.rsplit('/').next().unwrap();
+                node = &schema["definitions"][name];
+            } else if let Some(variants) = node.get("anyOf").and_then(|v| v.as_array()) {
+                let Some(table) = variants.iter().find(|v| v.get("properties").is_some()) else {
+                    return node;
+                };
+                node = table;
+            } else {
+                return node;
+            }
+        }
+    }
+
+    fn walk(
+        schema: &Json,
+        node: &Json,
+        value: &toml_span::Value<'_>,
+        path: &str,
+        missing: &mut Vec<String>,
+    ) {
+        let node = resolve(schema, node);
+
+        if let Some(table) = value.as_table() {
+            let Some(props) = node.get("properties") else {
+                return;
+            };
+
+            for (key, value) in table {
+                let path = format!("{path}.{}", key.name);
+                if let Some(prop) = props.get(&*key.name) {
+                    walk(schema, prop, value, &path, missing);
+                } else {
+                    missing.push(path);
+                }
+            }
+        } else if let Some(arr) = value.as_array() {
+            let Some(items) = node.get("items") else {
+                return;
+            };
+
+            for value in arr {
+                walk(schema, items, value, path, missing);
+            }
+        }
+    }
+
+    /// Ensures every key used in the example and test configurations is
+    /// present in the schema, so that the schema is updated along with the
+    /// config
+    #[test]
+    fn schema_in_sync() {
+        let schema: Json = serde_json::from_str(super::SCHEMA).expect("invalid schema");
+
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+        let configs = [
+            "deny.toml",
+            "deny.template.toml",
+            "tests/cfg/advisories.toml",
+            "tests/cfg/bans.toml",
+            "tests/cfg/licenses.toml",
+            "tests/cfg/sources.toml",
+        ];
+
+        let mut missing = Vec::new();
+        for cfg in configs {
+            let contents = std::fs::read_to_string(root.join(cfg)).unwrap();
+            let value = toml_span::parse(&contents).unwrap();
+            walk(&schema, &schema, &value, cfg, &mut missing);
+        }
+
+        assert!(
+            missing.is_empty(),
+            "keys missing from the schema:\n{}",
+            missing.join("\n")
+        );
+    }
+}
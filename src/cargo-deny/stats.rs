@@ -8,6 +8,19 @@ pub struct Stats {
     pub warnings: u32,
     pub notes: u32,
     pub helps: u32,
+    /// If true, warnings are treated as errors when determining if the
+    /// check failed
+    #[serde(skip)]
+    pub deny_warnings: bool,
+}
+
+impl Stats {
+    /// Returns true if the check had 1 or more errors, or 1 or more warnings
+    /// if warnings are denied
+    #[inline]
+    pub fn failed(&self) -> bool {
+        self.errors > 0 || (self.deny_warnings && self.warnings > 0)
+    }
 }
 
 #[derive(Default, Serialize)]
@@ -22,18 +35,44 @@ pub struct AllStats {
     pub sources: Option<Stats>,
 }
 
+impl AllStats {
+    /// Denies warnings for every check if the total number of warnings across
+    /// all checks exceeds the maximum, returning the total if it did
+    pub fn deny_warnings_above(&mut self, max: u32) -> Option<u32> {
+        let checks = [
+            &mut self.advisories,
+            &mut self.bans,
+            &mut self.licenses,
+            &mut self.sources,
+        ];
+
+        let mut checks: Vec<_> = checks.into_iter().filter_map(Option::as_mut).collect();
+        let warnings = checks.iter().map(|stats| stats.warnings).sum::<u32>();
+
+        if warnings <= max {
+            return None;
+        }
+
+        for stats in &mut checks {
+            stats.deny_warnings = true;
+        }
+
+        Some(warnings)
+    }
+}
+
 pub(crate) fn print_stats(
     stats: AllStats,
     show_stats: bool,
     log_level: log::LevelFilter,
     format: Format,
     color: crate::Color,
-) -> Option<i32> {
+) -> FailedChecks {
     // In the case of human, we print to stdout, to distinguish it from the rest
     // of the output, but for JSON we still go to stderr since presumably computers
     // will be looking at that output and we don't want to confuse them
     match format {
-        Format::Human => {
+        Format::Human | Format::Github | Format::Sarif | Format::Junit => {
             let mut summary = String::new();
 
             let color = crate::common::should_colorize(color, std::io::stdout());
@@ -67,23 +106,65 @@ pub(crate) fn print_stats(
         }
     }
 
-    stats_to_exit_code(stats)
+    FailedChecks::from_stats(&stats)
 }
 
-/// Given stats for checks, returns an exit code that is a bitset of the checks
-/// that failed, or None if there were no errors
-fn stats_to_exit_code(stats: AllStats) -> Option<i32> {
-    let exit_code = [stats.advisories, stats.bans, stats.licenses, stats.sources]
+/// The set of checks that were executed and had 1 or more errors
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FailedChecks(u8);
+
+impl FailedChecks {
+    pub const ADVISORIES: Self = Self(1 << 0);
+    pub const BANS: Self = Self(1 << 1);
+    pub const LICENSES: Self = Self(1 << 2);
+    pub const SOURCES: Self = Self(1 << 3);
+
+    fn from_stats(stats: &AllStats) -> Self {
+        [
+            (&stats.advisories, Self::ADVISORIES),
+            (&stats.bans, Self::BANS),
+            (&stats.licenses, Self::LICENSES),
+            (&stats.sources, Self::SOURCES),
+        ]
         .into_iter()
-        .enumerate()
-        .fold(0, |mut acc, (i, stats)| {
-            if stats.is_some_and(|s| s.errors > 0) {
-                acc |= 1 << i;
+        .fold(Self::default(), |acc, (stats, check)| {
+            if stats.as_ref().is_some_and(Stats::failed) {
+                acc | check
+            } else {
+                acc
             }
-            acc
-        });
+        })
+    }
 
-    (exit_code > 0).then_some(exit_code)
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Gets the exit code for the set of failed checks, or None if no checks
+    /// failed
+    ///
+    /// By default the exit code is the bitset of the failed checks, but if
+    /// `per_check` is set, the bitset is shifted so that the lowest bit is
+    /// not set by any check, allowing an exit code of 1 to be reserved for
+    /// fatal errors that prevented the checks from running at all
+    pub fn exit_code(self, per_check: bool) -> Option<i32> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let code = i32::from(self.0);
+        Some(if per_check { code << 1 } else { code })
+    }
+}
+
+impl std::ops::BitOr for FailedChecks {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
 }
 
 fn write_min_stats(mut summary: &mut String, stats: &AllStats, color: bool) {
@@ -97,7 +178,7 @@ fn write_min_stats(mut summary: &mut String, stats: &AllStats, color: bool) {
                 write!(
                     &mut summary,
                     "{}, ",
-                    if stats.errors > 0 {
+                    if stats.failed() {
                         Color::Red.paint("FAILED")
                     } else {
                         Color::Green.paint("ok")
@@ -108,7 +189,7 @@ fn write_min_stats(mut summary: &mut String, stats: &AllStats, color: bool) {
                 write!(
                     &mut summary,
                     "{}, ",
-                    if stats.errors > 0 { "FAILED" } else { "ok" }
+                    if stats.failed() { "FAILED" } else { "ok" }
                 )
                 .unwrap();
             }
@@ -133,7 +214,7 @@ fn write_full_stats(summary: &mut String, stats: &AllStats, color: bool) {
             max = std::cmp::max(
                 max,
                 s.map_or(0, |s| {
-                    let status = if s.errors > 0 {
+                    let status = if s.failed() {
                         "FAILED".len()
                     } else {
                         "ok".len()
@@ -152,7 +233,9 @@ fn write_full_stats(summary: &mut String, stats: &AllStats, color: bool) {
         max + 2 /* spaces */ + if color { 9 /* color escapes */ } else { 0 }
     };
 
+    let colors = crate::common::colors();
     let mut print_stats = |check: &str, stats: Option<&Stats>| {
+        use crate::common::ansi_color;
         use std::fmt::Write;
 
         if let Some(stats) = stats {
@@ -162,15 +245,16 @@ fn write_full_stats(summary: &mut String, stats: &AllStats, color: bool) {
                     "{:>column$}: {} errors, {} warnings, {} notes",
                     format!(
                         "{check} {}",
-                        if stats.errors > 0 {
+                        if stats.failed() {
                             Color::Red.paint("FAILED")
                         } else {
                             Color::Green.paint("ok")
                         }
                     ),
-                    Color::Red.paint(format!("{}", stats.errors)),
-                    Color::Yellow.paint(format!("{}", stats.warnings)),
-                    Color::Blue.paint(format!("{}", stats.notes + stats.helps)),
+                    ansi_color(colors.error, Color::Red).paint(format!("{}", stats.errors)),
+                    ansi_color(colors.warning, Color::Yellow).paint(format!("{}", stats.warnings)),
+                    ansi_color(colors.note, Color::Blue)
+                        .paint(format!("{}", stats.notes + stats.helps)),
                     column = column,
                 )
                 .unwrap();
@@ -178,7 +262,7 @@ fn write_full_stats(summary: &mut String, stats: &AllStats, color: bool) {
                 writeln!(
                     summary,
                     "{:>column$}: {} errors, {} warnings, {} notes",
-                    format!("{check} {}", if stats.errors > 0 { "FAILED" } else { "ok" }),
+                    format!("{check} {}", if stats.failed() { "FAILED" } else { "ok" }),
                     stats.errors,
                     stats.warnings,
                     stats.notes + stats.helps,
@@ -197,71 +281,90 @@ fn write_full_stats(summary: &mut String, stats: &AllStats, color: bool) {
 
 #[cfg(test)]
 mod test {
-    use super::{stats_to_exit_code as ec, AllStats, Stats};
+    use super::{AllStats, FailedChecks, Stats};
 
-    #[test]
-    fn exit_code() {
-        assert!(ec(AllStats::default()).is_none());
-        assert_eq!(
-            Some(1),
-            ec(AllStats {
-                advisories: Some(Stats {
-                    errors: 1,
-                    ..Default::default()
-                }),
+    fn failed(advisories: u32, bans: u32, licenses: u32, sources: u32) -> FailedChecks {
+        let stats = |errors| {
+            Some(Stats {
+                errors,
                 ..Default::default()
             })
-        );
-        assert_eq!(
-            Some(2),
-            ec(AllStats {
-                bans: Some(Stats {
-                    errors: 2,
-                    ..Default::default()
-                }),
-                ..Default::default()
-            })
-        );
-        assert_eq!(
-            Some(4),
-            ec(AllStats {
-                licenses: Some(Stats {
-                    errors: 4,
-                    ..Default::default()
-                }),
+        };
+
+        FailedChecks::from_stats(&AllStats {
+            advisories: stats(advisories),
+            bans: stats(bans),
+            licenses: stats(licenses),
+            sources: stats(sources),
+        })
+    }
+
+    #[test]
+    fn exit_code() {
+        let ec = |fc: FailedChecks| fc.exit_code(false);
+
+        assert!(ec(FailedChecks::from_stats(&AllStats::default())).is_none());
+        assert!(ec(failed(0, 0, 0, 0)).is_none());
+        assert_eq!(Some(1), ec(failed(1, 0, 0, 0)));
+        assert_eq!(Some(2), ec(failed(0, 2, 0, 0)));
+        assert_eq!(Some(4), ec(failed(0, 0, 4, 0)));
+        assert_eq!(Some(8), ec(failed(0, 0, 0, 8)));
+        assert_eq!(Some(1 | 2 | 4 | 8), ec(failed(8, 4, 2, 1)));
+    }
+
+    #[test]
+    fn deny_warnings() {
+        let stats = |warnings, deny_warnings| {
+            Some(Stats {
+                warnings,
+                deny_warnings,
                 ..Default::default()
             })
-        );
-        assert_eq!(
-            Some(8),
-            ec(AllStats {
-                sources: Some(Stats {
-                    errors: 8,
-                    ..Default::default()
-                }),
+        };
+
+        let failed = FailedChecks::from_stats(&AllStats {
+            advisories: stats(1, false),
+            bans: stats(0, true),
+            licenses: stats(2, true),
+            sources: None,
+        });
+
+        assert_eq!(Some(4), failed.exit_code(false));
+    }
+
+    #[test]
+    fn max_warnings() {
+        let stats = |warnings| {
+            Some(Stats {
+                warnings,
                 ..Default::default()
             })
-        );
-        assert_eq!(
-            Some(1 | 2 | 4 | 8),
-            ec(AllStats {
-                advisories: Some(Stats {
-                    errors: 8,
-                    ..Default::default()
-                }),
-                bans: Some(Stats {
-                    errors: 4,
-                    ..Default::default()
-                }),
-                licenses: Some(Stats {
-                    errors: 2,
-                    ..Default::default()
-                }),
-                sources: Some(Stats {
-                    errors: 1,
-                    ..Default::default()
-                }),
-            })
-        );
+        };
+
+        let mut all = AllStats {
+            advisories: stats(1),
+            bans: stats(0),
+            licenses: stats(2),
+            sources: None,
+        };
+
+        assert!(all.deny_warnings_above(3).is_none());
+        assert!(FailedChecks::from_stats(&all).is_empty());
+
+        assert_eq!(Some(3), all.deny_warnings_above(2));
+        assert_eq!(Some(1 | 4), FailedChecks::from_stats(&all).exit_code(false));
+    }
+
+    #[test]
+    fn exit_code_per_check() {
+        let ec = |fc: FailedChecks| fc.exit_code(true);
+
+        assert!(ec(failed(0, 0, 0, 0)).is_none());
+        assert_eq!(Some(2), ec(failed(1, 0, 0, 0)));
+        assert_eq!(Some(4), ec(failed(0, 2, 0, 0)));
+        assert_eq!(Some(8), ec(failed(0, 0, 4, 0)));
+        assert_eq!(Some(16), ec(failed(0, 0, 0, 8)));
+        assert_eq!(Some(4 | 16), ec(failed(0, 1, 0, 1)));
+        assert_eq!(Some(2 | 4 | 8 | 16), ec(failed(8, 4, 2, 1)));
     }
 }
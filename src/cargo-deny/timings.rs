@@ -0,0 +1,75 @@
+use crate::Format;
+use serde::Serialize;
+use std::time::Duration;
+
+#[derive(Serialize)]
+struct Phase {
+    name: String,
+    duration_ms: u64,
+}
+
+/// The wall-clock durations of each phase of `check`, which are printed if
+/// `--timings` is specified
+///
+/// Many of the phases are run in parallel, so the durations don't add up to
+/// the total time taken
+#[derive(Default)]
+pub struct Timings {
+    phases: parking_lot::Mutex<Vec<Phase>>,
+}
+
+impl Timings {
+    /// Records the duration of a phase
+    pub fn record(&self, name: impl Into<String>, duration: Duration) {
+        self.phases.lock().push(Phase {
+            name: name.into(),
+            duration_ms: duration.as_millis() as u64,
+        });
+    }
+
+    /// Prints each phase in the order it completed, as a table for humans, or
+    /// as a single object for JSON
+    pub fn print(self, format: Format) {
+        let phases = self.phases.into_inner();
+
+        match format {
+            Format::Human | Format::Github | Format::Sarif | Format::Junit => {
+                use std::fmt::Write;
+
+                let width = phases
+                    .iter()
+                    .map(|phase| phase.name.len())
+                    .max()
+                    .unwrap_or_default()
+                    .max("phase".len());
+
+                let mut table = String::new();
+                let _ = writeln!(table, "{:<width$} {:>10}", "phase", "duration");
+                for phase in &phases {
+                    let _ = writeln!(table, "{:<width$} {:>8}ms", phase.name, phase.duration_ms);
+                }
+
+                #[allow(clippy::disallowed_macros)]
+                {
+                    print!("{table}");
+                }
+            }
+            Format::Json => {
+                let timings = serde_json::json!({
+                    "type": "timings",
+                    "fields": {
+                        "phases": phases,
+                    },
+                });
+
+                let to_print = serde_json::to_vec(&timings).unwrap();
+
+                use std::io::Write;
+                let stderr = std::io::stderr();
+                let mut el = stderr.lock();
+                let _ = el.write_all(&to_print);
+                let _ = el.write(b"\n");
+            }
+        }
+    }
+}
@@ -52,8 +52,8 @@ pub trait UnvalidatedConfig {
     fn validate(self, ctx: ValidationContext<'_>) -> Self::ValidCfg;
 }
 
-#[derive(Clone)]
-#[cfg_attr(test, derive(Debug, PartialEq, Eq, serde::Serialize))]
+#[derive(Clone, serde::Serialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 pub struct Reason(pub Spanned<String>);
 
 impl From<Spanned<String>> for Reason {
@@ -88,3 +88,38 @@ where
 
     T::deserialize(&mut v).ok()
 }
+
+/// Deserialize an optional `YYYY-MM-DD` date field from the table, eg. `2025-01-01`
+pub fn take_date(
+    th: &mut toml_span::de_helpers::TableHelper<'_>,
+    field: &'static str,
+) -> Option<Spanned<time::Date>> {
+    let (_, mut val) = th.take(field)?;
+
+    match val.take_string(Some("a date, eg. '2025-01-01'")) {
+        Ok(s) => {
+            match time::Date::parse(
+                &s,
+                time::macros::format_description!("[year]-[month]-[day]"),
+            ) {
+                Ok(date) => Some(Spanned::with_span(date, val.span)),
+                Err(err) => {
+                    th.errors.push(
+                        (
+                            toml_span::ErrorKind::Custom(
+                                format!("failed to parse date: {err}").into(),
+                            ),
+                            val.span,
+                        )
+                            .into(),
+                    );
+                    None
+                }
+            }
+        }
+        Err(err) => {
+            th.errors.push(err);
+            None
+        }
+    }
+}
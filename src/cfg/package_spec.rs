@@ -1,4 +1,4 @@
-use crate::{cfg::Span, Spanned};
+use crate::{bans::cfg::Checksum, cfg::Span, Spanned};
 use semver::VersionReq;
 use std::fmt;
 use toml_span::{
@@ -14,10 +14,14 @@ use toml_span::{
 /// of a [`semver::Version`] as Cargo's are meant for disambiguating graph operations
 /// whereas ours may be targeting single or multiple packages. In practice this
 /// is mainly just a superset of Cargo's version
+///
+/// When specified in table form, a `checksum` can also be provided to only
+/// match the crates.io crate whose lockfile checksum is exactly that value
 #[derive(Clone, PartialEq, Eq)]
 pub struct PackageSpec {
     pub name: Spanned<String>,
     pub version_req: Option<VersionReq>,
+    pub checksum: Option<Spanned<Checksum>>,
 }
 
 impl fmt::Display for PackageSpec {
@@ -28,13 +32,27 @@ impl fmt::Display for PackageSpec {
             write!(f, " = {vr}")?;
         }
 
+        if let Some(cs) = &self.checksum {
+            f.write_str(" (")?;
+            for byte in cs.value.0 {
+                write!(f, "{byte:02x}")?;
+            }
+            f.write_str(")")?;
+        }
+
         Ok(())
     }
 }
 
 impl fmt::Debug for PackageSpec {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} = {:?}", self.name.value, self.version_req)
+        write!(f, "{} = {:?}", self.name.value, self.version_req)?;
+
+        if let Some(cs) = &self.checksum {
+            write!(f, " ({:?})", cs.value)?;
+        }
+
+        Ok(())
     }
 }
 
@@ -62,6 +80,7 @@ impl<'de> Deserialize<'de> for PackageSpec {
             }
         }
 
+        let mut checksum = None;
         let ctx = match value.take() {
             ValueInner::String(s) => Ctx::from_str(s, value.span),
             ValueInner::Table(tab) => {
@@ -69,6 +88,7 @@ impl<'de> Deserialize<'de> for PackageSpec {
 
                 if let Some(mut val) = th.table.remove("crate") {
                     let s = val.take_string(Some("a crate spec"))?;
+                    checksum = th.optional_s("checksum");
                     th.finalize(Some(value))?;
 
                     Ctx::from_str(s, val.span)
@@ -82,6 +102,7 @@ impl<'de> Deserialize<'de> for PackageSpec {
                         }
                     })?;
                     let version = th.optional::<Spanned<Cow<'_, str>>>("version");
+                    let checksum = th.optional_s("checksum");
 
                     th.finalize(Some(value))?;
 
@@ -96,7 +117,11 @@ impl<'de> Deserialize<'de> for PackageSpec {
                         None
                     };
 
-                    return Ok(Self { name, version_req });
+                    return Ok(Self {
+                        name,
+                        version_req,
+                        checksum,
+                    });
                 }
             }
             other => return Err(expected("a string or table", other, value.span).into()),
@@ -120,11 +145,14 @@ impl<'de> Deserialize<'de> for PackageSpec {
             (Spanned::with_span(ctx.inner.into(), ctx.span), None)
         };
 
-        Ok(Self { name, version_req })
+        Ok(Self {
+            name,
+            version_req,
+            checksum,
+        })
     }
 }
 
-#[cfg(test)]
 impl serde::Serialize for PackageSpec {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -134,6 +162,9 @@ impl serde::Serialize for PackageSpec {
         let mut map = serializer.serialize_map(Some(3))?;
         map.serialize_entry("name", &self.name.value)?;
         map.serialize_entry("version-req", &self.version_req)?;
+        if let Some(cs) = &self.checksum {
+            map.serialize_entry("checksum", &cs.value)?;
+        }
         map.end()
     }
 }
@@ -142,7 +173,7 @@ use std::cmp::Ordering;
 
 impl Ord for PackageSpec {
     fn cmp(&self, other: &Self) -> Ordering {
-        match self.name.value.cmp(&other.name.value) {
+        let ord = match self.name.value.cmp(&other.name.value) {
             Ordering::Equal => match (&self.version_req, &other.version_req) {
                 (None, None) => Ordering::Equal,
                 (Some(_), None) => Ordering::Less,
@@ -218,7 +249,12 @@ impl Ord for PackageSpec {
                 }
             },
             o => o,
-        }
+        };
+
+        ord.then_with(|| {
+            let checksum = |ps: &Self| ps.checksum.as_ref().map(|cs| &cs.value);
+            checksum(self).cmp(&checksum(other))
+        })
     }
 }
 
@@ -228,7 +264,7 @@ impl PartialOrd for PackageSpec {
     }
 }
 
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(serde::Serialize)]
 pub struct PackageSpecOrExtended<T> {
     pub spec: PackageSpec,
     pub inner: Option<T>,
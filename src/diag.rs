@@ -1,9 +1,11 @@
 pub mod general;
 mod grapher;
+pub mod json;
 pub mod krate_spans;
 mod sink;
 
-pub use grapher::{cs_diag_to_json, diag_to_json, write_graph_as_text, InclusionGrapher};
+pub use grapher::{write_graph_as_text, InclusionGrapher};
+pub use json::{cs_diag_to_json, diag_to_json};
 pub use sink::{DiagnosticOverrides, ErrorSink};
 
 use std::{collections::BTreeMap, ops::Range};
@@ -185,11 +187,13 @@ impl From<crate::LintLevel> for Severity {
     }
 }
 
+#[derive(Clone)]
 pub struct GraphNode {
     pub kid: Kid,
     pub feature: Option<String>,
 }
 
+#[derive(Clone)]
 pub struct Diag {
     pub diag: Diagnostic,
     pub graph_nodes: smallvec::SmallVec<[GraphNode; 2]>,
@@ -206,6 +210,41 @@ impl Diag {
             with_features: false,
         }
     }
+
+    /// A stable identifier for this specific diagnostic, used to acknowledge
+    /// individual diagnostics via `output.acknowledge`
+    ///
+    /// Only the code, message, and crates the diagnostic pertains to are used,
+    /// so that the fingerprint doesn't change when eg. the config or lockfile
+    /// is edited
+    ///
+    /// Crates are identified by their name, version, and source, but path
+    /// sources are only identified as such, as they are absolute paths that
+    /// differ between machines and checkouts
+    pub fn fingerprint(&self) -> String {
+        let mut data = String::new();
+        data.push_str(self.diag.code.as_deref().unwrap_or_default());
+        data.push('\n');
+        data.push_str(&self.diag.message);
+
+        for gn in &self.graph_nodes {
+            let source = gn.kid.source();
+            let source = if source.starts_with("path+") {
+                "path"
+            } else {
+                source
+            };
+
+            data.push('\n');
+            data.push_str(gn.kid.name());
+            data.push(' ');
+            data.push_str(gn.kid.version());
+            data.push(' ');
+            data.push_str(source);
+        }
+
+        format!("{:08x}", crate::hash(data.as_bytes()))
+    }
 }
 
 impl From<Diagnostic> for Diag {
@@ -214,6 +253,20 @@ impl From<Diagnostic> for Diag {
     }
 }
 
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    strum::VariantArray,
+    strum::VariantNames,
+    serde::Serialize,
+)]
+#[strum(serialize_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
 pub enum Check {
     Advisories,
     Bans,
@@ -221,6 +274,9 @@ pub enum Check {
     Sources,
 }
 
+crate::enum_deser!(Check);
+
+#[derive(Clone)]
 pub struct Pack {
     pub check: Check,
     pub(crate) diags: Vec<Diag>,
@@ -273,6 +329,31 @@ impl Pack {
     pub fn iter(&self) -> impl Iterator<Item = &Diag> {
         self.diags.iter()
     }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Diag> {
+        self.diags.iter_mut()
+    }
+
+    /// Sorts the diagnostics in the pack with the specified comparator
+    #[inline]
+    pub fn sort_by(&mut self, compare: impl FnMut(&Diag, &Diag) -> std::cmp::Ordering) {
+        self.diags.sort_by(compare);
+    }
+
+    /// Removes and returns the diagnostics in the pack that match the predicate
+    #[inline]
+    pub fn take_matching(&mut self, pred: impl FnMut(&Diag) -> bool) -> Vec<Diag> {
+        let (taken, kept) = std::mem::take(&mut self.diags).into_iter().partition(pred);
+        self.diags = kept;
+        taken
+    }
+
+    /// The crate the diagnostics pertain to, if any
+    #[inline]
+    pub fn kid(&self) -> Option<&Kid> {
+        self.kid.as_ref()
+    }
 }
 
 impl IntoIterator for Pack {
@@ -407,6 +488,44 @@ mod test {
         insta::assert_debug_snapshot!(unique);
     }
 
+    /// Ensures fingerprints don't depend on where path crates are located on
+    /// disk, but do depend on the crates themselves
+    #[test]
+    fn fingerprints_are_stable() {
+        use super::{Diag, Diagnostic, GraphNode};
+
+        let fingerprint = |repr: &str| {
+            let mut diag = Diag::new(
+                Diagnostic::error()
+                    .with_code("banned")
+                    .with_message("crate 'a = 0.1.0' is explicitly banned"),
+            );
+            diag.graph_nodes.push(GraphNode {
+                kid: krates::Kid::from(krates::cm::PackageId { repr: repr.into() }),
+                feature: None,
+            });
+            diag.fingerprint()
+        };
+
+        let local = fingerprint("path+file:///home/me/project/crates/a#0.1.0");
+        assert_eq!(
+            local,
+            fingerprint("path+file:///ci/runner/work/project/crates/a#0.1.0")
+        );
+        assert_ne!(
+            local,
+            fingerprint("path+file:///home/me/project/crates/a#0.2.0")
+        );
+
+        let registry = "registry+https://github.com/rust-lang/crates.io-index#a@0.1.0";
+        assert_eq!(fingerprint(registry), fingerprint(registry));
+        assert_ne!(local, fingerprint(registry));
+        assert_ne!(
+            fingerprint(registry),
+            fingerprint("git+https://github.com/a/a?rev=1234#a@0.1.0")
+        );
+    }
+
     /// While _most_ of the possible output if codespan is covered by various tests
     /// there are a couple of cases that aren't covered, so we just generate a
     /// set of outputs to easily show output differences when changing settings
@@ -648,4 +767,38 @@ lines
 
         insta::assert_snapshot!(String::from_utf8(term.into_inner()).unwrap());
     }
+
+    /// Pins the shape of the JSON diagnostic format, if this test fails due to
+    /// a breaking change to the format, `json::SCHEMA_VERSION` _must_ be bumped
+    #[test]
+    fn json_schema() {
+        use codespan_reporting::diagnostic::{Diagnostic, Label};
+
+        let mut files = super::Files::new();
+
+        const FILE: &str = "[bans]\ndeny = [\n    { name = \"openssl\" },\n]\n";
+        let file_id = files.add("deny.toml", FILE);
+        let start = FILE.find("\"openssl\"").unwrap();
+
+        let minimal = super::Diag::new(Diagnostic::note().with_message("minimal"));
+
+        let mut full = super::Diag::new(
+            Diagnostic::error()
+                .with_message("full")
+                .with_code("banned")
+                .with_labels(vec![
+                    Label::primary(file_id, start..start + 9).with_message("banned here"),
+                    Label::secondary(file_id, 0..6),
+                ])
+                .with_notes(vec!["first note".into(), "second note".into()]),
+        );
+        full.extra = Some(("extra", serde_json::json!({ "key": "value" })));
+
+        let diags: Vec<_> = [minimal, full]
+            .into_iter()
+            .map(|diag| super::diag_to_json(diag, &files, None))
+            .collect();
+
+        insta::assert_json_snapshot!(diags);
+    }
 }
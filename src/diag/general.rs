@@ -20,6 +20,9 @@ use std::fmt;
 #[strum(serialize_all = "kebab-case")]
 pub enum Code {
     Deprecated,
+    Annotation,
+    UnusedAnnotation,
+    GraphChanged,
 }
 
 impl From<Code> for String {
@@ -0,0 +1,146 @@
+//! The JSON format used for diagnostics when `--format json` is specified
+//!
+//! Every diagnostic is emitted as a single [`JsonDiagnostic`] object per line.
+//! The shape of these types is considered stable for a particular
+//! [`SCHEMA_VERSION`], any breaking change to them _must_ be accompanied by
+//! an increment of the version.
+
+pub use super::grapher::{GraphNode as JsonGraphNode, NodeInner as JsonNodeInner};
+use super::{Diag, FileId, Files, InclusionGrapher, Severity};
+
+/// The current version of the JSON diagnostic format
+///
+/// Version 1 is the unversioned format emitted by earlier releases, which is
+/// identical to version 2 other than the lack of the `schema_version` field.
+pub const SCHEMA_VERSION: u32 = 2;
+
+pub type CsDiag = codespan_reporting::diagnostic::Diagnostic<FileId>;
+
+/// The top level envelope for a diagnostic
+#[derive(serde::Serialize)]
+pub struct JsonDiagnostic {
+    /// Always [`SCHEMA_VERSION`]
+    pub schema_version: u32,
+    /// Always `"diagnostic"`
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub fields: DiagnosticFields,
+}
+
+#[derive(serde::Serialize)]
+pub struct DiagnosticFields {
+    pub severity: JsonSeverity,
+    pub message: String,
+    /// The unique code for the diagnostic, see [`super::DiagnosticCode`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<JsonLabel>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+    /// The inclusion graphs for each crate the diagnostic pertains to, only
+    /// present if the diagnostic was emitted for crates in the graph
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub graphs: Option<Vec<JsonGraphNode>>,
+    /// Additional check specific data, eg. the `advisory` for advisory
+    /// diagnostics
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(serde::Serialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonSeverity {
+    Error,
+    Warning,
+    Note,
+    Help,
+    Bug,
+}
+
+impl From<Severity> for JsonSeverity {
+    fn from(s: Severity) -> Self {
+        match s {
+            Severity::Error => Self::Error,
+            Severity::Warning => Self::Warning,
+            Severity::Note => Self::Note,
+            Severity::Help => Self::Help,
+            Severity::Bug => Self::Bug,
+        }
+    }
+}
+
+/// A location in a source file that a diagnostic refers to
+#[derive(serde::Serialize)]
+pub struct JsonLabel {
+    pub message: String,
+    /// The source text the label spans
+    pub span: String,
+    /// The 1-based line the span starts on
+    pub line: usize,
+    /// The 1-based column the span starts on
+    pub column: usize,
+}
+
+pub fn cs_diag_to_json(diag: CsDiag, files: &Files) -> JsonDiagnostic {
+    let labels = diag
+        .labels
+        .into_iter()
+        .map(|label| {
+            let location = files
+                .location(label.file_id, label.range.start as u32)
+                .unwrap();
+
+            JsonLabel {
+                message: label.message,
+                span: files.source(label.file_id)[label.range]
+                    .trim_matches('"')
+                    .to_owned(),
+                line: location.line.to_usize() + 1,
+                column: location.column.to_usize() + 1,
+            }
+        })
+        .collect();
+
+    JsonDiagnostic {
+        schema_version: SCHEMA_VERSION,
+        kind: "diagnostic",
+        fields: DiagnosticFields {
+            severity: diag.severity.into(),
+            message: diag.message,
+            code: diag.code,
+            labels,
+            notes: diag.notes,
+            graphs: None,
+            extra: serde_json::Map::new(),
+        },
+    }
+}
+
+pub fn diag_to_json(
+    diag: Diag,
+    files: &Files,
+    grapher: Option<&InclusionGrapher<'_>>,
+) -> JsonDiagnostic {
+    let mut to_print = cs_diag_to_json(diag.diag, files);
+
+    if let Some(grapher) = &grapher {
+        let graphs = diag
+            .graph_nodes
+            .iter()
+            .filter_map(|gn| {
+                grapher
+                    .build_graph(gn, if diag.with_features { usize::MAX } else { 0 })
+                    .ok()
+            })
+            .collect();
+
+        to_print.fields.graphs = Some(graphs);
+    }
+
+    if let Some((key, val)) = diag.extra {
+        to_print.fields.extra.insert(key.to_owned(), val);
+    }
+
+    to_print
+}
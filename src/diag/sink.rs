@@ -29,6 +29,23 @@ impl ErrorSink {
                 {
                     diag.diag.severity = new_severity;
                 }
+
+                if overrides.acknowledged.is_empty() && !overrides.list_fingerprints {
+                    continue;
+                }
+
+                let fingerprint = diag.fingerprint();
+
+                if overrides.acknowledged.contains(&fingerprint) {
+                    diag.diag.severity = Severity::Note;
+                    diag.diag
+                        .notes
+                        .push("acknowledged in `output.acknowledge`".to_owned());
+                }
+
+                if overrides.list_fingerprints {
+                    diag.diag.notes.push(format!("fingerprint: {fingerprint}"));
+                }
             }
         }
 
@@ -40,9 +57,15 @@ use super::Severity;
 
 /// Each diagnostic will have a default severity, but these can be overridden
 /// by the user via the CLI so that eg. warnings can be made into errors on CI
+#[derive(Default)]
 pub struct DiagnosticOverrides {
     pub code_overrides: std::collections::BTreeMap<&'static str, Severity>,
     pub level_overrides: Vec<(Severity, Severity)>,
+    /// The fingerprints of individual diagnostics that have been acknowledged,
+    /// and are downgraded to notes regardless of their severity
+    pub acknowledged: std::collections::BTreeSet<String>,
+    /// If true, the fingerprint of every diagnostic is added as a note
+    pub list_fingerprints: bool,
 }
 
 impl DiagnosticOverrides {
@@ -5,6 +5,7 @@ use std::{cmp, collections::BTreeMap, fmt};
 use url::Url;
 
 pub mod advisories;
+pub mod annotations;
 pub mod bans;
 pub mod cfg;
 pub mod diag;
@@ -27,9 +28,23 @@ pub use toml_span::{
 
 /// The possible lint levels for the various lints. These function similarly
 /// to the standard [Rust lint levels](https://doc.rust-lang.org/rustc/lints/levels.html)
-#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, strum::VariantNames, strum::VariantArray)]
-#[cfg_attr(test, derive(serde::Serialize))]
-#[cfg_attr(test, serde(rename_all = "kebab-case"))]
+///
+/// Lint levels are ordered by their strictness, ie. `Allow < Warn < Deny`
+#[derive(
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    strum::VariantNames,
+    strum::VariantArray,
+    strum::IntoStaticStr,
+    serde::Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "kebab-case")]
 pub enum LintLevel {
     /// A debug or info diagnostic _may_ be emitted if the lint is violated
@@ -147,7 +162,7 @@ impl Source {
                 }
             }
             "git" => {
-                let mut url = Url::parse(url_str).context("failed to parse url")?;
+                let mut url = parse_git_url(url_str).context("failed to parse url")?;
                 let (spec, spec_value) = normalize_git_url(&mut url);
 
                 Ok(Self::Git {
@@ -173,6 +188,16 @@ impl Source {
         Some(*spec)
     }
 
+    /// Gets the commit a git source is locked to, which is the fragment of
+    /// the source url that cargo records in the lockfile
+    #[inline]
+    pub fn git_locked_rev(&self) -> Option<&str> {
+        let Self::Git { url, .. } = self else {
+            return None;
+        };
+        url.fragment().filter(|rev| !rev.is_empty())
+    }
+
     #[inline]
     pub fn is_registry(&self) -> bool {
         !self.is_git()
@@ -240,6 +265,9 @@ pub struct Krate {
     pub name: String,
     pub id: Kid,
     pub version: Version,
+    pub edition: cm::Edition,
+    /// The minimum version of Rust the crate declares it supports
+    pub rust_version: Option<Version>,
     pub source: Option<Source>,
     pub authors: Vec<String>,
     pub repository: Option<String>,
@@ -251,6 +279,11 @@ pub struct Krate {
     pub features: BTreeMap<String, Vec<String>>,
     pub targets: Vec<cm::Target>,
     pub publish: Option<Vec<String>>,
+    /// The name of the native library the crate links to, if any
+    pub links: Option<String>,
+    /// The checksum recorded in the lockfile for crates.io crates, this is not
+    /// part of the cargo metadata so is filled in after the graph is built
+    pub checksum: std::sync::OnceLock<bans::cfg::Checksum>,
 }
 
 #[cfg(test)]
@@ -259,6 +292,8 @@ impl Default for Krate {
         Self {
             name: "".to_owned(),
             version: Version::new(0, 1, 0),
+            edition: cm::Edition::E2021,
+            rust_version: None,
             authors: Vec::new(),
             id: Kid::default(),
             source: None,
@@ -271,6 +306,8 @@ impl Default for Krate {
             manifest_path: PathBuf::new(),
             repository: None,
             publish: None,
+            links: None,
+            checksum: std::sync::OnceLock::new(),
         }
     }
 }
@@ -328,6 +365,8 @@ impl From<cm::Package> for Krate {
             name: pkg.name,
             id: pkg.id.into(),
             version: pkg.version,
+            edition: pkg.edition,
+            rust_version: pkg.rust_version,
             authors: pkg.authors,
             repository: pkg.repository,
             source,
@@ -352,6 +391,8 @@ impl From<cm::Package> for Krate {
             // },
             features: pkg.features,
             publish: pkg.publish,
+            links: pkg.links,
+            checksum: std::sync::OnceLock::new(),
         }
     }
 }
@@ -466,6 +507,48 @@ pub struct CheckCtx<'ctx, T> {
     pub log_level: log::LevelFilter,
     /// Files that can show span information in diagnostics
     pub files: &'ctx diag::Files,
+    /// Optional callback invoked at the major phase boundaries of the check
+    pub progress: Option<ProgressFn>,
+    /// Requests that checks add notes with commands or config edits that
+    /// remediate actionable diagnostics
+    pub suggest_fixes: bool,
+}
+
+impl<T> CheckCtx<'_, T> {
+    /// Invokes the progress callback, if one was provided
+    #[inline]
+    pub(crate) fn report_progress(&self, event: ProgressEvent) {
+        if let Some(progress) = &self.progress {
+            progress(event);
+        }
+    }
+}
+
+/// Callback used to report [`ProgressEvent`]s
+pub type ProgressFn = Box<dyn Fn(ProgressEvent) + Send + Sync>;
+
+/// Events emitted at the major phase boundaries of the various checks, for
+/// library consumers that want to report progress without parsing logs
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProgressEvent {
+    /// License information is about to be gathered for the number of crates
+    GatheringLicenses { krates: usize },
+    /// License information was gathered for the number of crates
+    LicensesGathered { krates: usize },
+    /// The crates are about to be queried against the advisory databases
+    QueryingAdvisories { krates: usize, databases: usize },
+    /// The advisory databases and indices were queried
+    AdvisoriesQueried {
+        /// The number of advisories that matched a crate
+        advisories: usize,
+        /// The number of crates that were yanked, or failed to be queried
+        yanked: usize,
+    },
+    /// The bans check is about to be run against the number of crates
+    CheckingBans { krates: usize },
+    /// The bans check has finished
+    BansChecked,
 }
 
 /// Checks if a version satisfies the specifies the specified version requirement.
@@ -477,14 +560,48 @@ pub fn match_req(version: &Version, req: Option<&semver::VersionReq>) -> bool {
 
 #[inline]
 pub fn match_krate(krate: &Krate, pid: &cfg::PackageSpec) -> bool {
-    krate.name == pid.name.value && match_req(&krate.version, pid.version_req.as_ref())
+    krate.name == pid.name.value
+        && match_req(&krate.version, pid.version_req.as_ref())
+        && pid
+            .checksum
+            .as_ref()
+            .map_or(true, |cs| krate.checksum.get() == Some(&cs.value))
 }
 
 use sources::cfg::GitSpec;
 
+/// Parses a git URL, additionally accepting the scp-like `[user@]host:path`
+/// syntax supported by git, eg. `git@github.com:EmbarkStudios/cargo-deny.git`,
+/// as well as `ssh://` URLs that use a `:` instead of a `/` to separate the
+/// host from the path
+pub(crate) fn parse_git_url(url: &str) -> Result<Url, url::ParseError> {
+    let scp_like = |rest: &str| {
+        let (host, path) = rest.split_once(':')?;
+
+        // Local paths (including windows drive letters) are not scp-like, and
+        // an ssh port must be numeric
+        (!host.contains('/')
+            && host.contains(['@', '.'])
+            && !path.starts_with(|c: char| c.is_ascii_digit()))
+        .then(|| format!("ssh://{host}/{}", path.trim_start_matches('/')))
+    };
+
+    let ssh = if let Some(rest) = url.strip_prefix("ssh://") {
+        scp_like(rest)
+    } else if !url.contains("://") {
+        scp_like(url)
+    } else {
+        None
+    };
+
+    Url::parse(ssh.as_deref().unwrap_or(url))
+}
+
 /// Normalizes the URL so that different representations can be compared to each other.
 ///
-/// At the moment we just remove a tailing `.git` but there are more possible optimisations.
+/// This removes a trailing `.git` and `/`, and canonicalizes `ssh://` URLs to
+/// `https://` as, like cargo, we consider them the same repository, dropping
+/// the user and port, which are irrelevant for comparisons.
 ///
 /// See <https://github.com/rust-lang/cargo/blob/1f6c6bd5e7bbdf596f7e88e6db347af5268ab113/src/cargo/util/canonical_url.rs#L31-L57>
 /// for what cargo does
@@ -492,6 +609,28 @@ use sources::cfg::GitSpec;
 pub(crate) fn normalize_git_url(url: &mut Url) -> (GitSpec, Option<String>) {
     const GIT_EXT: &str = ".git";
 
+    // The url crate doesn't allow changing the scheme between "special" (https)
+    // and non-special (ssh) schemes, so we need to reconstruct it
+    if url.scheme() == "ssh" {
+        if let Some(host) = url.host_str() {
+            let mut canonical = format!("https://{host}{}", url.path());
+
+            if let Some(query) = url.query() {
+                canonical.push('?');
+                canonical.push_str(query);
+            }
+
+            if let Some(fragment) = url.fragment() {
+                canonical.push('#');
+                canonical.push_str(fragment);
+            }
+
+            if let Ok(canonical) = Url::parse(&canonical) {
+                *url = canonical;
+            }
+        }
+    }
+
     let needs_chopping = url.path().ends_with(&GIT_EXT);
     if needs_chopping {
         let last = {
@@ -553,6 +692,23 @@ pub fn utf8path(pb: std::path::PathBuf) -> anyhow::Result<PathBuf> {
     PathBuf::try_from(pb).context("non-utf8 path")
 }
 
+/// Creates the HTTP client used for the requests cargo-deny makes itself, ie.
+/// the ones not made by gix or tame-index when fetching advisory databases
+/// and indices
+///
+/// Every request identifies itself as cargo-deny, and is bounded by a timeout
+/// so that an unresponsive server can't hang the whole run
+pub fn http_client() -> anyhow::Result<reqwest::blocking::Client> {
+    use anyhow::Context as _;
+
+    reqwest::blocking::Client::builder()
+        .user_agent(concat!("cargo-deny/", env!("CARGO_PKG_VERSION")))
+        .connect_timeout(std::time::Duration::from_secs(10))
+        .timeout(std::time::Duration::from_secs(60))
+        .build()
+        .context("failed to create HTTP client")
+}
+
 /// Adds the crates.io index with the specified settings to the builder for
 /// feature resolution
 pub fn krates_with_index(
@@ -678,6 +834,47 @@ mod test {
         );
     }
 
+    /// Ensures the different ways of specifying the same git repository are
+    /// normalized to the same URL
+    #[test]
+    fn normalizes_git_urls() {
+        for url in [
+            "https://github.com/EmbarkStudios/cargo-deny",
+            "https://github.com/EmbarkStudios/cargo-deny.git",
+            "https://github.com/EmbarkStudios/cargo-deny/",
+            "ssh://git@github.com/EmbarkStudios/cargo-deny.git",
+            "ssh://git@github.com:22/EmbarkStudios/cargo-deny",
+            "ssh://git@github.com:EmbarkStudios/cargo-deny.git",
+            "git@github.com:EmbarkStudios/cargo-deny.git",
+            "git@github.com:/EmbarkStudios/cargo-deny",
+            "github.com:EmbarkStudios/cargo-deny",
+        ] {
+            let mut parsed = super::parse_git_url(url).unwrap();
+            super::normalize_git_url(&mut parsed);
+            assert_eq!(
+                parsed.as_str(),
+                "https://github.com/EmbarkStudios/cargo-deny",
+                "{url}"
+            );
+        }
+
+        let mut parsed =
+            super::parse_git_url("git@github.com:EmbarkStudios/cargo-deny.git?rev=abcdef").unwrap();
+        let (spec, value) = super::normalize_git_url(&mut parsed);
+        assert_eq!(
+            parsed.as_str(),
+            "https://github.com/EmbarkStudios/cargo-deny?rev=abcdef"
+        );
+        assert_eq!(spec, super::GitSpec::Rev);
+        assert_eq!(value.as_deref(), Some("abcdef"));
+
+        // Ensure other urls without an authority are not mistaken for scp-like urls
+        assert_eq!(
+            super::parse_git_url("file:/path/to/repo").unwrap().scheme(),
+            "file"
+        );
+    }
+
     #[test]
     fn inexact_match_fails_for_different_hosts() {
         let krate = Krate {
@@ -13,12 +13,22 @@
 
 /// Configuration for license checking
 pub mod cfg;
+mod compat;
 mod diags;
+mod fetch;
 mod gather;
-
-use crate::diag::{CfgCoord, Check, Diagnostic, Label, Pack, Severity};
-pub use gather::{Gatherer, LicenseInfo, LicenseStore, Summary};
-use gather::{KrateLicense, LicenseExprInfo, LicenseExprSource};
+mod lock;
+
+use crate::{
+    diag::{CfgCoord, Check, Diagnostic, FileId, Label, Pack, Severity},
+    LintLevel,
+};
+pub use fetch::FetchedLicenseFiles;
+pub use gather::{
+    Gatherer, KrateLicense, LicenseExprInfo, LicenseExprSource, LicenseInfo, LicenseStore,
+    SuggestedClarification, Summary,
+};
+pub use lock::LicenseLock;
 
 pub use diags::Code;
 
@@ -29,11 +39,124 @@ struct Hits {
     exceptions: BitVec<usize, LocalBits>,
 }
 
+/// Describes which licenses would need to be allowed for an expression that
+/// failed to be satisfied, so that it is clear if allowing a single license
+/// would be enough, or if the expression can't reasonably be satisfied under
+/// the current config
+///
+/// `accepted` is whether each requirement, in order, was accepted, or `None`
+/// if it was rejected for a reason that allowing the license doesn't change
+fn describe_fix(expr: &spdx::Expression, accepted: &[Option<bool>]) -> String {
+    use spdx::expression::{ExprNode, ExpressionReq, Operator};
+
+    // The licenses that need to be allowed for each subexpression to be
+    // satisfied, or `None` if allowing licenses can't satisfy it
+    let mut stack = Vec::<Option<Vec<&ExpressionReq>>>::new();
+    let mut reqs = accepted.iter();
+
+    for node in expr.iter() {
+        match node {
+            ExprNode::Req(er) => stack.push(match reqs.next() {
+                Some(Some(true)) => Some(Vec::new()),
+                Some(Some(false)) => Some(vec![er]),
+                _ => None,
+            }),
+            ExprNode::Op(op) => {
+                let (Some(rhs), Some(lhs)) = (stack.pop(), stack.pop()) else {
+                    break;
+                };
+
+                stack.push(match op {
+                    // Both sides must be satisfied
+                    Operator::And => lhs.zip(rhs).map(|(mut lhs, rhs)| {
+                        for er in rhs {
+                            if !lhs.iter().any(|l| l.req == er.req) {
+                                lhs.push(er);
+                            }
+                        }
+                        lhs
+                    }),
+                    // Only the side that needs the fewest licenses matters
+                    Operator::Or => match (lhs, rhs) {
+                        (Some(lhs), Some(rhs)) => {
+                            Some(if rhs.len() < lhs.len() { rhs } else { lhs })
+                        }
+                        (lhs, rhs) => lhs.or(rhs),
+                    },
+                });
+            }
+        }
+    }
+
+    let quoted = |ers: &[&ExpressionReq]| {
+        ers.iter()
+            .map(|er| format!("'{}'", er.req))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match stack.pop().flatten() {
+        Some(missing) if missing.len() == 1 => {
+            // There may be multiple licenses that would each satisfy the
+            // expression on their own, eg. `MIT OR Apache-2.0`
+            let requirements: Vec<_> = expr.requirements().zip(accepted).collect();
+            let mut candidates = Vec::<&ExpressionReq>::new();
+
+            for (er, _) in requirements.iter().filter(|(_, acc)| **acc == Some(false)) {
+                if candidates.iter().any(|c| c.req == er.req) {
+                    continue;
+                }
+
+                if expr.evaluate(|req| {
+                    req == &er.req
+                        || requirements
+                            .iter()
+                            .any(|(other, acc)| &other.req == req && **acc == Some(true))
+                }) {
+                    candidates.push(*er);
+                }
+            }
+
+            if candidates.len() > 1 {
+                format!(
+                    "allowing any one of {} would satisfy the license requirements",
+                    quoted(&candidates)
+                )
+            } else {
+                format!(
+                    "allowing {} would satisfy the license requirements",
+                    quoted(&missing)
+                )
+            }
+        }
+        Some(missing) if !missing.is_empty() => format!(
+            "no single license can be allowed to satisfy the license requirements, at least {} would need to be allowed: {}",
+            missing.len(),
+            quoted(&missing)
+        ),
+        _ => "the license requirements can't be satisfied by allowing additional licenses"
+            .to_owned(),
+    }
+}
+
+/// Whether the requirement is the SPDX `NOASSERTION` identifier, which states
+/// that the license could not be determined
+#[inline]
+fn is_no_assertion(req: &spdx::LicenseReq) -> bool {
+    req.license.id().is_some_and(|id| id.name == "NOASSERTION")
+}
+
+/// Evaluates a license expression against the config
+///
+/// `origin` is the crate the expression belongs to and where the expression
+/// was retrieved from, if it belongs to a crate at all. Exceptions only apply
+/// to crates, so only the general allow list is consulted otherwise.
 fn evaluate_expression(
-    ctx: &crate::CheckCtx<'_, cfg::ValidConfig>,
-    krate_lic_nfo: &KrateLicense<'_>,
+    cfg: &cfg::ValidConfig,
+    report_accepted: bool,
+    origin: Option<(&KrateLicense<'_>, &LicenseExprSource)>,
     expr: &spdx::Expression,
-    nfo: &LicenseExprInfo,
+    (file_id, offset): (FileId, usize),
     hits: &mut Hits,
 ) -> Diagnostic {
     // TODO: If an expression with the same hash is encountered
@@ -42,7 +165,11 @@ fn evaluate_expression(
     enum Reason {
         ExplicitAllowance,
         ExplicitException,
+        ExplicitExpressionException,
         NotExplicitlyAllowed,
+        UnknownLicenseRef,
+        NoAssertion,
+        DeprecatedLicense,
     }
 
     let mut reasons = smallvec::SmallVec::<[(Reason, bool); 8]>::new();
@@ -61,16 +188,51 @@ fn evaluate_expression(
         };
     }
 
-    let cfg = &ctx.cfg;
+    let mut unknown_ref_warned = false;
+    let mut deprecated_warned = false;
 
     // Check to see if the crate matches an exception, which is additional to
     // the general allow list
-    let exception_ind = cfg
-        .exceptions
-        .iter()
-        .position(|exc| crate::match_krate(krate_lic_nfo.krate, &exc.spec));
+    let exception_ind = origin.and_then(|(krate_lic_nfo, _)| {
+        cfg.exceptions.iter().position(|exc| {
+            crate::match_krate(krate_lic_nfo.krate, &exc.spec)
+                && exc
+                    .source
+                    .as_ref()
+                    .map_or(true, |src| src.value.matches(krate_lic_nfo.krate))
+        })
+    });
+
+    // An exception for the exact expression allows the crate wholesale,
+    // regardless of the individual licenses in the expression
+    let expression_exception = exception_ind.filter(|ind| {
+        cfg.exceptions[*ind]
+            .expression
+            .as_ref()
+            .is_some_and(|exc_expr| exc_expr.matches(expr))
+    });
+
+    if let Some(ind) = expression_exception {
+        hits.exceptions.as_mut_bitslice().set(ind, true);
+    }
 
     let eval_res = expr.evaluate_with_failures(|req| {
+        if expression_exception.is_some() {
+            allow!(ExplicitExpressionException);
+        }
+
+        // 0. Deprecated license identifiers can be denied regardless of whether
+        // they would otherwise be allowed
+        if req.license.id().is_some_and(|id| id.is_deprecated()) {
+            match cfg.deny_deprecated {
+                LintLevel::Allow => {}
+                LintLevel::Warn => deprecated_warned = true,
+                LintLevel::Deny => {
+                    deny!(DeprecatedLicense);
+                }
+            }
+        }
+
         // 1. Exceptions are additional per-crate licenses that aren't blanket
         // allowed by all crates, note that we check these before denials so you
         // can allow an exception
@@ -94,40 +256,163 @@ fn evaluate_expression(
             }
         }
 
+        // 3. Custom license references, eg. `LicenseRef-Proprietary`, that
+        // weren't explicitly allowed are handled by the user's lint level.
+        // `NOASSERTION` is a valid SPDX identifier, but it states that the
+        // license is unknown rather than naming a license, so it is treated
+        // the same as an unknown reference
+        if let spdx::LicenseItem::Other { .. } = &req.license {
+            match cfg.unknown_license_ref {
+                LintLevel::Allow => {
+                    allow!(UnknownLicenseRef);
+                }
+                LintLevel::Warn => {
+                    unknown_ref_warned = true;
+                    allow!(UnknownLicenseRef);
+                }
+                LintLevel::Deny => {
+                    deny!(UnknownLicenseRef);
+                }
+            }
+        } else if is_no_assertion(req) {
+            match cfg.unknown_license_ref {
+                LintLevel::Allow => {
+                    allow!(NoAssertion);
+                }
+                LintLevel::Warn => {
+                    unknown_ref_warned = true;
+                    allow!(NoAssertion);
+                }
+                LintLevel::Deny => {
+                    deny!(NoAssertion);
+                }
+            }
+        }
+
         deny!(NotExplicitlyAllowed);
     });
 
     let (message, severity) = match eval_res {
         Err(_) => ("failed to satisfy license requirements", Severity::Error),
+        Ok(_) if unknown_ref_warned => (
+            "license requirements satisfied by an unknown license reference",
+            Severity::Warning,
+        ),
+        Ok(_) if deprecated_warned => (
+            "license requirements satisfied by a deprecated license identifier",
+            Severity::Warning,
+        ),
         Ok(_) => ("license requirements satisfied", Severity::Help),
     };
 
     let mut labels = Vec::with_capacity(reasons.len() + 1);
-    labels.extend(krate_lic_nfo.labels.clone());
+    let mut notes = Vec::new();
 
-    labels.push(
-        Label::secondary(nfo.file_id, nfo.offset..nfo.offset + expr.as_ref().len()).with_message(
-            format!(
+    if let Some((krate_lic_nfo, source)) = origin {
+        labels.extend(krate_lic_nfo.labels.clone());
+
+        labels.push(
+            Label::secondary(file_id, offset..offset + expr.as_ref().len()).with_message(format!(
                 "license expression retrieved via {}",
-                match &nfo.source {
+                match source {
                     LicenseExprSource::Metadata => "Cargo.toml `license`".to_owned(),
                     LicenseExprSource::UserOverride => "user override".to_owned(),
                     LicenseExprSource::LicenseFiles(lfs) => lfs.join(", "),
                     LicenseExprSource::OverlayOverride => unreachable!(),
                 }
-            ),
-        ),
-    );
+            )),
+        );
+
+        notes.extend(krate_lic_nfo.notes.iter().cloned());
+
+        if expression_exception.is_some() {
+            notes.push(format!(
+                "license expression '{expr}' retrieved via {} is explicitly allowed via an exception",
+                match source {
+                    LicenseExprSource::Metadata => "Cargo.toml `license`",
+                    LicenseExprSource::UserOverride => "user override",
+                    LicenseExprSource::LicenseFiles(_) => "license files",
+                    LicenseExprSource::OverlayOverride => unreachable!(),
+                },
+                expr = expr.as_ref(),
+            ));
+        }
+    }
+
+    if cfg.deny_deprecated != LintLevel::Allow {
+        for er in expr.requirements() {
+            let Some(id) = er.req.license.id().filter(|id| id.is_deprecated()) else {
+                continue;
+            };
+
+            if deprecated_warned {
+                labels.push(
+                    Label::primary(
+                        file_id,
+                        offset + er.span.start as usize..offset + er.span.end as usize,
+                    )
+                    .with_message("deprecated license identifier"),
+                );
+            }
+
+            notes.push(match replacement_for_deprecated(&er.req) {
+                Some(replacement) => format!(
+                    "'{}' is a deprecated SPDX identifier, use '{replacement}' instead",
+                    id.name
+                ),
+                None => format!("'{}' is a deprecated SPDX identifier", id.name),
+            });
+        }
+    }
 
-    let mut notes = krate_lic_nfo.notes.clone();
+    // Whether each requirement was accepted, or if it was rejected, whether
+    // allowing the license would change that
+    let fix_note = (severity == Severity::Error).then(|| {
+        let accepted: Vec<_> = reasons
+            .iter()
+            .map(|(reason, accepted)| {
+                (*accepted || !matches!(reason, Reason::DeprecatedLicense)).then_some(*accepted)
+            })
+            .collect();
+        describe_fix(expr, &accepted)
+    });
 
     for ((reason, accepted), failed_req) in reasons.into_iter().zip(expr.requirements()) {
-        if accepted && ctx.log_level < log::LevelFilter::Info {
+        if accepted
+            && !report_accepted
+            && !matches!(reason, Reason::UnknownLicenseRef | Reason::NoAssertion)
+        {
             continue;
         }
 
         if !accepted && severity == Severity::Error {
-            if let Some(id) = failed_req.req.license.id() {
+            // Classifications in the config take precedence over the metadata
+            // in the SPDX license list
+            let name = compat::req_name(&failed_req.req);
+            let class = cfg
+                .classifications
+                .iter()
+                .find(|class| class.license.value == name);
+
+            if let spdx::LicenseItem::Other { .. } = &failed_req.req.license {
+                notes.push(format!(
+                    "{} is a custom license reference, it must be explicitly allowed",
+                    failed_req.req
+                ));
+
+                if class.and_then(|class| class.osi) == Some(true) {
+                    notes.push("  - OSI approved".into());
+                }
+
+                if class.and_then(|class| class.copyleft) == Some(true) {
+                    notes.push("  - Copyleft".into());
+                }
+            } else if is_no_assertion(&failed_req.req) {
+                notes.push(
+                    "NOASSERTION states that the license could not be determined, it must be explicitly allowed"
+                        .into(),
+                );
+            } else if let Some(id) = failed_req.req.license.id() {
                 notes.push(format!("{} - {}:", id.name, id.full_name));
 
                 let len = notes.len();
@@ -136,7 +421,10 @@ fn evaluate_expression(
                     notes.push("  - **DEPRECATED**".into());
                 }
 
-                if id.is_osi_approved() {
+                if class
+                    .and_then(|class| class.osi)
+                    .unwrap_or_else(|| id.is_osi_approved())
+                {
                     notes.push("  - OSI approved".into());
                 }
 
@@ -144,7 +432,10 @@ fn evaluate_expression(
                     notes.push("  - FSF Free/Libre".into());
                 }
 
-                if id.is_copyleft() {
+                if class
+                    .and_then(|class| class.copyleft)
+                    .unwrap_or_else(|| id.is_copyleft())
+                {
                     notes.push("  - Copyleft".into());
                 }
 
@@ -160,9 +451,8 @@ fn evaluate_expression(
 
         labels.push(
             Label::primary(
-                nfo.file_id,
-                nfo.offset + failed_req.span.start as usize
-                    ..nfo.offset + failed_req.span.end as usize,
+                file_id,
+                offset + failed_req.span.start as usize..offset + failed_req.span.end as usize,
             )
             .with_message(format!(
                 "{}: {}",
@@ -170,12 +460,26 @@ fn evaluate_expression(
                 match reason {
                     Reason::ExplicitAllowance => "license is explicitly allowed",
                     Reason::ExplicitException => "license is explicitly allowed via an exception",
+                    Reason::ExplicitExpressionException => {
+                        "license expression is explicitly allowed via an exception"
+                    }
                     Reason::NotExplicitlyAllowed => "license is not explicitly allowed",
+                    Reason::UnknownLicenseRef => match cfg.unknown_license_ref {
+                        LintLevel::Deny => "unknown license reference is denied",
+                        _ => "unknown license reference is allowed",
+                    },
+                    Reason::NoAssertion => match cfg.unknown_license_ref {
+                        LintLevel::Deny => "license is not asserted, NOASSERTION is denied",
+                        _ => "license is not asserted, NOASSERTION is allowed",
+                    },
+                    Reason::DeprecatedLicense => "license identifier is deprecated",
                 }
             )),
         );
     }
 
+    notes.extend(fix_note);
+
     Diagnostic::new(severity)
         .with_message(message)
         .with_code(if severity != Severity::Error {
@@ -187,6 +491,44 @@ fn evaluate_expression(
         .with_notes(notes)
 }
 
+/// Evaluates a license expression against the config independently of any
+/// crate, reporting whether each of its requirements is accepted or rejected
+///
+/// The expression is treated as if it belonged to a crate that doesn't match
+/// any of the `exceptions`, `file_id` being the file containing just the
+/// expression so that the requirements can be labeled
+pub fn evaluate(cfg: &cfg::ValidConfig, expr: &spdx::Expression, file_id: FileId) -> Diagnostic {
+    let mut hits = Hits {
+        allowed: BitVec::repeat(false, cfg.allowed.len()),
+        exceptions: BitVec::repeat(false, cfg.exceptions.len()),
+    };
+
+    evaluate_expression(cfg, true, None, expr, (file_id, 0), &mut hits)
+}
+
+/// Gets the non-deprecated SPDX identifier that should be used instead of a
+/// deprecated one, if there is one
+fn replacement_for_deprecated(req: &spdx::LicenseReq) -> Option<&'static str> {
+    let spdx::LicenseItem::Spdx { id, or_later } = &req.license else {
+        return None;
+    };
+
+    // The GNU licenses were deprecated in favor of explicit `-only` and
+    // `-or-later` identifiers
+    let suffix = if *or_later { "or-later" } else { "only" };
+    if let Some(replacement) = spdx::license_id(&format!("{}-{suffix}", id.name)) {
+        return Some(replacement.name);
+    }
+
+    Some(match id.name {
+        "BSD-2-Clause-FreeBSD" | "BSD-2-Clause-NetBSD" => "BSD-2-Clause",
+        "bzip2-1.0.5" => "bzip2-1.0.6",
+        "Nunit" => "zlib-acknowledgement",
+        "StandardML-NJ" => "SMLNJ",
+        _ => return None,
+    })
+}
+
 pub fn check(
     ctx: crate::CheckCtx<'_, cfg::ValidConfig>,
     summary: Summary<'_>,
@@ -211,14 +553,25 @@ pub fn check(
         // If the user has set this, check if it's a private workspace
         // crate or a crate from a private registry and just print out
         // a help message that we skipped it
-        if ctx.cfg.private.ignore
-            && (krate_lic_nfo.krate.is_private(&private_registries)
+        let skip_private = if ctx.cfg.private.ignore {
+            krate_lic_nfo.krate.is_private(&private_registries)
                 || ctx
                     .cfg
                     .ignore_sources
                     .iter()
-                    .any(|url| krate_lic_nfo.krate.matches_url(url, true)))
-        {
+                    .any(|url| krate_lic_nfo.krate.matches_url(url, true))
+        } else if ctx.cfg.workspace_treatment == cfg::WorkspaceTreatment::IgnorePrivate {
+            // Unlike `private.ignore`, this only applies to the workspace's
+            // own crates
+            krate_lic_nfo.krate.is_private(&private_registries)
+                && ctx.krates.workspace_members().any(|n| {
+                    matches!(n, krates::Node::Krate { id, .. } if id == &krate_lic_nfo.krate.id)
+                })
+        } else {
+            false
+        };
+
+        if skip_private {
             pack.push(diags::SkippedPrivateWorkspaceCrate {
                 krate: krate_lic_nfo.krate,
             });
@@ -229,19 +582,67 @@ pub fn check(
         match &krate_lic_nfo.lic_info {
             LicenseInfo::SpdxExpression { expr, nfo } => {
                 pack.push(evaluate_expression(
-                    &ctx,
-                    &krate_lic_nfo,
+                    &ctx.cfg,
+                    ctx.log_level >= log::LevelFilter::Info,
+                    Some((&krate_lic_nfo, &nfo.source)),
                     expr,
-                    nfo,
+                    (nfo.file_id, nfo.offset),
                     &mut hits,
                 ));
+
+                if ctx.cfg.check_internal_compatibility != LintLevel::Allow {
+                    for conflict in compat::find_conflicts(expr, &ctx.cfg.incompatible) {
+                        pack.push(diags::IncompatibleLicenses {
+                            severity: ctx.cfg.check_internal_compatibility.into(),
+                            krate: krate_lic_nfo.krate,
+                            conflict,
+                            expr_file_id: nfo.file_id,
+                            expr_offset: nfo.offset,
+                            cfg_file_id: ctx.cfg.file_id,
+                        });
+                    }
+                }
+
+                if let Some(locked) = ctx
+                    .cfg
+                    .lock
+                    .as_ref()
+                    .and_then(|lock| lock.get(&krate_lic_nfo.krate.name))
+                {
+                    if locked != expr.as_ref() {
+                        pack.push(diags::LicenseChanged {
+                            krate: krate_lic_nfo.krate,
+                            locked,
+                            expr,
+                            nfo,
+                        });
+                    }
+                }
             }
             LicenseInfo::Unlicensed => {
-                pack.push(diags::Unlicensed {
-                    krate: krate_lic_nfo.krate,
-                    severity: Severity::Error,
-                    breadcrumbs: krate_lic_nfo.labels.into_iter().collect(),
-                });
+                let krate = krate_lic_nfo.krate;
+
+                // Crates that only point to a license file can't be evaluated
+                // without a clarification, so tell the user that, rather than
+                // that the crate is unlicensed
+                if ctx.cfg.require_clarification_for_license_file != LintLevel::Allow
+                    && krate.license.is_none()
+                    && krate.license_file.is_some()
+                {
+                    pack.push(diags::ClarificationRequired {
+                        krate,
+                        severity: ctx.cfg.require_clarification_for_license_file.into(),
+                        breadcrumbs: krate_lic_nfo.labels.into_iter().collect(),
+                        notes: krate_lic_nfo.notes,
+                    });
+                } else {
+                    pack.push(diags::Unlicensed {
+                        krate: krate_lic_nfo.krate,
+                        severity: Severity::Error,
+                        breadcrumbs: krate_lic_nfo.labels.into_iter().collect(),
+                        notes: krate_lic_nfo.notes,
+                    });
+                }
             }
         }
 
@@ -250,6 +651,83 @@ pub fn check(
         }
     }
 
+    if let Some(wc) = ctx
+        .cfg
+        .workspace_consistency
+        .as_ref()
+        .filter(|wc| wc.level != LintLevel::Allow)
+    {
+        let mut pack = Pack::new(Check::Licenses);
+
+        // Workspace members that don't declare a license, eg. unpublished
+        // crates, are not considered
+        let members: Vec<_> = ctx
+            .krates
+            .workspace_members()
+            .filter_map(|wsm| {
+                let krates::Node::Krate { krate, .. } = wsm else {
+                    return None;
+                };
+                Some((krate, krate.license.as_deref()?.trim()))
+            })
+            .collect();
+
+        let expected = if let Some(canonical) = &wc.canonical {
+            Some(canonical.value.as_str())
+        } else {
+            let mut counts = std::collections::BTreeMap::<&str, usize>::new();
+            for (_, license) in &members {
+                *counts.entry(*license).or_default() += 1;
+            }
+
+            // Ties are broken by the lexicographically smallest expression so
+            // that the result is the same every run
+            counts
+                .into_iter()
+                .rev()
+                .max_by_key(|(_, count)| *count)
+                .map(|(license, _)| license)
+        };
+
+        for (krate, license) in members {
+            let Some(expected) = expected.filter(|expected| *expected != license) else {
+                continue;
+            };
+
+            // Point at the license in the member's manifest if we can find it
+            let license_label = ctx
+                .krate_spans
+                .manifest(&krate.id)
+                .and_then(|manifest| {
+                    let root = toml_span::parse(ctx.files.source(manifest.id)).ok()?;
+                    let span = root.pointer("/package/license")?.span;
+                    Some(Label::primary(manifest.id, span))
+                })
+                .unwrap_or_else(|| {
+                    Label::primary(
+                        ctx.krate_spans.lock_id,
+                        ctx.krate_spans.lock_span(&krate.id).total,
+                    )
+                });
+
+            pack.push(diags::InconsistentWorkspaceLicense {
+                severity: wc.level.into(),
+                krate,
+                license,
+                expected,
+                license_label,
+                canonical_cfg: wc.canonical.as_ref().map(|canonical| CfgCoord {
+                    file: ctx.cfg.file_id,
+                    span: canonical.span,
+                }),
+            });
+        }
+
+        if !pack.is_empty() {
+            sink.push(pack);
+        }
+    }
+
     {
         let mut pack = Pack::new(Check::Licenses);
 
@@ -299,6 +777,23 @@ pub fn check(
             });
         }
 
+        // Print diagnostics for clarifications that weren't applied, either
+        // because the crate wasn't encountered, or its license files changed
+        for clarification in summary
+            .clarification_hits
+            .into_iter()
+            .zip(ctx.cfg.clarifications.iter())
+            .filter_map(|(hit, clarification)| if !hit { Some(clarification) } else { None })
+        {
+            pack.push(diags::UnmatchedClarification {
+                severity: ctx.cfg.unused_clarification.into(),
+                clarification_cfg: CfgCoord {
+                    file: ctx.cfg.file_id,
+                    span: clarification.spec.name.span,
+                },
+            });
+        }
+
         if !pack.is_empty() {
             sink.push(pack);
         }
@@ -5,15 +5,29 @@ use crate::{
     diag::{Diagnostic, FileId, Label},
     LintLevel, PathBuf, Span, Spanned,
 };
-use toml_span::{de_helpers::TableHelper, value::Value, DeserError, Deserialize};
+use toml_span::{
+    de_helpers::TableHelper,
+    value::{Value, ValueInner},
+    DeserError, Deserialize,
+};
 
 const DEFAULT_CONFIDENCE_THRESHOLD: f32 = 0.8;
+const DEFAULT_LICENSE_FILE_PATTERNS: &[&str] = &["LICENSE*", "COPYING*", "LICENCE*"];
 
 /// Allows agreement of licensing terms based on whether the license is
 /// [OSI Approved](https://opensource.org/licenses) or [considered free](
 /// https://www.gnu.org/licenses/license-list.en.html) by the FSF
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, strum::VariantArray, strum::VariantNames)]
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    strum::VariantArray,
+    strum::VariantNames,
+    serde::Serialize,
+)]
 #[strum(serialize_all = "kebab-case")]
 pub enum BlanketAgreement {
     /// The license must be both OSI Approved and FSF/Free Libre
@@ -35,9 +49,33 @@ pub enum BlanketAgreement {
 
 crate::enum_deser!(BlanketAgreement);
 
+/// Determines how the workspace's own crates are treated by the license check
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    strum::VariantArray,
+    strum::VariantNames,
+    serde::Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum WorkspaceTreatment {
+    /// Workspace crates are checked the same as every other crate
+    #[default]
+    Check,
+    /// Workspace crates that are not published, or are only published to
+    /// private registries, are not checked
+    IgnorePrivate,
+}
+
+crate::enum_deser!(WorkspaceTreatment);
+
 /// Configures how private crates are handled and detected
-#[derive(Default)]
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(Default, serde::Serialize)]
 pub struct Private {
     /// If enabled, ignores workspace crates that aren't published, or are
     /// only published to private registries
@@ -70,8 +108,7 @@ impl<'de> Deserialize<'de> for Private {
 }
 
 /// The path and hash of a LICENSE file
-#[derive(PartialEq, Eq)]
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(PartialEq, Eq, serde::Serialize)]
 pub struct FileSource {
     /// The crate relative path of the LICENSE file
     /// Spanned so we can report typos on it in case it never matches anything.
@@ -146,18 +183,210 @@ pub struct Exception {
     /// One or more [SPDX identifiers](https://spdx.org/licenses/) that are
     /// allowed only for this crate.
     pub allow: Vec<Licensee>,
+    /// The exact license expression of the crate, if it matches the crate is
+    /// allowed regardless of the individual licenses in the expression
+    pub expression: Option<LicenseExpression>,
+    /// The source the crate must come from for the exception to apply
+    pub source: Option<Spanned<ExceptionSource>>,
 }
 
 impl<'de> Deserialize<'de> for Exception {
     fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
         let spec = PackageSpec::deserialize(value)?;
 
+        let span = value.span;
         let mut th = TableHelper::new(value)?;
-        let allow = th.required("allow")?;
+        let allow = th.optional("allow");
+        let expression = th.optional("expression");
+        let source = th.optional_s("source");
+
+        // At least one of the forms must be specified, we report `allow` as
+        // missing since it's the most common form
+        if allow.is_none() && expression.is_none() {
+            th.errors
+                .push((toml_span::ErrorKind::MissingField("allow"), span).into());
+        }
 
         th.finalize(None)?;
 
-        Ok(Self { spec, allow })
+        Ok(Self {
+            spec,
+            allow: allow.unwrap_or_default(),
+            expression,
+            source,
+        })
+    }
+}
+
+/// The source a crate must come from for an [`Exception`] to apply to it
+#[derive(Debug, PartialEq, Eq)]
+pub enum ExceptionSource {
+    /// crates.io, regardless of the protocol used to access it
+    CratesIo,
+    /// A registry or git repository, matched against the crate's source url
+    Url(url::Url),
+}
+
+impl ExceptionSource {
+    /// Returns true if the crate comes from this source
+    pub fn matches(&self, krate: &crate::Krate) -> bool {
+        match self {
+            Self::CratesIo => krate.is_crates_io(),
+            Self::Url(url) => krate.matches_url(url, true),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ExceptionSource {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let val = value.take_string(Some("`crates-io` or a source url"))?;
+
+        if val == "crates-io" {
+            return Ok(Self::CratesIo);
+        }
+
+        let (scheme, url) = val.split_once('+').unwrap_or(("", &val));
+        let parsed = if scheme == "git" {
+            crate::parse_git_url(url).map(|mut url| {
+                crate::normalize_git_url(&mut url);
+                url
+            })
+        } else {
+            url::Url::parse(url)
+        };
+
+        parsed.map(Self::Url).map_err(|pe| {
+            toml_span::Error::from((
+                toml_span::ErrorKind::Custom(pe.to_string().into()),
+                value.span,
+            ))
+            .into()
+        })
+    }
+}
+
+impl serde::Serialize for ExceptionSource {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::CratesIo => serializer.serialize_str("crates-io"),
+            Self::Url(url) => serializer.serialize_str(url.as_str()),
+        }
+    }
+}
+
+/// A full SPDX license expression
+#[derive(Debug)]
+pub struct LicenseExpression(pub Spanned<spdx::Expression>);
+
+impl LicenseExpression {
+    /// Returns true if the expression is structurally the same as the other
+    /// expression, ie. differences in whitespace or redundant parentheses are
+    /// ignored
+    pub fn matches(&self, other: &spdx::Expression) -> bool {
+        use spdx::expression::ExprNode;
+
+        let mut ours = self.0.value.iter();
+        let mut theirs = other.iter();
+
+        loop {
+            match (ours.next(), theirs.next()) {
+                (None, None) => return true,
+                (Some(ExprNode::Op(a)), Some(ExprNode::Op(b))) if a == b => {}
+                (Some(ExprNode::Req(a)), Some(ExprNode::Req(b))) if a.req == b.req => {}
+                _ => return false,
+            }
+        }
+    }
+}
+
+impl PartialEq for LicenseExpression {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(&other.0.value)
+    }
+}
+
+impl Eq for LicenseExpression {}
+
+impl<'de> Deserialize<'de> for LicenseExpression {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let val = value.take_string(Some("an SPDX license expression"))?;
+
+        match spdx::Expression::parse(&val) {
+            Ok(expr) => Ok(Self(Spanned::with_span(expr, value.span))),
+            Err(pe) => {
+                let offset = value.span.start;
+
+                Err(toml_span::Error {
+                    kind: toml_span::ErrorKind::Custom(pe.reason.to_string().into()),
+                    span: (pe.span.start + offset..pe.span.end + offset).into(),
+                    line_info: None,
+                }
+                .into())
+            }
+        }
+    }
+}
+
+impl serde::Serialize for LicenseExpression {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.value.as_ref().serialize(serializer)
+    }
+}
+
+/// A license that is incompatible with 1 or more other licenses when both are
+/// required by the same crate
+#[derive(serde::Serialize)]
+pub struct Incompatibility {
+    /// The SPDX identifier or `LicenseRef-` of the license
+    pub license: Spanned<String>,
+    /// The licenses that are incompatible with `license`
+    pub with: Vec<Spanned<String>>,
+}
+
+impl<'de> Deserialize<'de> for Incompatibility {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+        let license = th.required_s("license")?;
+        let with = th.required("with")?;
+
+        th.finalize(None)?;
+
+        Ok(Self { license, with })
+    }
+}
+
+/// Overrides the metadata of a license, eg. to treat a license as copyleft
+/// even though it isn't classified as such in the SPDX license list
+#[derive(serde::Serialize)]
+pub struct Classification {
+    /// The SPDX identifier or `LicenseRef-` of the license
+    pub license: Spanned<String>,
+    /// Whether the license is considered copyleft
+    pub copyleft: Option<bool>,
+    /// Whether the license is considered OSI approved
+    pub osi: Option<bool>,
+}
+
+impl<'de> Deserialize<'de> for Classification {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+        let license = th.required_s("license")?;
+        let copyleft = th.optional("copyleft");
+        let osi = th.optional("osi");
+
+        th.finalize(None)?;
+
+        Ok(Self {
+            license,
+            copyleft,
+            osi,
+        })
     }
 }
 
@@ -184,7 +413,6 @@ impl<'de> Deserialize<'de> for Licensee {
     }
 }
 
-#[cfg(test)]
 impl serde::Serialize for Licensee {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -194,9 +422,46 @@ impl serde::Serialize for Licensee {
     }
 }
 
+/// Configures the check that every workspace member declares the same license
+#[derive(serde::Serialize)]
+pub struct WorkspaceConsistency {
+    /// How to handle workspace members whose license differs
+    pub level: LintLevel,
+    /// The license expression every workspace member must declare, if not
+    /// specified, the expression declared by the most members is used
+    pub canonical: Option<Spanned<String>>,
+}
+
+impl<'de> Deserialize<'de> for WorkspaceConsistency {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        match value.take() {
+            ValueInner::String(s) => {
+                value.set(ValueInner::String(s));
+                Ok(Self {
+                    level: LintLevel::deserialize(value)?,
+                    canonical: None,
+                })
+            }
+            ValueInner::Table(tab) => {
+                let mut th = TableHelper::from((tab, value.span));
+                let level = th.optional("level").unwrap_or(LintLevel::Warn);
+                let canonical = th.optional("canonical");
+                th.finalize(None)?;
+
+                Ok(Self { level, canonical })
+            }
+            other => {
+                Err(toml_span::de_helpers::expected("a string or table", other, value.span).into())
+            }
+        }
+    }
+}
+
 /// Top level configuration for the a license check
 pub struct Config {
     pub private: Private,
+    /// Determines how the workspace's own crates are treated
+    pub workspace_treatment: WorkspaceTreatment,
     /// The minimum confidence threshold we allow when determining the license
     /// in a text file, on a 0.0 (none) to 1.0 (maximum) scale
     pub confidence_threshold: f32,
@@ -205,6 +470,27 @@ pub struct Config {
     /// Determines the response to licenses in th `allow`ed list which do not
     /// exist in the dependency tree.
     pub unused_allowed_license: LintLevel,
+    /// Determines the response to clarifications which were not applied to
+    /// any crate in the dependency tree
+    pub unused_clarification: LintLevel,
+    /// Determines the response to custom `LicenseRef-` license references that
+    /// are not explicitly allowed
+    pub unknown_license_ref: LintLevel,
+    /// Determines the response to license expressions that require licenses
+    /// that are incompatible with each other
+    pub check_internal_compatibility: LintLevel,
+    /// Determines the response to crates that only specify a `license-file`
+    /// and have no clarification to provide an SPDX expression for it
+    pub require_clarification_for_license_file: LintLevel,
+    /// Determines the response to license requirements that use deprecated
+    /// SPDX license identifiers, eg. `GPL-2.0` instead of `GPL-2.0-only`
+    pub deny_deprecated: LintLevel,
+    /// Additional licenses that are incompatible with each other, on top of
+    /// the built-in set
+    pub incompatible: Vec<Incompatibility>,
+    /// Overrides the built-in metadata of licenses, eg. whether they are
+    /// considered copyleft
+    pub classify: Vec<Classification>,
     /// Overrides the license expression used for a particular crate as long as
     /// it exactly matches the specified license files and hashes
     pub clarify: Vec<Clarification>,
@@ -214,6 +500,18 @@ pub struct Config {
     /// If true, performs license checks for dev-dependencies for workspace
     /// crates as well
     pub include_dev: bool,
+    /// If true, only license files that would be included in the crate's
+    /// package, per the `include` and `exclude` fields of its manifest, are
+    /// considered
+    pub respect_package_include: bool,
+    /// Glob patterns for the file names in the root of a crate that are
+    /// considered license files
+    pub license_file_patterns: Option<Vec<Spanned<String>>>,
+    /// If set, checks that every workspace member declares the same license
+    pub workspace_consistency: Option<WorkspaceConsistency>,
+    /// If true, the license files of crates.io crates whose source is not
+    /// present locally are downloaded from their package
+    pub fetch_missing_license_files: bool,
     deprecated_spans: Vec<Span>,
 }
 
@@ -221,12 +519,24 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             private: Private::default(),
+            workspace_treatment: WorkspaceTreatment::default(),
             unused_allowed_license: LintLevel::Warn,
+            unused_clarification: LintLevel::Warn,
+            unknown_license_ref: LintLevel::Deny,
+            check_internal_compatibility: LintLevel::Allow,
+            require_clarification_for_license_file: LintLevel::Allow,
+            deny_deprecated: LintLevel::Allow,
+            incompatible: Vec::new(),
+            classify: Vec::new(),
             confidence_threshold: DEFAULT_CONFIDENCE_THRESHOLD,
             allow: Vec::new(),
             clarify: Vec::new(),
             exceptions: Vec::new(),
             include_dev: false,
+            respect_package_include: false,
+            license_file_patterns: None,
+            workspace_consistency: None,
+            fetch_missing_license_files: false,
             deprecated_spans: Vec::new(),
         }
     }
@@ -241,6 +551,7 @@ impl<'de> Deserialize<'de> for Config {
         let mut fdeps = Vec::new();
 
         let private = th.optional("private").unwrap_or_default();
+        let workspace_treatment = th.optional("workspace-treatment").unwrap_or_default();
         let _unlicensed = deprecated(&mut th, "unlicensed", &mut fdeps).unwrap_or(LintLevel::Deny);
         let _allow_osi_fsf_free = deprecated(&mut th, "allow-osi-fsf-free", &mut fdeps)
             .unwrap_or(BlanketAgreement::default());
@@ -254,20 +565,53 @@ impl<'de> Deserialize<'de> for Config {
         let unused_allowed_license = th
             .optional("unused-allowed-license")
             .unwrap_or(LintLevel::Warn);
+        let unused_clarification = th
+            .optional("unused-clarification")
+            .unwrap_or(LintLevel::Warn);
+        let unknown_license_ref = th
+            .optional("unknown-license-ref")
+            .unwrap_or(LintLevel::Deny);
+        let check_internal_compatibility = th
+            .optional("check-internal-compatibility")
+            .unwrap_or(LintLevel::Allow);
+        let require_clarification_for_license_file = th
+            .optional("require-clarification-for-license-file")
+            .unwrap_or(LintLevel::Allow);
+        let deny_deprecated = th.optional("deny-deprecated").unwrap_or(LintLevel::Allow);
+        let incompatible = th.optional("incompatible").unwrap_or_default();
+        let classify = th.optional("classify").unwrap_or_default();
         let clarify = th.optional("clarify").unwrap_or_default();
         let exceptions = th.optional("exceptions").unwrap_or_default();
         let include_dev = th.optional("include-dev").unwrap_or_default();
+        let respect_package_include = th.optional("respect-package-include").unwrap_or_default();
+        let license_file_patterns = th.optional("license-file-patterns");
+        let workspace_consistency = th.optional("workspace-consistency");
+        let fetch_missing_license_files = th
+            .optional("fetch-missing-license-files")
+            .unwrap_or_default();
 
         th.finalize(None)?;
 
         Ok(Self {
             private,
+            workspace_treatment,
             confidence_threshold,
             allow,
             unused_allowed_license,
+            unused_clarification,
+            unknown_license_ref,
+            check_internal_compatibility,
+            require_clarification_for_license_file,
+            deny_deprecated,
+            incompatible,
+            classify,
             clarify,
             exceptions,
             include_dev,
+            respect_package_include,
+            license_file_patterns,
+            workspace_consistency,
+            fetch_missing_license_files,
             deprecated_spans: fdeps,
         })
     }
@@ -310,6 +654,8 @@ impl crate::cfg::UnvalidatedConfig for Config {
         exceptions.extend(self.exceptions.into_iter().map(|exc| ValidException {
             spec: exc.spec,
             allowed: exc.allow,
+            expression: exc.expression,
+            source: exc.source,
             file_id: ctx.cfg_id,
         }));
 
@@ -343,6 +689,66 @@ impl crate::cfg::UnvalidatedConfig for Config {
             });
         }
 
+        // Ensure that every incompatible license is either a known SPDX
+        // identifier or a custom license reference
+        let mut incompatible = self.incompatible;
+        for inc in &mut incompatible {
+            let mut is_valid = |lic: &Spanned<String>| {
+                if lic.value.starts_with("LicenseRef-") || spdx::license_id(&lic.value).is_some() {
+                    return true;
+                }
+
+                ctx.push(
+                    Diagnostic::error()
+                        .with_message("unknown SPDX license identifier")
+                        .with_labels(vec![Label::primary(ctx.cfg_id, lic.span)]),
+                );
+                false
+            };
+
+            if !is_valid(&inc.license) {
+                inc.with.clear();
+                continue;
+            }
+
+            inc.with.retain(|lic| is_valid(lic));
+        }
+        incompatible.retain(|inc| !inc.with.is_empty());
+
+        let mut classifications = self.classify;
+        classifications.retain(|class| {
+            if class.license.value.starts_with("LicenseRef-")
+                || spdx::license_id(&class.license.value).is_some()
+            {
+                return true;
+            }
+
+            ctx.push(
+                Diagnostic::error()
+                    .with_message("unknown SPDX license identifier")
+                    .with_labels(vec![Label::primary(ctx.cfg_id, class.license.span)]),
+            );
+            false
+        });
+
+        if let Some(canonical) = self
+            .workspace_consistency
+            .as_ref()
+            .and_then(|wc| wc.canonical.as_ref())
+        {
+            if let Err(err) = spdx::Expression::parse(&canonical.value) {
+                let offset = canonical.span.start;
+                let expr_span = offset + err.span.start..offset + err.span.end;
+
+                ctx.push(
+                    Diagnostic::error()
+                        .with_message("unable to parse license expression")
+                        .with_labels(vec![Label::primary(ctx.cfg_id, expr_span)
+                            .with_message(err.reason.to_string())]),
+                );
+            }
+        }
+
         use crate::diag::general::{Deprecated, DeprecationReason};
 
         // Output any deprecations, we'll remove the fields at the same time we
@@ -360,16 +766,65 @@ impl crate::cfg::UnvalidatedConfig for Config {
             );
         }
 
+        let license_file_patterns = if let Some(patterns) = self.license_file_patterns {
+            let mut builder = globset::GlobSetBuilder::new();
+            let mut valid = Vec::with_capacity(patterns.len());
+
+            for pattern in patterns {
+                match globset::Glob::new(&pattern.value) {
+                    Ok(glob) => {
+                        builder.add(glob);
+                        valid.push(pattern);
+                    }
+                    Err(err) => {
+                        ctx.push(
+                            Diagnostic::error()
+                                .with_message(format!("invalid glob pattern: {err}"))
+                                .with_labels(vec![Label::primary(ctx.cfg_id, pattern.span)]),
+                        );
+                    }
+                }
+            }
+
+            match builder.build() {
+                Ok(set) => LicenseFilePatterns {
+                    set,
+                    patterns: valid,
+                },
+                Err(err) => {
+                    ctx.push(Diagnostic::error().with_message(format!(
+                        "failed to build license file patterns glob set: {err}"
+                    )));
+                    LicenseFilePatterns::default()
+                }
+            }
+        } else {
+            LicenseFilePatterns::default()
+        };
+
         ValidConfig {
             file_id: ctx.cfg_id,
             private: self.private,
+            workspace_treatment: self.workspace_treatment,
             unused_allowed_license: self.unused_allowed_license,
+            unused_clarification: self.unused_clarification,
+            unknown_license_ref: self.unknown_license_ref,
+            check_internal_compatibility: self.check_internal_compatibility,
+            require_clarification_for_license_file: self.require_clarification_for_license_file,
+            deny_deprecated: self.deny_deprecated,
+            incompatible,
+            classifications,
             confidence_threshold: self.confidence_threshold,
             clarifications,
             exceptions,
             allowed,
             ignore_sources,
             include_dev: self.include_dev,
+            respect_package_include: self.respect_package_include,
+            license_file_patterns,
+            workspace_consistency: self.workspace_consistency,
+            fetch_missing_license_files: self.fetch_missing_license_files,
+            lock: None,
         }
     }
 }
@@ -409,6 +864,8 @@ pub fn load_exceptions(
                 cfg.exceptions.push(ValidException {
                     spec: exc.spec,
                     allowed: exc.allow,
+                    expression: exc.expression,
+                    source: exc.source,
                     file_id,
                 });
             }
@@ -419,6 +876,48 @@ pub fn load_exceptions(
     }
 }
 
+/// The glob patterns used to determine which files in the root of a crate are
+/// license files
+pub struct LicenseFilePatterns {
+    set: globset::GlobSet,
+    /// The patterns in the set, in the order they were specified
+    pub patterns: Vec<Spanned<String>>,
+}
+
+impl LicenseFilePatterns {
+    /// Returns true if the file name matches any of the patterns
+    #[inline]
+    pub fn is_match(&self, file_name: &str) -> bool {
+        self.set.is_match(file_name)
+    }
+}
+
+impl Default for LicenseFilePatterns {
+    fn default() -> Self {
+        let mut builder = globset::GlobSetBuilder::new();
+        for pattern in DEFAULT_LICENSE_FILE_PATTERNS {
+            builder.add(globset::Glob::new(pattern).expect("failed to parse builtin glob"));
+        }
+
+        Self {
+            set: builder.build().expect("failed to build builtin glob set"),
+            patterns: DEFAULT_LICENSE_FILE_PATTERNS
+                .iter()
+                .map(|pattern| Spanned::new((*pattern).to_owned()))
+                .collect(),
+        }
+    }
+}
+
+impl serde::Serialize for LicenseFilePatterns {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.patterns.iter().map(|pattern| &pattern.value))
+    }
+}
+
 #[doc(hidden)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct ValidClarification {
@@ -428,7 +927,6 @@ pub struct ValidClarification {
     pub license_files: Vec<FileSource>,
 }
 
-#[cfg(test)]
 impl serde::Serialize for ValidClarification {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -444,26 +942,42 @@ impl serde::Serialize for ValidClarification {
 }
 
 #[doc(hidden)]
-#[derive(Debug, PartialEq, Eq)]
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
 pub struct ValidException {
     pub spec: PackageSpec,
     pub allowed: Vec<Licensee>,
+    pub expression: Option<LicenseExpression>,
+    pub source: Option<Spanned<ExceptionSource>>,
     pub file_id: FileId,
 }
 
 #[doc(hidden)]
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(serde::Serialize)]
 pub struct ValidConfig {
     pub file_id: FileId,
     pub private: Private,
+    pub workspace_treatment: WorkspaceTreatment,
     pub unused_allowed_license: LintLevel,
+    pub unused_clarification: LintLevel,
+    pub unknown_license_ref: LintLevel,
+    pub check_internal_compatibility: LintLevel,
+    pub require_clarification_for_license_file: LintLevel,
+    pub deny_deprecated: LintLevel,
+    pub incompatible: Vec<Incompatibility>,
+    pub classifications: Vec<Classification>,
     pub confidence_threshold: f32,
     pub allowed: Vec<Licensee>,
     pub clarifications: Vec<ValidClarification>,
     pub exceptions: Vec<ValidException>,
     pub ignore_sources: Vec<url::Url>,
     pub include_dev: bool,
+    pub respect_package_include: bool,
+    pub license_file_patterns: LicenseFilePatterns,
+    pub workspace_consistency: Option<WorkspaceConsistency>,
+    pub fetch_missing_license_files: bool,
+    /// The previously recorded license expressions, set via `--license-lock`
+    #[serde(skip)]
+    pub lock: Option<super::LicenseLock>,
 }
 
 #[cfg(test)]
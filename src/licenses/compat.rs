@@ -0,0 +1,174 @@
+//! Detection of license requirements within a single expression that are
+//! known to be incompatible with each other
+
+use std::borrow::Cow;
+
+/// The built-in set of licenses that are known to be incompatible with each
+/// other for distribution, if both are required by the same crate
+///
+/// This is intentionally conservative, and can be extended via the
+/// `licenses.incompatible` config
+const BUILTIN: &[(&str, &[&str])] = &[
+    (
+        "GPL-2.0-only",
+        &[
+            "Apache-2.0",
+            "BSD-4-Clause",
+            "CDDL-1.0",
+            "EPL-1.0",
+            "GPL-3.0-only",
+            "GPL-3.0-or-later",
+            "MPL-1.1",
+        ],
+    ),
+    (
+        "GPL-2.0-or-later",
+        &["BSD-4-Clause", "CDDL-1.0", "EPL-1.0", "MPL-1.1"],
+    ),
+    (
+        "GPL-3.0-only",
+        &["BSD-4-Clause", "CDDL-1.0", "EPL-1.0", "MPL-1.1"],
+    ),
+    (
+        "GPL-3.0-or-later",
+        &["BSD-4-Clause", "CDDL-1.0", "EPL-1.0", "MPL-1.1"],
+    ),
+    (
+        "AGPL-3.0-only",
+        &[
+            "BSD-4-Clause",
+            "CDDL-1.0",
+            "EPL-1.0",
+            "GPL-2.0-only",
+            "MPL-1.1",
+        ],
+    ),
+    (
+        "AGPL-3.0-or-later",
+        &[
+            "BSD-4-Clause",
+            "CDDL-1.0",
+            "EPL-1.0",
+            "GPL-2.0-only",
+            "MPL-1.1",
+        ],
+    ),
+];
+
+/// Gets the identifier used to match a license requirement against the
+/// incompatibility matrix
+#[inline]
+pub(crate) fn req_name(req: &spdx::LicenseReq) -> Cow<'static, str> {
+    match &req.license {
+        spdx::LicenseItem::Spdx { id, .. } => Cow::Borrowed(id.name),
+        spdx::LicenseItem::Other { lic_ref, .. } => Cow::Owned(format!("LicenseRef-{lic_ref}")),
+    }
+}
+
+/// A pair of licenses that are both required by an expression, but are
+/// incompatible with each other
+pub(crate) struct Conflict<'e> {
+    pub(crate) first: &'e spdx::expression::ExpressionReq,
+    pub(crate) second: &'e spdx::expression::ExpressionReq,
+    /// If the conflict came from the user's config, the span of the entry
+    pub(crate) cfg: Option<crate::cfg::Span>,
+}
+
+/// Finds every pair of incompatible licenses that _must_ both be satisfied for
+/// the expression to be satisfied
+pub(crate) fn find_conflicts<'e>(
+    expr: &'e spdx::Expression,
+    user: &[super::cfg::Incompatibility],
+) -> Vec<Conflict<'e>> {
+    // A license is required if the expression can't be satisfied without it,
+    // eg. both licenses in `A AND B` are required, but neither in `A OR B`
+    let mut required: Vec<&spdx::expression::ExpressionReq> = Vec::new();
+    for er in expr.requirements() {
+        let name = req_name(&er.req);
+        if required.iter().any(|r| req_name(&r.req) == name) {
+            continue;
+        }
+
+        if !expr.evaluate(|req| req_name(req) != name) {
+            required.push(er);
+        }
+    }
+
+    let mut conflicts = Vec::new();
+
+    if required.len() < 2 {
+        return conflicts;
+    }
+
+    let find = |name: &str| required.iter().find(|r| req_name(&r.req) == name).copied();
+
+    let builtin = BUILTIN
+        .iter()
+        .flat_map(|(lic, with)| with.iter().map(move |w| (*lic, *w, None)));
+    let user = user.iter().flat_map(|inc| {
+        inc.with.iter().map(move |w| {
+            (
+                inc.license.value.as_str(),
+                w.value.as_str(),
+                Some(inc.license.span),
+            )
+        })
+    });
+
+    for (lic, with, cfg) in builtin.chain(user) {
+        let Some((first, second)) = find(lic).zip(find(with)) else {
+            continue;
+        };
+
+        // Avoid reporting the same pair multiple times if it is specified
+        // multiple times, or in both directions
+        if conflicts.iter().any(|c: &Conflict<'_>| {
+            (std::ptr::eq(c.first, first) && std::ptr::eq(c.second, second))
+                || (std::ptr::eq(c.first, second) && std::ptr::eq(c.second, first))
+        }) {
+            continue;
+        }
+
+        conflicts.push(Conflict { first, second, cfg });
+    }
+
+    conflicts
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn conflicts(expr: &str) -> Vec<(String, String)> {
+        let expr = spdx::Expression::parse(expr).unwrap();
+        find_conflicts(&expr, &[])
+            .into_iter()
+            .map(|c| {
+                (
+                    req_name(&c.first.req).into(),
+                    req_name(&c.second.req).into(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_required_conflicts() {
+        assert_eq!(
+            conflicts("GPL-2.0-only AND Apache-2.0"),
+            [("GPL-2.0-only".to_owned(), "Apache-2.0".to_owned())]
+        );
+        assert_eq!(
+            conflicts("MIT AND (GPL-2.0-only AND BSD-4-Clause)"),
+            [("GPL-2.0-only".to_owned(), "BSD-4-Clause".to_owned())]
+        );
+    }
+
+    #[test]
+    fn ignores_optional_conflicts() {
+        assert!(conflicts("GPL-2.0-only OR Apache-2.0").is_empty());
+        assert!(conflicts("(GPL-2.0-only OR MIT) AND Apache-2.0").is_empty());
+        assert!(conflicts("(GPL-2.0-only AND Apache-2.0) OR MIT").is_empty());
+        assert!(conflicts("MIT AND Apache-2.0").is_empty());
+    }
+}
@@ -23,6 +23,11 @@ pub enum Code {
     LicenseNotEncountered,
     LicenseExceptionNotEncountered,
     MissingClarificationFile,
+    IncompatibleLicenses,
+    ClarificationNotEncountered,
+    LicenseChanged,
+    ClarificationRequired,
+    InconsistentWorkspaceLicense,
 }
 
 impl From<Code> for String {
@@ -35,6 +40,7 @@ pub(crate) struct Unlicensed<'a> {
     pub(crate) severity: Severity,
     pub(crate) krate: &'a Krate,
     pub(crate) breadcrumbs: Vec<Label>,
+    pub(crate) notes: Vec<String>,
 }
 
 impl<'a> From<Unlicensed<'a>> for Diag {
@@ -43,6 +49,77 @@ impl<'a> From<Unlicensed<'a>> for Diag {
             .with_message(format!("{} is unlicensed", u.krate))
             .with_code(Code::Unlicensed)
             .with_labels(u.breadcrumbs)
+            .with_notes(u.notes)
+            .into()
+    }
+}
+
+pub(crate) struct ClarificationRequired<'a> {
+    pub(crate) severity: Severity,
+    pub(crate) krate: &'a Krate,
+    pub(crate) breadcrumbs: Vec<Label>,
+    pub(crate) notes: Vec<String>,
+}
+
+impl<'a> From<ClarificationRequired<'a>> for Diag {
+    fn from(cr: ClarificationRequired<'a>) -> Self {
+        let mut notes = vec![format!(
+            "{} only specifies a `license-file`, which requires a `[[licenses.clarify]]` entry to determine its license",
+            cr.krate.name
+        )];
+        notes.extend(cr.notes);
+
+        Diagnostic::new(cr.severity)
+            .with_message(format!(
+                "{} has a license file but no clarification",
+                cr.krate
+            ))
+            .with_code(Code::ClarificationRequired)
+            .with_labels(cr.breadcrumbs)
+            .with_notes(notes)
+            .into()
+    }
+}
+
+pub(crate) struct InconsistentWorkspaceLicense<'a> {
+    pub(crate) severity: Severity,
+    pub(crate) krate: &'a Krate,
+    pub(crate) license: &'a str,
+    pub(crate) expected: &'a str,
+    pub(crate) license_label: Label,
+    /// The location of the canonical license, if it was configured rather than
+    /// inferred from the other workspace members
+    pub(crate) canonical_cfg: Option<CfgCoord>,
+}
+
+impl<'a> From<InconsistentWorkspaceLicense<'a>> for Diag {
+    fn from(iwl: InconsistentWorkspaceLicense<'a>) -> Self {
+        let mut labels = vec![iwl
+            .license_label
+            .with_message(format!("expected '{}'", iwl.expected))];
+
+        let notes = if let Some(canonical_cfg) = iwl.canonical_cfg {
+            labels.push(
+                canonical_cfg
+                    .into_label()
+                    .with_message("canonical license expression"),
+            );
+            Vec::new()
+        } else {
+            vec![format!(
+                "'{}' is the license declared by the most workspace members",
+                iwl.expected
+            )]
+        };
+
+        Diagnostic::new(iwl.severity)
+            .with_message(format!(
+                "workspace member {} declares license '{}', which differs from the rest of the workspace",
+                iwl.krate, iwl.license
+            ))
+            .with_code(Code::InconsistentWorkspaceLicense)
+            .with_labels(labels)
+            .with_notes(notes)
             .into()
     }
 }
@@ -95,6 +172,24 @@ impl From<UnmatchedLicenseException> for Diag {
     }
 }
 
+pub(crate) struct UnmatchedClarification {
+    pub(crate) severity: Severity,
+    pub(crate) clarification_cfg: CfgCoord,
+}
+
+impl From<UnmatchedClarification> for Diag {
+    fn from(uc: UnmatchedClarification) -> Self {
+        Diagnostic::new(uc.severity)
+            .with_message("clarification was not applied to any crate")
+            .with_code(Code::ClarificationNotEncountered)
+            .with_labels(vec![uc
+                .clarification_cfg
+                .into_label()
+                .with_message("unmatched clarification")])
+            .into()
+    }
+}
+
 pub(crate) struct MissingClarificationFile<'a> {
     pub(crate) expected: &'a crate::cfg::Spanned<crate::PathBuf>,
     pub(crate) cfg_file_id: crate::diag::FileId,
@@ -106,3 +201,78 @@ impl<'a> From<MissingClarificationFile<'a>> for Label {
             .with_message("unable to locate specified license file")
     }
 }
+
+pub(crate) struct IncompatibleLicenses<'a> {
+    pub(crate) severity: Severity,
+    pub(crate) krate: &'a Krate,
+    pub(crate) conflict: super::compat::Conflict<'a>,
+    pub(crate) expr_file_id: crate::diag::FileId,
+    pub(crate) expr_offset: usize,
+    pub(crate) cfg_file_id: crate::diag::FileId,
+}
+
+impl<'a> From<IncompatibleLicenses<'a>> for Diag {
+    fn from(il: IncompatibleLicenses<'a>) -> Self {
+        let first = &il.conflict.first.req;
+        let second = &il.conflict.second.req;
+        let span = |er: &spdx::expression::ExpressionReq| {
+            il.expr_offset + er.span.start as usize..il.expr_offset + er.span.end as usize
+        };
+
+        let mut labels = vec![
+            Label::primary(il.expr_file_id, span(il.conflict.first))
+                .with_message("required license"),
+            Label::primary(il.expr_file_id, span(il.conflict.second))
+                .with_message("incompatible required license"),
+        ];
+
+        if let Some(cfg) = il.conflict.cfg {
+            labels.push(
+                Label::secondary(il.cfg_file_id, cfg)
+                    .with_message("incompatibility configured here"),
+            );
+        }
+
+        Diagnostic::new(il.severity)
+            .with_message(format!(
+                "license requirements for '{}' are incompatible with each other",
+                il.krate
+            ))
+            .with_code(Code::IncompatibleLicenses)
+            .with_labels(labels)
+            .with_notes(vec![format!(
+                "'{first}' and '{second}' are both required, but are incompatible for distribution"
+            )])
+            .into()
+    }
+}
+
+pub(crate) struct LicenseChanged<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) locked: &'a str,
+    pub(crate) expr: &'a spdx::Expression,
+    pub(crate) nfo: &'a super::LicenseExprInfo,
+}
+
+impl<'a> From<LicenseChanged<'a>> for Diag {
+    fn from(lc: LicenseChanged<'a>) -> Self {
+        let expr = lc.expr.as_ref();
+
+        Diagnostic::new(Severity::Warning)
+            .with_message(format!(
+                "license of '{}' has changed from '{}' to '{expr}'",
+                lc.krate, lc.locked
+            ))
+            .with_code(Code::LicenseChanged)
+            .with_labels(vec![Label::primary(
+                lc.nfo.file_id,
+                lc.nfo.offset..lc.nfo.offset + expr.len(),
+            )
+            .with_message("current license expression")])
+            .with_notes(vec![format!(
+                "the license lock recorded '{}' for '{}'",
+                lc.locked, lc.krate.name
+            )])
+            .into()
+    }
+}
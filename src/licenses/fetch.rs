@@ -0,0 +1,284 @@
+//! Retrieval of the license files of crates.io crates whose source is not
+//! present locally, from the package that is published to crates.io
+
+use super::cfg::LicenseFilePatterns;
+use crate::{Kid, Krate, Path, PathBuf};
+use anyhow::Context as _;
+use std::{collections::BTreeMap, io::Read};
+
+/// The size of each block in a tar archive
+const BLOCK_SIZE: u64 = 512;
+
+/// The roots containing the license files retrieved for crates whose source is
+/// not present locally
+#[derive(Default)]
+pub struct FetchedLicenseFiles {
+    roots: BTreeMap<Kid, PathBuf>,
+}
+
+impl FetchedLicenseFiles {
+    /// Retrieves the license files for every crates.io crate whose source is
+    /// not present locally
+    ///
+    /// The license files of each crate are cached in
+    /// `$CARGO_HOME/license-files/<name>-<version>`, and only crates that are
+    /// not already cached are downloaded. If `offline` is true, only the cache
+    /// is used.
+    pub fn fetch(krates: &crate::Krates, patterns: &LicenseFilePatterns, offline: bool) -> Self {
+        let missing: Vec<_> = krates
+            .krates()
+            .filter(|krate| {
+                krate.is_crates_io() && !krate.manifest_path.parent().is_some_and(|p| p.exists())
+            })
+            .collect();
+
+        if missing.is_empty() {
+            return Self::default();
+        }
+
+        let cache_dir = match home::cargo_home()
+            .context("failed to resolve CARGO_HOME")
+            .and_then(|ch| crate::utf8path(ch.join("license-files")))
+        {
+            Ok(cd) => cd,
+            Err(err) => {
+                log::error!("unable to determine license file cache directory: {err:#}");
+                return Self::default();
+            }
+        };
+
+        let client = if offline {
+            None
+        } else {
+            match crate::http_client() {
+                Ok(client) => Some(client),
+                Err(err) => {
+                    log::error!("unable to fetch license files: {err:#}");
+                    None
+                }
+            }
+        };
+
+        let mut roots = BTreeMap::new();
+
+        for krate in missing {
+            let root = cache_dir.join(format!("{}-{}", krate.name, krate.version));
+
+            if !root.exists() {
+                let Some(client) = &client else {
+                    continue;
+                };
+
+                if let Err(err) = fetch_license_files(client, krate, patterns, &root) {
+                    log::warn!("failed to fetch license files for crate '{krate}': {err:#}");
+                    continue;
+                }
+            }
+
+            roots.insert(krate.id.clone(), root);
+        }
+
+        Self { roots }
+    }
+
+    /// Gets the directory containing the fetched license files for the crate
+    #[inline]
+    pub fn root(&self, krate: &Krate) -> Option<&Path> {
+        self.roots.get(&krate.id).map(PathBuf::as_path)
+    }
+}
+
+/// Downloads the crate's package and extracts the license files from it
+fn fetch_license_files(
+    client: &reqwest::blocking::Client,
+    krate: &Krate,
+    patterns: &LicenseFilePatterns,
+    root: &Path,
+) -> anyhow::Result<()> {
+    let res = client
+        .get(format!(
+            "https://static.crates.io/crates/{name}/{name}-{version}.crate",
+            name = krate.name,
+            version = krate.version
+        ))
+        .send()?
+        .error_for_status()?;
+
+    // Extract to a temporary directory first so that a failed fetch is not
+    // mistaken for a crate without license files the next time
+    let tmp = PathBuf::from(format!("{root}.tmp"));
+    if tmp.exists() {
+        std::fs::remove_dir_all(&tmp).with_context(|| format!("failed to remove '{tmp}'"))?;
+    }
+    std::fs::create_dir_all(&tmp).with_context(|| format!("failed to create '{tmp}'"))?;
+
+    let prefix = format!("{}-{}/", krate.name, krate.version);
+    extract(flate2::read::GzDecoder::new(res), &tmp, |path| {
+        let Some(rel) = path.strip_prefix(&prefix) else {
+            return None;
+        };
+
+        let is_license = if rel.contains('/') {
+            krate
+                .license_file
+                .as_ref()
+                .is_some_and(|lf| lf.as_str() == rel)
+        } else {
+            patterns.is_match(rel)
+        };
+
+        is_license.then_some(rel)
+    })?;
+
+    std::fs::rename(&tmp, root).with_context(|| format!("failed to move '{tmp}' to '{root}'"))?;
+    Ok(())
+}
+
+/// Extracts the regular files from the tar archive that the filter returns a
+/// relative path for into the output directory
+fn extract(
+    mut archive: impl Read,
+    out: &Path,
+    filter: impl Fn(&str) -> Option<&str>,
+) -> anyhow::Result<()> {
+    let mut header = [0u8; BLOCK_SIZE as usize];
+    let mut long_name = None;
+
+    loop {
+        archive.read_exact(&mut header)?;
+
+        // The archive is terminated by zeroed blocks
+        if header.iter().all(|b| *b == 0) {
+            break;
+        }
+
+        let field = |range: std::ops::Range<usize>| -> anyhow::Result<&str> {
+            let field = &header[range];
+            let end = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+            std::str::from_utf8(&field[..end]).context("tar header field is not utf-8")
+        };
+
+        let size = u64::from_str_radix(field(124..136)?.trim(), 8)
+            .context("failed to parse tar entry size")?;
+        let kind = header[156];
+
+        let path = if let Some(long_name) = long_name.take() {
+            long_name
+        } else {
+            // The ustar prefix, which is only set for long paths
+            let prefix = field(345..500)?;
+            if prefix.is_empty() {
+                field(0..100)?.to_owned()
+            } else {
+                format!("{prefix}/{}", field(0..100)?)
+            }
+        };
+
+        let mut entry = (&mut archive).take(size);
+
+        match kind {
+            // GNU extension, the data is the path of the next entry
+            b'L' => {
+                let mut name = String::new();
+                entry.read_to_string(&mut name)?;
+                long_name = Some(name.trim_end_matches('\0').to_owned());
+            }
+            b'0' | 0 => {
+                if let Some(rel) = filter(&path) {
+                    // The path comes from the archive, so it must not be able
+                    // to escape the output directory
+                    anyhow::ensure!(
+                        Path::new(rel)
+                            .components()
+                            .all(|c| matches!(c, camino::Utf8Component::Normal(_))),
+                        "tar entry '{path}' has an invalid relative path '{rel}'"
+                    );
+
+                    let out_path = out.join(rel);
+                    if let Some(parent) = out_path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+
+                    let mut file = std::fs::File::create(&out_path)
+                        .with_context(|| format!("failed to create '{out_path}'"))?;
+                    std::io::copy(&mut entry, &mut file)?;
+                }
+            }
+            _ => {}
+        }
+
+        // Skip the remainder of the entry, including the padding to the next block
+        std::io::copy(&mut entry, &mut std::io::sink())?;
+        let padding = size.next_multiple_of(BLOCK_SIZE) - size;
+        std::io::copy(&mut (&mut archive).take(padding), &mut std::io::sink())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(archive: &mut Vec<u8>, path: &str, contents: &[u8]) {
+        let mut header = [0u8; BLOCK_SIZE as usize];
+        header[..path.len()].copy_from_slice(path.as_bytes());
+        let size = format!("{:011o}", contents.len());
+        header[124..124 + size.len()].copy_from_slice(size.as_bytes());
+        header[156] = b'0';
+
+        archive.extend_from_slice(&header);
+        archive.extend_from_slice(contents);
+        archive.resize(
+            (archive.len() as u64).next_multiple_of(BLOCK_SIZE) as usize,
+            0,
+        );
+    }
+
+    #[test]
+    fn extracts_license_files() {
+        let mut archive = Vec::new();
+        entry(&mut archive, "krate-0.1.0/Cargo.toml", b"[package]");
+        entry(&mut archive, "krate-0.1.0/LICENSE-MIT", b"mit");
+        entry(&mut archive, "krate-0.1.0/src/lib.rs", b"");
+        entry(&mut archive, "krate-0.1.0/legal/LICENSE", b"custom");
+        archive.resize(archive.len() + BLOCK_SIZE as usize * 2, 0);
+
+        let td = tempfile::tempdir().unwrap();
+        let out = Path::from_path(td.path()).unwrap();
+
+        extract(archive.as_slice(), out, |path| {
+            let rel = path.strip_prefix("krate-0.1.0/")?;
+            (rel.starts_with("LICENSE") || rel == "legal/LICENSE").then_some(rel)
+        })
+        .unwrap();
+
+        assert_eq!(std::fs::read(out.join("LICENSE-MIT")).unwrap(), b"mit");
+        assert_eq!(std::fs::read(out.join("legal/LICENSE")).unwrap(), b"custom");
+        assert!(!out.join("Cargo.toml").exists());
+        assert!(!out.join("src").exists());
+    }
+
+    /// Ensures entries can't be written outside of the output directory, even
+    /// if the filter accepts them
+    #[test]
+    fn rejects_escaping_paths() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Path::from_path(td.path()).unwrap();
+        let out = root.join("out");
+
+        for path in ["krate-0.1.0/../escaped", "krate-0.1.0//escaped"] {
+            let mut archive = Vec::new();
+            entry(&mut archive, path, b"escaped");
+            archive.resize(archive.len() + BLOCK_SIZE as usize * 2, 0);
+
+            let err = extract(archive.as_slice(), &out, |path| {
+                path.strip_prefix("krate-0.1.0/")
+            })
+            .unwrap_err();
+            assert!(err.to_string().contains("invalid relative path"));
+        }
+
+        assert!(!root.join("escaped").exists());
+    }
+}
@@ -1,8 +1,9 @@
-use super::cfg::{FileSource, ValidClarification, ValidConfig};
+use super::cfg::{FileSource, LicenseFilePatterns, ValidClarification, ValidConfig};
 use crate::{
     diag::{FileId, Files, Label},
     Krate, Path, PathBuf,
 };
+use bitvec::prelude::*;
 use rayon::prelude::*;
 use smallvec::SmallVec;
 use std::{fmt, sync::Arc};
@@ -13,9 +14,10 @@ const LICENSE_CACHE: &[u8] = include_bytes!("../../resources/spdx_cache.bin.zstd
 fn iter_clarifications<'a>(
     all: &'a [ValidClarification],
     krate: &'a Krate,
-) -> impl Iterator<Item = &'a ValidClarification> {
+) -> impl Iterator<Item = (usize, &'a ValidClarification)> {
     all.iter()
-        .filter(move |vc| crate::match_krate(krate, &vc.spec))
+        .enumerate()
+        .filter(move |(_, vc)| crate::match_krate(krate, &vc.spec))
 }
 
 impl fmt::Debug for FileSource {
@@ -27,7 +29,10 @@ impl fmt::Debug for FileSource {
     }
 }
 
-fn find_license_files(dir: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
+fn find_license_files(
+    dir: &Path,
+    patterns: &LicenseFilePatterns,
+) -> Result<Vec<PathBuf>, std::io::Error> {
     let entries = std::fs::read_dir(dir)?;
     Ok(entries
         .filter_map(|e| {
@@ -40,10 +45,7 @@ fn find_license_files(dir: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
                     }
                 };
 
-                if p.is_file()
-                    && p.file_name()
-                        .is_some_and(|f| f.starts_with("LICENSE") || f.starts_with("COPYING"))
-                {
+                if p.is_file() && p.file_name().is_some_and(|f| patterns.is_match(f)) {
                     Some(p.strip_prefix(dir).unwrap().to_owned())
                 } else {
                     None
@@ -53,6 +55,53 @@ fn find_license_files(dir: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
         .collect())
 }
 
+/// Retains only the paths that would be included in the crate's package, per
+/// the `include` and `exclude` fields in its manifest, using the same
+/// precedence as cargo, ie. `exclude` is ignored if `include` is specified
+fn retain_packaged(manifest_path: &Path, paths: &mut Vec<PathBuf>) {
+    let manifest = match std::fs::read_to_string(manifest_path) {
+        Ok(m) => m,
+        Err(err) => {
+            log::warn!("failed to read manifest '{manifest_path}': {err}");
+            return;
+        }
+    };
+
+    let root = match toml_span::parse(&manifest) {
+        Ok(root) => root,
+        Err(err) => {
+            log::warn!("failed to parse manifest '{manifest_path}': {err}");
+            return;
+        }
+    };
+
+    let globs = |key: &str| -> Option<globset::GlobSet> {
+        let patterns = root.pointer(key)?.as_array()?;
+
+        let mut builder = globset::GlobSetBuilder::new();
+        for pattern in patterns.iter().filter_map(|p| p.as_str()) {
+            // Patterns are relative to the package root regardless of a
+            // leading `/`
+            match globset::Glob::new(pattern.trim_start_matches('/')) {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(err) => {
+                    log::warn!("invalid pattern '{pattern}' in '{manifest_path}': {err}");
+                }
+            }
+        }
+
+        builder.build().ok()
+    };
+
+    if let Some(include) = globs("/package/include") {
+        paths.retain(|path| include.is_match(path));
+    } else if let Some(exclude) = globs("/package/exclude") {
+        paths.retain(|path| !exclude.is_match(path));
+    }
+}
+
 fn get_file_source(root: &Path, path: PathBuf) -> PackFile {
     use std::io::BufRead;
 
@@ -134,11 +183,26 @@ struct GatheredExpr {
     file_sources: Vec<String>,
 }
 
-impl LicensePack {
-    fn read(krate: &Krate) -> Self {
-        let root = krate.manifest_path.parent().unwrap();
+struct FailedExpr {
+    synthesized_toml: String,
+    failures: Vec<Label>,
+    /// The licenses askalono identified, but with a confidence below the
+    /// user's threshold, along with the file they were found in and the score
+    guesses: Vec<(PathBuf, &'static str, f32)>,
+}
 
-        let mut lic_paths = match find_license_files(root) {
+impl LicensePack {
+    /// Reads the license files for the crate, from the fetched root if the
+    /// crate's source is not present locally
+    fn read(
+        krate: &Krate,
+        fetched_root: Option<&Path>,
+        patterns: &LicenseFilePatterns,
+        respect_package_include: bool,
+    ) -> Self {
+        let root = fetched_root.unwrap_or_else(|| krate.manifest_path.parent().unwrap());
+
+        let mut lic_paths = match find_license_files(root, patterns) {
             Ok(paths) => paths,
             Err(e) => {
                 return Self {
@@ -149,6 +213,11 @@ impl LicensePack {
             }
         };
 
+        // Fetched license files are already limited to the packaged files
+        if respect_package_include && fetched_root.is_none() {
+            retain_packaged(&krate.manifest_path, &mut lic_paths);
+        }
+
         // Add the explicitly specified license if it wasn't
         // already found in the root directory
         if let Some(lf) = &krate.license_file {
@@ -202,7 +271,7 @@ impl LicensePack {
         file: FileId,
         strategy: &askalono::ScanStrategy<'_>,
         confidence: f32,
-    ) -> Result<GatheredExpr, (String, Vec<Label>)> {
+    ) -> Result<GatheredExpr, FailedExpr> {
         use std::fmt::Write;
 
         let mut expr = String::new();
@@ -212,15 +281,17 @@ impl LicensePack {
         if let Some(err) = &self.err {
             write!(synth_toml, "license-files = \"{err}\"").unwrap();
             let len = synth_toml.len();
-            return Err((
-                synth_toml,
-                vec![Label::secondary(file, 17..len - 1)
+            return Err(FailedExpr {
+                synthesized_toml: synth_toml,
+                failures: vec![Label::secondary(file, 17..len - 1)
                     .with_message("unable to gather license files")],
-            ));
+                guesses: Vec::new(),
+            });
         }
 
         let mut failures = Vec::new();
         let mut notes = Vec::new();
+        let mut guesses = Vec::new();
         synth_toml.push_str("license-files = [\n");
 
         for lic_contents in &self.license_files {
@@ -290,6 +361,14 @@ impl LicensePack {
                                     write!(synth_toml, ", license = \"{}\"", identified.name)
                                         .unwrap();
 
+                                    if let Some(id) = spdx::license_id(identified.name) {
+                                        guesses.push((
+                                            lic_contents.path.clone(),
+                                            id.name,
+                                            lic_match.score,
+                                        ));
+                                    }
+
                                     failures.push(
                                         Label::secondary(file, start + 8..end)
                                             .with_message("low confidence in the license text"),
@@ -338,9 +417,90 @@ impl LicensePack {
                 file_sources: sources,
             })
         } else {
-            Err((synth_toml, failures))
+            Err(FailedExpr {
+                synthesized_toml: synth_toml,
+                failures,
+                guesses,
+            })
         }
     }
+
+    /// Suggests a clarification that the user can add to their configuration
+    /// once they have confirmed the license(s) of the crate, using the best
+    /// guess for each license file
+    ///
+    /// Returns `None` if none of the license files could be read
+    fn suggest_clarification(
+        &self,
+        guesses: &[(PathBuf, &'static str, f32)],
+    ) -> Option<SuggestedClarification> {
+        let license_files: Vec<_> = self
+            .license_files
+            .iter()
+            .filter_map(|lf| {
+                if let PackFileData::Good(data) = &lf.data {
+                    Some((lf.path.clone(), data.hash))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        if license_files.is_empty() {
+            return None;
+        }
+
+        let mut expr = String::new();
+        for (_, id, _) in guesses {
+            if expr.split(" AND ").any(|g| g == *id) {
+                continue;
+            }
+
+            if !expr.is_empty() {
+                expr.push_str(" AND ");
+            }
+
+            expr.push_str(id);
+        }
+
+        Some(SuggestedClarification {
+            expression: (!expr.is_empty()).then_some(expr),
+            license_files,
+        })
+    }
+}
+
+/// A `[[licenses.clarify]]` entry suggested for a crate whose license could
+/// not be determined with enough confidence
+#[derive(Debug)]
+pub struct SuggestedClarification {
+    /// The expression made up of the best guess for each license file, if
+    /// any of the license files resembled a known license
+    pub expression: Option<String>,
+    /// The crate relative path and hash of each license file
+    pub license_files: Vec<(PathBuf, u32)>,
+}
+
+impl SuggestedClarification {
+    /// Creates a `[[licenses.clarify]]` stanza that the user can paste into
+    /// their configuration
+    pub fn stanza(&self, krate: &Krate) -> String {
+        use std::fmt::Write;
+
+        let mut stanza = format!(
+            "[[licenses.clarify]]\ncrate = \"{}@{}\"\nexpression = \"{}\"\nlicense-files = [\n",
+            krate.name,
+            krate.version,
+            self.expression.as_deref().unwrap_or("<SPDX expression>"),
+        );
+
+        for (path, hash) in &self.license_files {
+            writeln!(stanza, "    {{ path = \"{path}\", hash = 0x{hash:08x} }},").unwrap();
+        }
+
+        stanza.push(']');
+        stanza
+    }
 }
 
 #[derive(Debug)]
@@ -379,6 +539,9 @@ pub enum LicenseInfo {
 pub struct KrateLicense<'a> {
     pub krate: &'a Krate,
     pub lic_info: LicenseInfo,
+    /// The clarification suggested for an unlicensed crate, if it has license
+    /// files that could be read
+    pub suggested_clarification: Option<SuggestedClarification>,
 
     pub(crate) notes: Vec<String>,
 
@@ -390,6 +553,8 @@ pub struct KrateLicense<'a> {
 pub struct Summary<'a> {
     store: Arc<LicenseStore>,
     pub nfos: Vec<KrateLicense<'a>>,
+    /// Whether each of the configured clarifications was applied to a crate
+    pub(crate) clarification_hits: BitVec<usize, LocalBits>,
 }
 
 impl Summary<'_> {
@@ -397,6 +562,7 @@ impl Summary<'_> {
         Self {
             store,
             nfos: Vec::new(),
+            clarification_hits: BitVec::new(),
         }
     }
 }
@@ -427,6 +593,9 @@ impl Default for LicenseStore {
 pub struct Gatherer {
     store: Arc<LicenseStore>,
     threshold: f32,
+    progress: Option<crate::ProgressFn>,
+    single_threaded: bool,
+    fetched: Option<super::FetchedLicenseFiles>,
 }
 
 impl Default for Gatherer {
@@ -434,6 +603,9 @@ impl Default for Gatherer {
         Self {
             store: Arc::new(LicenseStore::default()),
             threshold: 0.8,
+            progress: None,
+            single_threaded: false,
+            fetched: None,
         }
     }
 }
@@ -468,6 +640,29 @@ impl Gatherer {
         self
     }
 
+    /// Sets a callback that is invoked when license gathering starts and finishes
+    #[inline]
+    pub fn with_progress(mut self, progress: crate::ProgressFn) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// If true, crates are gathered serially on the calling thread rather
+    /// than in parallel
+    #[inline]
+    pub fn with_single_threaded(mut self, single_threaded: bool) -> Self {
+        self.single_threaded = single_threaded;
+        self
+    }
+
+    /// Sets the license files that were fetched for crates whose source is
+    /// not present locally
+    #[inline]
+    pub fn with_fetched_license_files(mut self, fetched: super::FetchedLicenseFiles) -> Self {
+        self.fetched = Some(fetched);
+        self
+    }
+
     pub fn gather<'k>(
         self,
         krates: &'k crate::Krates,
@@ -484,7 +679,10 @@ impl Gatherer {
             .optimize(false)
             .max_passes(1);
 
-        let files_lock = std::sync::Arc::new(parking_lot::RwLock::new(files));
+        let clarification_hits = parking_lot::Mutex::new(BitVec::<usize, LocalBits>::repeat(
+            false,
+            cfg.map_or(0, |cfg| cfg.clarifications.len()),
+        ));
 
         // Most users will not care about licenses for dev dependencies
         let krates = if cfg.is_some_and(|cfg| cfg.include_dev) {
@@ -493,6 +691,44 @@ impl Gatherer {
             krates.krates_filtered(krates::DepKind::Dev)
         };
 
+        let default_patterns;
+        let license_file_patterns = if let Some(cfg) = cfg {
+            &cfg.license_file_patterns
+        } else {
+            default_patterns = LicenseFilePatterns::default();
+            &default_patterns
+        };
+
+        // Only consider license files that would actually be published
+        let respect_package_include = cfg.is_some_and(|cfg| cfg.respect_package_include);
+
+        if let Some(progress) = &self.progress {
+            progress(crate::ProgressEvent::GatheringLicenses {
+                krates: krates.len(),
+            });
+        }
+
+        // Synthesize a minimal Cargo.toml for each crate for reporting diagnostics
+        // for where we retrieved license stuff. This is done up front, in the
+        // same order as the crates, so that file ids are assigned the same way
+        // every run, regardless of how the crates are scheduled below
+        let krates: Vec<_> = krates
+            .into_iter()
+            .map(|krate| {
+                let synth_manifest = format!(
+                    "[package]\nname = \"{}\"\nversion = \"{}\"\nlicense = \"{}\"\n",
+                    krate.name,
+                    krate.version,
+                    krate.license.as_deref().unwrap_or_default(),
+                );
+
+                (krate, files.add(krate.id.repr.clone(), synth_manifest))
+            })
+            .collect();
+
+        let files_lock = std::sync::Arc::new(parking_lot::RwLock::new(files));
+        let fetched = self.fetched.as_ref();
+
         // Retrieve the license expression we'll use to evaluate the user's overall
         // constraints with.
         //
@@ -521,56 +757,40 @@ impl Gatherer {
         // 4. `license-file` + all LICENSE(-*)? files - Due to the prevalance
         // of dual-licensing in the rust ecosystem, many people forgo setting
         // license-file, so we use it and/or any LICENSE files
-        summary.nfos = krates
-            .into_par_iter()
-            .map(|krate| {
-                // Attempt an SPDX expression that we can validate the user's acceptable
-                // license terms with
-                let mut synth_id = None;
-
-                let mut labels = smallvec::SmallVec::<[Label; 1]>::new();
-
-                let mut get_span = |key: &'static str| -> (FileId, std::ops::Range<usize>) {
-                    if let Some(id) = synth_id {
-                        let l = files_lock.read();
-                        (synth_id.unwrap(), get_toml_span(key, l.source(id)))
-                    } else {
-                        // Synthesize a minimal Cargo.toml for reporting diagnostics
-                        // for where we retrieved license stuff
-                        let synth_manifest = format!(
-                            "[package]\nname = \"{}\"\nversion = \"{}\"\nlicense = \"{}\"\n",
-                            krate.name,
-                            krate.version,
-                            krate.license.as_deref().unwrap_or_default(),
-                        );
+        let gather_krate = |(krate, synth_id): (&'k Krate, FileId)| {
+            // Attempt an SPDX expression that we can validate the user's acceptable
+            // license terms with
+            let mut labels = smallvec::SmallVec::<[Label; 1]>::new();
 
-                        {
-                            let mut fl = files_lock.write();
-                            synth_id = Some(fl.add(krate.id.repr.clone(), synth_manifest));
-                            (
-                                synth_id.unwrap(),
-                                get_toml_span(key, fl.source(synth_id.unwrap())),
-                            )
-                        }
-                    }
-                };
+            let fetched_root = fetched.and_then(|fetched| fetched.root(krate));
 
-                let mut license_pack = None;
+            let get_span = |key: &'static str| -> (FileId, std::ops::Range<usize>) {
+                let l = files_lock.read();
+                (synth_id, get_toml_span(key, l.source(synth_id)))
+            };
 
-                // 1
-                if let Some(cfg) = cfg {
-                    for clarification in iter_clarifications(&cfg.clarifications, krate) {
-                        let lp = if let Some(lp) = &mut license_pack {
-                            lp
-                        } else {
-                            license_pack = Some(LicensePack::read(krate));
-                            license_pack.as_mut().unwrap()
-                        };
+            let mut license_pack = None;
 
-                        // Check to see if the clarification provided exactly matches
-                        // the set of detected licenses, if they do, we use the clarification's
-                        // license expression as the license requirements for this crate
-                        let clarifications_match = clarification.license_files.iter().all(|clf| {
+            // 1
+            if let Some(cfg) = cfg {
+                for (i, clarification) in iter_clarifications(&cfg.clarifications, krate) {
+                    let lp = if let Some(lp) = &mut license_pack {
+                        lp
+                    } else {
+                        license_pack = Some(LicensePack::read(
+                            krate,
+                            fetched_root,
+                            license_file_patterns,
+                            respect_package_include,
+                        ));
+                        license_pack.as_mut().unwrap()
+                    };
+
+                    // Check to see if the clarification provided exactly matches
+                    // the set of detected licenses, if they do, we use the clarification's
+                    // license expression as the license requirements for this crate
+                    let clarifications_match =
+                        clarification.license_files.iter().all(|clf| {
                             match lp.insert_clarification(clf) {
                                 Ok(_) => true,
                                 Err(reason) => {
@@ -591,42 +811,83 @@ impl Gatherer {
                             }
                         });
 
-                        if clarifications_match {
-                            return KrateLicense {
-                                krate,
-                                lic_info: LicenseInfo::SpdxExpression {
-                                    expr: clarification.expression.clone(),
-                                    nfo: LicenseExprInfo {
-                                        file_id: cfg.file_id,
-                                        offset: clarification.expr_offset,
-                                        source: LicenseExprSource::UserOverride,
-                                    },
+                    if clarifications_match {
+                        clarification_hits.lock().set(i, true);
+
+                        return KrateLicense {
+                            krate,
+                            lic_info: LicenseInfo::SpdxExpression {
+                                expr: clarification.expression.clone(),
+                                nfo: LicenseExprInfo {
+                                    file_id: cfg.file_id,
+                                    offset: clarification.expr_offset,
+                                    source: LicenseExprSource::UserOverride,
                                 },
-                                labels,
-                                notes: Vec::new(),
-                            };
-                        }
+                            },
+                            suggested_clarification: None,
+                            labels,
+                            notes: Vec::new(),
+                        };
                     }
                 }
+            }
+
+            // 2 TODO
+
+            // 3
+            if let Some(license_field) = &krate.license {
+                // Reasons this can fail:
+                //
+                // * Empty! The rust crate used to validate this field has a bug
+                // https://github.com/rust-lang-nursery/license-exprs/issues/23
+                // * It also just does basic lexing, so parens, duplicate operators,
+                // unpaired exceptions etc can all fail validation
+                //
+                // Note that these only apply to _old_ versions, as `spdx`
+                // is now used by crates.io to validate, but it uses lax
+                // rules to allow some license identifiers that aren't
+                // technically correct
+
+                match spdx::Expression::parse(license_field) {
+                    Ok(validated) => {
+                        let (id, span) = get_span("license");
+
+                        return KrateLicense {
+                            krate,
+                            lic_info: LicenseInfo::SpdxExpression {
+                                expr: validated,
+                                nfo: LicenseExprInfo {
+                                    file_id: id,
+                                    offset: span.start,
+                                    source: LicenseExprSource::Metadata,
+                                },
+                            },
+                            suggested_clarification: None,
+                            labels,
+                            notes: Vec::new(),
+                        };
+                    }
+                    Err(err) => {
+                        let (id, lic_span) = get_span("license");
+                        let lic_span =
+                            lic_span.start + err.span.start..lic_span.start + err.span.end;
+
+                        labels.push(
+                            Label::secondary(id, lic_span).with_message(err.reason.to_string()),
+                        );
 
-                // 2 TODO
-
-                // 3
-                if let Some(license_field) = &krate.license {
-                    // Reasons this can fail:
-                    //
-                    // * Empty! The rust crate used to validate this field has a bug
-                    // https://github.com/rust-lang-nursery/license-exprs/issues/23
-                    // * It also just does basic lexing, so parens, duplicate operators,
-                    // unpaired exceptions etc can all fail validation
-                    //
-                    // Note that these only apply to _old_ versions, as `spdx`
-                    // is now used by crates.io to validate, but it uses lax
-                    // rules to allow some license identifiers that aren't
-                    // technically correct
-
-                    match spdx::Expression::parse(license_field) {
-                        Ok(validated) => {
+                        // If we fail strict parsing, attempt to use lax parsing,
+                        // though still emitting a warning so the user is aware
+                        if let Ok(validated) = spdx::Expression::parse_mode(
+                            license_field,
+                            spdx::ParseMode {
+                                allow_lower_case_operators: true,
+                                // We already force correct this when loading crates
+                                allow_slash_as_or_operator: false,
+                                allow_imprecise_license_names: true,
+                                allow_postfix_plus_on_gpl: true,
+                            },
+                        ) {
                             let (id, span) = get_span("license");
 
                             return KrateLicense {
@@ -639,161 +900,167 @@ impl Gatherer {
                                         source: LicenseExprSource::Metadata,
                                     },
                                 },
+                                suggested_clarification: None,
                                 labels,
                                 notes: Vec::new(),
                             };
                         }
-                        Err(err) => {
-                            let (id, lic_span) = get_span("license");
-                            let lic_span =
-                                lic_span.start + err.span.start..lic_span.start + err.span.end;
+                    }
+                }
+            } else {
+                let (id, lic_span) = get_span("license");
+                labels.push(
+                    Label::secondary(id, lic_span)
+                        .with_message("license expression was not specified"),
+                );
+            }
+
+            // 4
+            // We might have already loaded the licenses to check them against a clarification
+            let license_pack = license_pack.unwrap_or_else(|| {
+                LicensePack::read(
+                    krate,
+                    fetched_root,
+                    license_file_patterns,
+                    respect_package_include,
+                )
+            });
+            let mut notes = Vec::new();
+            let mut suggested_clarification = None;
+
+            if !license_pack.license_files.is_empty() {
+                let (id, _) = get_span("license");
+
+                match license_pack.get_expression(id, &strategy, threshold) {
+                    Ok(GatheredExpr {
+                        synthesized_toml,
+                        failures,
+                        notes,
+                        expr,
+                        file_sources,
+                    }) => {
+                        // Push our synthesized license files toml content to the end of
+                        // the other synthesized toml then fixup all of our spans
+                        let expr_offset = {
+                            let mut fl = files_lock.write();
+
+                            let (new_source, offset) = {
+                                let source = fl.source(id);
+                                (
+                                    format!(
+                                        "{source}files-expr = \"{expr}\"\n{synthesized_toml}\n"
+                                    ),
+                                    (source.len() + 14),
+                                )
+                            };
 
+                            fl.update(id, new_source);
+                            offset
+                        };
+
+                        let fail_offset = expr_offset + expr.to_string().len() + 2;
+
+                        for fail in failures {
+                            let span = fail.range.start + fail_offset..fail.range.end + fail_offset;
                             labels.push(
-                                Label::secondary(id, lic_span).with_message(err.reason.to_string()),
+                                Label::secondary(fail.file_id, span).with_message(fail.message),
                             );
+                        }
 
-                            // If we fail strict parsing, attempt to use lax parsing,
-                            // though still emitting a warning so the user is aware
-                            if let Ok(validated) = spdx::Expression::parse_mode(
-                                license_field,
-                                spdx::ParseMode {
-                                    allow_lower_case_operators: true,
-                                    // We already force correct this when loading crates
-                                    allow_slash_as_or_operator: false,
-                                    allow_imprecise_license_names: true,
-                                    allow_postfix_plus_on_gpl: true,
+                        return KrateLicense {
+                            krate,
+                            lic_info: LicenseInfo::SpdxExpression {
+                                expr,
+                                nfo: LicenseExprInfo {
+                                    file_id: id,
+                                    offset: expr_offset,
+                                    source: LicenseExprSource::LicenseFiles(file_sources),
                                 },
-                            ) {
-                                let (id, span) = get_span("license");
-
-                                return KrateLicense {
-                                    krate,
-                                    lic_info: LicenseInfo::SpdxExpression {
-                                        expr: validated,
-                                        nfo: LicenseExprInfo {
-                                            file_id: id,
-                                            offset: span.start,
-                                            source: LicenseExprSource::Metadata,
-                                        },
-                                    },
-                                    labels,
-                                    notes: Vec::new(),
-                                };
-                            }
-                        }
+                            },
+                            suggested_clarification: None,
+                            labels,
+                            notes,
+                        };
                     }
-                } else {
-                    let (id, lic_span) = get_span("license");
-                    labels.push(
-                        Label::secondary(id, lic_span)
-                            .with_message("license expression was not specified"),
-                    );
-                }
-
-                // 4
-                // We might have already loaded the licenses to check them against a clarification
-                let license_pack = license_pack.unwrap_or_else(|| LicensePack::read(krate));
-
-                if !license_pack.license_files.is_empty() {
-                    let (id, _) = get_span("license");
+                    Err(FailedExpr {
+                        synthesized_toml: new_toml,
+                        failures: lic_file_labels,
+                        guesses,
+                    }) => {
+                        // Push our synthesized license files toml content to the end of
+                        // the other synthesized toml then fixup all of our spans
+                        let old_end = {
+                            let mut fl = files_lock.write();
 
-                    match license_pack.get_expression(id, &strategy, threshold) {
-                        Ok(GatheredExpr {
-                            synthesized_toml,
-                            failures,
-                            notes,
-                            expr,
-                            file_sources,
-                        }) => {
-                            // Push our synthesized license files toml content to the end of
-                            // the other synthesized toml then fixup all of our spans
-                            let expr_offset = {
-                                let mut fl = files_lock.write();
-
-                                let (new_source, offset) = {
-                                    let source = fl.source(id);
-                                    (
-                                        format!(
-                                            "{source}files-expr = \"{expr}\"\n{synthesized_toml}\n"
-                                        ),
-                                        (source.len() + 14),
-                                    )
-                                };
-
-                                fl.update(id, new_source);
-                                offset
+                            let (new_source, old_end) = {
+                                let source = fl.source(id);
+                                (format!("{source}{new_toml}\n"), source.len())
                             };
 
-                            let fail_offset = expr_offset + expr.to_string().len() + 2;
+                            fl.update(id, new_source);
+                            old_end
+                        };
 
-                            for fail in failures {
-                                let span =
-                                    fail.range.start + fail_offset..fail.range.end + fail_offset;
-                                labels.push(
-                                    Label::secondary(fail.file_id, span).with_message(fail.message),
-                                );
-                            }
+                        for label in lic_file_labels {
+                            let span = label.range.start + old_end..label.range.end + old_end;
+                            labels.push(
+                                Label::secondary(label.file_id, span).with_message(label.message),
+                            );
+                        }
 
-                            return KrateLicense {
-                                krate,
-                                lic_info: LicenseInfo::SpdxExpression {
-                                    expr,
-                                    nfo: LicenseExprInfo {
-                                        file_id: id,
-                                        offset: expr_offset,
-                                        source: LicenseExprSource::LicenseFiles(file_sources),
-                                    },
-                                },
-                                labels,
-                                notes,
-                            };
+                        // Even though we couldn't determine the license(s) with
+                        // enough confidence, give the user a starting point for
+                        // clarifying the license(s) themselves
+                        for (path, id, score) in &guesses {
+                            notes.push(format!(
+                                    "'{path}' is possibly '{id}', but the confidence score {score:.2} is below the threshold {threshold:.2}"
+                                ));
                         }
-                        Err((new_toml, lic_file_labels)) => {
-                            // Push our synthesized license files toml content to the end of
-                            // the other synthesized toml then fixup all of our spans
-                            let old_end = {
-                                let mut fl = files_lock.write();
-
-                                let (new_source, old_end) = {
-                                    let source = fl.source(id);
-                                    (format!("{source}{new_toml}\n"), source.len())
-                                };
-
-                                fl.update(id, new_source);
-                                old_end
-                            };
 
-                            for label in lic_file_labels {
-                                let span = label.range.start + old_end..label.range.end + old_end;
-                                labels.push(
-                                    Label::secondary(label.file_id, span)
-                                        .with_message(label.message),
-                                );
-                            }
+                        suggested_clarification = license_pack.suggest_clarification(&guesses);
+                        if let Some(suggested) = &suggested_clarification {
+                            notes.push("if the license(s) can be confirmed, the following can be added to your config:".to_owned());
+                            notes.push(suggested.stanza(krate));
                         }
                     }
                 }
+            }
 
-                // Just get a label for the crate name
-                let (id, nspan) = get_span("name");
-                labels.push(Label::primary(id, nspan).with_message(
+            // Just get a label for the crate name
+            let (id, nspan) = get_span("name");
+            labels.push(
+                Label::primary(id, nspan).with_message(
                     "a valid license expression could not be retrieved for the crate",
-                ));
+                ),
+            );
+
+            // Well, we tried our very best. Actually that's not true, we could scan for license
+            // files not prefixed by LICENSE, and recurse into subdirectories, but honestly
+            // at that point it's probably better to open a PR or something because the license
+            // information is not conventional and probably warrants closer inspection
+            KrateLicense {
+                krate,
+                lic_info: LicenseInfo::Unlicensed,
+                suggested_clarification,
+                labels,
+                notes,
+            }
+        };
 
-                // Well, we tried our very best. Actually that's not true, we could scan for license
-                // files not prefixed by LICENSE, and recurse into subdirectories, but honestly
-                // at that point it's probably better to open a PR or something because the license
-                // information is not conventional and probably warrants closer inspection
-                KrateLicense {
-                    krate,
-                    lic_info: LicenseInfo::Unlicensed,
-                    labels,
-                    notes: Vec::new(),
-                }
-            })
-            .collect();
+        summary.nfos = if self.single_threaded {
+            krates.into_iter().map(gather_krate).collect()
+        } else {
+            krates.into_par_iter().map(gather_krate).collect()
+        };
 
         summary.nfos.par_sort_by_key(|nfo| nfo.krate);
+        summary.clarification_hits = clarification_hits.into_inner();
+
+        if let Some(progress) = &self.progress {
+            progress(crate::ProgressEvent::LicensesGathered {
+                krates: summary.nfos.len(),
+            });
+        }
 
         summary
     }
@@ -826,4 +1093,76 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn retains_packaged_license_files() {
+        use crate::PathBuf;
+
+        let td = tempfile::tempdir().unwrap();
+        let manifest_path = crate::utf8path(td.path().join("Cargo.toml")).unwrap();
+
+        let retained = |manifest: &str| {
+            std::fs::write(&manifest_path, manifest).unwrap();
+
+            let mut paths = vec![
+                PathBuf::from("LICENSE"),
+                PathBuf::from("LICENSE.draft"),
+                PathBuf::from("COPYING"),
+            ];
+            super::retain_packaged(&manifest_path, &mut paths);
+            paths
+        };
+
+        assert_eq!(
+            retained("[package]\nname = \"a\"\ninclude = [\"/src\", \"LICENSE\"]\n"),
+            [PathBuf::from("LICENSE")]
+        );
+        assert_eq!(
+            retained("[package]\nname = \"a\"\nexclude = [\"*.draft\"]\n"),
+            [PathBuf::from("LICENSE"), PathBuf::from("COPYING")]
+        );
+        // `exclude` is ignored if `include` is specified
+        assert_eq!(
+            retained(
+                "[package]\nname = \"a\"\ninclude = [\"LICENSE*\"]\nexclude = [\"LICENSE\"]\n"
+            ),
+            [PathBuf::from("LICENSE"), PathBuf::from("LICENSE.draft")]
+        );
+        assert_eq!(retained("[package]\nname = \"a\"\n").len(), 3);
+    }
+
+    #[test]
+    fn finds_license_files() {
+        use crate::{licenses::cfg::LicenseFilePatterns, PathBuf};
+
+        let td = tempfile::tempdir().unwrap();
+        let root = crate::utf8path(td.path().to_owned()).unwrap();
+
+        let found = |files: &[&str]| {
+            for entry in std::fs::read_dir(&root).unwrap() {
+                std::fs::remove_file(entry.unwrap().path()).unwrap();
+            }
+
+            for file in files {
+                std::fs::write(root.join(file), "license text").unwrap();
+            }
+
+            let mut found =
+                super::find_license_files(&root, &LicenseFilePatterns::default()).unwrap();
+            found.sort();
+            found
+        };
+
+        // A crate whose only license file is COPYING
+        assert_eq!(found(&["COPYING", "README.md"]), [PathBuf::from("COPYING")]);
+        assert_eq!(
+            found(&["LICENCE-MIT", "LICENSE-APACHE", "Cargo.toml"]),
+            [
+                PathBuf::from("LICENCE-MIT"),
+                PathBuf::from("LICENSE-APACHE")
+            ]
+        );
+        // Not part of the default patterns
+        assert!(found(&["UNLICENSE", "NOTICE"]).is_empty());
+    }
 }
@@ -0,0 +1,105 @@
+//! A record of the license expression of every crate in the graph, used to
+//! detect when the license of a crate changes, eg. when a crate is relicensed
+//! in a patch release
+
+use super::{LicenseInfo, Summary};
+use anyhow::Context as _;
+use std::collections::BTreeMap;
+
+const HEADER: &str = "# This file is generated by `cargo deny check --update-license-lock`\n";
+
+/// The license expression recorded for each crate, keyed by crate name
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct LicenseLock {
+    licenses: BTreeMap<String, String>,
+}
+
+impl LicenseLock {
+    /// Loads the lock at the specified path, returning `None` if it doesn't
+    /// exist yet
+    pub fn load(path: &crate::Path) -> anyhow::Result<Option<Self>> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read license lock '{path}'"))
+            }
+        };
+
+        contents
+            .parse()
+            .map(Some)
+            .with_context(|| format!("failed to parse license lock '{path}'"))
+    }
+
+    /// Creates a lock from the license expressions that were gathered for
+    /// each crate, crates without a license expression are not recorded
+    pub fn from_summary(summary: &Summary<'_>) -> Self {
+        let licenses = summary
+            .nfos
+            .iter()
+            .filter_map(|nfo| match &nfo.lic_info {
+                // Crates are sorted by name and version, so if there are
+                // multiple versions of a crate, the latest one is recorded
+                LicenseInfo::SpdxExpression { expr, .. } => {
+                    Some((nfo.krate.name.clone(), expr.as_ref().to_owned()))
+                }
+                LicenseInfo::Unlicensed => None,
+            })
+            .collect();
+
+        Self { licenses }
+    }
+
+    /// Gets the license expression that was recorded for the crate
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.licenses.get(name).map(String::as_str)
+    }
+
+    /// Writes the lock to the specified path
+    pub fn write(&self, path: &crate::Path) -> anyhow::Result<()> {
+        std::fs::write(path, self.to_string())
+            .with_context(|| format!("failed to write license lock '{path}'"))
+    }
+}
+
+impl std::str::FromStr for LicenseLock {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let doc: toml_edit::DocumentMut = s.parse()?;
+
+        let Some(table) = doc.get("licenses") else {
+            return Ok(Self::default());
+        };
+
+        let table = table.as_table_like().context("`licenses` is not a table")?;
+
+        let licenses = table
+            .iter()
+            .map(|(name, expr)| {
+                let expr = expr
+                    .as_str()
+                    .with_context(|| format!("license for '{name}' is not a string"))?;
+                Ok((name.to_owned(), expr.to_owned()))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self { licenses })
+    }
+}
+
+impl std::fmt::Display for LicenseLock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut table = toml_edit::Table::new();
+        for (name, expr) in &self.licenses {
+            table.insert(name, toml_edit::value(expr.as_str()));
+        }
+
+        let mut doc = toml_edit::DocumentMut::new();
+        doc.insert("licenses", toml_edit::Item::Table(table));
+
+        write!(f, "{HEADER}{doc}")
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    advisories::cfg::Config as AdvisoriesConfig, bans::cfg::Config as BansConfig,
+    advisories::cfg::Config as AdvisoriesConfig, bans::cfg::Config as BansConfig, cfg::PackageSpec,
     licenses::cfg::Config as LicensesConfig, sources::cfg::Config as SourcesConfig, Spanned,
 };
 use toml_span::{
@@ -13,6 +13,32 @@ pub struct Target {
     pub features: Vec<String>,
 }
 
+impl Target {
+    /// Whether this is the `*` wildcard, which disables target filtering
+    #[inline]
+    pub fn is_wildcard(&self) -> bool {
+        matches!(&self.filter.value, krates::Target::Unknown(triple) if triple == "*")
+    }
+}
+
+impl serde::Serialize for Target {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let triple = match &self.filter.value {
+            krates::Target::Builtin(ti) => ti.triple.as_str(),
+            krates::Target::Unknown(triple) => triple.as_str(),
+        };
+
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("triple", triple)?;
+        map.serialize_entry("features", &self.features)?;
+        map.end()
+    }
+}
+
 impl<'de> Deserialize<'de> for Target {
     fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
         let (triple, features) = match value.take() {
@@ -39,16 +65,24 @@ impl<'de> Deserialize<'de> for Target {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, serde::Serialize)]
 pub struct GraphConfig {
     pub targets: Vec<Target>,
     pub exclude: Vec<String>,
+    /// Globs, relative to the workspace root, for the manifests of workspace
+    /// members that are excluded from the roots of the graph
+    pub exclude_paths: Vec<String>,
     pub features: Vec<String>,
     pub all_features: bool,
     pub no_default_features: bool,
     /// By default, dev dependencies for workspace crates are not ignored
     pub exclude_dev: bool,
+    /// The names of crates whose dev dependencies are ignored
+    pub exclude_dev_deps_of: Vec<String>,
     pub exclude_unpublished: bool,
+    /// Crates only used by the host are resolved without their default
+    /// features, set via `host-default-features = false`
+    pub no_host_default_features: bool,
 }
 
 impl<'de> Deserialize<'de> for GraphConfig {
@@ -56,36 +90,209 @@ impl<'de> Deserialize<'de> for GraphConfig {
         let mut th = TableHelper::new(value)?;
         let targets = th.optional("targets").unwrap_or_default();
         let exclude = th.optional("exclude").unwrap_or_default();
+        let exclude_paths = th.optional("exclude-paths").unwrap_or_default();
         let features = th.optional("features").unwrap_or_default();
         let all_features = th.optional("all-features").unwrap_or_default();
         let no_default_features = th.optional("no-default-features").unwrap_or_default();
         let exclude_dev = th.optional("exclude-dev").unwrap_or_default();
+        let exclude_dev_deps_of = th.optional("exclude-dev-deps-of").unwrap_or_default();
         let exclude_unpublished = th.optional("exclude-unpublished").unwrap_or_default();
+        let no_host_default_features = !th.optional("host-default-features").unwrap_or(true);
         th.finalize(None)?;
 
         Ok(Self {
             targets,
             exclude,
+            exclude_paths,
             features,
             all_features,
             no_default_features,
             exclude_dev,
+            exclude_dev_deps_of,
             exclude_unpublished,
+            no_host_default_features,
         })
     }
 }
 
-#[derive(Default)]
+/// One of the 8 standard terminal colors
+#[derive(
+    Clone, Copy, PartialEq, Eq, Debug, strum::VariantArray, strum::VariantNames, serde::Serialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum NamedColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+crate::enum_deser!(NamedColor);
+
+/// A terminal color, either a named color or a code in the 256-color palette
+#[derive(Clone, Copy, PartialEq, Eq, Debug, serde::Serialize)]
+#[serde(untagged)]
+pub enum TermColor {
+    Named(NamedColor),
+    Ansi256(u8),
+}
+
+impl TermColor {
+    /// Converts to the color used by the diagnostic renderer
+    pub fn to_termcolor(self) -> codespan_reporting::term::termcolor::Color {
+        use codespan_reporting::term::termcolor::Color;
+
+        match self {
+            Self::Named(named) => match named {
+                NamedColor::Black => Color::Black,
+                NamedColor::Red => Color::Red,
+                NamedColor::Green => Color::Green,
+                NamedColor::Yellow => Color::Yellow,
+                NamedColor::Blue => Color::Blue,
+                NamedColor::Magenta => Color::Magenta,
+                NamedColor::Cyan => Color::Cyan,
+                NamedColor::White => Color::White,
+            },
+            Self::Ansi256(code) => Color::Ansi256(code),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TermColor {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        match value.take() {
+            ValueInner::String(s) => {
+                value.set(ValueInner::String(s));
+                Ok(Self::Named(NamedColor::deserialize(value)?))
+            }
+            ValueInner::Integer(i) => {
+                value.set(ValueInner::Integer(i));
+                Ok(Self::Ansi256(u8::deserialize(value)?))
+            }
+            other => Err(toml_span::de_helpers::expected(
+                "a color name or 256-color code",
+                other,
+                value.span,
+            )
+            .into()),
+        }
+    }
+}
+
+/// The colors used for each diagnostic severity, each color that is not
+/// specified uses the default color for wherever it is displayed
+#[derive(Default, Clone, Copy, serde::Serialize)]
+pub struct OutputColors {
+    /// Errors, and `ERROR` log messages
+    pub error: Option<TermColor>,
+    /// Warnings, and `WARN` log messages
+    pub warning: Option<TermColor>,
+    /// Notes, and `INFO` log messages
+    pub note: Option<TermColor>,
+    pub help: Option<TermColor>,
+}
+
+impl OutputColors {
+    /// Applies the colors to the styles of the diagnostic renderer
+    pub fn apply(&self, config: &mut codespan_reporting::term::Config) {
+        let styles = &mut config.styles;
+
+        if let Some(error) = self.error {
+            let fg = Some(error.to_termcolor());
+            styles.header_bug.set_fg(fg);
+            styles.header_error.set_fg(fg);
+            styles.primary_label_bug.set_fg(fg);
+            styles.primary_label_error.set_fg(fg);
+        }
+
+        if let Some(warning) = self.warning {
+            let fg = Some(warning.to_termcolor());
+            styles.header_warning.set_fg(fg);
+            styles.primary_label_warning.set_fg(fg);
+        }
+
+        if let Some(note) = self.note {
+            let fg = Some(note.to_termcolor());
+            styles.header_note.set_fg(fg);
+            styles.primary_label_note.set_fg(fg);
+        }
+
+        if let Some(help) = self.help {
+            let fg = Some(help.to_termcolor());
+            styles.header_help.set_fg(fg);
+            styles.primary_label_help.set_fg(fg);
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for OutputColors {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+        let error = th.optional("error");
+        let warning = th.optional("warning");
+        let note = th.optional("note");
+        let help = th.optional("help");
+        th.finalize(None)?;
+        Ok(Self {
+            error,
+            warning,
+            note,
+            help,
+        })
+    }
+}
+
+#[derive(Default, serde::Serialize)]
 pub struct OutputConfig {
     pub feature_depth: Option<u32>,
+    /// The checks whose warnings are treated as errors when determining if
+    /// the check failed
+    pub deny_warnings: Vec<crate::diag::Check>,
+    /// The fingerprints of individual diagnostics that are downgraded to notes
+    pub acknowledge: Vec<String>,
+    /// The colors used for each severity in the human output
+    pub colors: OutputColors,
 }
 
 impl<'de> Deserialize<'de> for OutputConfig {
     fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
         let mut th = TableHelper::new(value)?;
         let feature_depth = th.optional("feature-depth");
+        let deny_warnings = th.optional("deny-warnings").unwrap_or_default();
+        let acknowledge = th.optional("acknowledge").unwrap_or_default();
+        let colors = th.optional("colors").unwrap_or_default();
+        th.finalize(None)?;
+        Ok(Self {
+            feature_depth,
+            deny_warnings,
+            acknowledge,
+            colors,
+        })
+    }
+}
+
+/// A freeform note attached to the crates matching the spec, which is emitted
+/// whenever a matching crate is present in the graph
+#[derive(serde::Serialize)]
+pub struct Annotation {
+    pub spec: PackageSpec,
+    pub note: Spanned<String>,
+}
+
+impl<'de> Deserialize<'de> for Annotation {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let spec = PackageSpec::deserialize(value)?;
+
+        let mut th = TableHelper::new(value)?;
+        let note = th.required("note")?;
         th.finalize(None)?;
-        Ok(Self { feature_depth })
+
+        Ok(Self { spec, note })
     }
 }
 
@@ -96,6 +303,7 @@ pub struct RootConfig {
     pub sources: Option<SourcesConfig>,
     pub graph: GraphConfig,
     pub output: OutputConfig,
+    pub annotations: Vec<Annotation>,
     // Bit ugly but we keep track of usage of deprecated options until they
     // are removed
     pub graph_deprecated: Vec<crate::Span>,
@@ -156,6 +364,8 @@ impl<'de> Deserialize<'de> for RootConfig {
             None
         };
 
+        let annotations = th.optional("annotations").unwrap_or_default();
+
         th.finalize(None)?;
 
         Ok(Self {
@@ -167,6 +377,7 @@ impl<'de> Deserialize<'de> for RootConfig {
             graph_deprecated,
             output,
             output_deprecated,
+            annotations,
         })
     }
 }
@@ -1,7 +1,13 @@
 pub mod cfg;
+mod checksums;
 mod diags;
+mod owners;
+mod patches;
+mod registries;
 use cfg::ValidConfig;
+pub use checksums::{set_locked_checksums, Checksums, CrateChecksums};
 pub use diags::Code;
+pub use owners::CrateOwners;
 
 use crate::{
     diag::{CfgCoord, Check, ErrorSink, Label, Pack},
@@ -14,18 +20,62 @@ pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>)
     use bitvec::prelude::*;
 
     // early out if everything is allowed
-    if ctx.cfg.unknown_registry == LintLevel::Allow && ctx.cfg.unknown_git == LintLevel::Allow {
+    if ctx.cfg.unknown_registry == LintLevel::Allow
+        && ctx.cfg.unknown_git == LintLevel::Allow
+        && ctx.cfg.deny_patched == LintLevel::Allow
+        && ctx.cfg.multiple_git_revs == LintLevel::Allow
+        && ctx.cfg.detect_name_collision == LintLevel::Allow
+        && ctx.cfg.git_require_locked_rev == LintLevel::Allow
+        && (ctx.cfg.untrusted_owner == LintLevel::Allow || ctx.cfg.owners.is_none())
+        && (ctx.cfg.verify_checksums == LintLevel::Allow || ctx.cfg.checksums.is_none())
+        && ctx.cfg.require_https == LintLevel::Allow
+    {
         return;
     }
 
     let mut sink = sink.into();
 
+    let patched = if ctx.cfg.deny_patched != LintLevel::Allow {
+        ctx.krate_spans.workspace_id.map(|wid| {
+            patches::find(ctx.krates, ctx.files.source(wid))
+                .map(|patched| (wid, patched))
+                .unwrap_or_else(|err| {
+                    log::error!("failed to read patches from the workspace manifest: {err:#}");
+                    (wid, Vec::new())
+                })
+        })
+    } else {
+        None
+    };
+    let mut patch_hits: BitVec = BitVec::repeat(false, ctx.cfg.allowed_patches.len());
+
     // scan through each crate and check the source of it
 
     // keep track of which sources are actually encountered, so we can emit a
     // warning if the user has listed a source that no crates are actually using
     let mut source_hits: BitVec = BitVec::repeat(false, ctx.cfg.allowed_sources.len());
     let mut org_hits: BitVec = BitVec::repeat(false, ctx.cfg.allowed_orgs.len());
+    let mut git_host_hits: BitVec = BitVec::repeat(false, ctx.cfg.allowed_git_hosts.len());
+
+    // Group git sources by their repository, so that we can detect when the
+    // same repository is used at different revisions
+    let mut git_repos = std::collections::BTreeMap::<
+        String,
+        std::collections::BTreeMap<(cfg::GitSpec, Option<&str>), Vec<&crate::Krate>>,
+    >::new();
+
+    // Group registry crates by their name, so that we can detect when the
+    // same name is sourced from different registries
+    let mut registry_names = std::collections::BTreeMap::<
+        &str,
+        std::collections::BTreeMap<String, Vec<&crate::Krate>>,
+    >::new();
+
+    let private_registries = if ctx.cfg.allow_private_registries.value {
+        registries::configured(ctx.krates.workspace_root())
+    } else {
+        Vec::new()
+    };
 
     let min_git_spec = ctx.cfg.required_git_spec.as_ref().map(|rgs| {
         (
@@ -38,11 +88,6 @@ pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>)
     });
 
     for krate in ctx.krates.krates() {
-        let source = match &krate.source {
-            Some(source) => source,
-            None => continue,
-        };
-
         let mut pack = Pack::with_kid(Check::Sources, krate.id.clone());
 
         let mut sl = None;
@@ -51,10 +96,139 @@ pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>)
             Label::primary(ctx.krate_spans.lock_id, span.source).with_message("source")
         };
 
+        if let Some((wid, patch)) = patched.as_ref().and_then(|(wid, patched)| {
+            patched
+                .iter()
+                .find(|p| p.krate.id == krate.id)
+                .map(|p| (*wid, p))
+        }) {
+            let root = ctx.krates.workspace_root();
+            if let Some(ind) = ctx
+                .cfg
+                .allowed_patches
+                .iter()
+                .position(|ap| match &ap.value {
+                    cfg::PatchSource::Git(url) => krate.matches_url(url, true),
+                    cfg::PatchSource::Path(path) => {
+                        krate.source.is_none() && krate.manifest_path.starts_with(root.join(path))
+                    }
+                })
+            {
+                patch_hits.as_mut_bitslice().set(ind, true);
+                pack.push(diags::ExplicitlyAllowedSource {
+                    src_label: sl.get_or_insert_with(label),
+                    type_name: "patched",
+                    allow_cfg: CfgCoord {
+                        file: ctx.cfg.file_id,
+                        span: ctx.cfg.allowed_patches[ind].span,
+                    },
+                });
+            } else {
+                pack.push(diags::PatchedSource {
+                    krate,
+                    original: &patch.original,
+                    from: &patch.from,
+                    src_label: sl.get_or_insert_with(label),
+                    patch_cfg: CfgCoord {
+                        file: wid,
+                        span: patch.span,
+                    },
+                    lint_level: ctx.cfg.deny_patched,
+                });
+            }
+        }
+
+        if let Some(owners) = ctx
+            .cfg
+            .owners
+            .as_ref()
+            .filter(|_| ctx.cfg.untrusted_owner != LintLevel::Allow && krate.is_crates_io())
+            .and_then(|owners| owners.get(&krate.name))
+        {
+            if !owners
+                .iter()
+                .any(|owner| is_trusted(owner, &ctx.cfg.trusted_owners))
+            {
+                pack.push(diags::UntrustedOwner {
+                    krate,
+                    owners,
+                    src_label: sl.get_or_insert_with(label),
+                    lint_level: ctx.cfg.untrusted_owner,
+                });
+            }
+        }
+
+        if let Some(checksums) = ctx
+            .cfg
+            .checksums
+            .as_ref()
+            .filter(|_| ctx.cfg.verify_checksums != LintLevel::Allow && krate.is_crates_io())
+            .and_then(|checksums| checksums.get(krate))
+        {
+            if !checksums.locked.eq_ignore_ascii_case(&checksums.index) {
+                pack.push(diags::LockfileChecksumMismatch {
+                    krate,
+                    checksums,
+                    src_label: sl.get_or_insert_with(label),
+                    lint_level: ctx.cfg.verify_checksums,
+                });
+            }
+        }
+
+        let source = match &krate.source {
+            Some(source) => source,
+            None => {
+                if !pack.is_empty() {
+                    sink.push(pack);
+                }
+                continue;
+            }
+        };
+
+        // Even if the source is allowed, plain http can be tampered with
+        if ctx.cfg.require_https != LintLevel::Allow {
+            let url = match source {
+                crate::Source::CratesIo(_) => None,
+                crate::Source::Registry(url) | crate::Source::Sparse(url) => Some(url),
+                crate::Source::Git { url, .. } => Some(url),
+            };
+
+            if url.is_some_and(is_insecure) {
+                pack.push(diags::InsecureSource {
+                    krate,
+                    src_label: sl.get_or_insert_with(label),
+                    lint_level: ctx.cfg.require_https,
+                });
+            }
+        }
+
         // get allowed list of sources to check
         let (lint_level, type_name) = if source.is_registry() {
+            registry_names
+                .entry(krate.name.as_str())
+                .or_default()
+                .entry(source.to_string())
+                .or_default()
+                .push(krate);
+
             (ctx.cfg.unknown_registry, "registry")
         } else if let Some(spec) = source.git_spec() {
+            if let crate::Source::Git {
+                url, spec_value, ..
+            } = source
+            {
+                let mut repo = url.clone();
+                repo.set_query(None);
+                repo.set_fragment(None);
+
+                git_repos
+                    .entry(repo.into())
+                    .or_default()
+                    .entry((spec, spec_value.as_deref()))
+                    .or_default()
+                    .push(krate);
+            }
+
             // Ensure the git source has at least the minimum specification
             if let Some((min, cfg_coord)) = &min_git_spec {
                 if spec < *min {
@@ -67,6 +241,16 @@ pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>)
                 }
             }
 
+            if ctx.cfg.git_require_locked_rev != LintLevel::Allow
+                && !source.git_locked_rev().is_some_and(is_commit)
+            {
+                pack.push(diags::GitRevNotLocked {
+                    krate,
+                    src_label: sl.get_or_insert_with(label),
+                    lint_level: ctx.cfg.git_require_locked_rev,
+                });
+            }
+
             (ctx.cfg.unknown_git, "git")
         } else {
             continue;
@@ -85,6 +269,9 @@ pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>)
             // it's crates.io since that will be a vast majority of crates and
             // is the default, so we might not have a real source location anyways
             if krate.is_crates_io() {
+                if !pack.is_empty() {
+                    sink.push(pack);
+                }
                 continue;
             }
 
@@ -97,6 +284,31 @@ pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>)
                 },
             }
             .into()
+        } else if let Some((registry, _)) = private_registries
+            .iter()
+            .find(|(_, url)| source.is_registry() && krate.matches_url(url, true))
+        {
+            diags::SourceAllowedByPrivateRegistry {
+                src_label: sl.get_or_insert_with(label),
+                registry,
+                allow_cfg: CfgCoord {
+                    file: ctx.cfg.file_id,
+                    span: ctx.cfg.allow_private_registries.span,
+                },
+            }
+            .into()
+        } else if let Some(ind) = ctx.cfg.allowed_git_hosts.iter().position(|host| {
+            matches!(source, crate::Source::Git { url, .. } if is_under_git_host(url, &host.value))
+        }) {
+            git_host_hits.as_mut_bitslice().set(ind, true);
+            diags::SourceAllowedByGitHost {
+                src_label: sl.get_or_insert_with(label),
+                host_cfg: CfgCoord {
+                    file: ctx.cfg.file_id,
+                    span: ctx.cfg.allowed_git_hosts[ind].span,
+                },
+            }
+            .into()
         } else if let Some((orgt, orgname)) = krate.source.as_ref().and_then(|s| {
             let crate::Source::Git { url, .. } = s else {
                 return None;
@@ -147,8 +359,74 @@ pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>)
         sink.push(pack);
     }
 
+    if ctx.cfg.multiple_git_revs != LintLevel::Allow {
+        for (repo, revs) in git_repos {
+            if revs.len() < 2 {
+                continue;
+            }
+
+            let mut pack = Pack::new(Check::Sources);
+            pack.push(diags::MultipleGitRevs {
+                repo: &repo,
+                revs: revs.into_iter().collect(),
+                krate_spans: ctx.krate_spans,
+                lint_level: ctx.cfg.multiple_git_revs,
+            });
+            sink.push(pack);
+        }
+    }
+
+    if ctx.cfg.detect_name_collision != LintLevel::Allow {
+        for (name, registries) in registry_names {
+            if registries.len() < 2 {
+                continue;
+            }
+
+            let mut pack = Pack::new(Check::Sources);
+            pack.push(diags::NameCollision {
+                name,
+                registries: registries.into_iter().collect(),
+                krate_spans: ctx.krate_spans,
+                lint_level: ctx.cfg.detect_name_collision,
+            });
+            sink.push(pack);
+        }
+    }
+
     let mut pack = Pack::new(Check::Sources);
 
+    if ctx.cfg.require_https != LintLevel::Allow {
+        for src in ctx
+            .cfg
+            .allowed_sources
+            .iter()
+            .filter(|src| is_insecure(&src.url.value))
+        {
+            pack.push(diags::InsecureAllowSource {
+                allow_src_cfg: CfgCoord {
+                    span: src.url.span,
+                    file: ctx.cfg.file_id,
+                },
+                lint_level: ctx.cfg.require_https,
+            });
+        }
+
+        for host in ctx
+            .cfg
+            .allowed_git_hosts
+            .iter()
+            .filter(|host| is_insecure(&host.value))
+        {
+            pack.push(diags::InsecureAllowSource {
+                allow_src_cfg: CfgCoord {
+                    span: host.span,
+                    file: ctx.cfg.file_id,
+                },
+                lint_level: ctx.cfg.require_https,
+            });
+        }
+    }
+
     for src in source_hits
         .into_iter()
         .zip(ctx.cfg.allowed_sources.into_iter())
@@ -168,6 +446,19 @@ pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>)
         });
     }
 
+    for ap in patch_hits
+        .into_iter()
+        .zip(ctx.cfg.allowed_patches.into_iter())
+        .filter_map(|(hit, ap)| (!hit && patched.is_some()).then_some(ap))
+    {
+        pack.push(diags::UnmatchedAllowSource {
+            allow_src_cfg: CfgCoord {
+                span: ap.span,
+                file: ctx.cfg.file_id,
+            },
+        });
+    }
+
     for (org_type, orgs) in org_hits
         .into_iter()
         .zip(ctx.cfg.allowed_orgs.into_iter())
@@ -182,12 +473,53 @@ pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>)
         });
     }
 
+    for host in git_host_hits
+        .into_iter()
+        .zip(ctx.cfg.allowed_git_hosts.into_iter())
+        .filter_map(|(hit, host)| (!hit).then_some(host))
+    {
+        pack.push(diags::UnmatchedAllowGitHost {
+            allow_host_cfg: CfgCoord {
+                span: host.span,
+                file: ctx.cfg.file_id,
+            },
+        });
+    }
+
     if !pack.is_empty() {
         sink.push(pack);
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+/// Determines if a git source is hosted under the allowed host, ie. the hosts
+/// are the same and the path segments of the allowed host, if any, are a
+/// prefix of the path segments of the source
+fn is_under_git_host(url: &url::Url, host: &url::Url) -> bool {
+    if url.host() != host.host() || url.port_or_known_default() != host.port_or_known_default() {
+        return false;
+    }
+
+    let segments = |u: &'_ url::Url| {
+        u.path_segments()
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+    };
+
+    segments(url).starts_with(&segments(host))
+}
+
+/// Determines if the url uses plain http, including sparse registries, whose
+/// scheme is `sparse+http`
+#[inline]
+fn is_insecure(url: &url::Url) -> bool {
+    let scheme = url.scheme();
+    scheme == "http" || scheme.ends_with("+http")
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum OrgType {
     Github,
     Gitlab,
@@ -222,3 +554,23 @@ fn get_org(url: &url::Url) -> Option<(OrgType, &str)> {
             .map(|org| (org_type, org))
     })
 }
+
+/// Checks if a crates.io owner is one of the trusted owners, either as a user
+/// or as a team in a trusted GitHub organization, ie. `github:<org>:<team>`
+fn is_trusted(owner: &str, trusted: &[crate::Spanned<String>]) -> bool {
+    let org = owner
+        .strip_prefix("github:")
+        .and_then(|team| team.split_once(':'))
+        .map(|(org, _team)| org);
+
+    trusted.iter().any(|to| {
+        owner.eq_ignore_ascii_case(&to.value)
+            || org.is_some_and(|org| org.eq_ignore_ascii_case(&to.value))
+    })
+}
+
+/// Checks if a locked git revision is a full commit hash, either SHA-1 or
+/// SHA-256
+fn is_commit(rev: &str) -> bool {
+    matches!(rev.len(), 40 | 64) && rev.bytes().all(|b| b.is_ascii_hexdigit())
+}
@@ -45,8 +45,10 @@ impl<'de> Deserialize<'de> for Orgs {
     Default,
     strum::VariantArray,
     strum::VariantNames,
+    serde::Serialize,
 )]
 #[strum(serialize_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
 pub enum GitSpec {
     /// Specifies the `HEAD` of the remote
     #[default]
@@ -89,9 +91,38 @@ pub struct Config {
     /// The list of hosts with optional paths from which one or more git repos
     /// can be sourced.
     pub private: Vec<Spanned<String>>,
+    /// The list of git hosts, with an optional path, eg. an organization,
+    /// under which any git repo can be sourced from
+    pub allow_git_hosts: Vec<Spanned<String>>,
     /// The minimum specification required for git sources. Defaults to allowing
     /// any.
     pub required_git_spec: Option<Spanned<GitSpec>>,
+    /// How to handle crates whose source was changed via `[patch]` or `[replace]`
+    pub deny_patched: LintLevel,
+    /// The git urls or local paths that crates can be patched with
+    pub allow_patched: Vec<Spanned<String>>,
+    /// How to handle the same git repository being used at more than one
+    /// revision
+    pub multiple_git_revs: LintLevel,
+    /// How to handle the same crate name being sourced from more than one
+    /// registry
+    pub detect_name_collision: LintLevel,
+    /// If true, the registries configured in the `[registries]` table of the
+    /// cargo config are allowed
+    pub allow_private_registries: Spanned<bool>,
+    /// How to handle git sources that aren't locked to a specific commit in
+    /// the lockfile
+    pub git_require_locked_rev: LintLevel,
+    /// The crates.io users or GitHub organizations, at least one of which must
+    /// own each crates.io crate
+    pub trusted_owners: Vec<Spanned<String>>,
+    /// How to handle crates.io crates that have none of the trusted owners
+    pub untrusted_owner: LintLevel,
+    /// How to handle crates.io crates whose checksum in the lockfile doesn't
+    /// match the checksum in the crates.io index
+    pub verify_checksums: LintLevel,
+    /// How to handle allowed or encountered sources that use plain `http`
+    pub require_https: LintLevel,
 }
 
 impl<'de> Deserialize<'de> for Config {
@@ -105,7 +136,24 @@ impl<'de> Deserialize<'de> for Config {
         let allow_git = th.optional("allow-git").unwrap_or_default();
         let allow_org = th.optional("allow-org").unwrap_or_default();
         let private = th.optional("private").unwrap_or_default();
+        let allow_git_hosts = th.optional("allow-git-hosts").unwrap_or_default();
         let required_git_spec = th.optional("required-git-spec");
+        let deny_patched = th.optional("deny-patched").unwrap_or(LintLevel::Allow);
+        let allow_patched = th.optional("allow-patched").unwrap_or_default();
+        let multiple_git_revs = th.optional("multiple-git-revs").unwrap_or(LintLevel::Warn);
+        let detect_name_collision = th
+            .optional("detect-name-collision")
+            .unwrap_or(LintLevel::Allow);
+        let allow_private_registries = th
+            .optional_s("allow-private-registries")
+            .unwrap_or(Spanned::new(false));
+        let git_require_locked_rev = th
+            .optional("git-require-locked-rev")
+            .unwrap_or(LintLevel::Allow);
+        let trusted_owners = th.optional("trusted-owners").unwrap_or_default();
+        let untrusted_owner = th.optional("untrusted-owner").unwrap_or(LintLevel::Warn);
+        let verify_checksums = th.optional("verify-checksums").unwrap_or(LintLevel::Allow);
+        let require_https = th.optional("require-https").unwrap_or(LintLevel::Allow);
 
         th.finalize(None)?;
 
@@ -116,7 +164,18 @@ impl<'de> Deserialize<'de> for Config {
             allow_git,
             allow_org,
             private,
+            allow_git_hosts,
             required_git_spec,
+            deny_patched,
+            allow_patched,
+            multiple_git_revs,
+            detect_name_collision,
+            allow_private_registries,
+            git_require_locked_rev,
+            trusted_owners,
+            untrusted_owner,
+            verify_checksums,
+            require_https,
         })
     }
 }
@@ -130,7 +189,18 @@ impl Default for Config {
             allow_git: Vec::new(),
             allow_org: Orgs::default(),
             private: Vec::new(),
+            allow_git_hosts: Vec::new(),
             required_git_spec: None,
+            deny_patched: LintLevel::Allow,
+            allow_patched: Vec::new(),
+            multiple_git_revs: LintLevel::Warn,
+            detect_name_collision: LintLevel::Allow,
+            allow_private_registries: Spanned::new(false),
+            git_require_locked_rev: LintLevel::Allow,
+            trusted_owners: Vec::new(),
+            untrusted_owner: LintLevel::Warn,
+            verify_checksums: LintLevel::Allow,
+            require_https: LintLevel::Allow,
         }
     }
 }
@@ -161,7 +231,13 @@ impl cfg::UnvalidatedConfig for Config {
                 }
             }
 
-            match url::Url::parse(&astr[skip..]) {
+            let parsed = if is_git {
+                crate::parse_git_url(&astr[skip..])
+            } else {
+                url::Url::parse(&astr[skip..])
+            };
+
+            match parsed {
                 Ok(mut url) => {
                     if is_git {
                         crate::normalize_git_url(&mut url);
@@ -206,26 +282,110 @@ impl cfg::UnvalidatedConfig for Config {
             )
             .collect();
 
+        let mut allowed_git_hosts = Vec::with_capacity(self.allow_git_hosts.len());
+        for host in self.allow_git_hosts {
+            // Hosts are usually specified without a scheme, eg. `github.com/org`
+            let astr = host.value.strip_prefix("git+").unwrap_or(&host.value);
+            let parsed = if astr.contains("://") {
+                crate::parse_git_url(astr)
+            } else {
+                url::Url::parse(&format!("https://{astr}"))
+            };
+
+            match parsed {
+                Ok(mut url) => {
+                    crate::normalize_git_url(&mut url);
+                    url.set_query(None);
+                    url.set_fragment(None);
+
+                    allowed_git_hosts.push(UrlSpan {
+                        value: url,
+                        span: host.span,
+                    });
+                }
+                Err(pe) => {
+                    ctx.push(
+                        Diagnostic::error()
+                            .with_message("failed to parse git host")
+                            .with_labels(vec![
+                                Label::primary(ctx.cfg_id, host.span).with_message(pe.to_string())
+                            ]),
+                    );
+                }
+            }
+        }
+
+        let mut allowed_patches = Vec::with_capacity(self.allow_patched.len());
+        for ap in self.allow_patched {
+            // Anything that looks like a url is treated as a git source, as
+            // crates can only be patched with git or path sources
+            let source = if ap.value.contains("://") || ap.value.contains('@') {
+                match crate::parse_git_url(ap.value.strip_prefix("git+").unwrap_or(&ap.value)) {
+                    Ok(mut url) => {
+                        crate::normalize_git_url(&mut url);
+                        PatchSource::Git(url)
+                    }
+                    Err(pe) => {
+                        ctx.push(
+                            Diagnostic::error()
+                                .with_message("failed to parse url")
+                                .with_labels(vec![Label::primary(ctx.cfg_id, ap.span)
+                                    .with_message(pe.to_string())]),
+                        );
+                        continue;
+                    }
+                }
+            } else {
+                PatchSource::Path(ap.value.into())
+            };
+
+            allowed_patches.push(Spanned::with_span(source, ap.span));
+        }
+
         ValidConfig {
             file_id: ctx.cfg_id,
             unknown_registry: self.unknown_registry,
             unknown_git: self.unknown_git,
             allowed_sources,
             allowed_orgs,
+            allowed_git_hosts,
             required_git_spec: self.required_git_spec,
+            deny_patched: self.deny_patched,
+            allowed_patches,
+            multiple_git_revs: self.multiple_git_revs,
+            detect_name_collision: self.detect_name_collision,
+            allow_private_registries: self.allow_private_registries,
+            git_require_locked_rev: self.git_require_locked_rev,
+            trusted_owners: self.trusted_owners,
+            untrusted_owner: self.untrusted_owner,
+            owners: None,
+            verify_checksums: self.verify_checksums,
+            checksums: None,
+            require_https: self.require_https,
         }
     }
 }
 
 pub type UrlSpan = Spanned<url::Url>;
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, serde::Serialize)]
 pub struct UrlSource {
     pub url: UrlSpan,
     pub exact: bool,
 }
 
+/// A source that crates are allowed to be patched with
+#[derive(PartialEq, Eq, Debug, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PatchSource {
+    /// A git repository
+    Git(url::Url),
+    /// A local path, relative to the workspace root
+    Path(crate::PathBuf),
+}
+
 #[doc(hidden)]
+#[derive(serde::Serialize)]
 #[cfg_attr(test, derive(Debug))]
 pub struct ValidConfig {
     pub file_id: FileId,
@@ -234,7 +394,26 @@ pub struct ValidConfig {
     pub unknown_git: LintLevel,
     pub allowed_sources: Vec<UrlSource>,
     pub allowed_orgs: Vec<(OrgType, Spanned<String>)>,
+    pub allowed_git_hosts: Vec<UrlSpan>,
     pub required_git_spec: Option<Spanned<GitSpec>>,
+    pub deny_patched: LintLevel,
+    pub allowed_patches: Vec<Spanned<PatchSource>>,
+    pub multiple_git_revs: LintLevel,
+    pub detect_name_collision: LintLevel,
+    pub allow_private_registries: Spanned<bool>,
+    pub git_require_locked_rev: LintLevel,
+    pub trusted_owners: Vec<Spanned<String>>,
+    pub untrusted_owner: LintLevel,
+    /// The owners of each crates.io crate, retrieved by the binary if there
+    /// are trusted owners to check against
+    #[serde(skip)]
+    pub owners: Option<super::CrateOwners>,
+    pub verify_checksums: LintLevel,
+    /// The lockfile and index checksums of each crates.io crate, retrieved by
+    /// the binary if checksums are verified
+    #[serde(skip)]
+    pub checksums: Option<super::CrateChecksums>,
+    pub require_https: LintLevel,
 }
 
 #[cfg(test)]
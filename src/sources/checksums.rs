@@ -0,0 +1,134 @@
+//! Retrieval of the checksums of crates.io crates from both the lockfile and
+//! the locally cached crates.io index, used to detect lockfiles that have
+//! been tampered with
+
+use anyhow::Context as _;
+use std::collections::BTreeMap;
+
+/// The checksum of a crate recorded in the lockfile, and the one recorded in
+/// the crates.io index
+#[derive(Debug)]
+pub struct Checksums {
+    pub locked: String,
+    pub index: String,
+}
+
+/// The checksums of crates.io crates, keyed by crate name and version
+#[derive(Default, Debug)]
+pub struct CrateChecksums {
+    checksums: BTreeMap<(String, semver::Version), Checksums>,
+}
+
+impl CrateChecksums {
+    /// Gets the checksums of every crates.io crate in the graph, using the
+    /// lockfile checksums set by [`set_locked_checksums`] and the index
+    /// entries already loaded for the advisories check
+    ///
+    /// The index is never fetched, crates without a cached index entry, or
+    /// without a checksum in the lockfile, are logged and omitted
+    pub fn load(krates: &crate::Krates, indices: &crate::advisories::Indices<'_>) -> Self {
+        let mut checksums = BTreeMap::new();
+        let mut missing = 0;
+
+        for krate in krates.krates().filter(|krate| krate.is_crates_io()) {
+            let Some(locked) = krate.checksum.get() else {
+                log::debug!("crate '{krate}' has no checksum in the lockfile");
+                continue;
+            };
+
+            let Some(index) = indices.checksum(krate) else {
+                missing += 1;
+                continue;
+            };
+
+            checksums.insert(
+                (krate.name.clone(), krate.version.clone()),
+                Checksums {
+                    locked: locked.to_string(),
+                    index: index.to_owned(),
+                },
+            );
+        }
+
+        if missing > 0 {
+            log::warn!("unable to verify the checksums of {missing} crate(s) that are not in the local crates.io index cache");
+        }
+
+        Self { checksums }
+    }
+
+    /// Gets the checksums of the specified crate, if they could be retrieved
+    #[inline]
+    pub fn get(&self, krate: &crate::Krate) -> Option<&Checksums> {
+        self.checksums
+            .get(&(krate.name.clone(), krate.version.clone()))
+    }
+}
+
+impl FromIterator<((String, semver::Version), Checksums)> for CrateChecksums {
+    fn from_iter<I: IntoIterator<Item = ((String, semver::Version), Checksums)>>(iter: I) -> Self {
+        Self {
+            checksums: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Fills in [`crate::Krate::checksum`] for every crates.io crate in the graph
+/// from the workspace's lockfile, so that package specs with a `checksum` can
+/// be matched
+pub fn set_locked_checksums(krates: &crate::Krates) -> anyhow::Result<()> {
+    let lock_path = krates.workspace_root().join("Cargo.lock");
+    let contents = std::fs::read_to_string(&lock_path)
+        .with_context(|| format!("failed to read '{lock_path}'"))?;
+    let locked =
+        parse_lockfile(&contents).with_context(|| format!("failed to parse '{lock_path}'"))?;
+
+    for krate in krates.krates().filter(|krate| krate.is_crates_io()) {
+        let Some(checksum) = locked.get(&(krate.name.clone(), krate.version.clone())) else {
+            continue;
+        };
+
+        match checksum.parse() {
+            Ok(checksum) => {
+                let _ = krate.checksum.set(checksum);
+            }
+            Err(_) => {
+                log::warn!("crate '{krate}' has an invalid checksum '{checksum}' in the lockfile");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Gets the checksum of every crates.io package in the lockfile
+fn parse_lockfile(contents: &str) -> anyhow::Result<BTreeMap<(String, semver::Version), String>> {
+    let lockfile = toml_span::parse(contents)?;
+
+    let Some(packages) = lockfile.pointer("/package").and_then(|p| p.as_array()) else {
+        return Ok(BTreeMap::new());
+    };
+
+    Ok(packages
+        .iter()
+        .filter_map(|pkg| {
+            let pkg = pkg.as_table()?;
+            let field = |key: &str| pkg.get(key).and_then(|v| v.as_str());
+
+            // Cargo always records crates.io with its git url, regardless of
+            // whether the sparse protocol is used
+            let source = field("source")?;
+            if source.strip_prefix("registry+") != Some(tame_index::CRATES_IO_INDEX)
+                && source != tame_index::CRATES_IO_HTTP_INDEX
+            {
+                return None;
+            }
+
+            let version = field("version")?.parse().ok()?;
+            Some((
+                (field("name")?.to_owned(), version),
+                field("checksum")?.to_owned(),
+            ))
+        })
+        .collect())
+}
@@ -1,6 +1,6 @@
 use crate::{
-    diag::{CfgCoord, Diag, Diagnostic, Label, Severity},
-    LintLevel,
+    diag::{CfgCoord, Diag, Diagnostic, GraphNode, KrateSpans, Label, Severity},
+    Krate, LintLevel,
 };
 
 #[derive(
@@ -22,6 +22,16 @@ pub enum Code {
     SourceNotAllowed,
     UnmatchedSource,
     UnmatchedOrganization,
+    PatchedSource,
+    MultipleGitRevs,
+    AllowedByPrivateRegistry,
+    NameCollision,
+    UntrustedOwner,
+    GitRevNotLocked,
+    LockfileChecksumMismatch,
+    InsecureSource,
+    AllowedByGitHost,
+    UnmatchedGitHost,
 }
 
 impl From<Code> for String {
@@ -94,6 +104,52 @@ impl<'a> From<SourceAllowedByOrg<'a>> for Diag {
     }
 }
 
+pub(crate) struct SourceAllowedByGitHost<'a> {
+    pub(crate) src_label: &'a Label,
+    pub(crate) host_cfg: CfgCoord,
+}
+
+impl<'a> From<SourceAllowedByGitHost<'a>> for Diag {
+    fn from(sabgh: SourceAllowedByGitHost<'a>) -> Self {
+        Diagnostic::new(Severity::Note)
+            .with_message("source allowed by git host allowance")
+            .with_code(Code::AllowedByGitHost)
+            .with_labels(vec![
+                sabgh.src_label.clone(),
+                sabgh
+                    .host_cfg
+                    .into_label()
+                    .with_message("git host allowance"),
+            ])
+            .into()
+    }
+}
+
+pub(crate) struct SourceAllowedByPrivateRegistry<'a> {
+    pub(crate) src_label: &'a Label,
+    pub(crate) registry: &'a str,
+    pub(crate) allow_cfg: CfgCoord,
+}
+
+impl<'a> From<SourceAllowedByPrivateRegistry<'a>> for Diag {
+    fn from(sabpr: SourceAllowedByPrivateRegistry<'a>) -> Self {
+        Diagnostic::new(Severity::Note)
+            .with_message(format!(
+                "source allowed by configured private registry '{}'",
+                sabpr.registry
+            ))
+            .with_code(Code::AllowedByPrivateRegistry)
+            .with_labels(vec![
+                sabpr.src_label.clone(),
+                sabpr
+                    .allow_cfg
+                    .into_label()
+                    .with_message("private registry allowance"),
+            ])
+            .into()
+    }
+}
+
 pub(crate) struct SourceNotExplicitlyAllowed<'a> {
     pub(crate) src_label: &'a Label,
     pub(crate) type_name: &'a str,
@@ -113,6 +169,142 @@ impl<'a> From<SourceNotExplicitlyAllowed<'a>> for Diag {
     }
 }
 
+pub(crate) struct PatchedSource<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) original: &'a str,
+    pub(crate) from: &'a str,
+    pub(crate) src_label: &'a Label,
+    pub(crate) patch_cfg: CfgCoord,
+    pub(crate) lint_level: LintLevel,
+}
+
+impl<'a> From<PatchedSource<'a>> for Diag {
+    fn from(ps: PatchedSource<'a>) -> Self {
+        Diagnostic::new(ps.lint_level.into())
+            .with_message(format!(
+                "crate '{}' is a patched replacement for '{}' from {}",
+                ps.krate, ps.original, ps.from,
+            ))
+            .with_code(Code::PatchedSource)
+            .with_labels(vec![
+                ps.src_label.clone(),
+                ps.patch_cfg.into_label().with_message("patched here"),
+            ])
+            .into()
+    }
+}
+
+pub(crate) struct GitRevNotLocked<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) src_label: &'a Label,
+    pub(crate) lint_level: LintLevel,
+}
+
+impl<'a> From<GitRevNotLocked<'a>> for Diag {
+    fn from(grnl: GitRevNotLocked<'a>) -> Self {
+        Diagnostic::new(grnl.lint_level.into())
+            .with_message(format!(
+                "git source for crate '{}' is not locked to a commit",
+                grnl.krate
+            ))
+            .with_code(Code::GitRevNotLocked)
+            .with_labels(vec![grnl.src_label.clone()])
+            .with_notes(vec![
+                "cargo always records the resolved commit of git sources, so the lockfile entry may be malformed".to_owned(),
+            ])
+            .into()
+    }
+}
+
+pub(crate) struct InsecureSource<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) src_label: &'a Label,
+    pub(crate) lint_level: LintLevel,
+}
+
+impl<'a> From<InsecureSource<'a>> for Diag {
+    fn from(is: InsecureSource<'a>) -> Self {
+        Diagnostic::new(is.lint_level.into())
+            .with_message(format!(
+                "source for crate '{}' uses plain http rather than https",
+                is.krate
+            ))
+            .with_code(Code::InsecureSource)
+            .with_labels(vec![is.src_label.clone()])
+            .into()
+    }
+}
+
+pub(crate) struct InsecureAllowSource {
+    pub(crate) allow_src_cfg: CfgCoord,
+    pub(crate) lint_level: LintLevel,
+}
+
+impl From<InsecureAllowSource> for Diag {
+    fn from(ias: InsecureAllowSource) -> Self {
+        Diagnostic::new(ias.lint_level.into())
+            .with_message("allowed source uses plain http rather than https")
+            .with_code(Code::InsecureSource)
+            .with_labels(vec![ias
+                .allow_src_cfg
+                .into_label()
+                .with_message("insecure source allowance")])
+            .into()
+    }
+}
+
+pub(crate) struct UntrustedOwner<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) owners: &'a [String],
+    pub(crate) src_label: &'a Label,
+    pub(crate) lint_level: LintLevel,
+}
+
+impl<'a> From<UntrustedOwner<'a>> for Diag {
+    fn from(uo: UntrustedOwner<'a>) -> Self {
+        let notes = if uo.owners.is_empty() {
+            vec!["crate has no owners".to_owned()]
+        } else {
+            vec![format!("owners: {}", uo.owners.join(", "))]
+        };
+
+        Diagnostic::new(uo.lint_level.into())
+            .with_message(format!(
+                "crate '{}' is not owned by a trusted owner",
+                uo.krate
+            ))
+            .with_code(Code::UntrustedOwner)
+            .with_labels(vec![uo.src_label.clone()])
+            .with_notes(notes)
+            .into()
+    }
+}
+
+pub(crate) struct LockfileChecksumMismatch<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) checksums: &'a super::Checksums,
+    pub(crate) src_label: &'a Label,
+    pub(crate) lint_level: LintLevel,
+}
+
+impl<'a> From<LockfileChecksumMismatch<'a>> for Diag {
+    fn from(cm: LockfileChecksumMismatch<'a>) -> Self {
+        Diagnostic::new(cm.lint_level.into())
+            .with_message(format!(
+                "checksum of crate '{}' in the lockfile does not match the crates.io index",
+                cm.krate
+            ))
+            .with_code(Code::LockfileChecksumMismatch)
+            .with_labels(vec![cm.src_label.clone()])
+            .with_notes(vec![
+                format!("lockfile checksum: {}", cm.checksums.locked),
+                format!("index checksum: {}", cm.checksums.index),
+                "the lockfile may have been tampered with".to_owned(),
+            ])
+            .into()
+    }
+}
+
 pub(crate) struct UnmatchedAllowSource {
     pub(crate) allow_src_cfg: CfgCoord,
 }
@@ -150,3 +342,141 @@ impl From<UnmatchedAllowOrg> for Diag {
             .into()
     }
 }
+
+pub(crate) struct UnmatchedAllowGitHost {
+    pub(crate) allow_host_cfg: CfgCoord,
+}
+
+impl From<UnmatchedAllowGitHost> for Diag {
+    fn from(uagh: UnmatchedAllowGitHost) -> Self {
+        Diagnostic::new(Severity::Warning)
+            .with_message("allowed git host was not encountered")
+            .with_code(Code::UnmatchedGitHost)
+            .with_labels(vec![uagh
+                .allow_host_cfg
+                .into_label()
+                .with_message("no git source fell under this host")])
+            .into()
+    }
+}
+
+pub(crate) struct MultipleGitRevs<'a> {
+    pub(crate) repo: &'a str,
+    /// Each distinct specifier the repository is used with, and the crates
+    /// sourced with it
+    pub(crate) revs: Vec<((super::cfg::GitSpec, Option<&'a str>), Vec<&'a Krate>)>,
+    pub(crate) krate_spans: &'a KrateSpans<'a>,
+    pub(crate) lint_level: LintLevel,
+}
+
+impl<'a> From<MultipleGitRevs<'a>> for Diag {
+    fn from(mgr: MultipleGitRevs<'a>) -> Self {
+        use super::cfg::GitSpec;
+
+        let rev = |spec: GitSpec, value: Option<&str>| match (spec, value) {
+            (GitSpec::Any, _) | (_, None) => "the default branch".to_owned(),
+            (spec, Some(value)) => format!("{spec} '{value}'"),
+        };
+
+        let mut labels = Vec::new();
+        let mut notes = Vec::new();
+        let mut graph_nodes = smallvec::SmallVec::new();
+
+        for ((spec, value), krates) in &mgr.revs {
+            let rev = rev(*spec, *value);
+
+            for krate in krates {
+                labels.push(
+                    Label::primary(
+                        mgr.krate_spans.lock_id,
+                        mgr.krate_spans.lock_span(&krate.id).source,
+                    )
+                    .with_message(rev.clone()),
+                );
+                graph_nodes.push(GraphNode {
+                    kid: krate.id.clone(),
+                    feature: None,
+                });
+            }
+
+            notes.push(format!(
+                "{rev}: {}",
+                krates
+                    .iter()
+                    .map(|krate| format!("{} {}", krate.name, krate.version))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        let mut diag: Diag = Diagnostic::new(mgr.lint_level.into())
+            .with_message(format!(
+                "found {} different revisions of git repository '{}'",
+                mgr.revs.len(),
+                mgr.repo,
+            ))
+            .with_code(Code::MultipleGitRevs)
+            .with_labels(labels)
+            .with_notes(notes)
+            .into();
+
+        diag.graph_nodes = graph_nodes;
+        diag
+    }
+}
+
+pub(crate) struct NameCollision<'a> {
+    pub(crate) name: &'a str,
+    /// Each distinct registry the crate name is sourced from, and the crates
+    /// sourced from it
+    pub(crate) registries: Vec<(String, Vec<&'a Krate>)>,
+    pub(crate) krate_spans: &'a KrateSpans<'a>,
+    pub(crate) lint_level: LintLevel,
+}
+
+impl<'a> From<NameCollision<'a>> for Diag {
+    fn from(nc: NameCollision<'a>) -> Self {
+        let mut labels = Vec::new();
+        let mut notes = Vec::new();
+        let mut graph_nodes = smallvec::SmallVec::new();
+
+        for (registry, krates) in &nc.registries {
+            for krate in krates {
+                labels.push(
+                    Label::primary(
+                        nc.krate_spans.lock_id,
+                        nc.krate_spans.lock_span(&krate.id).source,
+                    )
+                    .with_message(registry.clone()),
+                );
+                graph_nodes.push(GraphNode {
+                    kid: krate.id.clone(),
+                    feature: None,
+                });
+            }
+
+            notes.push(format!(
+                "{registry}: {}",
+                krates
+                    .iter()
+                    .map(|krate| format!("{} {}", krate.name, krate.version))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        let mut diag: Diag = Diagnostic::new(nc.lint_level.into())
+            .with_message(format!(
+                "crate name '{}' is sourced from {} different registries",
+                nc.name,
+                nc.registries.len(),
+            ))
+            .with_code(Code::NameCollision)
+            .with_labels(labels)
+            .with_notes(notes)
+            .into();
+
+        diag.graph_nodes = graph_nodes;
+        diag
+    }
+}
@@ -0,0 +1,165 @@
+//! Retrieval of the owners of crates.io crates via the crates.io API, used to
+//! check crates against the configured `trusted-owners`
+
+use anyhow::Context as _;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::{Duration, Instant},
+};
+
+/// How long the owners of a crate are cached before being fetched again
+const CACHE_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The crates.io [data access policy](https://crates.io/data-access) asks that
+/// automated tools make at most 1 request per second
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(serde::Deserialize)]
+struct OwnersResponse {
+    users: Vec<Owner>,
+}
+
+#[derive(serde::Deserialize)]
+struct Owner {
+    /// The login of the owner, which for teams is `github:<org>:<team>`
+    login: String,
+}
+
+/// The owners of crates.io crates, keyed by crate name
+#[derive(Default, Debug)]
+pub struct CrateOwners {
+    owners: BTreeMap<String, Vec<String>>,
+}
+
+impl CrateOwners {
+    /// Gets the owners of every crates.io crate in the graph
+    ///
+    /// Owners are cached in `$CARGO_HOME/crate-owners` for a day, crates
+    /// whose owners can't be retrieved are logged and omitted
+    pub fn fetch(krates: &crate::Krates) -> Self {
+        let names: BTreeSet<_> = krates
+            .krates()
+            .filter(|krate| krate.is_crates_io())
+            .map(|krate| krate.name.as_str())
+            .collect();
+
+        let cache_dir = home::cargo_home()
+            .ok()
+            .and_then(|ch| crate::utf8path(ch.join("crate-owners")).ok());
+
+        let client = match crate::http_client() {
+            Ok(client) => client,
+            Err(err) => {
+                log::error!("unable to fetch crate owners: {err:#}");
+                return Self::default();
+            }
+        };
+
+        let mut owners = BTreeMap::new();
+        let mut pending = Vec::new();
+
+        for name in names {
+            let cache_path = cache_dir.as_ref().map(|cd| cd.join(format!("{name}.json")));
+
+            if let Some(cached) = cache_path.as_deref().and_then(read_cache) {
+                owners.insert(name.to_owned(), cached);
+            } else {
+                pending.push((name, cache_path));
+            }
+        }
+
+        // Requests are rate limited, so a cold cache can take several minutes
+        // for a large graph, which is worth a warning rather than appearing
+        // to hang
+        if !pending.is_empty() {
+            let cost = REQUEST_INTERVAL * (pending.len() as u32 - 1);
+            let level = if cost >= Duration::from_secs(30) {
+                log::Level::Warn
+            } else {
+                log::Level::Info
+            };
+
+            log::log!(
+                level,
+                "fetching the owners of {} crate(s) from crates.io, this will take at least {}s",
+                pending.len(),
+                cost.as_secs()
+            );
+        }
+
+        let mut last_request = None::<Instant>;
+
+        for (name, cache_path) in pending {
+            if let Some(wait) =
+                last_request.and_then(|lr| REQUEST_INTERVAL.checked_sub(lr.elapsed()))
+            {
+                std::thread::sleep(wait);
+            }
+            last_request = Some(Instant::now());
+
+            match fetch_owners(&client, name) {
+                Ok((logins, body)) => {
+                    if let Some(cache_path) = &cache_path {
+                        if let Err(err) = std::fs::create_dir_all(cache_path.parent().unwrap())
+                            .and_then(|_| std::fs::write(cache_path, body))
+                        {
+                            log::warn!("failed to cache owners to '{cache_path}': {err}");
+                        }
+                    }
+
+                    owners.insert(name.to_owned(), logins);
+                }
+                Err(err) => {
+                    log::warn!("failed to fetch owners of crate '{name}': {err:#}");
+                }
+            }
+        }
+
+        Self { owners }
+    }
+
+    /// Gets the owners of the specified crate, if they could be retrieved
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&[String]> {
+        self.owners.get(name).map(Vec::as_slice)
+    }
+}
+
+impl FromIterator<(String, Vec<String>)> for CrateOwners {
+    fn from_iter<I: IntoIterator<Item = (String, Vec<String>)>>(iter: I) -> Self {
+        Self {
+            owners: iter.into_iter().collect(),
+        }
+    }
+}
+
+fn parse_owners(body: &str) -> anyhow::Result<Vec<String>> {
+    let res: OwnersResponse = serde_json::from_str(body)?;
+    Ok(res.users.into_iter().map(|owner| owner.login).collect())
+}
+
+fn read_cache(path: &crate::Path) -> Option<Vec<String>> {
+    let modified = std::fs::metadata(path).and_then(|md| md.modified()).ok()?;
+    if modified
+        .elapsed()
+        .map_or(true, |elapsed| elapsed > CACHE_DURATION)
+    {
+        return None;
+    }
+
+    parse_owners(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+fn fetch_owners(
+    client: &reqwest::blocking::Client,
+    name: &str,
+) -> anyhow::Result<(Vec<String>, String)> {
+    let body = client
+        .get(format!("https://crates.io/api/v1/crates/{name}/owners"))
+        .send()?
+        .error_for_status()?
+        .text()?;
+
+    let owners = parse_owners(&body).context("failed to parse owners response")?;
+    Ok((owners, body))
+}
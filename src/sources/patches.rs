@@ -0,0 +1,99 @@
+//! Detection of crates whose source was changed via `[patch]` or `[replace]`
+//! in the workspace root manifest
+
+use crate::{Krate, Krates, Span};
+use toml_span::value::ValueInner;
+
+/// A crate in the graph that replaced another crate
+pub(crate) struct Patched<'k> {
+    pub(crate) krate: &'k Krate,
+    /// The name of the crate that was replaced
+    pub(crate) original: String,
+    /// Where the original crate comes from, eg. `crates.io`
+    pub(crate) from: String,
+    /// The span of the key of the `[patch]` or `[replace]` entry
+    pub(crate) span: Span,
+}
+
+/// Finds all of the crates in the graph that are the result of a `[patch]`
+/// or `[replace]` in the specified workspace root manifest
+///
+/// Patches can only be to git or path sources, so registry crates are never
+/// considered patched
+pub(crate) fn find<'k>(krates: &'k Krates, manifest: &str) -> anyhow::Result<Vec<Patched<'k>>> {
+    let mut root = toml_span::parse(manifest)?;
+
+    let mut patched = Vec::new();
+    let mut add = |name: &str, version: Option<&str>, original: &str, from: String, span: Span| {
+        patched.extend(
+            krates
+                .krates_by_name(name)
+                .filter(|km| {
+                    km.krate.source.as_ref().map_or(true, |src| src.is_git())
+                        && version.map_or(true, |v| km.krate.version.to_string() == v)
+                })
+                .map(|km| Patched {
+                    krate: km.krate,
+                    original: original.to_owned(),
+                    from: from.clone(),
+                    span,
+                }),
+        );
+    };
+
+    // The package name of the replacement can differ from the name of the
+    // crate it is replacing
+    let package_name = |value: &toml_span::Value<'_>| {
+        value
+            .as_table()
+            .and_then(|t| t.get("package"))
+            .and_then(|p| p.as_str())
+            .map(String::from)
+    };
+
+    if let Some(ValueInner::Table(patches)) = root.pointer_mut("/patch").map(|p| p.take()) {
+        for (registry, table) in patches {
+            let from = if registry.name == "crates-io" {
+                "crates.io".to_owned()
+            } else {
+                format!("'{}'", registry.name)
+            };
+
+            let Some(table) = table.as_table() else {
+                continue;
+            };
+
+            for (key, value) in table {
+                let name = package_name(value);
+                add(
+                    name.as_deref().unwrap_or(&key.name),
+                    None,
+                    &key.name,
+                    from.clone(),
+                    key.span,
+                );
+            }
+        }
+    }
+
+    if let Some(ValueInner::Table(replaces)) = root.pointer_mut("/replace").map(|p| p.take()) {
+        for (key, value) in replaces {
+            // Replacements are keyed by a package id spec, eg. `foo:1.0.0`
+            let (original, version) = match key.name.split_once([':', '@']) {
+                Some((name, version)) => (name, Some(version)),
+                None => (key.name.as_ref(), None),
+            };
+
+            let name = package_name(&value);
+            add(
+                name.as_deref().unwrap_or(original),
+                version,
+                original,
+                "crates.io".to_owned(),
+                key.span,
+            );
+        }
+    }
+
+    Ok(patched)
+}
@@ -0,0 +1,184 @@
+//! Discovery of the registries configured in the `[registries]` table of the
+//! cargo configuration, see <https://doc.rust-lang.org/cargo/reference/config.html#registries>
+
+use crate::{Path, PathBuf};
+use std::collections::BTreeMap;
+
+/// Gets the name and index url of every registry configured for the
+/// workspace rooted at the specified path
+///
+/// Like cargo, configuration files closer to the workspace root take
+/// precedence over ones further up the directory tree, followed by the
+/// configuration in `$CARGO_HOME`, while `CARGO_REGISTRIES_<name>_INDEX`
+/// environment variables take precedence over all of them
+pub(crate) fn configured(root: &Path) -> Vec<(String, url::Url)> {
+    let cargo_home = home::cargo_home()
+        .ok()
+        .and_then(|ch| crate::utf8path(ch).ok());
+
+    let config_dirs = root
+        .ancestors()
+        .map(|dir| dir.join(".cargo"))
+        .chain(cargo_home);
+
+    read_configured(std::env::vars(), config_dirs)
+}
+
+/// Gets the registries from the environment variables and the cargo
+/// configuration in each directory, in order of precedence
+fn read_configured(
+    vars: impl Iterator<Item = (String, String)>,
+    config_dirs: impl Iterator<Item = PathBuf>,
+) -> Vec<(String, url::Url)> {
+    let mut registries = BTreeMap::new();
+
+    for (key, value) in vars {
+        let Some(name) = key
+            .strip_prefix("CARGO_REGISTRIES_")
+            .and_then(|k| k.strip_suffix("_INDEX"))
+        else {
+            continue;
+        };
+
+        registries.insert(name.to_lowercase().replace('_', "-"), value);
+    }
+
+    for dir in config_dirs {
+        // cargo prefers `config.toml`, but still supports the extensionless
+        // `config` for backwards compatibility
+        let Some((path, contents)) = ["config.toml", "config"].into_iter().find_map(|name| {
+            let path = dir.join(name);
+            std::fs::read_to_string(&path).ok().map(|c| (path, c))
+        }) else {
+            continue;
+        };
+
+        if let Err(err) = read_registries(&contents, &mut registries) {
+            log::warn!("failed to read registries from '{path}': {err}");
+        }
+    }
+
+    registries
+        .into_iter()
+        .filter_map(|(name, index)| {
+            // The index url can be prefixed with the protocol, eg. `sparse+`,
+            // which is irrelevant when comparing against crate sources
+            let url = index.strip_prefix("sparse+").unwrap_or(&index);
+            match url::Url::parse(url) {
+                Ok(url) => Some((name, url)),
+                Err(err) => {
+                    log::warn!("failed to parse index url '{index}' for registry '{name}': {err}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+fn read_registries(
+    contents: &str,
+    registries: &mut BTreeMap<String, String>,
+) -> Result<(), toml_span::DeserError> {
+    let root = toml_span::parse(contents)?;
+
+    let Some(table) = root
+        .pointer("/registries")
+        .and_then(|registries| registries.as_table())
+    else {
+        return Ok(());
+    };
+
+    for (name, registry) in table {
+        let Some(index) = registry
+            .as_table()
+            .and_then(|r| r.get("index"))
+            .and_then(|i| i.as_str())
+        else {
+            continue;
+        };
+
+        registries
+            .entry(name.name.to_string())
+            .or_insert_with(|| index.to_owned());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::PathBuf;
+
+    /// Ensures registries are read from the environment and the nearest
+    /// configuration, that the names of environment variables are mapped to
+    /// the registry name, and that the protocol prefix is stripped
+    #[test]
+    fn reads_configured_registries() {
+        let td = tempfile::tempdir().unwrap();
+        let root = PathBuf::from_path_buf(td.path().to_owned()).unwrap();
+
+        let write = |path: &str, contents: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+
+        write(
+            "workspace/.cargo/config.toml",
+            r#"
+[registries.nearest]
+index = "sparse+https://nearest.example.com/index/"
+
+[registries.shadowed]
+index = "https://workspace.example.com/index"
+"#,
+        );
+        write(
+            ".cargo/config",
+            r#"
+[registries.shadowed]
+index = "https://parent.example.com/index"
+
+[registries.from-env]
+index = "https://config.example.com/index"
+
+[registries.parent]
+index = "https://parent.example.com/index"
+"#,
+        );
+        write(
+            "cargo-home/config.toml",
+            "[registries.home]\nindex = \"https://home.example.com/index\"\n",
+        );
+
+        let vars = [
+            (
+                "CARGO_REGISTRIES_FROM_ENV_INDEX".to_owned(),
+                "https://env.example.com/index".to_owned(),
+            ),
+            ("CARGO_REGISTRY_TOKEN".to_owned(), "secret".to_owned()),
+        ];
+        let config_dirs = [
+            root.join("workspace/.cargo"),
+            root.join(".cargo"),
+            root.join("cargo-home"),
+        ];
+
+        let registries: Vec<_> = super::read_configured(vars.into_iter(), config_dirs.into_iter())
+            .into_iter()
+            .map(|(name, url)| (name, url.to_string()))
+            .collect();
+
+        assert_eq!(
+            registries,
+            [
+                ("from-env", "https://env.example.com/index"),
+                ("home", "https://home.example.com/index"),
+                ("nearest", "https://nearest.example.com/index/"),
+                ("parent", "https://parent.example.com/index"),
+                ("shadowed", "https://workspace.example.com/index"),
+            ]
+            .map(|(name, url)| (name.to_owned(), url.to_owned()))
+        );
+    }
+}
@@ -46,8 +46,13 @@ impl<'k> KrateGather<'k> {
             kb.include_targets(self.targets.iter().map(|t| (t, vec![])));
         }
 
-        kb.build(cmd, krates::NoneFilter)
-            .expect("failed to build crate graph")
+        let krates = kb
+            .build(cmd, krates::NoneFilter)
+            .expect("failed to build crate graph");
+
+        // Not every test project has a lockfile
+        let _ = crate::sources::set_locked_checksums(&krates);
+        krates
     }
 }
 
@@ -288,6 +293,8 @@ where
                 colorize: false,
                 log_level: log::LevelFilter::Info,
                 files: &ctx.files,
+                progress: None,
+                suggest_fixes: false,
             };
             runner(cctx, tx);
         },
@@ -330,7 +337,10 @@ where
     gathered
         .unwrap()
         .into_iter()
-        .map(|d| diag::diag_to_json(d, &ctx.files, Some(&grapher)))
+        .map(|d| {
+            serde_json::to_value(diag::diag_to_json(d, &ctx.files, Some(&grapher)))
+                .expect("failed to serialize diagnostic")
+        })
         .collect()
 }
 
@@ -370,7 +380,7 @@ macro_rules! overrides {
 
             $crate::diag::DiagnosticOverrides {
                 code_overrides: map,
-                level_overrides: Vec::new(),
+                ..Default::default()
             }
         }
     }
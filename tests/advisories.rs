@@ -1,6 +1,6 @@
 use cargo_deny::{
     advisories::{self, cfg},
-    field_eq, func_name,
+    assert_field_eq, field_eq, func_name,
     test_utils::{self as tu},
     Krates,
 };
@@ -40,6 +40,7 @@ fn load() -> TestCtx {
             "tests/advisory-db".into(),
             vec![],
             advisories::Fetch::Disallow(time::Duration::days(10000)),
+            None,
         )
         .unwrap()
     };
@@ -83,6 +84,42 @@ fn detects_vulnerabilities() {
     insta::assert_json_snapshot!(diag);
 }
 
+/// Ensures a database set loaded once can be shared by checks running
+/// concurrently, rather than each check needing to load its own
+#[test]
+fn shares_dbs_between_checks() {
+    let TestCtx { dbs, krates } = load();
+    let name = func_name!();
+
+    let check = || {
+        let mut diags = tu::gather_diagnostics::<cfg::Config, _, _>(
+            &krates,
+            name,
+            tu::Config::new(""),
+            |ctx, tx| {
+                advisories::check(
+                    ctx,
+                    &dbs,
+                    Option::<advisories::NoneReporter>::None,
+                    None,
+                    tx,
+                );
+            },
+        );
+        diags.sort_by_cached_key(|diag| diag.to_string());
+        diags
+    };
+
+    let (first, second) = std::thread::scope(|s| {
+        let first = s.spawn(check);
+        let second = s.spawn(check);
+        (first.join().unwrap(), second.join().unwrap())
+    });
+
+    assert!(find_by_code(&first, "RUSTSEC-2019-0001").is_some());
+    assert_eq!(first, second);
+}
+
 /// Validates we emit diagnostics when an unmaintained advisory is detected
 #[test]
 fn detects_unmaintained() {
@@ -127,6 +164,39 @@ fn detects_unsound() {
     insta::assert_json_snapshot!(unsound_diag);
 }
 
+/// Validates each class of informational advisory uses its configured lint
+/// level, while other classes are unaffected
+#[test]
+fn configures_informational_levels() {
+    let TestCtx { dbs, krates } = load();
+
+    let cfg = tu::Config::new("informational = { unsound = 'warn', unmaintained = 'allow' }");
+
+    let diags =
+        tu::gather_diagnostics::<cfg::Config, _, _>(&krates, func_name!(), cfg, |ctx, tx| {
+            advisories::check(
+                ctx,
+                &dbs,
+                Option::<advisories::NoneReporter>::None,
+                None,
+                tx,
+            );
+        });
+
+    let unsound = find_by_code(&diags, "RUSTSEC-2019-0036").unwrap();
+    assert_field_eq!(unsound, "/fields/code", "unsound");
+    assert_field_eq!(unsound, "/fields/severity", "warning");
+
+    let unmaintained = find_by_code(&diags, "RUSTSEC-2016-0004").unwrap();
+    assert_field_eq!(unmaintained, "/fields/code", "unmaintained");
+    assert_field_eq!(unmaintained, "/fields/severity", "note");
+
+    assert!(diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "vulnerability"))
+        .all(|d| field_eq!(d, "/fields/severity", "error")));
+}
+
 /// Validates that advisories that are ignored still have diagnostics emitted for
 /// them, but with 'note' severity
 #[test]
@@ -172,6 +242,170 @@ ignore = [
     insta::assert_json_snapshot!(ignored);
 }
 
+/// Validates vulnerabilities with a CVSS severity below the threshold use the
+/// configured lint level, while ones at or above it are still denied
+#[test]
+fn downgrades_below_severity_threshold() {
+    let TestCtx { dbs, krates } = load();
+
+    let check = |cfg: &str| {
+        let cfg = tu::Config::new(cfg);
+        tu::gather_diagnostics::<cfg::Config, _, _>(&krates, func_name!(), cfg, |ctx, tx| {
+            advisories::check(
+                ctx,
+                &dbs,
+                Option::<advisories::NoneReporter>::None,
+                None,
+                tx,
+            );
+        })
+    };
+
+    // RUSTSEC-2019-0001 has a CVSS score of 7.5, ie. high
+    let diags = check("severity = { threshold = 'critical', below-threshold = 'warn' }");
+    let diag = find_by_code(&diags, "RUSTSEC-2019-0001").unwrap();
+    assert_field_eq!(diag, "/fields/severity", "warning");
+    assert!(iter_notes(diag)
+        .unwrap()
+        .any(|note| note == "the CVSS severity 'high' is below the threshold 'critical'"));
+
+    let diags = check("severity = { threshold = 'high', below-threshold = 'warn' }");
+    let diag = find_by_code(&diags, "RUSTSEC-2019-0001").unwrap();
+    assert_field_eq!(diag, "/fields/severity", "error");
+}
+
+/// Validates advisories for crates only reachable via dev-dependencies use the
+/// `dev-only` lint level, while other crates are unaffected
+#[test]
+fn downgrades_dev_only() {
+    let TestCtx { dbs, .. } = load();
+
+    // Turn the direct dependency on ammonia 0.7.0 into a dev-dependency, note
+    // that ammonia 1.2.0 is still reachable via a normal dependency
+    let mut md: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string("tests/test_data/advisories/06_advisories.json").unwrap(),
+    )
+    .unwrap();
+
+    for pkg in md["packages"].as_array_mut().unwrap() {
+        if pkg["name"] != "advisories" {
+            continue;
+        }
+
+        for dep in pkg["dependencies"].as_array_mut().unwrap() {
+            if dep["name"] == "ammonia" {
+                dep["kind"] = "dev".into();
+            }
+        }
+    }
+
+    for node in md["resolve"]["nodes"].as_array_mut().unwrap() {
+        if !node["id"].as_str().unwrap().starts_with("advisories ") {
+            continue;
+        }
+
+        for dep in node["deps"].as_array_mut().unwrap() {
+            if dep["name"] == "ammonia" {
+                dep["dep_kinds"][0]["kind"] = "dev".into();
+            }
+        }
+    }
+
+    let krates: Krates = krates::Builder::new()
+        .build_with_metadata(serde_json::from_value(md).unwrap(), krates::NoneFilter)
+        .unwrap();
+
+    let cfg = tu::Config::new("dev-only = 'warn'");
+
+    let diags =
+        tu::gather_diagnostics::<cfg::Config, _, _>(&krates, func_name!(), cfg, |ctx, tx| {
+            advisories::check(
+                ctx,
+                &dbs,
+                Option::<advisories::NoneReporter>::None,
+                None,
+                tx,
+            );
+        });
+
+    let find = |version: &str| {
+        diags
+            .iter()
+            .find(|d| {
+                field_eq!(d, "/fields/graphs/0/Krate/name", "ammonia")
+                    && field_eq!(d, "/fields/graphs/0/Krate/version", version)
+                    && iter_notes(d).is_some_and(|mut n| n.any(|n| n.contains("RUSTSEC-2019-0001")))
+            })
+            .unwrap()
+    };
+
+    let dev_only = find("0.7.0");
+    assert_field_eq!(dev_only, "/fields/severity", "warning");
+    assert!(iter_notes(dev_only).unwrap().any(
+        |n| n == "'ammonia = 0.7.0' is only reachable from the workspace via dev-dependencies"
+    ));
+
+    let normal = find("1.2.0");
+    assert_field_eq!(normal, "/fields/severity", "error");
+}
+
+/// Validates open advisories are warnings while within the `max-open` budget,
+/// and errors once the budget is exceeded
+#[test]
+fn budgets_open_advisories() {
+    let TestCtx { dbs, krates } = load();
+
+    let advisories = |count: u32| {
+        let cfg = tu::Config::new(format!("max-open = {{ count = {count} }}"));
+
+        tu::gather_diagnostics::<cfg::Config, _, _>(&krates, func_name!(), cfg, |ctx, tx| {
+            advisories::check(
+                ctx,
+                &dbs,
+                Option::<advisories::NoneReporter>::None,
+                None,
+                tx,
+            );
+        })
+    };
+
+    let is_advisory = |d: &&serde_json::Value| {
+        ["vulnerability", "unmaintained", "unsound", "notice"]
+            .iter()
+            .any(|code| field_eq!(d, "/fields/code", *code))
+    };
+
+    let diags = advisories(1000);
+    let open: Vec<_> = diags.iter().filter(is_advisory).collect();
+    assert!(!open.is_empty());
+    assert!(open
+        .iter()
+        .all(|d| field_eq!(d, "/fields/severity", "warning")));
+    assert!(!diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "advisory-budget-exceeded")));
+
+    let diags = advisories(0);
+    assert!(diags
+        .iter()
+        .filter(is_advisory)
+        .all(|d| field_eq!(d, "/fields/severity", "error")));
+
+    let exceeded = diags
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "advisory-budget-exceeded"))
+        .unwrap();
+    assert_field_eq!(exceeded, "/fields/severity", "error");
+    assert_field_eq!(
+        exceeded,
+        "/fields/message",
+        format!(
+            "{} advisories are open, which exceeds the budget of 0",
+            open.len()
+        )
+    );
+}
+
 /// Validates we can detect yanked crates from sparse, git, and
 /// non crates.io registries
 #[test]
@@ -366,6 +600,7 @@ fn fails_on_stale_advisory_database() {
         "tests/advisory-db".into(),
         vec![],
         advisories::Fetch::Disallow(time::Duration::seconds(0)),
+        None,
     )
     .unwrap_err()
     .to_string()
@@ -393,6 +628,7 @@ fn do_open(td: &tempfile::TempDir, f: Fetch) -> advisories::AdvisoryDb {
         to_path(td).unwrap().to_owned(),
         vec![TEST_DB_URL.parse().unwrap()],
         f,
+        None,
     )
     .unwrap();
 
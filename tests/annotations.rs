@@ -0,0 +1,79 @@
+use cargo_deny::{
+    annotations, assert_field_eq, field_eq, func_name, root_cfg::Annotation, test_utils as tu,
+};
+
+/// The `annotations` are part of the root config, so wrap them so they can be
+/// deserialized and validated on their own
+struct Config(Vec<Annotation>);
+
+impl<'de> toml_span::Deserialize<'de> for Config {
+    fn deserialize(
+        value: &mut toml_span::value::Value<'de>,
+    ) -> Result<Self, toml_span::DeserError> {
+        let mut th = toml_span::de_helpers::TableHelper::new(value)?;
+        let annotations = th.optional("annotations").unwrap_or_default();
+        th.finalize(None)?;
+        Ok(Self(annotations))
+    }
+}
+
+impl cargo_deny::UnvalidatedConfig for Config {
+    type ValidCfg = annotations::ValidConfig;
+
+    fn validate(self, ctx: cargo_deny::cfg::ValidationContext<'_>) -> Self::ValidCfg {
+        annotations::ValidConfig {
+            file_id: ctx.cfg_id,
+            annotations: self.0,
+        }
+    }
+}
+
+#[test]
+fn emits_annotations() {
+    let md: krates::cm::Metadata = serde_json::from_str(
+        &std::fs::read_to_string("tests/test_data/features-galore/metadata.json").unwrap(),
+    )
+    .unwrap();
+    let krates: cargo_deny::Krates = krates::Builder::new()
+        .build_with_metadata(md, krates::NoneFilter)
+        .unwrap();
+
+    let cfg = tu::Config::<Config>::new(
+        "[[annotations]]
+crate = 'fnv'
+note = 'reviewed 2024-Q1, approved by security'
+
+[[annotations]]
+crate = 'fnv@2.0.0'
+note = 'not in the graph'
+",
+    );
+
+    let diags = tu::gather_diagnostics::<Config, _, _>(&krates, func_name!(), cfg, |ctx, tx| {
+        annotations::check(ctx, tx);
+    });
+
+    let annotated: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "annotation"))
+        .collect();
+    assert_eq!(annotated.len(), 1);
+    assert_field_eq!(annotated[0], "/fields/severity", "note");
+    assert_field_eq!(
+        annotated[0],
+        "/fields/message",
+        "crate 'fnv = 1.0.7': reviewed 2024-Q1, approved by security"
+    );
+
+    let unused: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "unused-annotation"))
+        .collect();
+    assert_eq!(unused.len(), 1);
+    assert_field_eq!(unused[0], "/fields/severity", "warning");
+    assert_field_eq!(
+        unused[0],
+        "/fields/message",
+        "annotated crate 'fnv = =2.0.0' was not encountered"
+    );
+}
@@ -1,4 +1,4 @@
-use cargo_deny::{func_name, test_utils::*};
+use cargo_deny::{assert_field_eq, field_eq, func_name, test_utils::*};
 
 /// Covers issue <https://github.com/EmbarkStudios/cargo-deny/issues/184>
 #[test]
@@ -85,6 +85,38 @@ fn deny_wildcards() {
     insta::assert_json_snapshot!(diags);
 }
 
+/// Ensures that skips scoped to duplicates don't prevent wildcards from being
+/// checked for the skipped crate
+#[test]
+fn skip_scoped_to_duplicates() {
+    let wildcards = |cfg: &str| {
+        gather_bans(
+            func_name!(),
+            KrateGather::new("wildcards/maincrate"),
+            format!("wildcards = 'deny'\n{cfg}"),
+        )
+        .into_iter()
+        .filter(|d| {
+            field_eq!(
+                d,
+                "/fields/message",
+                "found 1 wildcard dependency for crate 'wildcards-test-dep'"
+            )
+        })
+        .count()
+    };
+
+    assert_eq!(
+        wildcards("skip = [{ crate = 'wildcards-test-dep', scope = 'duplicates' }]"),
+        1
+    );
+    assert_eq!(wildcards("skip = [{ crate = 'wildcards-test-dep' }]"), 0);
+    assert_eq!(
+        wildcards("skip = [{ crate = 'wildcards-test-dep', scope = 'all' }]"),
+        0
+    );
+}
+
 /// Ensures that wildcard dependencies are still banned when
 /// allow-wildcard-paths is set to true but the package is public.
 #[test]
@@ -251,6 +283,125 @@ deny = [
     insta::assert_json_snapshot!(diags);
 }
 
+/// Ensures the number of versions of specific crates can be capped, overriding
+/// the global `multiple-versions` lint level
+#[test]
+fn caps_versions_for_specific_krates() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("duplicates"),
+        r"
+multiple-versions = 'deny'
+multiple-versions-include-dev = true
+max-versions = [
+    { name = 'block-buffer', count = 2 },
+    { name = 'digest', count = 1, level = 'deny' },
+]
+",
+    );
+
+    let with_code = |code: &str| -> Vec<_> {
+        diags
+            .iter()
+            .filter(|d| field_eq!(d, "/fields/code", code))
+            .collect()
+    };
+
+    let too_many = with_code("too-many-versions");
+    assert_eq!(too_many.len(), 1);
+    assert_field_eq!(too_many[0], "/fields/severity", "error");
+    assert_field_eq!(
+        too_many[0],
+        "/fields/message",
+        "found 2 versions of crate 'digest', which is more than the maximum of 1"
+    );
+    assert_field_eq!(too_many[0], "/fields/notes/0", "versions: 0.8.1, 0.10.3");
+
+    // Crates without a cap still use the global lint level
+    let duplicates = with_code("duplicate");
+    assert_eq!(duplicates.len(), 1);
+    assert_field_eq!(
+        duplicates[0],
+        "/fields/message",
+        "found 2 duplicate entries for crate 'generic-array'"
+    );
+}
+
+/// Ensures `use-instead` suggestions that don't exist on crates.io are
+/// flagged, while suggestions that couldn't be looked up are not
+#[test]
+fn detects_unknown_suggestions() {
+    let krates = KrateGather::new("allow_wrappers/maincrate").gather();
+
+    let diags = gather_diagnostics::<cargo_deny::bans::cfg::Config, _, _>(
+        &krates,
+        func_name!(),
+        r#"
+[[deny]]
+name = "dangerous-dep"
+use-instead = "a-better-krate"
+
+[[deny]]
+name = "openssl"
+use-instead = "rustls"
+
+[[deny]]
+name = "failure"
+use-instead = "not-looked-up"
+"#
+        .into(),
+        |mut ctx, tx| {
+            ctx.cfg.suggested_crates = Some(
+                [("a-better-krate", false), ("rustls", true)]
+                    .into_iter()
+                    .map(|(name, exists)| (name.to_owned(), exists))
+                    .collect(),
+            );
+            cargo_deny::bans::check(ctx, None, tx);
+        },
+    );
+
+    let unknown: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "unknown-suggestion"))
+        .collect();
+    assert_eq!(unknown.len(), 1);
+    assert_field_eq!(unknown[0], "/fields/severity", "warning");
+    assert_field_eq!(
+        unknown[0],
+        "/fields/message",
+        "crate 'a-better-krate' suggested instead of banned crate 'dangerous-dep' does not exist on crates.io"
+    );
+}
+
+/// Ensures a ban with a checksum only applies to the crate whose lockfile
+/// checksum matches, rather than every version of the crate
+#[test]
+fn deny_by_checksum() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("duplicates"),
+        r"
+multiple-versions = 'allow'
+deny = [
+    { crate = 'block-buffer', checksum = 'c0940dc441f31689269e10ac70eb1002a3a1d3ad1390e030043662eb7fe4688b' },
+    { crate = 'generic-array', checksum = '0000000000000000000000000000000000000000000000000000000000000000' },
+]
+",
+    );
+
+    let banned: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "banned"))
+        .collect();
+    assert_eq!(banned.len(), 1);
+    assert_field_eq!(
+        banned[0],
+        "/fields/message",
+        "crate 'block-buffer = 0.7.3' is explicitly banned"
+    );
+}
+
 // Ensures that dependencies brought in by target specific features are banned
 #[test]
 fn deny_target_specific_dependencies() {
@@ -305,6 +456,37 @@ deny = [
     insta::assert_json_snapshot!(diags);
 }
 
+/// Ensures that dependencies gated by `cfg()` expressions are evaluated against
+/// the requested target triples
+#[test]
+fn evaluates_cfg_target_dependencies() {
+    let gather = |targets| {
+        let krates = KrateGather {
+            name: "target-cfg/maincrate",
+            targets,
+            ..Default::default()
+        }
+        .gather();
+
+        let mut names: Vec<_> = krates.krates().map(|k| k.name.clone()).collect();
+        names.sort();
+        names
+    };
+
+    assert_eq!(
+        gather(&["x86_64-unknown-linux-gnu"]),
+        ["linux-dep", "target-cfg", "unix-dep"]
+    );
+    assert_eq!(
+        gather(&["x86_64-pc-windows-msvc"]),
+        ["target-cfg", "windows-dep"]
+    );
+    assert_eq!(
+        gather(&["aarch64-apple-darwin"]),
+        ["target-cfg", "unix-dep"]
+    );
+}
+
 /// Ensures that duplicate workspace items are found and linted
 #[test]
 fn deny_duplicate_workspace_items() {
@@ -328,6 +510,190 @@ unused = 'warn'
     insta::assert_json_snapshot!(diags);
 }
 
+/// Ensures dependencies that declare a version rather than using the shared
+/// workspace dependency are linted
+#[test]
+fn requires_workspace_table() {
+    let not_used = |cfg: &str| {
+        gather_bans(
+            func_name!(),
+            KrateGather {
+                name: "workspace",
+                no_default_features: true,
+                targets: &["x86_64-unknown-linux-gnu", "x86_64-pc-windows-msvc"],
+                ..Default::default()
+            },
+            cfg,
+        )
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "workspace-table-not-used"))
+        .collect::<Vec<_>>()
+    };
+
+    let diags = not_used(
+        r"
+multiple-versions = 'allow'
+
+[workspace-dependencies]
+duplicates = 'allow'
+unused = 'allow'
+require-workspace-table = 'warn'
+",
+    );
+
+    assert!(diags
+        .iter()
+        .all(|d| field_eq!(d, "/fields/severity", "warning")));
+    assert!(diags.iter().any(|d| field_eq!(
+        d,
+        "/fields/message",
+        "crate root = 0.1.0 declares a version for dependency 'spdx' rather than using the shared workspace dependency"
+    )));
+
+    assert!(not_used(
+        r"
+multiple-versions = 'allow'
+
+[workspace-dependencies]
+duplicates = 'allow'
+unused = 'allow'
+"
+    )
+    .is_empty());
+}
+
+/// Ensures every external crate with the `default` feature enabled is reported,
+/// but not workspace crates
+#[test]
+fn reports_default_features() {
+    let reported = |cfg: &str| {
+        gather_bans(
+            func_name!(),
+            KrateGather {
+                name: "features-galore",
+                no_default_features: false,
+                targets: &["x86_64-unknown-linux-gnu"],
+                ..Default::default()
+            },
+            cfg,
+        )
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "default-feature-enabled"))
+        .collect::<Vec<_>>()
+    };
+
+    let diags = reported("report-default-features = 'note'");
+
+    assert!(diags
+        .iter()
+        .all(|d| field_eq!(d, "/fields/severity", "note")));
+    assert!(diags.iter().any(|d| field_eq!(
+        d,
+        "/fields/message",
+        "'default' feature enabled for crate 'libc = 0.2.134'"
+    )));
+    assert!(!diags.iter().any(|d| field_eq!(
+        d,
+        "/fields/message",
+        "'default' feature enabled for crate 'features-galore = 0.1.0'"
+    )));
+
+    assert!(reported("").is_empty());
+}
+
+/// Ensures crates that are resolved to a different version only for build
+/// dependencies are reported
+#[test]
+fn reports_host_target_version_skew() {
+    let reported = |cfg: &str| {
+        gather_bans(func_name!(), KrateGather::new("sources"), cfg)
+            .into_iter()
+            .filter(|d| field_eq!(d, "/fields/code", "host-target-version-skew"))
+            .collect::<Vec<_>>()
+    };
+
+    let diags = reported(
+        r"
+multiple-versions = 'allow'
+report-host-target-version-skew = 'warn'
+",
+    );
+
+    let skewed = diags
+        .iter()
+        .find(|d| {
+            field_eq!(
+                d,
+                "/fields/message",
+                "crate 'hashbrown' is resolved to different versions for the host and the target"
+            )
+        })
+        .expect("hashbrown was not reported");
+
+    assert_field_eq!(skewed, "/fields/severity", "warning");
+    assert_field_eq!(
+        skewed,
+        "/fields/notes",
+        ["target: hashbrown = 0.12.3", "host: hashbrown = 0.7.2"]
+    );
+
+    // cfg-if has multiple versions, but is only used by build dependencies
+    assert!(!diags.iter().any(|d| field_eq!(
+        d,
+        "/fields/message",
+        "crate 'cfg-if' is resolved to different versions for the host and the target"
+    )));
+
+    assert!(reported("multiple-versions = 'allow'").is_empty());
+}
+
+/// Ensures crates with multiple versions that are only used by build
+/// dependencies are reported
+#[test]
+fn reports_build_graph_duplicates() {
+    let reported = |cfg: &str| {
+        gather_bans(func_name!(), KrateGather::new("sources"), cfg)
+            .into_iter()
+            .filter(|d| field_eq!(d, "/fields/code", "build-graph-duplicate"))
+            .collect::<Vec<_>>()
+    };
+
+    let diags = reported(
+        r"
+multiple-versions = 'allow'
+build-graph-duplicates = 'warn'
+",
+    );
+
+    let duplicated = diags
+        .iter()
+        .find(|d| {
+            field_eq!(
+                d,
+                "/fields/message",
+                "found 2 duplicate versions of crate 'cfg-if' that is only used at build time"
+            )
+        })
+        .expect("cfg-if was not reported");
+
+    assert_field_eq!(duplicated, "/fields/severity", "warning");
+    assert_field_eq!(
+        duplicated,
+        "/fields/notes",
+        ["version: 0.1.10", "version: 1.0.0"]
+    );
+
+    // hashbrown has multiple versions, but only one of them is only used by
+    // build dependencies
+    assert!(!diags.iter().any(|d| field_eq!(
+        d,
+        "/fields/message",
+        "found 2 duplicate versions of crate 'hashbrown' that is only used at build time"
+    )));
+
+    assert!(reported("multiple-versions = 'allow'").is_empty());
+}
+
 /// Ensures skips generate warnings if they aren't needed
 #[test]
 fn unused_skips_generate_warnings() {
@@ -354,3 +720,425 @@ skip = [
 
     insta::assert_json_snapshot!(diags);
 }
+
+/// Ensures unused skips suggest removing the skip when requested
+#[test]
+fn unused_skips_suggest_fixes() {
+    let krates = KrateGather::new("duplicates").gather();
+
+    let unmatched = |suggest_fixes: bool| {
+        gather_diagnostics::<cargo_deny::bans::cfg::Config, _, _>(
+            &krates,
+            func_name!(),
+            "skip = ['not-in-the-graph']".into(),
+            |mut ctx, tx| {
+                ctx.suggest_fixes = suggest_fixes;
+                cargo_deny::bans::check(ctx, None, tx);
+            },
+        )
+        .into_iter()
+        .find(|d| field_eq!(d, "/fields/code", "unmatched-skip"))
+        .expect("skip was matched")
+    };
+
+    assert_field_eq!(
+        unmatched(true),
+        "/fields/notes",
+        ["Suggested fix: remove this entry from `bans.skip`"]
+    );
+    assert!(unmatched(false).pointer("/fields/notes").is_none());
+}
+
+/// Ensures skips are ignored, with a warning, once they have expired
+#[test]
+fn expired_skips_are_ignored() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("duplicates"),
+        r"
+multiple-versions = 'deny'
+multiple-versions-include-dev = true
+skip = [
+    { name = 'block-buffer', version = '=0.7.3', expires = '2000-01-01' },
+    { name = 'digest', version = '=0.8.1', expires = '2999-01-01' },
+]
+",
+    );
+
+    let expired: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "expired-skip"))
+        .collect();
+    assert_eq!(expired.len(), 1);
+    assert_field_eq!(expired[0], "/fields/severity", "warning");
+    assert_field_eq!(
+        expired[0],
+        "/fields/message",
+        "skip for crate 'block-buffer = =0.7.3' expired on 2000-01-01"
+    );
+
+    // The expired skip is not applied, but the unexpired one is
+    assert!(diags.iter().any(|d| field_eq!(
+        d,
+        "/fields/message",
+        "found 2 duplicate entries for crate 'block-buffer'"
+    )));
+    assert!(!diags.iter().any(|d| field_eq!(
+        d,
+        "/fields/message",
+        "found 2 duplicate entries for crate 'digest'"
+    )));
+}
+
+/// Ensures crates using an edition older than the configured minimum are
+/// flagged, including workspace crates, unless they are exempted
+#[test]
+fn flags_outdated_editions() {
+    let mut diags = gather_bans(
+        func_name!(),
+        KrateGather::new("allow_wrappers/maincrate"),
+        "min-edition = { edition = '2021', level = 'deny', allow = ['safe-wrapper'] }",
+    );
+
+    diags.retain(|d| field_eq!(d, "/fields/code", "outdated-edition"));
+
+    let mut flagged: Vec<_> = diags
+        .iter()
+        .map(|d| {
+            assert_field_eq!(d, "/fields/severity", "error");
+            d.pointer("/fields/graphs/0/Krate/name")
+                .and_then(|n| n.as_str())
+                .unwrap()
+        })
+        .collect();
+    flagged.sort_unstable();
+
+    assert_eq!(flagged, ["allow-wrappers-crate", "dangerous-dep"]);
+}
+
+/// Ensures crates that declare a `rust-version` newer than the maximum are
+/// flagged, and crates without one are skipped
+#[test]
+fn flags_rust_version_too_new() {
+    let md: krates::cm::Metadata = serde_json::from_str(
+        &std::fs::read_to_string("tests/test_data/features-galore/metadata.json").unwrap(),
+    )
+    .unwrap();
+    let krates: cargo_deny::Krates = krates::Builder::new()
+        .build_with_metadata(md, krates::NoneFilter)
+        .unwrap();
+
+    let cfg = Config::<cargo_deny::bans::cfg::Config>::new(
+        "max-rust-version = { version = '1.56', level = 'deny' }",
+    );
+
+    let diags = gather_diagnostics::<cargo_deny::bans::cfg::Config, _, _>(
+        &krates,
+        func_name!(),
+        cfg,
+        |ctx, tx| {
+            cargo_deny::bans::check(ctx, None, tx);
+        },
+    );
+
+    let diags: Vec<_> = diags
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "rust-version-too-new"))
+        .collect();
+
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "error");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "crate 'time = 0.3.14' requires rust 1.59.0, which is newer than the maximum rust version 1.56.0"
+    );
+}
+
+/// Ensures the number of crates in the graph is checked against the maximum,
+/// and the crates that exceed it are reported
+#[test]
+fn flags_too_many_crates() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("prerelease"),
+        "max-crates = { count = 2 }",
+    );
+
+    let diags: Vec<_> = diags
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "too-many-crates"))
+        .collect();
+
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "warning");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "the crate graph contains 3 crates, which is 1 more than the maximum of 2"
+    );
+    assert_field_eq!(
+        diags[0],
+        "/fields/notes/0",
+        "exceeds the maximum: rc-dep = 2.0.0-rc.1"
+    );
+
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("prerelease"),
+        "max-crates = { count = 3, level = 'deny' }",
+    );
+
+    assert!(!diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "too-many-crates")));
+}
+
+/// Ensures crates that resolve to pre-release versions are flagged, unless
+/// they are explicitly allowed
+#[test]
+fn flags_prerelease_versions() {
+    let mut diags = gather_bans(
+        func_name!(),
+        KrateGather::new("prerelease"),
+        "deny-prerelease = 'warn'\nallow-prerelease = ['rc-dep']",
+    );
+
+    diags.retain(|d| field_eq!(d, "/fields/code", "prerelease-version"));
+
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "warning");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "crate 'beta-dep = 1.0.0-beta.1' is a pre-release version ('beta.1')"
+    );
+}
+
+/// Ensures `deny-below` bans versions below the minimum, and that unmatched
+/// `deny-below` bans are reported separately from regular bans
+#[test]
+fn deny_below_minimum_version() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("allow_wrappers/maincrate"),
+        r#"
+deny = [
+    { name = "dangerous-dep", deny-below = "0.2.0", reason = "fixes a soundness bug" },
+    { name = "safe-wrapper", deny-below = "0.1.0" },
+    { name = "not-a-crate", deny-below = "1.0.0" },
+]
+"#,
+    );
+
+    let banned: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "banned"))
+        .collect();
+    assert_eq!(banned.len(), 1);
+    assert_field_eq!(
+        banned[0],
+        "/fields/message",
+        "dangerous-dep 0.1.0 is below the required minimum 0.2.0"
+    );
+
+    let unmatched: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "unmatched-deny-below"))
+        .collect();
+    assert_eq!(unmatched.len(), 1);
+    assert_field_eq!(unmatched[0], "/fields/severity", "warning");
+    assert_field_eq!(
+        unmatched[0],
+        "/fields/message",
+        "crate 'not-a-crate' with a required minimum of 1.0.0 was not encountered"
+    );
+}
+
+/// Ensures `deny-features-present` only bans a crate when one of the features
+/// is enabled, and reports the crate that enabled it
+#[test]
+fn deny_features_present() {
+    let banned = |features: &[&str]| {
+        gather_bans(
+            func_name!(),
+            KrateGather {
+                name: "features-galore",
+                features,
+                no_default_features: true,
+                targets: &["x86_64-unknown-linux-gnu"],
+                ..Default::default()
+            },
+            r#"
+multiple-versions = 'allow'
+deny = [{ name = "git2", deny-features-present = ["ssh"] }]
+"#,
+        )
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "banned"))
+        .collect::<Vec<_>>()
+    };
+
+    let diags = banned(&["ssh"]);
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "crate 'git2 = 0.14.4' is banned as feature(s) 'ssh' are enabled"
+    );
+    assert_field_eq!(
+        diags[0],
+        "/fields/notes/0",
+        "'ssh' enabled by 'features-galore = 0.1.0'"
+    );
+
+    assert!(banned(&[]).is_empty());
+}
+
+/// Ensures the origin of each enabled feature is reported for the configured
+/// crates
+#[test]
+fn reports_feature_origins() {
+    let diags: Vec<_> = gather_bans(
+        func_name!(),
+        KrateGather {
+            name: "features-galore",
+            features: &["ssh"],
+            no_default_features: true,
+            targets: &["x86_64-unknown-linux-gnu"],
+            ..Default::default()
+        },
+        "multiple-versions = 'allow'\nreport-feature-origin = ['git2']",
+    )
+    .into_iter()
+    .filter(|d| field_eq!(d, "/fields/code", "feature-origin"))
+    .collect();
+
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "note");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "enabled features for crate 'git2 = 0.14.4'"
+    );
+
+    let notes = diags[0]
+        .pointer("/fields/notes")
+        .and_then(|n| n.as_array())
+        .unwrap();
+    assert!(notes
+        .iter()
+        .any(|n| n == "'ssh' enabled by 'features-galore = 0.1.0'"));
+    assert!(notes
+        .iter()
+        .any(|n| n == "'ssh_key_from_memory' enabled by 'features-galore = 0.1.0'"));
+}
+
+/// Ensures `default`, and the features it enables, are attributed to the
+/// dependents that don't disable default features
+#[test]
+fn reports_default_feature_origins() {
+    let diags: Vec<_> = gather_bans(
+        func_name!(),
+        KrateGather {
+            name: "features-galore",
+            features: &["serde"],
+            no_default_features: true,
+            targets: &["x86_64-unknown-linux-gnu"],
+            ..Default::default()
+        },
+        "multiple-versions = 'allow'\nreport-feature-origin = ['serde']",
+    )
+    .into_iter()
+    .filter(|d| field_eq!(d, "/fields/code", "feature-origin"))
+    .collect();
+
+    assert_eq!(diags.len(), 1);
+
+    let notes = diags[0]
+        .pointer("/fields/notes")
+        .and_then(|n| n.as_array())
+        .unwrap();
+    assert!(notes
+        .iter()
+        .any(|n| n == "'default' enabled by 'features-galore = 0.1.0'"));
+    assert!(notes
+        .iter()
+        .any(|n| n == "'std' enabled by 'features-galore = 0.1.0', feature 'default'"));
+}
+
+/// Ensures crates with the same name and version, but from different sources,
+/// are reported when `duplicate-sources` is enabled
+#[test]
+fn detects_duplicate_sources() {
+    let cfg = "duplicate-sources = 'warn'\nmultiple-versions = 'allow'";
+    let diags = gather_bans(func_name!(), KrateGather::new("duplicate-sources"), cfg);
+
+    let dupes: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "duplicate-source"))
+        .collect();
+    assert_eq!(dupes.len(), 1);
+    assert_field_eq!(dupes[0], "/fields/severity", "warning");
+    assert_field_eq!(
+        dupes[0],
+        "/fields/message",
+        "found 2 sources for crate 'smallvec'"
+    );
+
+    let notes = dupes[0]
+        .pointer("/fields/notes")
+        .and_then(|n| n.as_array())
+        .unwrap();
+    assert_eq!(notes.len(), 2);
+    assert!(notes.iter().any(|n| n
+        .as_str()
+        .unwrap()
+        .starts_with("smallvec 1.13.2 from git+https://github.com/servo/rust-smallvec")));
+    assert!(notes.iter().any(|n| n
+        .as_str()
+        .unwrap()
+        .starts_with("smallvec 1.13.2 from registry+")));
+
+    // Nothing is reported by default
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("duplicate-sources"),
+        "multiple-versions = 'allow'",
+    );
+    assert!(!diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "duplicate-source")));
+}
+
+/// Ensures the progress events for the bans check are reported in order
+#[test]
+fn reports_progress() {
+    use cargo_deny::ProgressEvent;
+    use std::sync::{Arc, Mutex};
+
+    let krates = KrateGather::new("duplicates").gather();
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    gather_diagnostics::<cargo_deny::bans::cfg::Config, _, _>(
+        &krates,
+        func_name!(),
+        Config::default(),
+        |mut ctx, tx| {
+            let events = events.clone();
+            ctx.progress = Some(Box::new(move |event| events.lock().unwrap().push(event)));
+            cargo_deny::bans::check(ctx, None, tx);
+        },
+    );
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        [
+            ProgressEvent::CheckingBans {
+                krates: krates.len()
+            },
+            ProgressEvent::BansChecked,
+        ]
+    );
+}
@@ -1,4 +1,4 @@
-use cargo_deny::{field_eq, func_name, test_utils::*};
+use cargo_deny::{assert_field_eq, field_eq, func_name, test_utils::*};
 
 macro_rules! ci_ignore {
     () => {
@@ -211,6 +211,91 @@ build-script = "1a850d791184374f614d01c86c8d6c9ba0500e64cb746edc9720ceaaa1cd8eaf
     insta::assert_json_snapshot!(diags);
 }
 
+/// Verifies vetted crates in `build.allow` are not checked, and that entries
+/// that don't match a crate that executes at build time are reported
+#[test]
+fn allows_vetted_crates() {
+    ci_ignore!();
+
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather {
+            name: "build-bans",
+            features: &["mixed", "scripts"],
+            no_default_features: true,
+            targets: &["x86_64-unknown-linux-gnu"],
+            ..Default::default()
+        },
+        Config::new(
+            r#"
+[build]
+allow-build-scripts = ["ittapi-sys", "ring"]
+allow = ["libc", "this-crate-does-not-exist"]
+executables = "allow"
+"#,
+        ),
+    );
+
+    assert!(!diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "build-script-not-allowed")));
+
+    let unmatched: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "unmatched-build-allow"))
+        .collect();
+    assert_eq!(unmatched.len(), 1);
+    assert!(field_eq!(
+        unmatched[0],
+        "/fields/labels/0/span",
+        "this-crate-does-not-exist"
+    ));
+}
+
+/// Verifies crates that declare a `links` key are reported, unless they are
+/// in `build.allow`
+#[test]
+fn reports_native_links() {
+    ci_ignore!();
+
+    let links = |cfg: &str| {
+        gather_bans(
+            func_name!(),
+            KrateGather {
+                name: "build-bans",
+                features: &["scripts"],
+                no_default_features: true,
+                targets: &["x86_64-unknown-linux-gnu"],
+                ..Default::default()
+            },
+            Config::new(cfg),
+        )
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "links-native-library"))
+        .collect::<Vec<_>>()
+    };
+
+    let diags = links("[build]\nexecutables = 'allow'\nlinks = 'warn'");
+
+    let ring = diags
+        .iter()
+        .find(|d| field_eq!(d, "/fields/graphs/0/Krate/name", "ring"))
+        .expect("ring was not reported");
+    assert_field_eq!(ring, "/fields/severity", "warning");
+    assert_field_eq!(
+        ring,
+        "/fields/message",
+        "crate 'ring = 0.16.20' links to the native library 'ring-asm'"
+    );
+
+    assert!(
+        !links("[build]\nexecutables = 'allow'\nlinks = 'note'\nallow = ['ring']")
+            .iter()
+            .any(|d| field_eq!(d, "/fields/graphs/0/Krate/name", "ring"))
+    );
+    assert!(links("[build]\nexecutables = 'allow'").is_empty());
+}
+
 /// Verifies executables are allowed by glob patterns
 #[test]
 fn allows_by_glob() {
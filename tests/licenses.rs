@@ -1,5 +1,5 @@
 use cargo_deny::{
-    diag, field_eq, func_name,
+    assert_field_eq, diag, field_eq, func_name,
     licenses::{self, cfg::Config},
     test_utils as tu, Krates,
 };
@@ -99,6 +99,85 @@ fn accepts_exceptions() {
     insta::assert_json_snapshot!(diags);
 }
 
+/// Ensures an exception with a source only applies to the crate if it comes
+/// from that source
+#[test]
+fn accepts_source_scoped_exceptions() {
+    let diags = |source: &str| {
+        let mut diags = gather_licenses_with_overrides(
+            func_name!(),
+            format!(
+                "exceptions = [{{ name = 'tinyvec_macros', allow = ['Zlib'], source = '{source}' }}]"
+            ),
+            None,
+        );
+        diags.retain(|d| {
+            field_eq!(d, "/fields/graphs/0/Krate/name", "tinyvec_macros")
+                || field_eq!(d, "/fields/code", "license-exception-not-encountered")
+        });
+        diags
+    };
+
+    let d = diags("crates-io");
+    assert_eq!(d.len(), 1);
+    assert_field_eq!(d[0], "/fields/code", "accepted");
+
+    // The crate comes from crates.io, so an exception for a git fork of it
+    // doesn't apply, and is reported as unmatched
+    let d = diags("git+https://github.com/Soveu/tinyvec");
+    assert_eq!(d.len(), 2);
+    assert!(d.iter().any(|d| field_eq!(d, "/fields/code", "rejected")));
+    assert!(d
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "license-exception-not-encountered")));
+}
+
+/// Ensures an exception for a crate's exact license expression allows the
+/// crate regardless of the individual licenses, and only if it matches
+#[test]
+fn accepts_expression_exceptions() {
+    let diags = |exceptions: &str| {
+        gather_licenses_with_overrides(
+            func_name!(),
+            format!("allow = ['MIT']\nexceptions = [{exceptions}]"),
+            None,
+        )
+    };
+
+    let find = |diags: &[serde_json::Value], name: &str| {
+        diags
+            .iter()
+            .find(|d| field_eq!(d, "/fields/graphs/0/Krate/name", name))
+            .cloned()
+            .unwrap()
+    };
+
+    // Whitespace and redundant parentheses don't affect the match
+    let d = diags(
+        "{ name = 'unicode-ident', expression = '((MIT OR Apache-2.0)  AND Unicode-DFS-2016)' }",
+    );
+    let diag = find(&d, "unicode-ident");
+    assert_field_eq!(diag, "/fields/code", "accepted");
+    assert!(diag["fields"]["notes"].as_array().unwrap().iter().any(|n| n
+        .as_str()
+        .unwrap()
+        .ends_with("is explicitly allowed via an exception")));
+    assert!(!d
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "license-exception-not-encountered")));
+
+    // The exception only applies to the crate it is for
+    assert_field_eq!(find(&d, "encoding_rs"), "/fields/code", "rejected");
+
+    // A different expression, even a subset, doesn't match, and the exception
+    // is reported as unused
+    let d = diags("{ name = 'unicode-ident', expression = 'MIT OR Apache-2.0' }");
+    assert_field_eq!(find(&d, "unicode-ident"), "/fields/code", "rejected");
+    assert!(d
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "license-exception-not-encountered")));
+}
+
 #[test]
 fn detects_unlicensed() {
     let cfg = tu::Config::new("");
@@ -110,6 +189,367 @@ fn detects_unlicensed() {
     insta::assert_json_snapshot!(diags);
 }
 
+/// Ensures workspace members that declare a license other than the canonical
+/// one are detected
+#[test]
+fn detects_inconsistent_workspace_licenses() {
+    let mut md: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string("tests/test_data/features-galore/metadata.json").unwrap(),
+    )
+    .unwrap();
+
+    let member = md["workspace_members"][0].as_str().unwrap().to_owned();
+    for pkg in md["packages"].as_array_mut().unwrap() {
+        if pkg["id"] == member.as_str() {
+            pkg["license"] = "MIT".into();
+        }
+    }
+
+    let krates: Krates = krates::Builder::new()
+        .build_with_metadata(serde_json::from_value(md).unwrap(), krates::NoneFilter)
+        .unwrap();
+
+    let inconsistent = |cfg: &str| {
+        let (ctx, summary) = setup(&krates, func_name!(), cfg.into());
+
+        tu::run_gather(ctx, |ctx, tx| {
+            crate::licenses::check(
+                ctx,
+                summary,
+                diag::ErrorSink {
+                    overrides: None,
+                    channel: tx,
+                },
+            );
+        })
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "inconsistent-workspace-license"))
+        .collect::<Vec<_>>()
+    };
+
+    // A single member is always consistent with itself
+    assert!(inconsistent("workspace-consistency = 'deny'").is_empty());
+
+    let diags = inconsistent("workspace-consistency = { canonical = 'Apache-2.0' }");
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "warning");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "workspace member features-galore = 0.1.0 declares license 'MIT', which differs from the rest of the workspace"
+    );
+}
+
+#[test]
+fn handles_unknown_license_refs() {
+    let cfg = |extra: &str| {
+        tu::Config::<Config>::new(format!(
+            r#"
+{extra}
+[[clarify]]
+name = "features-galore"
+expression = "LicenseRef-Proprietary"
+license-files = []
+"#
+        ))
+    };
+
+    let find = |diags: Vec<serde_json::Value>| {
+        diags
+            .into_iter()
+            .find(|d| field_eq!(d, "/fields/graphs/0/Krate/name", "features-galore"))
+            .unwrap()
+    };
+
+    // Unknown references are denied by default
+    let diag = find(gather_licenses_with_overrides(func_name!(), cfg(""), None));
+    assert_field_eq!(diag, "/fields/code", "rejected");
+
+    // Explicitly allowed references are accepted regardless of the lint level
+    let diag = find(gather_licenses_with_overrides(
+        func_name!(),
+        cfg("allow = ['LicenseRef-Proprietary']"),
+        None,
+    ));
+    assert_field_eq!(diag, "/fields/code", "accepted");
+    assert_field_eq!(diag, "/fields/severity", "help");
+
+    let diag = find(gather_licenses_with_overrides(
+        func_name!(),
+        cfg("unknown-license-ref = 'warn'"),
+        None,
+    ));
+    assert_field_eq!(diag, "/fields/code", "accepted");
+    assert_field_eq!(diag, "/fields/severity", "warning");
+
+    let diag = find(gather_licenses_with_overrides(
+        func_name!(),
+        cfg("unknown-license-ref = 'allow'\nallow = ['LicenseRef-Other']"),
+        None,
+    ));
+    assert_field_eq!(diag, "/fields/code", "accepted");
+    assert_field_eq!(diag, "/fields/severity", "help");
+}
+
+/// Ensures licenses with exceptions can be allowed directly, and that the bare
+/// license does not satisfy a requirement that carries the exception
+#[test]
+fn allows_licenses_with_exceptions() {
+    let cfg = |allow: &str| {
+        tu::Config::<Config>::new(format!(
+            r#"
+allow = [{allow}]
+[[clarify]]
+name = "features-galore"
+expression = "Apache-2.0 WITH LLVM-exception"
+license-files = []
+"#
+        ))
+    };
+
+    let find = |diags: Vec<serde_json::Value>| {
+        diags
+            .into_iter()
+            .find(|d| field_eq!(d, "/fields/graphs/0/Krate/name", "features-galore"))
+            .unwrap()
+    };
+
+    let diag = find(gather_licenses_with_overrides(
+        func_name!(),
+        cfg("'Apache-2.0 WITH LLVM-exception'"),
+        None,
+    ));
+    assert_field_eq!(diag, "/fields/code", "accepted");
+
+    let diag = find(gather_licenses_with_overrides(
+        func_name!(),
+        cfg("'Apache-2.0'"),
+        None,
+    ));
+    assert_field_eq!(diag, "/fields/code", "rejected");
+}
+
+/// Ensures deprecated license identifiers can be warned about or denied
+#[test]
+fn handles_deprecated_licenses() {
+    let cfg = |extra: &str| {
+        tu::Config::<Config>::new(format!(
+            r#"
+allow = ["GPL-2.0"]
+{extra}
+[[clarify]]
+name = "features-galore"
+expression = "GPL-2.0"
+license-files = []
+"#
+        ))
+    };
+
+    let find = |diags: Vec<serde_json::Value>| {
+        diags
+            .into_iter()
+            .find(|d| field_eq!(d, "/fields/graphs/0/Krate/name", "features-galore"))
+            .unwrap()
+    };
+
+    // Deprecated identifiers are allowed by default
+    let diag = find(gather_licenses_with_overrides(func_name!(), cfg(""), None));
+    assert_field_eq!(diag, "/fields/code", "accepted");
+    assert_field_eq!(diag, "/fields/severity", "help");
+
+    let diag = find(gather_licenses_with_overrides(
+        func_name!(),
+        cfg("deny-deprecated = 'warn'"),
+        None,
+    ));
+    assert_field_eq!(diag, "/fields/code", "accepted");
+    assert_field_eq!(diag, "/fields/severity", "warning");
+    assert!(diag["fields"]["notes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|n| n == "'GPL-2.0' is a deprecated SPDX identifier, use 'GPL-2.0-only' instead"));
+
+    let diag = find(gather_licenses_with_overrides(
+        func_name!(),
+        cfg("deny-deprecated = 'deny'"),
+        None,
+    ));
+    assert_field_eq!(diag, "/fields/code", "rejected");
+}
+
+/// Ensures classifications override the metadata of rejected licenses
+#[test]
+fn classifies_licenses() {
+    let notes = |extra: &str| {
+        let cfg = tu::Config::<Config>::new(format!(
+            r#"
+allow = []
+{extra}
+[[clarify]]
+name = "features-galore"
+expression = "MIT"
+license-files = []
+"#
+        ));
+
+        let diag = gather_licenses_with_overrides(func_name!(), cfg, None)
+            .into_iter()
+            .find(|d| field_eq!(d, "/fields/graphs/0/Krate/name", "features-galore"))
+            .unwrap();
+        assert_field_eq!(diag, "/fields/code", "rejected");
+
+        diag["fields"]["notes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| n.as_str().unwrap().to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let builtin = notes("");
+    assert!(builtin.iter().any(|n| n == "  - OSI approved"));
+    assert!(!builtin.iter().any(|n| n == "  - Copyleft"));
+
+    let classified = notes("classify = [{ license = 'MIT', copyleft = true, osi = false }]");
+    assert!(!classified.iter().any(|n| n == "  - OSI approved"));
+    assert!(classified.iter().any(|n| n == "  - Copyleft"));
+}
+
+/// Ensures rejected expressions state whether allowing a single license would
+/// be enough to satisfy them
+#[test]
+fn describes_how_to_satisfy_rejected_expressions() {
+    let last_note = |allow: &str, expression: &str| {
+        let cfg = tu::Config::<Config>::new(format!(
+            r#"
+allow = [{allow}]
+[[clarify]]
+name = "features-galore"
+expression = "{expression}"
+license-files = []
+"#
+        ));
+
+        let diag = gather_licenses_with_overrides(func_name!(), cfg, None)
+            .into_iter()
+            .find(|d| field_eq!(d, "/fields/graphs/0/Krate/name", "features-galore"))
+            .unwrap();
+        assert_field_eq!(diag, "/fields/code", "rejected");
+
+        diag["fields"]["notes"]
+            .as_array()
+            .unwrap()
+            .last()
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .to_owned()
+    };
+
+    assert_eq!(
+        last_note("'MIT'", "MIT AND GPL-3.0-only"),
+        "allowing 'GPL-3.0-only' would satisfy the license requirements"
+    );
+    assert_eq!(
+        last_note("", "GPL-2.0-only OR GPL-3.0-only"),
+        "allowing any one of 'GPL-2.0-only', 'GPL-3.0-only' would satisfy the license requirements"
+    );
+    assert_eq!(
+        last_note("'ISC'", "(MIT AND Apache-2.0 AND Zlib) OR (ISC AND BSD-3-Clause AND Unlicense)"),
+        "no single license can be allowed to satisfy the license requirements, at least 2 would need to be allowed: 'BSD-3-Clause', 'Unlicense'"
+    );
+}
+
+/// Ensures expressions can be evaluated without a crate, in which case
+/// exceptions never apply
+#[test]
+fn evaluates_expressions_without_crates() {
+    let cfg = tu::ConfigData::<Config>::load_str(
+        "eval.toml",
+        r#"
+allow = ["MIT", "Apache-2.0"]
+exceptions = [{ name = "features-galore", allow = ["GPL-3.0-only"] }]
+"#,
+    )
+    .validate(|cfg| cfg);
+
+    let evaluate = |expr: &str| {
+        let mut files = diag::Files::new();
+        let file_id = files.add("--eval", expr);
+        licenses::evaluate(&cfg, &spdx::Expression::parse(expr).unwrap(), file_id)
+    };
+
+    let accepted = evaluate("MIT AND Apache-2.0");
+    assert_eq!(accepted.severity, diag::Severity::Help);
+    assert_eq!(
+        accepted
+            .labels
+            .iter()
+            .map(|l| l.message.as_str())
+            .collect::<Vec<_>>(),
+        [
+            "accepted: license is explicitly allowed",
+            "accepted: license is explicitly allowed"
+        ]
+    );
+
+    let rejected = evaluate("Apache-2.0 AND GPL-3.0-only");
+    assert_eq!(rejected.severity, diag::Severity::Error);
+    assert_eq!(
+        rejected.labels[1].message,
+        "rejected: license is not explicitly allowed"
+    );
+    assert_eq!(
+        rejected.notes.last().unwrap(),
+        "allowing 'GPL-3.0-only' would satisfy the license requirements"
+    );
+}
+
+/// Ensures `NOASSERTION` is treated as an unknown license, rather than as any
+/// other license identifier
+#[test]
+fn handles_no_assertion() {
+    let evaluate = |cfg: &str, expr: &str| {
+        let cfg = tu::ConfigData::<Config>::load_str("eval.toml", cfg).validate(|cfg| cfg);
+        let mut files = diag::Files::new();
+        let file_id = files.add("--eval", expr);
+        licenses::evaluate(&cfg, &spdx::Expression::parse(expr).unwrap(), file_id)
+    };
+
+    let rejected = evaluate("allow = ['MIT']", "MIT AND NOASSERTION");
+    assert_eq!(rejected.severity, diag::Severity::Error);
+    assert_eq!(
+        rejected.labels[1].message,
+        "rejected: license is not asserted, NOASSERTION is denied"
+    );
+    assert!(rejected.notes.contains(
+        &"NOASSERTION states that the license could not be determined, it must be explicitly allowed"
+            .to_owned()
+    ));
+    assert_eq!(
+        rejected.notes.last().unwrap(),
+        "allowing 'NOASSERTION' would satisfy the license requirements"
+    );
+
+    let allowed = evaluate("allow = ['MIT', 'NOASSERTION']", "MIT AND NOASSERTION");
+    assert_eq!(allowed.severity, diag::Severity::Help);
+    assert_eq!(
+        allowed.labels[1].message,
+        "accepted: license is explicitly allowed"
+    );
+
+    let warned = evaluate(
+        "allow = ['MIT']\nunknown-license-ref = 'warn'",
+        "MIT AND NOASSERTION",
+    );
+    assert_eq!(warned.severity, diag::Severity::Warning);
+    assert_eq!(
+        warned.labels[1].message,
+        "accepted: license is not asserted, NOASSERTION is allowed"
+    );
+}
+
 #[test]
 fn flags_unencountered_licenses() {
     let cfg = tu::Config::new("allow = ['Aladdin', 'MIT']");
@@ -126,6 +566,50 @@ fn flags_unencountered_licenses() {
     insta::assert_json_snapshot!(diags);
 }
 
+/// Ensures that individual diagnostics can be acknowledged by their
+/// fingerprint, without affecting other diagnostics with the same code
+#[test]
+fn acknowledges_fingerprints() {
+    let rejected = |overrides: diag::DiagnosticOverrides| {
+        let mut diags =
+            gather_licenses_with_overrides(func_name!(), "allow = ['Apache-2.0']", Some(overrides));
+        diags.retain(|d| field_eq!(d, "/fields/code", "rejected"));
+        diags
+    };
+
+    let diags = rejected(diag::DiagnosticOverrides {
+        list_fingerprints: true,
+        ..Default::default()
+    });
+    assert!(diags.len() > 1);
+
+    let fingerprint = |d: &serde_json::Value| {
+        d["fields"]["notes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find_map(|n| n.as_str()?.strip_prefix("fingerprint: ").map(String::from))
+            .unwrap()
+    };
+
+    let acknowledged = fingerprint(&diags[0]);
+    assert!(diags[1..].iter().all(|d| fingerprint(d) != acknowledged));
+
+    let diags = rejected(diag::DiagnosticOverrides {
+        acknowledged: [acknowledged].into_iter().collect(),
+        ..Default::default()
+    });
+
+    let (notes, errors): (Vec<_>, Vec<_>) = diags
+        .iter()
+        .partition(|d| field_eq!(d, "/fields/severity", "note"));
+    assert_eq!(notes.len(), 1);
+    assert!(!errors.is_empty());
+    assert!(errors
+        .iter()
+        .all(|d| field_eq!(d, "/fields/severity", "error")));
+}
+
 #[test]
 fn flags_unencountered_exceptions() {
     let cfg = tu::Config::new(
@@ -145,6 +629,33 @@ fn flags_unencountered_exceptions() {
     insta::assert_json_snapshot!(diags);
 }
 
+#[test]
+fn flags_unencountered_clarifications() {
+    let cfg = tu::Config::new(
+        r#"
+allow = ['MIT']
+[[clarify]]
+name = "features-galore"
+expression = "MIT"
+license-files = []
+
+[[clarify]]
+name = "bippity-boppity-boop"
+expression = "MIT"
+license-files = []
+"#,
+    );
+
+    let mut diags = gather_licenses_with_overrides(func_name!(), cfg, None);
+
+    diags.retain(|d| field_eq!(d, "/fields/code", "clarification-not-encountered"));
+
+    // Only the clarification for the crate that isn't in the graph is unused
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "warning");
+    assert_field_eq!(diags[0], "/fields/labels/0/span", "bippity-boppity-boop");
+}
+
 /// Ensures that invalid SPDX expressions in strict mode can be parsed when
 /// falling back to more lax rules, but still output a warning
 #[test]
@@ -231,6 +742,70 @@ license-files = [
     insta::assert_json_snapshot!(diags);
 }
 
+/// Ensures private workspace crates are skipped, including unlicensed ones,
+/// with both `private.ignore` and `workspace-treatment = "ignore-private"`
+#[test]
+fn ignores_private_workspace_crates() {
+    let mut cmd = krates::Cmd::new();
+    cmd.manifest_path("tests/test_data/private-workspace/Cargo.toml");
+
+    let krates: Krates = krates::Builder::new()
+        .build(cmd, krates::NoneFilter)
+        .unwrap();
+
+    let codes = |cfg: &str| {
+        let (ctx, summary) = setup(&krates, func_name!(), tu::Config::new(cfg));
+
+        let mut codes: Vec<_> = tu::run_gather(ctx, |ctx, tx| {
+            crate::licenses::check(
+                ctx,
+                summary,
+                diag::ErrorSink {
+                    overrides: None,
+                    channel: tx,
+                },
+            );
+        })
+        .into_iter()
+        .filter_map(|d| {
+            let name = d
+                .pointer("/fields/graphs/0/Krate/name")?
+                .as_str()?
+                .to_owned();
+            let code = d.pointer("/fields/code")?.as_str()?.to_owned();
+            Some((name, code))
+        })
+        .collect();
+        codes.sort();
+        codes
+    };
+
+    let skipped = [
+        ("internal-tool", "skipped-private-workspace-crate"),
+        ("public-tool", "accepted"),
+        ("unlicensed-tool", "skipped-private-workspace-crate"),
+    ]
+    .map(|(n, c)| (n.to_owned(), c.to_owned()));
+
+    assert_eq!(
+        codes("allow = ['MIT']\nworkspace-treatment = 'ignore-private'"),
+        skipped
+    );
+    assert_eq!(
+        codes("allow = ['MIT']\nprivate = { ignore = true }"),
+        skipped
+    );
+    assert_eq!(
+        codes("allow = ['MIT']"),
+        [
+            ("internal-tool", "rejected"),
+            ("public-tool", "accepted"),
+            ("unlicensed-tool", "unlicensed"),
+        ]
+        .map(|(n, c)| (n.to_owned(), c.to_owned()))
+    );
+}
+
 #[test]
 fn handles_dev_dependencies() {
     let cfg = tu::Config::new(
@@ -282,3 +857,53 @@ fn forces_apache_over_pixar() {
 
     insta::assert_json_snapshot!(diags);
 }
+
+#[test]
+fn detects_license_changes() {
+    let md: krates::cm::Metadata = serde_json::from_str(
+        &std::fs::read_to_string("tests/test_data/features-galore/metadata.json").unwrap(),
+    )
+    .unwrap();
+
+    let krates: Krates = krates::Builder::new()
+        .build_with_metadata(md, krates::NoneFilter)
+        .unwrap();
+
+    let cfg = tu::Config::<Config>::new("allow = ['Apache-2.0', 'MIT', 'Zlib']");
+    let (mut ctx, summary) = setup(&krates, func_name!(), cfg);
+
+    // The lock round trips through its serialized form
+    let lock = licenses::LicenseLock::from_summary(&summary);
+    assert_eq!(
+        lock.get("tinyvec_macros"),
+        Some("MIT OR Apache-2.0 OR Zlib")
+    );
+    assert_eq!(
+        lock,
+        lock.to_string().parse::<licenses::LicenseLock>().unwrap()
+    );
+
+    let lock: licenses::LicenseLock = "[licenses]\ntinyvec_macros = 'MIT'\n".parse().unwrap();
+    ctx.valid_cfg.lock = Some(lock);
+
+    let mut diags = tu::run_gather(ctx, |ctx, tx| {
+        crate::licenses::check(
+            ctx,
+            summary,
+            diag::ErrorSink {
+                overrides: None,
+                channel: tx,
+            },
+        );
+    });
+
+    diags.retain(|d| field_eq!(d, "/fields/code", "license-changed"));
+
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "warning");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "license of 'tinyvec_macros = 0.1.0' has changed from 'MIT' to 'MIT OR Apache-2.0 OR Zlib'"
+    );
+}
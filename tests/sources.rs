@@ -1,5 +1,5 @@
 use cargo_deny::{
-    func_name,
+    assert_field_eq, field_eq, func_name,
     sources::{self, cfg::Config},
     test_utils::{self as tu, KrateGather},
 };
@@ -80,6 +80,34 @@ fn allows_bitbucket_org() {
     insta::assert_json_snapshot!(diags);
 }
 
+/// Ensures git sources under an allowed host, and optional path, are allowed,
+/// and that the path is matched by segment rather than by string prefix
+#[test]
+fn allows_git_hosts() {
+    let cfg = "unknown-git = 'deny'
+    allow-git-hosts = [
+        'github.com/EmbarkStudios',
+        'https://gitlab.com',
+        'github.com/Embark',
+        'git.corp.net',
+    ]";
+
+    let diags = src_check(func_name!(), KrateGather::new("sources"), cfg);
+    let count = |code: &str| {
+        diags
+            .iter()
+            .filter(|d| field_eq!(d, "/fields/code", code))
+            .count()
+    };
+
+    // krates, spdx, amethyst_core, and amethyst_error
+    assert_eq!(count("allowed-by-git-host"), 4);
+    // anyhow and line-wrap
+    assert_eq!(count("source-not-allowed"), 2);
+    // github.com/Embark is not a prefix of github.com/EmbarkStudios
+    assert_eq!(count("unmatched-git-host"), 2);
+}
+
 #[test]
 fn allows_registry_index_git() {
     let cfg = "unknown-registry = 'deny'
@@ -192,3 +220,321 @@ fn validates_git_source_specs() {
         }
     }
 }
+
+/// Ensures crates whose source was changed by a `[patch]` are flagged, unless
+/// the patch source is explicitly allowed
+#[test]
+fn detects_patched_sources() {
+    let patched = |cfg: &str| {
+        src_check(func_name!(), KrateGather::new("patched"), cfg)
+            .into_iter()
+            .filter(|d| field_eq!(d, "/fields/code", "patched-source"))
+            .collect::<Vec<_>>()
+    };
+
+    let diags = patched("deny-patched = 'deny'");
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "error");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "crate 'smallvec = 1.99.0' is a patched replacement for 'smallvec' from crates.io"
+    );
+
+    assert!(patched("deny-patched = 'deny'\nallow-patched = ['smallvec']").is_empty());
+    assert!(patched("").is_empty());
+}
+
+/// Ensures the same git repository being used at different revisions is
+/// detected, and that each revision is listed
+#[test]
+fn detects_multiple_git_revs() {
+    let multiple = |cfg: &str| {
+        src_check(func_name!(), KrateGather::new("workspace"), cfg)
+            .into_iter()
+            .filter(|d| field_eq!(d, "/fields/code", "multiple-git-revs"))
+            .collect::<Vec<_>>()
+    };
+
+    let diags = multiple("");
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "warning");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "found 3 different revisions of git repository 'https://github.com/EmbarkStudios/spdx'"
+    );
+
+    let notes = diags[0]["fields"]["notes"].as_array().unwrap();
+    assert_eq!(notes.len(), 3);
+    assert_eq!(notes[0], "tag '0.6.0': spdx 0.6.0");
+
+    assert!(multiple("multiple-git-revs = 'allow'").is_empty());
+}
+
+/// Ensures the same crate name being sourced from more than one registry is
+/// detected
+#[test]
+fn detects_name_collisions() {
+    // Move one of the versions of idna to a private registry, as if it was a
+    // crate that shadows the crates.io name
+    const CRATES_IO: &str = "idna 0.2.3 (registry+https://github.com/rust-lang/crates.io-index)";
+    const PRIVATE: &str = "idna 0.2.3 (registry+https://sekretz.com/registry/index)";
+
+    let mut md: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string("tests/test_data/features-galore/metadata.json")
+            .unwrap()
+            .replace(CRATES_IO, PRIVATE),
+    )
+    .unwrap();
+
+    for pkg in md["packages"].as_array_mut().unwrap() {
+        if pkg["id"] == PRIVATE {
+            pkg["source"] = "registry+https://sekretz.com/registry/index".into();
+        }
+    }
+
+    let krates: cargo_deny::Krates = krates::Builder::new()
+        .build_with_metadata(serde_json::from_value(md).unwrap(), krates::NoneFilter)
+        .unwrap();
+
+    let collisions = |cfg: &str| {
+        tu::gather_diagnostics::<Config, _, _>(&krates, func_name!(), cfg.into(), |ctx, tx| {
+            sources::check(ctx, tx);
+        })
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "name-collision"))
+        .collect::<Vec<_>>()
+    };
+
+    // Not checked by default
+    assert!(collisions("").is_empty());
+
+    let diags = collisions("detect-name-collision = 'deny'");
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "error");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "crate name 'idna' is sourced from 2 different registries"
+    );
+
+    let notes = diags[0]["fields"]["notes"].as_array().unwrap();
+    assert_eq!(notes.len(), 2);
+    assert!(notes.contains(&"registry+https://sekretz.com/registry/index: idna 0.2.3".into()));
+}
+
+/// Ensures crates.io crates that aren't owned by a trusted user or a team in a
+/// trusted organization are flagged
+#[test]
+fn detects_untrusted_owners() {
+    let krates = KrateGather {
+        name: "features-galore",
+        no_default_features: false,
+        targets: &["x86_64-unknown-linux-gnu"],
+        ..Default::default()
+    }
+    .gather();
+
+    let untrusted = |cfg: &str| {
+        tu::gather_diagnostics::<Config, _, _>(&krates, func_name!(), cfg.into(), |mut ctx, tx| {
+            ctx.cfg.owners = Some(
+                [
+                    ("cfg-if", &["alexcrichton"][..]),
+                    ("bitflags", &["KodrAus", "github:rust-lang:libs"]),
+                    ("fnv", &["dtolnay"]),
+                ]
+                .into_iter()
+                .map(|(name, owners)| {
+                    (
+                        name.to_owned(),
+                        owners.iter().map(|o| (*o).to_owned()).collect(),
+                    )
+                })
+                .collect(),
+            );
+            sources::check(ctx, tx);
+        })
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "untrusted-owner"))
+        .collect::<Vec<_>>()
+    };
+
+    let diags = untrusted("trusted-owners = ['rust-lang', 'dtolnay']\nuntrusted-owner = 'deny'");
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "error");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "crate 'cfg-if = 1.0.0' is not owned by a trusted owner"
+    );
+    assert_field_eq!(diags[0], "/fields/notes/0", "owners: alexcrichton");
+
+    assert!(untrusted("trusted-owners = ['rust-lang', 'dtolnay', 'alexcrichton']").is_empty());
+    assert!(untrusted("trusted-owners = ['dtolnay']\nuntrusted-owner = 'allow'").is_empty());
+}
+
+/// Ensures git sources without a locked commit are detected
+#[test]
+fn detects_unlocked_git_revs() {
+    // Move two crates.io crates to git sources, one of which is missing the
+    // commit that cargo always records in the lockfile
+    const SOURCES: &[(&str, &str)] = &[
+        ("fnv 1.0.7", "git+https://github.com/servo/rust-fnv?tag=v1.0.7"),
+        (
+            "itoa 1.0.3",
+            "git+https://github.com/dtolnay/itoa?tag=1.0.3#ee4df5bd10c7f3b3d5b9b5c5a8fa1f0a1e27e8b9",
+        ),
+    ];
+
+    let mut md = std::fs::read_to_string("tests/test_data/features-galore/metadata.json").unwrap();
+    for (krate, source) in SOURCES {
+        md = md.replace(
+            &format!("{krate} (registry+https://github.com/rust-lang/crates.io-index)"),
+            &format!("{krate} ({source})"),
+        );
+    }
+
+    let mut md: serde_json::Value = serde_json::from_str(&md).unwrap();
+    for pkg in md["packages"].as_array_mut().unwrap() {
+        if let Some((_, source)) = SOURCES
+            .iter()
+            .find(|(krate, _)| pkg["id"].as_str().unwrap().starts_with(krate))
+        {
+            pkg["source"] = (*source).into();
+        }
+    }
+
+    let krates: cargo_deny::Krates = krates::Builder::new()
+        .build_with_metadata(serde_json::from_value(md).unwrap(), krates::NoneFilter)
+        .unwrap();
+
+    let unlocked = |cfg: &str| {
+        tu::gather_diagnostics::<Config, _, _>(&krates, func_name!(), cfg.into(), |ctx, tx| {
+            sources::check(ctx, tx);
+        })
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "git-rev-not-locked"))
+        .collect::<Vec<_>>()
+    };
+
+    // Not checked by default
+    assert!(unlocked("").is_empty());
+
+    let diags = unlocked("git-require-locked-rev = 'deny'");
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "error");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "git source for crate 'fnv = 1.0.7' is not locked to a commit"
+    );
+}
+
+/// Ensures sources that use plain http are flagged, even if they are allowed
+#[test]
+fn detects_insecure_sources() {
+    const SOURCE: &str =
+        "git+http://github.com/servo/rust-fnv?tag=v1.0.7#a7c1ba8ebc29da29e5e9ad2b2a0bd5dd4ad6ee5c";
+
+    let md = std::fs::read_to_string("tests/test_data/features-galore/metadata.json")
+        .unwrap()
+        .replace(
+            "fnv 1.0.7 (registry+https://github.com/rust-lang/crates.io-index)",
+            &format!("fnv 1.0.7 ({SOURCE})"),
+        );
+
+    let mut md: serde_json::Value = serde_json::from_str(&md).unwrap();
+    for pkg in md["packages"].as_array_mut().unwrap() {
+        if pkg["id"].as_str().unwrap().starts_with("fnv 1.0.7") {
+            pkg["source"] = SOURCE.into();
+        }
+    }
+
+    let krates: cargo_deny::Krates = krates::Builder::new()
+        .build_with_metadata(serde_json::from_value(md).unwrap(), krates::NoneFilter)
+        .unwrap();
+
+    let insecure = |cfg: &str| {
+        tu::gather_diagnostics::<Config, _, _>(&krates, func_name!(), cfg.into(), |ctx, tx| {
+            sources::check(ctx, tx);
+        })
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "insecure-source"))
+        .collect::<Vec<_>>()
+    };
+
+    // Not checked by default
+    assert!(insecure("allow-git = ['http://github.com/servo/rust-fnv']").is_empty());
+
+    let diags = insecure(
+        "require-https = 'deny'
+    allow-git = ['http://github.com/servo/rust-fnv']",
+    );
+    assert_eq!(diags.len(), 2);
+    assert!(diags
+        .iter()
+        .all(|d| field_eq!(d, "/fields/severity", "error")));
+    assert!(diags.iter().any(|d| field_eq!(
+        d,
+        "/fields/message",
+        "source for crate 'fnv = 1.0.7' uses plain http rather than https"
+    )));
+    assert!(diags.iter().any(|d| field_eq!(
+        d,
+        "/fields/message",
+        "allowed source uses plain http rather than https"
+    )));
+}
+
+/// Ensures crates whose lockfile checksum doesn't match the index are detected
+#[test]
+fn detects_checksum_mismatches() {
+    let krates = KrateGather {
+        name: "features-galore",
+        no_default_features: false,
+        targets: &["x86_64-unknown-linux-gnu"],
+        ..Default::default()
+    }
+    .gather();
+
+    let mismatched = |cfg: &str| {
+        tu::gather_diagnostics::<Config, _, _>(&krates, func_name!(), cfg.into(), |mut ctx, tx| {
+            ctx.cfg.checksums = Some(
+                [
+                    ("cfg-if", "1.0.0", "baadf00d", "deadbeef"),
+                    ("fnv", "1.0.7", "3f9eec91", "3F9EEC91"),
+                ]
+                .into_iter()
+                .map(|(name, version, locked, index)| {
+                    (
+                        (name.to_owned(), version.parse().unwrap()),
+                        sources::Checksums {
+                            locked: locked.to_owned(),
+                            index: index.to_owned(),
+                        },
+                    )
+                })
+                .collect(),
+            );
+            sources::check(ctx, tx);
+        })
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "lockfile-checksum-mismatch"))
+        .collect::<Vec<_>>()
+    };
+
+    // Not checked by default
+    assert!(mismatched("").is_empty());
+
+    let diags = mismatched("verify-checksums = 'deny'");
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "error");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "checksum of crate 'cfg-if = 1.0.0' in the lockfile does not match the crates.io index"
+    );
+    assert_field_eq!(diags[0], "/fields/notes/0", "lockfile checksum: baadf00d");
+    assert_field_eq!(diags[0], "/fields/notes/1", "index checksum: deadbeef");
+}